@@ -522,6 +522,7 @@ fn mainnet_genesis(
             btc_withdrawal_fee: 500000,
             max_withdrawal_count: 100,
             verifier: BtcTxVerifier::Recover,
+            phantom: Default::default(),
         },
         x_staking: chainx::XStakingConfig {
             validator_count: 40,
@@ -776,6 +777,7 @@ fn malan_genesis(
             btc_withdrawal_fee: 500000,
             max_withdrawal_count: 100,
             verifier: BtcTxVerifier::Recover,
+            phantom: Default::default(),
         },
         x_staking: malan::XStakingConfig {
             validator_count: 40,
@@ -955,6 +957,7 @@ fn build_dev_genesis(
             btc_withdrawal_fee: 500000,
             max_withdrawal_count: 100,
             verifier: BtcTxVerifier::Recover,
+            phantom: Default::default(),
         },
         x_staking: dev::XStakingConfig {
             validator_count: 40,
@@ -78,6 +78,14 @@ pub struct RunCmd {
 
     #[clap(flatten)]
     pub logger: crate::logger::LoggerParams,
+
+    /// Maintain an index of the BTC balance changes in the frontier database directory.
+    ///
+    /// The index is queryable by the `btcledger_getBalanceAt` and `btcledger_getBalanceChanges`
+    /// RPCs. To index the past blocks, the node has to keep the state of them, e.g., with
+    /// `--pruning archive`.
+    #[clap(long)]
+    pub enable_btc_ledger_index: bool,
 }
 
 impl Cli {
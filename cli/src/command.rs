@@ -300,9 +300,10 @@ pub fn run() -> sc_cli::Result<()> {
     match &cli.subcommand {
         None => {
             let runner = cli.create_runner(&cli.run.base)?;
+            let enable_btc_ledger_index = cli.run.enable_btc_ledger_index;
 
             runner.run_node_until_exit(|config| async move {
-                service::build_full(config).map_err(sc_cli::Error::Service)
+                service::build_full(config, enable_btc_ledger_index).map_err(sc_cli::Error::Service)
             })
         }
         Some(Subcommand::Benchmark(cmd)) => {
@@ -86,6 +86,7 @@ pub fn local_testnet_trustees() -> Vec<(Chain, TrusteeInfoConfig, Vec<BtcTrustee
     let btc_config = TrusteeInfoConfig {
         min_trustee_count: 3,
         max_trustee_count: 15,
+        recovery: None,
     };
 
     let btc_trustees = vec![
@@ -114,6 +115,7 @@ pub fn benchmarks_trustees() -> Vec<(Chain, TrusteeInfoConfig, Vec<BtcTrusteePar
     let btc_config = TrusteeInfoConfig {
         min_trustee_count: 3,
         max_trustee_count: 15,
+        recovery: None,
     };
 
     let btc_trustees = vec![
@@ -145,6 +147,7 @@ pub fn mainnet_trustees() -> Vec<(Chain, TrusteeInfoConfig, Vec<BtcTrusteeParams
     let btc_config = TrusteeInfoConfig {
         min_trustee_count: 3,
         max_trustee_count: 15,
+        recovery: None,
     };
 
     let btc_trustees = vec![
@@ -15,7 +15,8 @@ use light_bitcoin::{
 use crate::{
     types::{BtcDepositInfo, BtcTxMetaType, OpReturnAccount, TrusteePair},
     utils::{
-        extract_addr_from_transaction, extract_opreturn_data, extract_output_addr, is_trustee_addr,
+        extract_addr_from_transaction, extract_opreturn_data, extract_output_addr,
+        is_recovery_spend, is_trustee_addr,
     },
 };
 
@@ -70,8 +71,20 @@ impl BtcTxTypeDetector {
             extract_addr_from_transaction(prev_tx, outpoint.index as usize, self.network)
         });
 
-        // detect X-BTC `Withdrawal`/`HotAndCold`/`TrusteeTransition` transaction
+        // detect X-BTC `TrusteeRecovery`/`Withdrawal`/`HotAndCold`/`TrusteeTransition` transaction
         if let Some(input_addr) = input_addr {
+            // inputs: trustee address spent by the timelocked recovery leaf, any input of the
+            // trustee transaction could reveal the recovery leaf
+            let is_trustee_input = is_trustee_addr(input_addr, current_trustee_pair)
+                || prev_trustee_pair.map_or(false, |pair| is_trustee_addr(input_addr, pair));
+            if is_trustee_input && tx.inputs.iter().any(is_recovery_spend) {
+                warn!(
+                    "[detect_transaction_type] Trustee address is spent by the recovery path, tx:{:?}",
+                    hash_rev(tx.hash())
+                );
+                return BtcTxMetaType::TrusteeRecovery;
+            }
+
             let all_outputs_is_trustee = tx
                 .outputs
                 .iter()
@@ -236,8 +249,13 @@ mod tests {
     use sp_runtime::AccountId32;
     use xp_gateway_common::OpReturnAccount;
 
-    use super::{Address, BtcTxTypeDetector, Network, Transaction};
-    use crate::extractor::{AccountExtractor, OpReturnExtractor};
+    use light_bitcoin::primitives::Bytes;
+
+    use super::{Address, BtcTxMetaType, BtcTxTypeDetector, Network, Transaction};
+    use crate::{
+        extractor::{AccountExtractor, OpReturnExtractor},
+        utils::recovery_script,
+    };
 
     fn account(addr: &str) -> AccountId32 {
         addr.parse::<AccountId32>().unwrap()
@@ -394,4 +412,51 @@ mod tests {
             assert_eq!(got, expect);
         }
     }
+
+    #[test]
+    fn test_detect_trustee_recovery() {
+        const DEPOSIT_HOT_ADDR: &str = "3LFSUKkP26hun42J1Dy6RATsbgmBJb27NF";
+        const DEPOSIT_COLD_ADDR: &str = "3FLBhPfEqmw4Wn5EQMeUzPLrQtJMprgwnw";
+        let btc_tx_detector = BtcTxTypeDetector::new(Network::Mainnet, 0);
+        let current_trustee_pair = (
+            DEPOSIT_HOT_ADDR.parse::<Address>().unwrap(),
+            DEPOSIT_COLD_ADDR.parse::<Address>().unwrap(),
+        );
+
+        // txid: b368d3b822ec6656af441ccfa0ea2c846ec445286fd264e94a9a6edf0d7a1108
+        // the first output is the X-BTC hot trustee address
+        let prev_tx = "020000000001012f0f1be54334c36baf9edce4051acfcc4634e27504e39bc6466a1dadd36110e40100000017160014cd286c8c974540b1019e351c33551dc152e7447bffffffff03307500000000000017a914cb94110435d0635223eebe25ed2aaabc03781c4587672400000000000017a9149b995c9fddc8e5086626f7123631891a209d83a4870000000000000000326a3035556a336568616d445a57506667413869415a656e6863416d5044616b6a6634614d626b424234645856766a6f57367802483045022100f27347145406cc9706cd4d83018b07303c30b8d43f935019bf1d3accb38696f70220546db7a30dc8f0c4f02e17460573d009d26d85bd98a32642e88c6f74e76ac7140121037788522b753d5517cd9191c96f741a0d2b479369697d41567b4b418c7979d77300000000".parse::<Transaction>().unwrap();
+        // txid: f5a1d788f448d6671f9fb7f8949850e28f0adbc39c40124e37cca4acfbf22915
+        // rewritten to spend the hot trustee utxo back to the hot trustee address
+        let mut tx = "02000000000101681bd0b1158c7dc4ade8818c20820bedb906773a48c614e6ddc44cfd3c37408f010000001716001485863aa315bc11a844bc1eee01547be6a302a7caffffffff03204e00000000000017a914cb94110435d0635223eebe25ed2aaabc03781c458717a501000000000017a914d5ea60928669d832351b023bcfb3e85c530817d9870000000000000000016a02483045022100be53337e0c816e4f4d61b8b535431199105f04a1c043bd1d0f0362a525d7678502204ec154badbc84435d0c059b742dfddccca6338042fbf7e77bbfdbbfba183e1a10121025eb9e1c63f28cccc67739ee940256fc26259e06167a0e9c411023bb1377ab1a000000000".parse::<Transaction>().unwrap();
+        tx.inputs[0].previous_output.index = 0;
+        tx.outputs.truncate(1);
+
+        let detect = |tx: &Transaction| {
+            btc_tx_detector.detect_transaction_type::<AccountId32, _>(
+                tx,
+                Some(&prev_tx),
+                OpReturnExtractor::extract_account,
+                current_trustee_pair,
+                None,
+            )
+        };
+        assert!(matches!(detect(&tx), BtcTxMetaType::HotAndCold));
+
+        // the recovery leaf revealed by the second input
+        let mut recovery_input = tx.inputs[0].clone();
+        recovery_input.previous_output.index = 1;
+        recovery_input.script_witness = vec![
+            Bytes::from(vec![1u8; 64]),
+            Bytes::from(recovery_script(144, &[2u8; 32])),
+            Bytes::from(vec![0xc0u8; 33]),
+        ];
+        tx.inputs.push(recovery_input.clone());
+        assert!(matches!(detect(&tx), BtcTxMetaType::TrusteeRecovery));
+
+        // the recovery leaf revealed by the first input
+        tx.inputs[0].script_witness = recovery_input.script_witness;
+        tx.inputs.truncate(1);
+        assert!(matches!(detect(&tx), BtcTxMetaType::TrusteeRecovery));
+    }
 }
@@ -25,6 +25,7 @@ pub enum BtcTxType {
     HotAndCold,
     TrusteeTransition,
     Irrelevance,
+    TrusteeRecovery,
 }
 
 impl Default for BtcTxType {
@@ -42,6 +43,7 @@ pub enum BtcTxMetaType<AccountId> {
     HotAndCold,
     TrusteeTransition,
     Irrelevance,
+    TrusteeRecovery,
 }
 
 impl<AccountId> BtcTxMetaType<AccountId> {
@@ -53,6 +55,7 @@ impl<AccountId> BtcTxMetaType<AccountId> {
             BtcTxMetaType::HotAndCold => BtcTxType::HotAndCold,
             BtcTxMetaType::TrusteeTransition => BtcTxType::TrusteeTransition,
             BtcTxMetaType::Irrelevance => BtcTxType::Irrelevance,
+            BtcTxMetaType::TrusteeRecovery => BtcTxType::TrusteeRecovery,
         }
     }
 }
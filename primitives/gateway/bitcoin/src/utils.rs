@@ -4,9 +4,10 @@ use frame_support::log::{error, warn};
 use sp_std::{cmp::Ordering, prelude::Vec};
 
 use light_bitcoin::{
-    chain::{Transaction, TransactionOutput},
+    chain::{Transaction, TransactionInput, TransactionOutput},
     keys::{Address, Network},
-    script::{Opcode, Script, ScriptType},
+    primitives::Bytes,
+    script::{Builder, Opcode, Script, ScriptType},
 };
 
 /// Extract address from a transaction output specified by outpoint_index.
@@ -67,6 +68,78 @@ pub fn is_trustee_addr(addr: Address, trustee_pair: (Address, Address)) -> bool
     addr.hash == hot_addr.hash || addr.hash == cold_addr.hash
 }
 
+/// The first byte of the taproot annex.
+const TAPROOT_ANNEX_TAG: u8 = 0x50;
+
+/// Build the tapscript of the timelocked recovery leaf.
+///
+/// `<csv_delay> OP_CHECKSEQUENCEVERIFY OP_DROP <x_only_pubkey> OP_CHECKSIG`
+pub fn recovery_script(csv_delay: u32, x_only_pubkey: &[u8]) -> Vec<u8> {
+    let builder = match csv_delay {
+        1..=16 => Builder::default().push_opcode(
+            Opcode::from_u8(Opcode::OP_1 as u8 + csv_delay as u8 - 1)
+                .expect("OP_1..OP_16 are valid opcodes; qed"),
+        ),
+        _ => Builder::default().push_bytes(&encode_script_num(csv_delay)),
+    };
+    let script: Bytes = builder
+        .push_opcode(Opcode::OP_CHECKSEQUENCEVERIFY)
+        .push_opcode(Opcode::OP_DROP)
+        .push_bytes(x_only_pubkey)
+        .push_opcode(Opcode::OP_CHECKSIG)
+        .into_script()
+        .into();
+    script.into()
+}
+
+/// Minimal little-endian encoding of a positive script number.
+fn encode_script_num(n: u32) -> Vec<u8> {
+    let mut bytes = n.to_le_bytes().to_vec();
+    while bytes.last() == Some(&0) {
+        bytes.pop();
+    }
+    // the most significant bit is the sign bit
+    if bytes.last().map_or(false, |b| b & 0x80 != 0) {
+        bytes.push(0);
+    }
+    bytes
+}
+
+/// Check if the tapscript is the timelocked recovery leaf built by `recovery_script`.
+pub fn is_recovery_script(script: &[u8]) -> bool {
+    let csv_end = match script.first() {
+        Some(op) if *op >= Opcode::OP_1 as u8 && *op <= Opcode::OP_16 as u8 => 1,
+        Some(len) if *len >= 1 && *len <= 4 => 1 + *len as usize,
+        _ => return false,
+    };
+    // OP_CHECKSEQUENCEVERIFY OP_DROP <32 bytes> OP_CHECKSIG
+    script.len() == csv_end + 36
+        && script[csv_end] == Opcode::OP_CHECKSEQUENCEVERIFY as u8
+        && script[csv_end + 1] == Opcode::OP_DROP as u8
+        && script[csv_end + 2] == 32
+        && script[csv_end + 35] == Opcode::OP_CHECKSIG as u8
+}
+
+/// Check if the input is a taproot script path spending which reveals a recovery leaf.
+///
+/// The witness of script path spending: `[args..., tapscript, control_block, (annex)]`.
+pub fn is_recovery_spend(input: &TransactionInput) -> bool {
+    let mut witness = input.script_witness.as_slice();
+    if witness.len() >= 2
+        && witness.last().and_then(|annex| annex.first()) == Some(&TAPROOT_ANNEX_TAG)
+    {
+        witness = &witness[..witness.len() - 1];
+    }
+    if witness.len() < 3 {
+        return false;
+    }
+    let control_block = &witness[witness.len() - 1];
+    if control_block.len() < 33 || (control_block.len() - 33) % 32 != 0 {
+        return false;
+    }
+    is_recovery_script(&witness[witness.len() - 2])
+}
+
 /// Extract the opreturn data from btc null data script.
 /// OP_RETURN format:
 /// - op_return + op_push(<0x4c) + data (op_push == data.len())
@@ -156,3 +229,60 @@ fn test_extract_opreturn_data() {
         b"5QZYGVVUPsp7cbqGUcHsRJUZrnmTuEyh6SLH6jdpfsFxgpRK@Laocius".to_vec()
     );
 }
+
+#[test]
+fn test_recovery_script() {
+    let x_only_pubkey = [2u8; 32];
+    // 144 blocks: 02 9000 b2 75 20 <pubkey> ac
+    let script = recovery_script(144, &x_only_pubkey);
+    assert_eq!(&script[..5], &[0x02, 0x90, 0x00, 0xb2, 0x75]);
+    assert!(is_recovery_script(&script));
+
+    // small delay is pushed by OP_N
+    let script = recovery_script(16, &x_only_pubkey);
+    assert_eq!(script[0], Opcode::OP_16 as u8);
+    assert!(is_recovery_script(&script));
+
+    // normal threshold leaf: <pubkey> OP_CHECKSIG
+    let mut script = vec![32];
+    script.extend_from_slice(&x_only_pubkey);
+    script.push(Opcode::OP_CHECKSIG as u8);
+    assert!(!is_recovery_script(&script));
+}
+
+#[test]
+fn test_is_recovery_spend() {
+    // txid: f5a1d788f448d6671f9fb7f8949850e28f0adbc39c40124e37cca4acfbf22915 (witness)
+    let tx = "02000000000101681bd0b1158c7dc4ade8818c20820bedb906773a48c614e6ddc44cfd3c37408f010000001716001485863aa315bc11a844bc1eee01547be6a302a7caffffffff03204e00000000000017a914cb94110435d0635223eebe25ed2aaabc03781c458717a501000000000017a914d5ea60928669d832351b023bcfb3e85c530817d9870000000000000000016a02483045022100be53337e0c816e4f4d61b8b535431199105f04a1c043bd1d0f0362a525d7678502204ec154badbc84435d0c059b742dfddccca6338042fbf7e77bbfdbbfba183e1a10121025eb9e1c63f28cccc67739ee940256fc26259e06167a0e9c411023bb1377ab1a000000000".parse::<Transaction>().unwrap();
+    let mut input = tx.inputs[0].clone();
+    // p2sh-p2wpkh spending
+    assert!(!is_recovery_spend(&input));
+
+    let signature = Bytes::from(vec![1u8; 64]);
+    let script = Bytes::from(recovery_script(144, &[2u8; 32]));
+    // the control block of a recovery leaf with a sibling node
+    let control_block = Bytes::from(vec![0xc0u8; 65]);
+    input.script_witness = vec![signature.clone(), script.clone(), control_block.clone()];
+    assert!(is_recovery_spend(&input));
+
+    // with the annex
+    input
+        .script_witness
+        .push(Bytes::from(vec![TAPROOT_ANNEX_TAG, 0]));
+    assert!(is_recovery_spend(&input));
+
+    // key path spending
+    input.script_witness = vec![signature.clone()];
+    assert!(!is_recovery_spend(&input));
+
+    // invalid control block
+    input.script_witness = vec![signature.clone(), script, Bytes::from(vec![0xc0u8; 34])];
+    assert!(!is_recovery_spend(&input));
+
+    // the threshold leaf: <pubkey> OP_CHECKSIG
+    let mut threshold_script = vec![32];
+    threshold_script.extend_from_slice(&[2u8; 32]);
+    threshold_script.push(Opcode::OP_CHECKSIG as u8);
+    input.script_witness = vec![signature, Bytes::from(threshold_script), control_block];
+    assert!(!is_recovery_spend(&input));
+}
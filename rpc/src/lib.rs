@@ -20,6 +20,7 @@ use sp_consensus_babe::BabeApi;
 
 use chainx_primitives::{AccountId, Balance, Block, BlockNumber, Hash, Index};
 
+use xpallet_btc_ledger_rpc::index::BalanceIndex;
 use xpallet_mining_asset_rpc_runtime_api::MiningWeight;
 use xpallet_mining_staking_rpc_runtime_api::VoteWeight;
 
@@ -107,6 +108,8 @@ pub struct FullDeps<C, P, SC, B, A: sc_transaction_pool::ChainApi> {
     pub grandpa: GrandpaDeps<B>,
     /// Frontier specific dependencies.
     pub frontier: FrontierDeps<A>,
+    /// The BTC ledger balance change index, if enabled.
+    pub btc_ledger_index: Option<Arc<BalanceIndex<Block, AccountId, Balance>>>,
 }
 
 pub fn overrides_handle<C, B>(client: Arc<C>) -> Arc<OverrideHandle<Block>>
@@ -209,7 +212,10 @@ where
     use pallet_transaction_payment_rpc::{TransactionPayment, TransactionPaymentApi};
     use substrate_frame_rpc_system::{FullSystem, SystemApi};
     use xpallet_assets_rpc::{Assets, XAssetsApi};
-    use xpallet_btc_ledger_rpc::{BtcLedger, BtcLedgerApi};
+    use xpallet_btc_ledger_rpc::{
+        index::{BtcLedgerIndex, BtcLedgerIndexApi},
+        BtcLedger, BtcLedgerApi,
+    };
     use xpallet_dex_spot_rpc::{XSpot, XSpotApi};
     use xpallet_gateway_bitcoin_rpc::{XGatewayBitcoin, XGatewayBitcoinApi};
     use xpallet_gateway_common_rpc::{XGatewayCommon, XGatewayCommonApi};
@@ -228,6 +234,7 @@ where
         grandpa,
         babe,
         frontier,
+        btc_ledger_index,
     } = deps;
 
     let BabeDeps {
@@ -298,6 +305,9 @@ where
         client.clone(),
     )));
     io.extend_with(BtcLedgerApi::to_delegate(BtcLedger::new(client.clone())));
+    if let Some(index) = btc_ledger_index {
+        io.extend_with(BtcLedgerIndexApi::to_delegate(BtcLedgerIndex::new(index)));
+    }
 
     // EVM
     {
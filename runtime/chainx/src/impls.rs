@@ -5,16 +5,20 @@
 use codec::{Decode, Encode};
 use scale_info::TypeInfo;
 use sp_runtime::{
-    traits::{DispatchInfoOf, SignedExtension},
+    traits::{DispatchInfoOf, SignedExtension, Zero},
     transaction_validity::{
         InvalidTransaction, TransactionValidity, TransactionValidityError, ValidTransaction,
     },
-    FixedPointNumber, Perquintill, RuntimeDebug,
+    ArithmeticError, DispatchError, DispatchResult, FixedPointNumber, Perquintill, RuntimeDebug,
 };
 
 use frame_support::{
     parameter_types,
-    traits::{Currency, ExistenceRequirement, Imbalance, OnUnbalanced, WithdrawReasons},
+    traits::{
+        Currency, ExistenceRequirement, Imbalance, OnUnbalanced, ReservableCurrency,
+        WithdrawReasons,
+    },
+    transactional,
 };
 
 use pallet_transaction_payment::{Multiplier, TargetedFeeAdjustment};
@@ -22,9 +26,9 @@ use pallet_transaction_payment::{Multiplier, TargetedFeeAdjustment};
 use xpallet_gateway_common::Call as XGatewayCommonCall;
 use xpallet_mining_staking::Call as XStakingCall;
 
-use chainx_primitives::{AccountId, Balance};
+use chainx_primitives::{AccountId, AssetId, Balance};
 
-use crate::{Authorship, Balances, Call, Runtime, XBtcLedger};
+use crate::{Authorship, Balances, Call, Runtime, XBtcLedger, XMiningAsset, X_BTC};
 
 type NegativeImbalance = <Balances as Currency<AccountId>>::NegativeImbalance;
 
@@ -85,6 +89,106 @@ impl OnUnbalanced<BTCNegativeImbalance> for DealWithBTCFees {
     }
 }
 
+/// 1 satoshi of `X_BTC` in the btc ledger, as `X_BTC` has 8 decimals and the ledger has 18.
+pub const SATOSHI: Balance = 10_000_000_000;
+
+fn satoshi_to_ledger(value: Balance) -> Result<Balance, DispatchError> {
+    value
+        .checked_mul(SATOSHI)
+        .ok_or_else(|| ArithmeticError::Overflow.into())
+}
+
+/// The btc ledger is the single source of truth of BTC, `X_BTC` in xassets is a view over it.
+pub struct BtcAssetLedger;
+impl xpallet_assets::AssetLedger<AccountId, Balance> for BtcAssetLedger {
+    fn holds(id: &AssetId) -> bool {
+        *id == X_BTC
+    }
+
+    fn free_balance(who: &AccountId) -> Balance {
+        XBtcLedger::free_satoshi(who)
+    }
+
+    fn reserved_balance(who: &AccountId) -> Balance {
+        XBtcLedger::reserved_satoshi(who)
+    }
+
+    fn total_issuance() -> Balance {
+        XBtcLedger::total_issuance_satoshi()
+    }
+
+    #[transactional]
+    fn deposit(who: &AccountId, reserved: bool, value: Balance) -> DispatchResult {
+        let amount = satoshi_to_ledger(value)?;
+        let imbalance = XBtcLedger::deposit_creating(who, amount);
+        if imbalance.peek() != amount {
+            return Err(DispatchError::Other("btc ledger deposit failed"));
+        }
+        drop(imbalance);
+        if reserved {
+            XBtcLedger::reserve(who, amount)?;
+        }
+        Ok(())
+    }
+
+    #[transactional]
+    fn withdraw(who: &AccountId, reserved: bool, value: Balance) -> DispatchResult {
+        let amount = satoshi_to_ledger(value)?;
+        if reserved {
+            let (_imbalance, remaining) = XBtcLedger::slash_reserved(who, amount);
+            if !remaining.is_zero() {
+                return Err(DispatchError::Other("btc ledger reserved balance too low"));
+            }
+        } else {
+            let _imbalance = XBtcLedger::withdraw(
+                who,
+                amount,
+                WithdrawReasons::TRANSFER,
+                ExistenceRequirement::AllowDeath,
+            )?;
+        }
+        Ok(())
+    }
+
+    #[transactional]
+    fn transfer(
+        from: &AccountId,
+        from_reserved: bool,
+        to: &AccountId,
+        to_reserved: bool,
+        value: Balance,
+    ) -> DispatchResult {
+        let amount = satoshi_to_ledger(value)?;
+        if from_reserved && !XBtcLedger::unreserve(from, amount).is_zero() {
+            return Err(DispatchError::Other("btc ledger reserved balance too low"));
+        }
+        if from != to {
+            <XBtcLedger as Currency<AccountId>>::transfer(
+                from,
+                to,
+                amount,
+                ExistenceRequirement::AllowDeath,
+            )?;
+        }
+        if to_reserved {
+            XBtcLedger::reserve(to, amount)?;
+        }
+        Ok(())
+    }
+}
+
+/// Settles the mining weights of `X_BTC` before the balance in the btc ledger changes, as the
+/// changes made directly in the ledger, e.g., by the EVM, bypass xassets. This is the only
+/// place settling them, xassets leaves the pre-change hooks of the held assets to the ledger.
+pub struct SettleBtcMiningWeight;
+impl xpallet_btc_ledger::OnBalanceChange<AccountId> for SettleBtcMiningWeight {
+    fn on_balance_change_pre(who: &AccountId) {
+        <XMiningAsset as xpallet_assets::OnAssetChanged<AccountId, Balance>>::on_issue_pre(
+            &X_BTC, who,
+        );
+    }
+}
+
 parameter_types! {
     pub const TargetBlockFullness: Perquintill = Perquintill::from_percent(25);
     pub AdjustmentVariable: Multiplier = Multiplier::saturating_from_rational(1, 100_000);
@@ -0,0 +1,181 @@
+use core::marker::PhantomData;
+use fp_evm::{Context, ExitSucceed, PrecompileFailure, PrecompileOutput, PrecompileResult};
+use frame_support::{log, traits::Get};
+use pallet_evm::{GasWeightMapping, Precompile};
+use sp_core::{H256, U256};
+use sp_std::vec::Vec;
+
+use xpallet_assets_bridge::abi::{self, selector, ParamType, Token};
+use xpallet_gateway_bitcoin::types::BtcHeaderInfo;
+
+use crate::precompiles::{dispatch_revert, ensure_gas, revert};
+
+const HEADER_BY_HASH: [u8; 4] = selector("headerByHash(bytes32)");
+const HEADER_BY_HEIGHT: [u8; 4] = selector("headerByHeight(uint256)");
+const BEST_HEADER: [u8; 4] = selector("bestHeader()");
+const CONFIRMATIONS: [u8; 4] = selector("confirmations(bytes32)");
+const VERIFY_TX: [u8; 4] = selector("verifyTx(bytes,bytes,bytes32)");
+
+/// The gas of double sha256 hashing a word of the tx and the proof.
+const HASH_WORD_GAS_COST: u64 = 24;
+
+/// Exposes the Bitcoin light client of `xpallet_gateway_bitcoin` to the smart contracts.
+///
+/// All the hashes are in the internal byte order of Bitcoin, i.e., the reverse of the hex
+/// shown by the block explorers.
+pub struct Spv<T: xpallet_gateway_bitcoin::Config + pallet_evm::Config> {
+    _marker: PhantomData<T>,
+}
+
+impl<T> Spv<T>
+where
+    T: xpallet_gateway_bitcoin::Config + pallet_evm::Config,
+{
+    fn process(input: &[u8], target_gas: Option<u64>) -> PrecompileResult {
+        if input.len() < 4 {
+            return Err(revert("spv: invalid input", 0));
+        }
+        let (method, args) = input.split_at(4);
+        let mut method_id = [0u8; 4];
+        method_id.copy_from_slice(method);
+
+        let (cost, output) = match method_id {
+            HEADER_BY_HASH => {
+                let cost = Self::reads_to_gas(4);
+                ensure_gas(target_gas, cost)?;
+                let args = decode(&[ParamType::Bytes32], args, cost)?;
+                let hash = btc_hash(&args[0]);
+                let info = xpallet_gateway_bitcoin::Pallet::<T>::headers(&hash)
+                    .ok_or_else(|| revert("spv: unknown header", cost))?;
+                (cost, Self::encode_header(info))
+            }
+            HEADER_BY_HEIGHT => {
+                let cost = Self::reads_to_gas(6);
+                ensure_gas(target_gas, cost)?;
+                let args = decode(&[ParamType::Uint], args, cost)?;
+                let height = match args[0] {
+                    Token::Uint(height) if height <= U256::from(u32::MAX) => height.low_u32(),
+                    _ => return Err(revert("spv: invalid height", cost)),
+                };
+                let info = xpallet_gateway_bitcoin::Pallet::<T>::main_chain_header_at(height)
+                    .ok_or_else(|| revert("spv: unknown header", cost))?;
+                (cost, Self::encode_header(info))
+            }
+            BEST_HEADER => {
+                let cost = Self::reads_to_gas(1);
+                ensure_gas(target_gas, cost)?;
+                let best = xpallet_gateway_bitcoin::Pallet::<T>::best_index();
+                let output = abi::encode(&[
+                    Token::Bytes32(evm_hash(&best.hash)),
+                    Token::Uint(best.height.into()),
+                ]);
+                (cost, output)
+            }
+            CONFIRMATIONS => {
+                let cost = Self::reads_to_gas(3);
+                ensure_gas(target_gas, cost)?;
+                let args = decode(&[ParamType::Bytes32], args, cost)?;
+                let confirmations =
+                    xpallet_gateway_bitcoin::Pallet::<T>::confirmations_of(&btc_hash(&args[0]))
+                        .ok_or_else(|| revert("spv: unknown header", cost))?;
+                (cost, abi::encode(&[Token::Uint(confirmations.into())]))
+            }
+            VERIFY_TX => {
+                let words = (args.len() as u64 + 31) / 32;
+                let cost = Self::reads_to_gas(4).saturating_add(HASH_WORD_GAS_COST * words);
+                ensure_gas(target_gas, cost)?;
+                let args = decode(
+                    &[ParamType::Bytes, ParamType::Bytes, ParamType::Bytes32],
+                    args,
+                    cost,
+                )?;
+                let (raw_tx, proof) = match (&args[0], &args[1]) {
+                    (Token::Bytes(raw_tx), Token::Bytes(proof)) => (raw_tx, proof),
+                    _ => unreachable!("decoded as bytes; qed"),
+                };
+                let block_hash = btc_hash(&args[2]);
+
+                let txid = xpallet_gateway_bitcoin::Pallet::<T>::verify_tx_inclusion(
+                    raw_tx, proof, block_hash,
+                )
+                .map_err(|err| dispatch_revert(err, cost))?;
+                let confirmations =
+                    xpallet_gateway_bitcoin::Pallet::<T>::confirmations_of(&block_hash)
+                        .unwrap_or_default();
+                let output = abi::encode(&[
+                    Token::Bytes32(evm_hash(&txid)),
+                    Token::Uint(confirmations.into()),
+                ]);
+                (cost, output)
+            }
+            _ => return Err(revert("spv: unknown method", 0)),
+        };
+
+        Ok(PrecompileOutput {
+            exit_status: ExitSucceed::Returned,
+            cost,
+            output,
+            logs: Default::default(),
+        })
+    }
+
+    /// Encodes the header as `(bytes32 hash, uint256 height, uint256 version, bytes32 prevHash,
+    /// bytes32 merkleRoot, uint256 time, uint256 bits, uint256 nonce, bool isMainChain,
+    /// uint256 confirmations)`.
+    fn encode_header(info: BtcHeaderInfo) -> Vec<u8> {
+        let header = info.header;
+        let hash = header.hash();
+        let confirmations =
+            xpallet_gateway_bitcoin::Pallet::<T>::confirmations_of(&hash).unwrap_or_default();
+        abi::encode(&[
+            Token::Bytes32(evm_hash(&hash)),
+            Token::Uint(info.height.into()),
+            Token::Uint(header.version.into()),
+            Token::Bytes32(evm_hash(&header.previous_header_hash)),
+            Token::Bytes32(evm_hash(&header.merkle_root_hash)),
+            Token::Uint(header.time.into()),
+            Token::Uint(u32::from(header.bits).into()),
+            Token::Uint(header.nonce.into()),
+            Token::Uint(U256::from((confirmations > 0) as u8)),
+            Token::Uint(confirmations.into()),
+        ])
+    }
+
+    fn reads_to_gas(reads: u64) -> u64 {
+        T::GasWeightMapping::weight_to_gas(T::DbWeight::get().reads(reads))
+    }
+}
+
+impl<T> Precompile for Spv<T>
+where
+    T: xpallet_gateway_bitcoin::Config + pallet_evm::Config,
+{
+    fn execute(
+        input: &[u8],
+        target_gas: Option<u64>,
+        context: &Context,
+        _: bool,
+    ) -> PrecompileResult {
+        log::debug!(target: "evm-spv", "caller: {:?}", context.caller);
+
+        Self::process(input, target_gas).map_err(|err| {
+            log::debug!(target: "evm-spv", "err = {:?}", err);
+            err
+        })
+    }
+}
+
+fn decode(types: &[ParamType], data: &[u8], cost: u64) -> Result<Vec<Token>, PrecompileFailure> {
+    abi::decode(types, data).map_err(|_| revert("spv: invalid arguments", cost))
+}
+
+fn btc_hash(token: &Token) -> xpallet_gateway_bitcoin::H256 {
+    match token {
+        Token::Bytes32(hash) => xpallet_gateway_bitcoin::H256::from_slice(hash.as_bytes()),
+        _ => unreachable!("decoded as bytes32; qed"),
+    }
+}
+
+fn evm_hash(hash: &xpallet_gateway_bitcoin::H256) -> H256 {
+    H256::from_slice(hash.as_bytes())
+}
@@ -0,0 +1,299 @@
+use core::marker::PhantomData;
+use fp_evm::{Context, ExitSucceed, Log, PrecompileFailure, PrecompileOutput, PrecompileResult};
+use frame_support::{log, traits::Get, weights::Weight};
+use pallet_evm::AddressMapping;
+use sp_core::{H160, H256, U256};
+use sp_runtime::{traits::UniqueSaturatedInto, DispatchResult};
+use sp_std::{vec, vec::Vec};
+
+use chainx_primitives::AssetId;
+use xpallet_assets_bridge::abi::{
+    self, selector, ParamType, Token, APPROVAL_EVENT, TRANSFER_EVENT,
+};
+
+use crate::precompiles::{dispatch_revert, ensure_gas, revert};
+
+/// The first 4 bytes of the address of an asset precompile, which is followed by 12 zero bytes
+/// and the big-endian `AssetId`, e.g., 0xffffffff00000000000000000000000000000001 for X-BTC.
+const ASSET_PRECOMPILE_PREFIX: [u8; 4] = [0xff; 4];
+
+const NAME: [u8; 4] = selector("name()");
+const SYMBOL: [u8; 4] = selector("symbol()");
+const DECIMALS: [u8; 4] = selector("decimals()");
+const TOTAL_SUPPLY: [u8; 4] = selector("totalSupply()");
+const BALANCE_OF: [u8; 4] = selector("balanceOf(address)");
+const TRANSFER: [u8; 4] = selector("transfer(address,uint256)");
+const APPROVE: [u8; 4] = selector("approve(address,uint256)");
+const ALLOWANCE: [u8; 4] = selector("allowance(address,address)");
+const TRANSFER_FROM: [u8; 4] = selector("transferFrom(address,address,uint256)");
+
+/// The code at the addresses of the asset precompiles, which reverts if it is ever executed.
+/// Solidity checks the code size of the callee before a call, which fails for an address without
+/// any code.
+pub const PRECOMPILE_CODE: [u8; 5] = [0x60, 0x00, 0x60, 0x00, 0xfd];
+
+const READ_GAS_COST: u64 = 5_000;
+const WRITE_GAS_COST: u64 = 50_000;
+
+/// Returns the address of the ERC20 precompile of the asset `id`.
+pub fn asset_address(id: AssetId) -> H160 {
+    let mut address = [0u8; 20];
+    address[0..4].copy_from_slice(&ASSET_PRECOMPILE_PREFIX);
+    address[16..20].copy_from_slice(&id.to_be_bytes());
+    H160(address)
+}
+
+/// Returns the `AssetId` encoded in the address of an ERC20 precompile.
+pub fn asset_id_of(address: H160) -> Option<AssetId> {
+    let bytes = address.as_bytes();
+    if bytes[0..4] != ASSET_PRECOMPILE_PREFIX || bytes[4..16].iter().any(|b| *b != 0) {
+        return None;
+    }
+    let mut id = [0u8; 4];
+    id.copy_from_slice(&bytes[16..20]);
+    Some(AssetId::from_be_bytes(id))
+}
+
+/// Inserts the dummy code at the address of the ERC20 precompile of asset `id`, returns whether
+/// the code was absent.
+fn insert_precompile_code<T: pallet_evm::Config>(id: AssetId) -> bool {
+    let address = asset_address(id);
+    if pallet_evm::AccountCodes::<T>::contains_key(address) {
+        return false;
+    }
+    pallet_evm::AccountCodes::<T>::insert(address, PRECOMPILE_CODE.to_vec());
+    true
+}
+
+/// Inserts the dummy code of the ERC20 precompiles of the existing non-native assets.
+pub fn insert_precompile_codes<T>() -> Weight
+where
+    T: xpallet_assets_registrar::Config + pallet_evm::Config,
+{
+    let native = <T as xpallet_assets_registrar::Config>::NativeAssetId::get();
+    let ids = xpallet_assets_registrar::Pallet::<T>::asset_ids()
+        .filter(|id| *id != native)
+        .collect::<Vec<_>>();
+    let inserted = ids
+        .iter()
+        .filter(|id| insert_precompile_code::<T>(**id))
+        .count() as Weight;
+    log::info!(target: "evm-erc20", "inserted the code of {} asset precompiles", inserted);
+    <T as frame_system::Config>::DbWeight::get().reads_writes(ids.len() as Weight + 1, inserted)
+}
+
+/// Inserts the dummy code of the ERC20 precompile of each non-native asset once registered,
+/// including the assets registered in the genesis.
+pub struct Erc20PrecompileCode<T>(PhantomData<T>);
+impl<T> xpallet_assets_registrar::RegistrarHandler for Erc20PrecompileCode<T>
+where
+    T: xpallet_assets_registrar::Config + pallet_evm::Config,
+{
+    fn on_register(asset_id: &AssetId, _has_mining_rights: bool) -> DispatchResult {
+        if *asset_id != <T as xpallet_assets_registrar::Config>::NativeAssetId::get() {
+            insert_precompile_code::<T>(*asset_id);
+        }
+        Ok(())
+    }
+}
+
+/// The ERC20 interface of the valid non-native assets of `xpallet_assets`, which works directly
+/// over the usable balances and keeps the allowances in `xpallet_assets_allowance`.
+pub struct Erc20<T: xpallet_assets_allowance::Config + pallet_evm::Config> {
+    _marker: PhantomData<T>,
+}
+
+impl<T> Erc20<T>
+where
+    T: xpallet_assets_allowance::Config + pallet_evm::Config,
+{
+    /// Returns the asset whose ERC20 precompile lives at `address`, if any.
+    fn asset_of(address: H160) -> Option<AssetId> {
+        asset_id_of(address).filter(|id| {
+            *id != <T as xpallet_assets_registrar::Config>::NativeAssetId::get()
+                && xpallet_assets_registrar::Pallet::<T>::is_valid(id)
+        })
+    }
+
+    pub fn is_precompile(address: H160) -> bool {
+        Self::asset_of(address).is_some()
+    }
+
+    /// Executes the call to `address`, returns `None` if it is not an asset precompile.
+    pub fn execute(
+        address: H160,
+        input: &[u8],
+        target_gas: Option<u64>,
+        context: &Context,
+        is_static: bool,
+    ) -> Option<PrecompileResult> {
+        let id = Self::asset_of(address)?;
+        log::debug!(target: "evm-erc20", "asset: {}, caller: {:?}", id, context.caller);
+
+        Some(
+            Self::process(id, address, input, target_gas, context, is_static).map_err(|err| {
+                log::debug!(target: "evm-erc20", "asset: {}, err = {:?}", id, err);
+                err
+            }),
+        )
+    }
+
+    fn process(
+        id: AssetId,
+        address: H160,
+        input: &[u8],
+        target_gas: Option<u64>,
+        context: &Context,
+        is_static: bool,
+    ) -> PrecompileResult {
+        // The balances of the caller must not be touched on behalf of another contract.
+        if context.address != address {
+            return Err(revert("erc20: delegate call is not allowed", READ_GAS_COST));
+        }
+        if input.len() < 4 {
+            return Err(revert("erc20: invalid input", READ_GAS_COST));
+        }
+        let (method, args) = input.split_at(4);
+
+        let mut method_id = [0u8; 4];
+        method_id.copy_from_slice(method);
+        let is_write = matches!(method_id, TRANSFER | APPROVE | TRANSFER_FROM);
+        let cost = if is_write {
+            WRITE_GAS_COST
+        } else {
+            READ_GAS_COST
+        };
+        ensure_gas(target_gas, cost)?;
+        if is_write && is_static {
+            return Err(revert("erc20: state change in static call", cost));
+        }
+
+        let (output, logs) = match method_id {
+            NAME | SYMBOL | DECIMALS => {
+                let info = xpallet_assets_registrar::Pallet::<T>::asset_info_of(id)
+                    .ok_or_else(|| revert("erc20: asset does not exist", cost))?;
+                let token = match method_id {
+                    NAME => Token::String(info.token_name().clone()),
+                    SYMBOL => Token::String(info.token().clone()),
+                    _ => Token::Uint(info.decimals().into()),
+                };
+                (abi::encode(&[token]), vec![])
+            }
+            TOTAL_SUPPLY => {
+                let total = xpallet_assets::Pallet::<T>::total_issuance(&id);
+                (abi::encode(&[Token::Uint(to_u256(total))]), vec![])
+            }
+            BALANCE_OF => {
+                let args = decode(&[ParamType::Address], args, cost)?;
+                let who = account::<T>(&args[0]);
+                let balance = xpallet_assets::Pallet::<T>::usable_balance(&who, &id);
+                (abi::encode(&[Token::Uint(to_u256(balance))]), vec![])
+            }
+            ALLOWANCE => {
+                let args = decode(&[ParamType::Address, ParamType::Address], args, cost)?;
+                let allowance = xpallet_assets_allowance::Pallet::<T>::allowance(
+                    id,
+                    &account::<T>(&args[0]),
+                    &account::<T>(&args[1]),
+                );
+                (abi::encode(&[Token::Uint(to_u256(allowance))]), vec![])
+            }
+            TRANSFER => {
+                let args = decode(&[ParamType::Address, ParamType::Uint], args, cost)?;
+                let value = balance(&args[1], cost)?;
+                xpallet_assets_allowance::Pallet::<T>::transfer(
+                    id,
+                    &T::AddressMapping::into_account_id(context.caller),
+                    &account::<T>(&args[0]),
+                    value.unique_saturated_into(),
+                )
+                .map_err(|err| dispatch_revert(err, cost))?;
+                let log = event(address, TRANSFER_EVENT, context.caller, &args[0], &args[1]);
+                (success(), vec![log])
+            }
+            APPROVE => {
+                let args = decode(&[ParamType::Address, ParamType::Uint], args, cost)?;
+                let value = balance(&args[1], cost)?;
+                xpallet_assets_allowance::Pallet::<T>::approve(
+                    id,
+                    &T::AddressMapping::into_account_id(context.caller),
+                    &account::<T>(&args[0]),
+                    value.unique_saturated_into(),
+                )
+                .map_err(|err| dispatch_revert(err, cost))?;
+                let log = event(address, APPROVAL_EVENT, context.caller, &args[0], &args[1]);
+                (success(), vec![log])
+            }
+            TRANSFER_FROM => {
+                let args = decode(
+                    &[ParamType::Address, ParamType::Address, ParamType::Uint],
+                    args,
+                    cost,
+                )?;
+                let value = balance(&args[2], cost)?;
+                let from = match args[0] {
+                    Token::Address(from) => from,
+                    _ => unreachable!("decoded as an address; qed"),
+                };
+                xpallet_assets_allowance::Pallet::<T>::transfer_from(
+                    id,
+                    &T::AddressMapping::into_account_id(context.caller),
+                    &T::AddressMapping::into_account_id(from),
+                    &account::<T>(&args[1]),
+                    value.unique_saturated_into(),
+                )
+                .map_err(|err| dispatch_revert(err, cost))?;
+                let log = event(address, TRANSFER_EVENT, from, &args[1], &args[2]);
+                (success(), vec![log])
+            }
+            _ => return Err(revert("erc20: unknown method", cost)),
+        };
+
+        Ok(PrecompileOutput {
+            exit_status: ExitSucceed::Returned,
+            cost,
+            output,
+            logs,
+        })
+    }
+}
+
+fn decode(types: &[ParamType], data: &[u8], cost: u64) -> Result<Vec<Token>, PrecompileFailure> {
+    abi::decode(types, data).map_err(|_| revert("erc20: invalid arguments", cost))
+}
+
+fn account<T: pallet_evm::Config>(token: &Token) -> T::AccountId {
+    match token {
+        Token::Address(address) => T::AddressMapping::into_account_id(*address),
+        _ => unreachable!("decoded as an address; qed"),
+    }
+}
+
+fn balance(token: &Token, cost: u64) -> Result<u128, PrecompileFailure> {
+    match token {
+        Token::Uint(value) if *value <= U256::from(u128::MAX) => Ok(value.low_u128()),
+        _ => Err(revert("erc20: value overflow", cost)),
+    }
+}
+
+fn to_u256<B: UniqueSaturatedInto<u128>>(balance: B) -> U256 {
+    U256::from(UniqueSaturatedInto::<u128>::unique_saturated_into(balance))
+}
+
+/// The ABI-encoded `true`.
+fn success() -> Vec<u8> {
+    abi::encode(&[Token::Uint(U256::one())])
+}
+
+/// Builds the log of a `Transfer` or `Approval` event.
+fn event(address: H160, topic: H256, from: H160, to: &Token, value: &Token) -> Log {
+    let to = match to {
+        Token::Address(to) => *to,
+        _ => unreachable!("decoded as an address; qed"),
+    };
+    Log {
+        address,
+        topics: vec![topic, H256::from(from), H256::from(to)],
+        data: abi::encode(&[value.clone()]),
+    }
+}
@@ -1,4 +1,4 @@
-use fp_evm::Context;
+use fp_evm::{Context, ExitError, ExitRevert, PrecompileFailure};
 use pallet_evm::{Precompile, PrecompileResult, PrecompileSet};
 use pallet_evm_precompile_blake2::Blake2F;
 use pallet_evm_precompile_bn128::{Bn128Add, Bn128Mul, Bn128Pairing};
@@ -7,7 +7,9 @@ use pallet_evm_precompile_modexp::Modexp;
 use pallet_evm_precompile_sha3fips::Sha3FIPS256;
 use pallet_evm_precompile_simple::{ECRecover, ECRecoverPublicKey, Identity, Ripemd160, Sha256};
 use sp_core::H160;
+use sp_runtime::DispatchError;
 use sp_std::marker::PhantomData;
+use xpallet_assets_bridge::abi::{self, Token, ERROR_STRING};
 
 /// We include the nine Istanbul precompiles
 /// (https://github.com/ethereum/go-ethereum/blob/3c46f557/core/vm/contracts.go#L69)
@@ -25,7 +27,7 @@ where
     /// Return all addresses that contain precompiles. This can be used to populate dummy code
     /// under the precompile.
     pub fn used_addresses() -> sp_std::vec::Vec<H160> {
-        sp_std::vec![1, 2, 3, 4, 5, 6, 7, 8, 9, 1024, 1025, 1026, 1027]
+        sp_std::vec![1, 2, 3, 4, 5, 6, 7, 8, 9, 1024, 1025, 1026, 1027, 2048, 2049, 2050]
             .into_iter()
             .map(hash)
             .collect()
@@ -36,11 +38,15 @@ where
 /// 0-1023: Ethereum Mainnet Precompiles
 /// 1024-2047 Precompiles that are not in Ethereum Mainnet but are neither ChainX specific
 /// 2048-4095 ChainX specific precompiles
+/// 0xffffffff00000000000000000000000000000000 + AssetId: ERC20 interfaces of the native assets
 impl<R> PrecompileSet for ChainXPrecompiles<R>
 where
     R: xpallet_assets_bridge::Config
+        + xpallet_assets_allowance::Config
+        + xpallet_mining_staking::Config
         + xpallet_gateway_common::Config
-        + xpallet_gateway_records::Config,
+        + xpallet_gateway_records::Config
+        + xpallet_gateway_bitcoin::Config,
     Dispatch<R>: Precompile,
 {
     fn execute(
@@ -75,14 +81,50 @@ where
             a if a == hash(1027) => Some(crate::withdraw::Withdraw::<R>::execute(
                 input, target_gas, context, is_static,
             )),
-            _ => None,
+            // ChainX specific precompiles :
+            a if a == hash(2048) => Some(crate::staking::Staking::<R>::execute(
+                input, target_gas, context, is_static,
+            )),
+            a if a == hash(2049) => Some(crate::spv::Spv::<R>::execute(
+                input, target_gas, context, is_static,
+            )),
+            a if a == hash(2050) => Some(crate::taproot::Taproot::<R>::execute(
+                input, target_gas, context, is_static,
+            )),
+            // The ERC20 interfaces of the native assets :
+            a => crate::erc20::Erc20::<R>::execute(a, input, target_gas, context, is_static),
         }
     }
     fn is_precompile(&self, address: H160) -> bool {
         Self::used_addresses().contains(&address)
+            || crate::erc20::Erc20::<R>::is_precompile(address)
     }
 }
 
 fn hash(a: u64) -> H160 {
     H160::from_low_u64_be(a)
 }
+
+/// Reverts with the reason encoded as `Error(string)`, like Solidity does.
+pub(crate) fn revert(reason: &str, cost: u64) -> PrecompileFailure {
+    PrecompileFailure::Revert {
+        exit_status: ExitRevert::Reverted,
+        output: abi::encode_call(ERROR_STRING, &[Token::String(reason.as_bytes().to_vec())]),
+        cost,
+    }
+}
+
+pub(crate) fn dispatch_revert(err: DispatchError, cost: u64) -> PrecompileFailure {
+    let reason: &'static str = err.into();
+    revert(reason, cost)
+}
+
+/// Fails with `OutOfGas` if the `cost` exceeds the `target_gas`.
+pub(crate) fn ensure_gas(target_gas: Option<u64>, cost: u64) -> Result<(), PrecompileFailure> {
+    match target_gas {
+        Some(gas) if gas < cost => Err(PrecompileFailure::Error {
+            exit_status: ExitError::OutOfGas,
+        }),
+        _ => Ok(()),
+    }
+}
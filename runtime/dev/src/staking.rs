@@ -0,0 +1,345 @@
+use codec::{Decode, Encode};
+use core::marker::PhantomData;
+use fp_evm::{Context, ExitSucceed, Log, PrecompileFailure, PrecompileOutput, PrecompileResult};
+use frame_support::{log, traits::Get};
+use frame_system::RawOrigin;
+use pallet_evm::{AddressMapping, GasWeightMapping, Precompile};
+use sp_core::{H160, H256, U256};
+use sp_runtime::traits::{StaticLookup, UniqueSaturatedInto};
+use sp_std::{vec, vec::Vec};
+
+use xpallet_assets_bridge::abi::{self, keccak256, selector, ParamType, Token};
+use xpallet_mining_staking::{Nominations, WeightInfo};
+
+use crate::precompiles::{dispatch_revert, ensure_gas, revert};
+
+type StakingWeight<T> = <T as xpallet_mining_staking::Config>::WeightInfo;
+
+/// The address of the staking precompile, i.e., 0x0000000000000000000000000000000000000800.
+pub const STAKING_PRECOMPILE: u64 = 2048;
+
+const BOND: [u8; 4] = selector("bond(bytes32,uint256)");
+const UNBOND: [u8; 4] = selector("unbond(bytes32,uint256)");
+const REBOND: [u8; 4] = selector("rebond(bytes32,bytes32,uint256)");
+const CLAIM: [u8; 4] = selector("claim(bytes32)");
+const UNLOCK_UNBONDED_WITHDRAWAL: [u8; 4] = selector("unlockUnbondedWithdrawal(bytes32,uint256)");
+const VALIDATORS: [u8; 4] = selector("validators()");
+const NOMINATIONS_OF: [u8; 4] = selector("nominationsOf(address)");
+const UNBONDED_CHUNKS_OF: [u8; 4] = selector("unbondedChunksOf(address,bytes32)");
+const PENDING_DIVIDENDS_OF: [u8; 4] = selector("pendingDividendsOf(address)");
+
+/// `Bonded(address indexed nominator, bytes32 indexed validator, uint256 value)`
+const BONDED_EVENT: H256 = H256(keccak256(b"Bonded(address,bytes32,uint256)"));
+/// `Unbonded(address indexed nominator, bytes32 indexed validator, uint256 value)`
+const UNBONDED_EVENT: H256 = H256(keccak256(b"Unbonded(address,bytes32,uint256)"));
+/// `Rebonded(address indexed nominator, bytes32 indexed from, bytes32 indexed to, uint256 value)`
+const REBONDED_EVENT: H256 = H256(keccak256(b"Rebonded(address,bytes32,bytes32,uint256)"));
+/// `Claimed(address indexed nominator, bytes32 indexed validator, uint256 dividend)`
+const CLAIMED_EVENT: H256 = H256(keccak256(b"Claimed(address,bytes32,uint256)"));
+/// `Withdrawn(address indexed nominator, bytes32 indexed validator, uint256 value)`
+const WITHDRAWN_EVENT: H256 = H256(keccak256(b"Withdrawn(address,bytes32,uint256)"));
+
+/// The interface of `xpallet_mining_staking` for the EVM accounts, which operates on the account
+/// mapped from the caller. The validators are identified by their 32 bytes public keys.
+pub struct Staking<T: xpallet_mining_staking::Config + pallet_evm::Config> {
+    _marker: PhantomData<T>,
+}
+
+impl<T> Staking<T>
+where
+    T: xpallet_mining_staking::Config + pallet_evm::Config,
+{
+    fn process(input: &[u8], target_gas: Option<u64>, context: &Context) -> PrecompileResult {
+        if input.len() < 4 {
+            return Err(revert("staking: invalid input", 0));
+        }
+        let (method, args) = input.split_at(4);
+        let mut method_id = [0u8; 4];
+        method_id.copy_from_slice(method);
+
+        let caller = context.caller;
+        let who = T::AddressMapping::into_account_id(caller);
+
+        let (cost, output, logs) = match method_id {
+            BOND | UNBOND => {
+                let cost = if method_id == BOND {
+                    Self::weight_to_gas(StakingWeight::<T>::bond())
+                } else {
+                    Self::weight_to_gas(StakingWeight::<T>::unbond())
+                };
+                ensure_gas(target_gas, cost)?;
+                let args = decode(&[ParamType::Bytes32, ParamType::Uint], args, cost)?;
+                let target = Self::account(&args[0], cost)?;
+                let value = balance(&args[1], cost)?;
+
+                let origin = RawOrigin::Signed(who).into();
+                let target = T::Lookup::unlookup(target);
+                let (result, topic) = if method_id == BOND {
+                    let result = xpallet_mining_staking::Pallet::<T>::bond(
+                        origin,
+                        target,
+                        value.unique_saturated_into(),
+                    );
+                    (result, BONDED_EVENT)
+                } else {
+                    let result = xpallet_mining_staking::Pallet::<T>::unbond(
+                        origin,
+                        target,
+                        value.unique_saturated_into(),
+                    );
+                    (result, UNBONDED_EVENT)
+                };
+                result.map_err(|err| dispatch_revert(err, cost))?;
+
+                let log = event(topic, caller, &args[..1], &args[1]);
+                (cost, success(), vec![log])
+            }
+            REBOND => {
+                let cost = Self::weight_to_gas(StakingWeight::<T>::rebond());
+                ensure_gas(target_gas, cost)?;
+                let args = decode(
+                    &[ParamType::Bytes32, ParamType::Bytes32, ParamType::Uint],
+                    args,
+                    cost,
+                )?;
+                let from = Self::account(&args[0], cost)?;
+                let to = Self::account(&args[1], cost)?;
+                let value = balance(&args[2], cost)?;
+
+                xpallet_mining_staking::Pallet::<T>::rebond(
+                    RawOrigin::Signed(who).into(),
+                    T::Lookup::unlookup(from),
+                    T::Lookup::unlookup(to),
+                    value.unique_saturated_into(),
+                )
+                .map_err(|err| dispatch_revert(err, cost))?;
+
+                let log = event(REBONDED_EVENT, caller, &args[..2], &args[2]);
+                (cost, success(), vec![log])
+            }
+            CLAIM => {
+                let cost = Self::weight_to_gas(StakingWeight::<T>::claim());
+                ensure_gas(target_gas, cost)?;
+                let args = decode(&[ParamType::Bytes32], args, cost)?;
+                let target = Self::account(&args[0], cost)?;
+
+                let before = Self::free_balance(&who);
+                xpallet_mining_staking::Pallet::<T>::claim(
+                    RawOrigin::Signed(who.clone()).into(),
+                    T::Lookup::unlookup(target),
+                )
+                .map_err(|err| dispatch_revert(err, cost))?;
+                let dividend = Self::free_balance(&who).saturating_sub(before);
+
+                let log = event(CLAIMED_EVENT, caller, &args, &Token::Uint(dividend));
+                (cost, success(), vec![log])
+            }
+            UNLOCK_UNBONDED_WITHDRAWAL => {
+                let cost = Self::weight_to_gas(StakingWeight::<T>::unlock_unbonded_withdrawal());
+                ensure_gas(target_gas, cost)?;
+                let args = decode(&[ParamType::Bytes32, ParamType::Uint], args, cost)?;
+                let target = Self::account(&args[0], cost)?;
+                let index = match &args[1] {
+                    Token::Uint(index) if *index <= U256::from(u32::MAX) => index.low_u32(),
+                    _ => return Err(revert("staking: invalid unbonded index", cost)),
+                };
+
+                let value = Nominations::<T>::get(&who, &target)
+                    .unbonded_chunks
+                    .get(index as usize)
+                    .map(|chunk| to_u256(chunk.value))
+                    .unwrap_or_default();
+                xpallet_mining_staking::Pallet::<T>::unlock_unbonded_withdrawal(
+                    RawOrigin::Signed(who).into(),
+                    T::Lookup::unlookup(target),
+                    index,
+                )
+                .map_err(|err| dispatch_revert(err, cost))?;
+
+                let log = event(WITHDRAWN_EVENT, caller, &args[..1], &Token::Uint(value));
+                (cost, success(), vec![log])
+            }
+            VALIDATORS => {
+                let bound = Self::reads_to_gas(Self::max_validators() + 2);
+                ensure_gas(target_gas, bound)?;
+                let validators = xpallet_mining_staking::Pallet::<T>::validator_set()
+                    .map(|validator| Self::account_token(&validator))
+                    .collect::<Vec<_>>();
+                let cost = bound.max(Self::reads_to_gas(validators.len() as u64 + 2));
+                ensure_gas(target_gas, cost)?;
+                (cost, abi::encode(&[Token::Array(validators)]), vec![])
+            }
+            NOMINATIONS_OF | PENDING_DIVIDENDS_OF => {
+                let base_cost = Self::reads_to_gas(1);
+                let args = decode(&[ParamType::Address], args, base_cost)?;
+                let nominator = match args[0] {
+                    Token::Address(nominator) => T::AddressMapping::into_account_id(nominator),
+                    _ => unreachable!("decoded as an address; qed"),
+                };
+
+                // Computing the dividend reads the ledgers of the validator and the nominator.
+                let reads_per_item = if method_id == NOMINATIONS_OF { 1 } else { 3 };
+                // A nominator can nominate each validator at most once.
+                let bound = Self::reads_to_gas(Self::max_validators() * reads_per_item + 2);
+                ensure_gas(target_gas, bound)?;
+
+                let values = if method_id == NOMINATIONS_OF {
+                    xpallet_mining_staking::Pallet::<T>::nomination_details_of(nominator)
+                        .into_iter()
+                        .map(|(validator, ledger)| (validator, ledger.nomination))
+                        .collect::<Vec<_>>()
+                } else {
+                    xpallet_mining_staking::Pallet::<T>::staking_dividend_of(nominator)
+                        .into_iter()
+                        .collect::<Vec<_>>()
+                };
+                let cost = bound.max(Self::reads_to_gas(values.len() as u64 * reads_per_item + 2));
+                ensure_gas(target_gas, cost)?;
+
+                let (validators, values): (Vec<_>, Vec<_>) = values
+                    .into_iter()
+                    .map(|(validator, value)| {
+                        (Self::account_token(&validator), Token::Uint(to_u256(value)))
+                    })
+                    .unzip();
+                let output = abi::encode(&[Token::Array(validators), Token::Array(values)]);
+                (cost, output, vec![])
+            }
+            UNBONDED_CHUNKS_OF => {
+                let cost = Self::reads_to_gas(1);
+                ensure_gas(target_gas, cost)?;
+                let args = decode(&[ParamType::Address, ParamType::Bytes32], args, cost)?;
+                let nominator = match args[0] {
+                    Token::Address(nominator) => T::AddressMapping::into_account_id(nominator),
+                    _ => unreachable!("decoded as an address; qed"),
+                };
+                let target = Self::account(&args[1], cost)?;
+
+                let (values, locked_until): (Vec<_>, Vec<_>) =
+                    Nominations::<T>::get(&nominator, &target)
+                        .unbonded_chunks
+                        .into_iter()
+                        .map(|chunk| {
+                            let locked_until: u32 = chunk.locked_until.unique_saturated_into();
+                            (
+                                Token::Uint(to_u256(chunk.value)),
+                                Token::Uint(locked_until.into()),
+                            )
+                        })
+                        .unzip();
+                let output = abi::encode(&[Token::Array(values), Token::Array(locked_until)]);
+                (cost, output, vec![])
+            }
+            _ => return Err(revert("staking: unknown method", 0)),
+        };
+
+        Ok(PrecompileOutput {
+            exit_status: ExitSucceed::Returned,
+            cost,
+            output,
+            logs,
+        })
+    }
+
+    fn weight_to_gas(weight: frame_support::weights::Weight) -> u64 {
+        T::GasWeightMapping::weight_to_gas(weight)
+    }
+
+    fn reads_to_gas(reads: u64) -> u64 {
+        Self::weight_to_gas(T::DbWeight::get().reads(reads))
+    }
+
+    /// The upper bound of the validators, which is charged before iterating the validators or
+    /// the nominations, as their actual count is only known once read.
+    fn max_validators() -> u64 {
+        xpallet_mining_staking::MaximumValidatorCount::<T>::get().into()
+    }
+
+    fn free_balance(who: &T::AccountId) -> U256 {
+        use frame_support::traits::Currency;
+        to_u256(<T as xpallet_mining_staking::Config>::Currency::free_balance(who))
+    }
+
+    fn account(token: &Token, cost: u64) -> Result<T::AccountId, PrecompileFailure> {
+        match token {
+            Token::Bytes32(pubkey) => T::AccountId::decode(&mut pubkey.as_bytes())
+                .map_err(|_| revert("staking: invalid validator", cost)),
+            _ => unreachable!("decoded as bytes32; qed"),
+        }
+    }
+
+    fn account_token(who: &T::AccountId) -> Token {
+        let mut pubkey = [0u8; 32];
+        let encoded = who.encode();
+        let len = encoded.len().min(32);
+        pubkey[..len].copy_from_slice(&encoded[..len]);
+        Token::Bytes32(H256(pubkey))
+    }
+}
+
+impl<T> Precompile for Staking<T>
+where
+    T: xpallet_mining_staking::Config + pallet_evm::Config,
+{
+    fn execute(
+        input: &[u8],
+        target_gas: Option<u64>,
+        context: &Context,
+        is_static: bool,
+    ) -> PrecompileResult {
+        log::debug!(target: "evm-staking", "caller: {:?}", context.caller);
+
+        // The stakes of the caller must not be touched on behalf of another contract.
+        if context.address != H160::from_low_u64_be(STAKING_PRECOMPILE) {
+            return Err(revert("staking: delegate call is not allowed", 0));
+        }
+        let is_view = input.len() >= 4
+            && matches!(
+                [input[0], input[1], input[2], input[3]],
+                VALIDATORS | NOMINATIONS_OF | UNBONDED_CHUNKS_OF | PENDING_DIVIDENDS_OF
+            );
+        if is_static && !is_view {
+            return Err(revert("staking: state change in static call", 0));
+        }
+
+        Self::process(input, target_gas, context).map_err(|err| {
+            log::debug!(target: "evm-staking", "err = {:?}", err);
+            err
+        })
+    }
+}
+
+fn decode(types: &[ParamType], data: &[u8], cost: u64) -> Result<Vec<Token>, PrecompileFailure> {
+    abi::decode(types, data).map_err(|_| revert("staking: invalid arguments", cost))
+}
+
+fn balance(token: &Token, cost: u64) -> Result<u128, PrecompileFailure> {
+    match token {
+        Token::Uint(value) if *value <= U256::from(u128::MAX) => Ok(value.low_u128()),
+        _ => Err(revert("staking: value overflow", cost)),
+    }
+}
+
+fn to_u256<B: UniqueSaturatedInto<u128>>(balance: B) -> U256 {
+    U256::from(UniqueSaturatedInto::<u128>::unique_saturated_into(balance))
+}
+
+/// The ABI-encoded `true`.
+fn success() -> Vec<u8> {
+    abi::encode(&[Token::Uint(U256::one())])
+}
+
+/// Builds the log of an event of `nominator`, with the `validators` indexed and the `value` as
+/// the data.
+fn event(topic: H256, nominator: H160, validators: &[Token], value: &Token) -> Log {
+    let mut topics = vec![topic, H256::from(nominator)];
+    topics.extend(validators.iter().map(|validator| match validator {
+        Token::Bytes32(validator) => *validator,
+        _ => unreachable!("decoded as bytes32; qed"),
+    }));
+    Log {
+        address: H160::from_low_u64_be(STAKING_PRECOMPILE),
+        topics,
+        data: abi::encode(&[value.clone()]),
+    }
+}
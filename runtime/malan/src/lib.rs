@@ -30,7 +30,10 @@ use sp_runtime::{
     },
     ApplyExtrinsicResult, DispatchError, Perbill, Percent, Permill, RuntimeDebug,
 };
-use sp_std::{collections::btree_map::BTreeMap, prelude::*};
+use sp_std::{
+    collections::{btree_map::BTreeMap, btree_set::BTreeSet},
+    prelude::*,
+};
 #[cfg(feature = "std")]
 use sp_version::NativeVersion;
 use sp_version::RuntimeVersion;
@@ -81,16 +84,21 @@ pub use xpallet_assets::{
 #[cfg(feature = "std")]
 pub use xpallet_gateway_bitcoin::h256_rev;
 pub use xpallet_gateway_bitcoin::{
-    hash_rev, types::BtcHeaderInfo, BtcHeader, BtcNetwork, BtcParams, BtcTxVerifier,
-    BtcWithdrawalProposal, Compact, H256,
+    hash_rev,
+    types::{BtcHeaderInfo, BtcReserveReport},
+    BtcHeader, BtcNetwork, BtcParams, BtcTxVerifier, BtcWithdrawalProposal, Compact, H256,
 };
 pub use xpallet_gateway_common::{
     trustees,
     types::{
         GenericTrusteeIntentionProps, GenericTrusteeSessionInfo, ScriptInfo, TrusteeInfoConfig,
+        TrusteeLiveness, WithdrawalAllowlist,
     },
 };
-pub use xpallet_gateway_records::{Withdrawal, WithdrawalRecordId};
+pub use xpallet_gateway_records::{
+    ArchivedWithdrawal, Withdrawal, WithdrawalFilter, WithdrawalPage, WithdrawalRecordId,
+    WithdrawalStateTransition,
+};
 pub use xpallet_mining_asset::MiningWeight;
 pub use xpallet_mining_staking::VoteWeight;
 
@@ -101,7 +109,10 @@ pub mod impls;
 mod migrations;
 
 use self::constants::{currency::*, time::*};
-use self::impls::{ChargeExtraFee, DealWithBTCFees, DealWithFees, SlowAdjustingFeeUpdate};
+use self::impls::{
+    BtcAssetLedger, ChargeExtraFee, DealWithBTCFees, DealWithFees, SettleBtcMiningWeight,
+    SlowAdjustingFeeUpdate, SATOSHI,
+};
 
 // EVM
 use chainx_runtime_common::NORMAL_DISPATCH_RATIO;
@@ -115,7 +126,15 @@ use pallet_evm::{
 };
 use sp_core::{H160, U256};
 use sp_runtime::traits::{Dispatchable, PostDispatchInfoOf};
+/// The ERC20 precompiles of the native assets.
+pub mod erc20;
 mod precompiles;
+/// The Bitcoin SPV precompile for the smart contracts.
+pub mod spv;
+/// The staking precompile for the EVM accounts.
+pub mod staking;
+/// The BIP340 schnorr and taproot utilities precompile for the smart contracts.
+pub mod taproot;
 mod withdraw;
 
 pub use precompiles::ChainXPrecompiles;
@@ -125,10 +144,10 @@ pub const VERSION: RuntimeVersion = RuntimeVersion {
     spec_name: create_runtime_str!("chainx"),
     impl_name: create_runtime_str!("chainx-malan"),
     authoring_version: 1,
-    spec_version: 32,
+    spec_version: 33,
     impl_version: 1,
     apis: RUNTIME_API_VERSIONS,
-    transaction_version: 7,
+    transaction_version: 8,
     state_version: 0,
 };
 
@@ -1000,7 +1019,7 @@ parameter_types! {
 impl xpallet_assets_registrar::Config for Runtime {
     type Event = Event;
     type NativeAssetId = ChainXAssetId;
-    type RegistrarHandler = XMiningAsset;
+    type RegistrarHandler = (XMiningAsset, erc20::Erc20PrecompileCode<Runtime>);
     type WeightInfo = xpallet_assets_registrar::weights::SubstrateWeight<Runtime>;
 }
 
@@ -1010,11 +1029,14 @@ impl xpallet_assets::Config for Runtime {
     type TreasuryAccount = SimpleTreasuryAccount;
     type OnCreatedAccount = frame_system::Provider<Runtime>;
     type OnAssetChanged = XMiningAsset;
+    type AssetLedger = BtcAssetLedger;
     type WeightInfo = xpallet_assets::weights::SubstrateWeight<Runtime>;
 }
 
 impl xpallet_gateway_records::Config for Runtime {
     type Event = Event;
+    type CouncilOrigin =
+        pallet_collective::EnsureProportionAtLeast<AccountId, CouncilCollective, 2, 3>;
     type WeightInfo = xpallet_gateway_records::weights::SubstrateWeight<Runtime>;
 }
 
@@ -1025,6 +1047,10 @@ impl MultisigAddressFor<AccountId> for MultisigProvider {
     }
 }
 
+parameter_types! {
+    pub const TrusteeInsurancePalletId: PalletId = PalletId(*b"pcx/trin");
+}
+
 impl xpallet_gateway_common::Config for Runtime {
     type Event = Event;
     type Validator = XStaking;
@@ -1036,6 +1062,7 @@ impl xpallet_gateway_common::Config for Runtime {
     type BitcoinTrusteeSessionProvider = trustees::bitcoin::BtcTrusteeSessionManager<Runtime>;
     type BitcoinTotalSupply = XGatewayBitcoin;
     type BitcoinWithdrawalProposal = XGatewayBitcoin;
+    type InsurancePalletId = TrusteeInsurancePalletId;
     type WeightInfo = xpallet_gateway_common::weights::SubstrateWeight<Runtime>;
 }
 
@@ -1044,6 +1071,7 @@ impl xpallet_gateway_bitcoin::Config for Runtime {
     type UnixTime = Timestamp;
     type CouncilOrigin =
         pallet_collective::EnsureProportionAtLeast<AccountId, CouncilCollective, 2, 3>;
+    type Family = xpallet_gateway_bitcoin::Bitcoin;
     type AccountExtractor = xp_gateway_bitcoin::OpReturnExtractor;
     type TrusteeSessionProvider = trustees::bitcoin::BtcTrusteeSessionManager<Runtime>;
     type TrusteeInfoUpdate = XGatewayCommon;
@@ -1115,12 +1143,23 @@ impl pallet_sudo::Config for Runtime {
 
 impl xpallet_ethereum_chain_id::Config for Runtime {}
 
+parameter_types! {
+    /// 1 satoshi, as BTC in the ledger has 18 decimals.
+    pub const BtcExistentialDeposit: Balance = 10_000_000_000;
+    pub const BtcSatoshi: Balance = SATOSHI;
+}
+
 impl xpallet_btc_ledger::Config for Runtime {
     type Balance = Balance;
     type Event = Event;
     type CouncilOrigin =
         pallet_collective::EnsureProportionAtLeast<AccountId, CouncilCollective, 2, 3>;
     type PalletId = TreasuryPalletId;
+    type MaxLocks = MaxLocks;
+    type ExistentialDeposit = BtcExistentialDeposit;
+    type Satoshi = BtcSatoshi;
+    type DustRemoval = DealWithBTCFees;
+    type OnBalanceChange = SettleBtcMiningWeight;
 }
 
 /// Current approximation of the gas/s consumption considering
@@ -1212,6 +1251,10 @@ impl xpallet_assets_bridge::Config for Runtime {
     type ClaimBond = ClaimBond;
 }
 
+impl xpallet_assets_allowance::Config for Runtime {
+    type Event = Event;
+}
+
 construct_runtime!(
     pub enum Runtime where
         Block = Block,
@@ -1293,6 +1336,9 @@ construct_runtime!(
         XAssetsBridge: xpallet_assets_bridge::{Pallet, Call, Storage, Config<T>, Event<T>} = 45,
 
         XBtcLedger: xpallet_btc_ledger::{Pallet, Call, Storage, Config<T>, Event<T>} = 46,
+
+        // Dependency on xpallet_assets, backs the ERC20 precompiles of the native assets.
+        XAssetsAllowance: xpallet_assets_allowance::{Pallet, Storage, Event<T>} = 47,
     }
 );
 
@@ -1334,8 +1380,26 @@ pub type Executive = frame_executive::Executive<
     frame_system::ChainContext<Runtime>,
     Runtime,
     AllPalletsWithSystem,
+    (BtcLedgerMigration, Erc20PrecompileCodeMigration),
 >;
 
+/// Upgrades the btc ledger before the pallet hooks, as `XAssets` moves the `X_BTC` balances
+/// into the upgraded ledger in its own hook.
+pub struct BtcLedgerMigration;
+impl OnRuntimeUpgrade for BtcLedgerMigration {
+    fn on_runtime_upgrade() -> Weight {
+        <XBtcLedger as OnRuntimeUpgrade>::on_runtime_upgrade()
+    }
+}
+
+/// Inserts the dummy code at the addresses of the ERC20 precompiles of the existing assets.
+pub struct Erc20PrecompileCodeMigration;
+impl OnRuntimeUpgrade for Erc20PrecompileCodeMigration {
+    fn on_runtime_upgrade() -> Weight {
+        erc20::insert_precompile_codes::<Runtime>()
+    }
+}
+
 pub struct TransactionConverter;
 impl fp_rpc::ConvertTransaction<UncheckedExtrinsic> for TransactionConverter {
     fn convert_transaction(&self, transaction: pallet_ethereum::Transaction) -> UncheckedExtrinsic {
@@ -1667,6 +1731,31 @@ impl_runtime_apis! {
         fn withdrawal_list_by_chain(chain: Chain) -> BTreeMap<u32, Withdrawal<AccountId, Balance, BlockNumber>> {
             XGatewayRecords::withdrawals_list_by_chain(chain)
         }
+
+        fn withdrawal_page(
+            filter: WithdrawalFilter<AccountId, BlockNumber>,
+            start_after: Option<WithdrawalRecordId>,
+            limit: u32,
+        ) -> WithdrawalPage<AccountId, Balance, BlockNumber> {
+            XGatewayRecords::withdrawal_page(filter, start_after, limit)
+        }
+
+        fn withdrawal_with_history(
+            id: WithdrawalRecordId,
+        ) -> Option<(Withdrawal<AccountId, Balance, BlockNumber>, Vec<WithdrawalStateTransition<BlockNumber>>)> {
+            XGatewayRecords::withdrawal_with_history(id)
+        }
+
+        fn archived_withdrawal(id: WithdrawalRecordId) -> Option<ArchivedWithdrawal<AccountId, Balance, BlockNumber>> {
+            XGatewayRecords::archived_withdrawal(id)
+        }
+
+        fn archived_withdrawals(
+            start_after: Option<WithdrawalRecordId>,
+            limit: u32,
+        ) -> Vec<(WithdrawalRecordId, ArchivedWithdrawal<AccountId, Balance, BlockNumber>)> {
+            XGatewayRecords::archived_withdrawals(start_after, limit)
+        }
     }
 
     impl xpallet_gateway_bitcoin_rpc_runtime_api::XGatewayBitcoinApi<Block, AccountId> for Runtime {
@@ -1689,6 +1778,10 @@ impl_runtime_apis! {
         fn get_btc_block_header(txid: H256) -> Option<BtcHeaderInfo> {
             XGatewayBitcoin::get_btc_block_header(txid)
         }
+
+        fn get_reserve_report() -> BtcReserveReport {
+            XGatewayBitcoin::get_reserve_report()
+        }
     }
 
     impl xpallet_btc_ledger_runtime_api::BtcLedgerApi<Block, AccountId, Balance> for Runtime {
@@ -1698,6 +1791,32 @@ impl_runtime_apis! {
         fn get_total() -> Balance {
             XBtcLedger::get_total()
         }
+        fn balance_changes() -> Vec<xpallet_btc_ledger::BalanceChange<AccountId, Balance>> {
+            let mut ledger_events = Vec::new();
+            let mut evm_extrinsics = BTreeSet::new();
+            let mut fee_paid = Vec::new();
+            for (index, record) in System::events().into_iter().enumerate() {
+                let index = index as u32;
+                let extrinsic_index = match record.phase {
+                    frame_system::Phase::ApplyExtrinsic(i) => Some(i),
+                    _ => None,
+                };
+                match record.event {
+                    Event::XBtcLedger(event) => ledger_events.push((index, extrinsic_index, event)),
+                    Event::Ethereum(pallet_ethereum::Event::Executed { .. }) => {
+                        evm_extrinsics.extend(extrinsic_index);
+                    }
+                    Event::XTransactionFee(xpallet_transaction_fee::Event::BTCFeePaid(
+                        beneficiary,
+                        amount,
+                    )) => {
+                        fee_paid.push((index, extrinsic_index, beneficiary, amount));
+                    }
+                    _ => {}
+                }
+            }
+            XBtcLedger::balance_changes(ledger_events, &evm_extrinsics, &fee_paid)
+        }
     }
 
     impl xpallet_gateway_common_rpc_runtime_api::XGatewayCommonApi<Block, AccountId, Balance, BlockNumber> for Runtime {
@@ -1728,6 +1847,14 @@ impl_runtime_apis! {
             XGatewayCommon::verify_withdrawal(asset_id, value, &addr, &memo)
         }
 
+        fn verify_account_withdrawal(who: AccountId, asset_id: AssetId, value: Balance, addr: AddrStr, memo: Memo) -> Result<(), DispatchError> {
+            XGatewayCommon::verify_account_withdrawal(&who, asset_id, value, &addr, &memo)
+        }
+
+        fn withdrawal_allowlist(who: AccountId) -> WithdrawalAllowlist<BlockNumber> {
+            XGatewayCommon::withdrawal_allowlist(&who)
+        }
+
         fn trustee_multisigs() -> BTreeMap<Chain, AccountId> {
             XGatewayCommon::trustee_multisigs()
         }
@@ -1761,6 +1888,10 @@ impl_runtime_apis! {
             let _ = XGatewayCommon::generate_multisig_addr(chain, &info.0)?;
             Ok(info)
         }
+
+        fn trustee_liveness(chain: Chain, session_number: Option<u32>) -> Vec<(AccountId, TrusteeLiveness)> {
+            XGatewayCommon::trustee_liveness(chain, session_number)
+        }
     }
 
     impl fp_rpc::ConvertTransactionRuntimeApi<Block> for Runtime {
@@ -0,0 +1,150 @@
+use core::marker::PhantomData;
+use fp_evm::{Context, ExitSucceed, PrecompileFailure, PrecompileOutput, PrecompileResult};
+use frame_support::{log, traits::Get};
+use pallet_evm::{GasWeightMapping, Precompile};
+use sp_core::{H256, U256};
+use sp_std::vec::Vec;
+
+use xpallet_assets_bridge::abi::{self, selector, ParamType, Token};
+use xpallet_gateway_bitcoin::taproot;
+
+use crate::precompiles::{dispatch_revert, ensure_gas, revert};
+
+const VERIFY_SCHNORR: [u8; 4] = selector("verifySchnorr(bytes32,bytes32,bytes)");
+const TWEAK_PUBKEY: [u8; 4] = selector("tweakPubkey(bytes32,bytes32)");
+const TAP_LEAF_HASH: [u8; 4] = selector("tapLeafHash(bytes)");
+const TAP_BRANCH_HASH: [u8; 4] = selector("tapBranchHash(bytes32,bytes32)");
+const TAPROOT_ADDRESS: [u8; 4] = selector("taprootAddress(bytes32)");
+
+/// The gas of a point multiplication on secp256k1, the same as `ECRecover`.
+const EC_MUL_GAS_COST: u64 = 3_000;
+/// The gas of a tagged hash, i.e. twice of the `Sha256` precompile.
+const TAGGED_HASH_GAS_COST: u64 = 120;
+/// The gas of tagged hashing a word.
+const HASH_WORD_GAS_COST: u64 = 12;
+
+/// The BIP340 schnorr signatures and the taproot (BIP341) utilities of
+/// `xpallet_gateway_bitcoin`, which derive the same taproot addresses as the trustees.
+///
+/// All the keys are x-only, i.e. the 32-byte x coordinate of the point with the even y.
+pub struct Taproot<T: xpallet_gateway_bitcoin::Config + pallet_evm::Config> {
+    _marker: PhantomData<T>,
+}
+
+impl<T> Taproot<T>
+where
+    T: xpallet_gateway_bitcoin::Config + pallet_evm::Config,
+{
+    fn process(input: &[u8], target_gas: Option<u64>) -> PrecompileResult {
+        if input.len() < 4 {
+            return Err(revert("taproot: invalid input", 0));
+        }
+        let (method, args) = input.split_at(4);
+        let mut method_id = [0u8; 4];
+        method_id.copy_from_slice(method);
+
+        let (cost, output) = match method_id {
+            VERIFY_SCHNORR => {
+                let cost = 2 * EC_MUL_GAS_COST + TAGGED_HASH_GAS_COST;
+                ensure_gas(target_gas, cost)?;
+                let args = decode(
+                    &[ParamType::Bytes32, ParamType::Bytes32, ParamType::Bytes],
+                    args,
+                    cost,
+                )?;
+                let valid = match &args[2] {
+                    Token::Bytes(sig) if sig.len() == 64 => {
+                        let mut signature = [0u8; 64];
+                        signature.copy_from_slice(sig);
+                        taproot::verify_schnorr(&bytes32(&args[0]), &bytes32(&args[1]), &signature)
+                    }
+                    _ => false,
+                };
+                (cost, abi::encode(&[Token::Uint(U256::from(valid as u8))]))
+            }
+            TWEAK_PUBKEY => {
+                let cost = EC_MUL_GAS_COST + TAGGED_HASH_GAS_COST;
+                ensure_gas(target_gas, cost)?;
+                let args = decode(&[ParamType::Bytes32, ParamType::Bytes32], args, cost)?;
+                let (output_key, is_odd) = xpallet_gateway_bitcoin::Pallet::<T>::taproot_tweak(
+                    &bytes32(&args[0]),
+                    &bytes32(&args[1]),
+                )
+                .map_err(|err| dispatch_revert(err, cost))?;
+                let output = abi::encode(&[
+                    Token::Bytes32(H256(output_key)),
+                    Token::Uint(U256::from(is_odd as u8)),
+                ]);
+                (cost, output)
+            }
+            TAP_LEAF_HASH => {
+                let words = (args.len() as u64 + 31) / 32;
+                let cost = TAGGED_HASH_GAS_COST.saturating_add(HASH_WORD_GAS_COST * words);
+                ensure_gas(target_gas, cost)?;
+                let args = decode(&[ParamType::Bytes], args, cost)?;
+                let script = match &args[0] {
+                    Token::Bytes(script) => script,
+                    _ => unreachable!("decoded as bytes; qed"),
+                };
+                let hash = taproot::tap_leaf_hash(script);
+                (cost, abi::encode(&[Token::Bytes32(H256(hash))]))
+            }
+            TAP_BRANCH_HASH => {
+                let cost = TAGGED_HASH_GAS_COST + 2 * HASH_WORD_GAS_COST;
+                ensure_gas(target_gas, cost)?;
+                let args = decode(&[ParamType::Bytes32, ParamType::Bytes32], args, cost)?;
+                let hash = taproot::tap_branch_hash(&bytes32(&args[0]), &bytes32(&args[1]));
+                (cost, abi::encode(&[Token::Bytes32(H256(hash))]))
+            }
+            TAPROOT_ADDRESS => {
+                let cost = EC_MUL_GAS_COST.saturating_add(T::GasWeightMapping::weight_to_gas(
+                    T::DbWeight::get().reads(1),
+                ));
+                ensure_gas(target_gas, cost)?;
+                let args = decode(&[ParamType::Bytes32], args, cost)?;
+                let address =
+                    xpallet_gateway_bitcoin::Pallet::<T>::taproot_address(&bytes32(&args[0]))
+                        .map_err(|err| dispatch_revert(err, cost))?;
+                (cost, abi::encode(&[Token::String(address)]))
+            }
+            _ => return Err(revert("taproot: unknown method", 0)),
+        };
+
+        Ok(PrecompileOutput {
+            exit_status: ExitSucceed::Returned,
+            cost,
+            output,
+            logs: Default::default(),
+        })
+    }
+}
+
+impl<T> Precompile for Taproot<T>
+where
+    T: xpallet_gateway_bitcoin::Config + pallet_evm::Config,
+{
+    fn execute(
+        input: &[u8],
+        target_gas: Option<u64>,
+        context: &Context,
+        _: bool,
+    ) -> PrecompileResult {
+        log::debug!(target: "evm-taproot", "caller: {:?}", context.caller);
+
+        Self::process(input, target_gas).map_err(|err| {
+            log::debug!(target: "evm-taproot", "err = {:?}", err);
+            err
+        })
+    }
+}
+
+fn decode(types: &[ParamType], data: &[u8], cost: u64) -> Result<Vec<Token>, PrecompileFailure> {
+    abi::decode(types, data).map_err(|_| revert("taproot: invalid arguments", cost))
+}
+
+fn bytes32(token: &Token) -> [u8; 32] {
+    match token {
+        Token::Bytes32(hash) => hash.0,
+        _ => unreachable!("decoded as bytes32; qed"),
+    }
+}
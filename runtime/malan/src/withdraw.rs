@@ -172,7 +172,8 @@ impl<
             }
         })?;
 
-        xpallet_gateway_common::Pallet::<T>::verify_withdrawal(
+        xpallet_gateway_common::Pallet::<T>::verify_account_withdrawal(
+            &from,
             1,
             balance.unique_saturated_into(),
             btc_addr,
@@ -14,7 +14,8 @@ use sp_runtime::traits::Block as BlockT;
 use std::sync::Arc;
 use std::time::Duration;
 
-use chainx_primitives::Block;
+use chainx_primitives::{AccountId, Balance, Block};
+use xpallet_btc_ledger_rpc::index::BalanceIndex;
 
 mod client;
 use client::RuntimeApiCollection;
@@ -68,6 +69,13 @@ pub fn frontier_database_dir(config: &Configuration) -> std::path::PathBuf {
     config_dir.join("frontier").join("db")
 }
 
+pub fn open_btc_ledger_index(
+    config: &Configuration,
+) -> Result<Arc<BalanceIndex<Block, AccountId, Balance>>, String> {
+    let path = xpallet_btc_ledger_rpc::index::index_database_dir(&frontier_database_dir(config));
+    Ok(Arc::new(BalanceIndex::open(&path)?))
+}
+
 pub fn open_frontier_backend(config: &Configuration) -> Result<Arc<fc_db::Backend<Block>>, String> {
     Ok(Arc::new(fc_db::Backend::<Block>::new(
         &fc_db::DatabaseSettings {
@@ -277,8 +285,11 @@ fn remote_keystore(_url: &str) -> Result<Arc<LocalKeystore>, &'static str> {
 }
 
 /// Creates a full service from the configuration.
+///
+/// The BTC ledger balance change index is maintained if `enable_btc_ledger_index` is set.
 pub fn new_full_base<RuntimeApi, Executor>(
     mut config: Configuration,
+    enable_btc_ledger_index: bool,
 ) -> Result<NewFullBase<RuntimeApi, Executor>, ServiceError>
 where
     RuntimeApi:
@@ -312,6 +323,11 @@ where
 
     let (babe_block_import, grandpa_link, babe_link) = import_setup;
     let (filter_pool, fee_history_cache, frontier_backend) = frontier_setup;
+    let btc_ledger_index = if enable_btc_ledger_index {
+        Some(open_btc_ledger_index(&config).map_err(ServiceError::Other)?)
+    } else {
+        None
+    };
 
     let auth_disc_publish_non_global_ips = config.network.allow_non_globals_in_dht;
     let grandpa_protocol_name = sc_finality_grandpa::protocol_standard_name(
@@ -405,6 +421,7 @@ where
         let fee_history_cache = fee_history_cache.clone();
         let is_authority = false;
         let max_past_logs = 10000;
+        let btc_ledger_index = btc_ledger_index.clone();
 
         Box::new(move |deny_unsafe, subscription_executor| {
             let deps = chainx_rpc::FullDeps {
@@ -437,6 +454,7 @@ where
                     overrides: overrides.clone(),
                     block_data_cache: block_data_cache.clone(),
                 },
+                btc_ledger_index: btc_ledger_index.clone(),
             };
 
             chainx_rpc::create_full(deps, subscription_task_executor.clone()).map_err(Into::into)
@@ -472,6 +490,14 @@ where
         .for_each(|()| futures::future::ready(())),
     );
 
+    if let Some(index) = btc_ledger_index {
+        task_manager.spawn_essential_handle().spawn_blocking(
+            "btc-ledger-index-sync",
+            Some("frontier"),
+            xpallet_btc_ledger_rpc::index::sync_balance_index(client.clone(), index),
+        );
+    }
+
     // Spawn Frontier EthFilterApi maintenance task.
     if let Some(filter_pool) = filter_pool {
         // Each filter is allowed to stay in the pool for 100 blocks.
@@ -649,7 +675,10 @@ where
 }
 
 /// Builds a new service for a full client.
-pub fn new_full<RuntimeApi, Executor>(config: Configuration) -> Result<TaskManager, ServiceError>
+pub fn new_full<RuntimeApi, Executor>(
+    config: Configuration,
+    enable_btc_ledger_index: bool,
+) -> Result<TaskManager, ServiceError>
 where
     RuntimeApi:
         ConstructRuntimeApi<Block, FullClient<RuntimeApi, Executor>> + Send + Sync + 'static,
@@ -657,7 +686,8 @@ where
         RuntimeApiCollection<StateBackend = sc_client_api::StateBackendFor<FullBackend, Block>>,
     Executor: NativeExecutionDispatch + 'static,
 {
-    new_full_base(config).map(|base: NewFullBase<RuntimeApi, Executor>| base.task_manager)
+    new_full_base(config, enable_btc_ledger_index)
+        .map(|base: NewFullBase<RuntimeApi, Executor>| base.task_manager)
 }
 
 /// Can be called for a `Configuration` to check if it is a configuration for the `ChainX` network.
@@ -684,12 +714,24 @@ impl IdentifyVariant for Box<dyn sc_service::ChainSpec> {
     }
 }
 
-pub fn build_full(config: Configuration) -> Result<TaskManager, ServiceError> {
+pub fn build_full(
+    config: Configuration,
+    enable_btc_ledger_index: bool,
+) -> Result<TaskManager, ServiceError> {
     if config.chain_spec.is_chainx() {
-        new_full::<chainx_runtime::RuntimeApi, chainx_executor::ChainXExecutor>(config)
+        new_full::<chainx_runtime::RuntimeApi, chainx_executor::ChainXExecutor>(
+            config,
+            enable_btc_ledger_index,
+        )
     } else if config.chain_spec.is_malan() {
-        new_full::<malan_runtime::RuntimeApi, chainx_executor::MalanExecutor>(config)
+        new_full::<malan_runtime::RuntimeApi, chainx_executor::MalanExecutor>(
+            config,
+            enable_btc_ledger_index,
+        )
     } else {
-        new_full::<dev_runtime::RuntimeApi, chainx_executor::DevExecutor>(config)
+        new_full::<dev_runtime::RuntimeApi, chainx_executor::DevExecutor>(
+            config,
+            enable_btc_ledger_index,
+        )
     }
 }
@@ -0,0 +1,141 @@
+// Copyright 2019-2023 ChainX Project Authors. Licensed under GPL-3.0.
+
+//! # Assets Allowance
+//!
+//! ## Overview
+//!
+//! ERC20-style allowances over the balances of `xpallet_assets`, which back the
+//! `approve`/`allowance`/`transferFrom` methods of the native-asset ERC20 precompiles.
+
+#![cfg_attr(not(feature = "std"), no_std)]
+
+#[cfg(test)]
+mod mock;
+#[cfg(test)]
+mod tests;
+
+use frame_support::{ensure, pallet_prelude::*, transactional};
+use sp_runtime::traits::{Bounded, Zero};
+
+use chainx_primitives::AssetId;
+use xpallet_assets::BalanceOf;
+
+pub use pallet::*;
+
+#[frame_support::pallet]
+pub mod pallet {
+    use super::*;
+
+    #[pallet::pallet]
+    #[pallet::generate_store(pub(super) trait Store)]
+    #[pallet::without_storage_info]
+    pub struct Pallet<T>(PhantomData<T>);
+
+    #[pallet::config]
+    pub trait Config: frame_system::Config + xpallet_assets::Config {
+        /// The overarching event type.
+        type Event: From<Event<Self>> + IsType<<Self as frame_system::Config>::Event>;
+    }
+
+    /// The amount of an asset which a spender is still allowed to transfer on behalf of an owner.
+    ///
+    /// Allowances: map (AssetId, owner, spender) => Balance
+    #[pallet::storage]
+    #[pallet::getter(fn allowances)]
+    pub type Allowances<T: Config> = StorageNMap<
+        _,
+        (
+            NMapKey<Twox64Concat, AssetId>,
+            NMapKey<Blake2_128Concat, T::AccountId>,
+            NMapKey<Blake2_128Concat, T::AccountId>,
+        ),
+        BalanceOf<T>,
+        ValueQuery,
+    >;
+
+    #[pallet::event]
+    #[pallet::generate_deposit(pub(super) fn deposit_event)]
+    pub enum Event<T: Config> {
+        /// The allowance of a spender was set by an owner. [asset_id, owner, spender, amount]
+        Approved(AssetId, T::AccountId, T::AccountId, BalanceOf<T>),
+    }
+
+    #[pallet::error]
+    pub enum Error<T> {
+        /// The amount exceeds the allowance of the spender.
+        InsufficientAllowance,
+    }
+}
+
+impl<T: Config> Pallet<T> {
+    /// Returns the amount of asset `id` which `spender` is allowed to transfer from `owner`.
+    pub fn allowance(id: AssetId, owner: &T::AccountId, spender: &T::AccountId) -> BalanceOf<T> {
+        Self::allowances((id, owner, spender))
+    }
+
+    /// Sets the allowance of `spender` over the asset `id` of `owner` to `amount`.
+    pub fn approve(
+        id: AssetId,
+        owner: &T::AccountId,
+        spender: &T::AccountId,
+        amount: BalanceOf<T>,
+    ) -> DispatchResult {
+        xpallet_assets::Pallet::<T>::ensure_not_native_asset(&id)?;
+        xpallet_assets_registrar::Pallet::<T>::ensure_asset_is_valid(&id)?;
+
+        if amount.is_zero() {
+            Allowances::<T>::remove((id, owner, spender));
+        } else {
+            Allowances::<T>::insert((id, owner, spender), amount);
+        }
+        Self::deposit_event(Event::<T>::Approved(
+            id,
+            owner.clone(),
+            spender.clone(),
+            amount,
+        ));
+        Ok(())
+    }
+
+    /// Transfers `value` of the usable asset `id` from `from` to `to`.
+    pub fn transfer(
+        id: AssetId,
+        from: &T::AccountId,
+        to: &T::AccountId,
+        value: BalanceOf<T>,
+    ) -> DispatchResult {
+        xpallet_assets::Pallet::<T>::ensure_not_native_asset(&id)?;
+        xpallet_assets::Pallet::<T>::can_transfer(&id)?;
+
+        xpallet_assets::Pallet::<T>::move_usable_balance(&id, from, to, value)
+            .map_err::<xpallet_assets::Error<T>, _>(Into::into)?;
+        Ok(())
+    }
+
+    /// Transfers `value` of the usable asset `id` from `from` to `to` on behalf of `spender`,
+    /// consuming the allowance of `spender`.
+    ///
+    /// An allowance of `Balance::max_value()` is regarded as unlimited and never decreases.
+    #[transactional]
+    pub fn transfer_from(
+        id: AssetId,
+        spender: &T::AccountId,
+        from: &T::AccountId,
+        to: &T::AccountId,
+        value: BalanceOf<T>,
+    ) -> DispatchResult {
+        let allowance = Self::allowance(id, from, spender);
+        ensure!(allowance >= value, Error::<T>::InsufficientAllowance);
+
+        if allowance != BalanceOf::<T>::max_value() {
+            let remaining = allowance - value;
+            if remaining.is_zero() {
+                Allowances::<T>::remove((id, from, spender));
+            } else {
+                Allowances::<T>::insert((id, from, spender), remaining);
+            }
+        }
+
+        Self::transfer(id, from, to, value)
+    }
+}
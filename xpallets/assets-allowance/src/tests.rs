@@ -0,0 +1,82 @@
+// Copyright 2019-2023 ChainX Project Authors. Licensed under GPL-3.0.
+
+use frame_support::{assert_noop, assert_ok};
+use xp_protocol::X_BTC;
+
+use crate::mock::{
+    Balance, ExtBuilder, Test, XAssets, XAssetsAllowance, XAssetsAllowanceErr, ALICE, BOB, CHARLIE,
+};
+
+#[test]
+fn approve_should_work() {
+    ExtBuilder::default().build_and_execute(|| {
+        assert_ok!(XAssetsAllowance::approve(X_BTC, &ALICE, &BOB, 50));
+        assert_eq!(XAssetsAllowance::allowance(X_BTC, &ALICE, &BOB), 50);
+        assert_eq!(XAssetsAllowance::allowance(X_BTC, &BOB, &ALICE), 0);
+
+        // approving again overrides the previous allowance.
+        assert_ok!(XAssetsAllowance::approve(X_BTC, &ALICE, &BOB, 20));
+        assert_eq!(XAssetsAllowance::allowance(X_BTC, &ALICE, &BOB), 20);
+
+        assert_ok!(XAssetsAllowance::approve(X_BTC, &ALICE, &BOB, 0));
+        assert_eq!(XAssetsAllowance::allowance(X_BTC, &ALICE, &BOB), 0);
+
+        // the native asset and unknown assets can not be approved.
+        assert_noop!(
+            XAssetsAllowance::approve(0, &ALICE, &BOB, 50),
+            xpallet_assets::Error::<Test>::DenyNativeAsset
+        );
+        assert_noop!(
+            XAssetsAllowance::approve(100, &ALICE, &BOB, 50),
+            xpallet_assets_registrar::Error::<Test>::AssetIsInvalid
+        );
+    });
+}
+
+#[test]
+fn transfer_from_should_work() {
+    ExtBuilder::default().build_and_execute(|| {
+        assert_noop!(
+            XAssetsAllowance::transfer_from(X_BTC, &BOB, &ALICE, &CHARLIE, 10),
+            XAssetsAllowanceErr::InsufficientAllowance
+        );
+
+        assert_ok!(XAssetsAllowance::approve(X_BTC, &ALICE, &BOB, 50));
+        assert_ok!(XAssetsAllowance::transfer_from(
+            X_BTC, &BOB, &ALICE, &CHARLIE, 30
+        ));
+        assert_eq!(XAssetsAllowance::allowance(X_BTC, &ALICE, &BOB), 20);
+        assert_eq!(XAssets::usable_balance(&ALICE, &X_BTC), 70);
+        assert_eq!(XAssets::usable_balance(&CHARLIE, &X_BTC), 330);
+
+        assert_noop!(
+            XAssetsAllowance::transfer_from(X_BTC, &BOB, &ALICE, &CHARLIE, 30),
+            XAssetsAllowanceErr::InsufficientAllowance
+        );
+
+        // the allowance is kept when the transfer fails.
+        assert_ok!(XAssetsAllowance::approve(X_BTC, &ALICE, &BOB, 500));
+        assert_noop!(
+            XAssetsAllowance::transfer_from(X_BTC, &BOB, &ALICE, &CHARLIE, 200),
+            xpallet_assets::Error::<Test>::InsufficientBalance
+        );
+        assert_eq!(XAssetsAllowance::allowance(X_BTC, &ALICE, &BOB), 500);
+
+        // an unlimited allowance never decreases.
+        assert_ok!(XAssetsAllowance::approve(
+            X_BTC,
+            &ALICE,
+            &BOB,
+            Balance::max_value()
+        ));
+        assert_ok!(XAssetsAllowance::transfer_from(
+            X_BTC, &BOB, &ALICE, &BOB, 70
+        ));
+        assert_eq!(
+            XAssetsAllowance::allowance(X_BTC, &ALICE, &BOB),
+            Balance::max_value()
+        );
+        assert_eq!(XAssets::usable_balance(&ALICE, &X_BTC), 0);
+        assert_eq!(XAssets::usable_balance(&BOB, &X_BTC), 270);
+    });
+}
@@ -1,49 +1,402 @@
 // Copyright 2019-2023 ChainX Project Authors. Licensed under GPL-3.0.
 
+//! A minimal Solidity ABI encoder and decoder for the contract calls of the assets bridge.
+//!
+//! Only the types used by the bridge and the runtime precompiles are supported: `address`,
+//! `uint256`, `bytes32`, `bytes`, `string` and the dynamic arrays of them.
+
 use super::*;
 
-pub fn mint_into_encode(account: H160, amount: u128) -> Vec<u8> {
-    // signature ++ account ++ amount
-    let length = 16 + 20 + 32;
-    let mut v = Vec::with_capacity(length);
+/// The function selector of `mint_into(address,uint256)`, i.e., 0xefe51695.
+pub const MINT_INTO: [u8; 4] = selector("mint_into(address,uint256)");
+
+/// The function selector of `burn_from(address,uint256)`, i.e., 0x0f536f84.
+pub const BURN_FROM: [u8; 4] = selector("burn_from(address,uint256)");
+
+/// The selector of `Error(string)`, with which the revert reason is encoded.
+pub const ERROR_STRING: [u8; 4] = selector("Error(string)");
 
-    // bytes4(keccak256(bytes("mint_into(address,uint256)"))
-    // 0xefe51695
-    let sig_mint = [239u8, 229, 22, 149];
+/// The topic of the ERC20 event `Transfer(address indexed from, address indexed to, uint256 value)`.
+pub const TRANSFER_EVENT: H256 = H256(keccak256(b"Transfer(address,address,uint256)"));
 
-    // first 16-bytes
-    v.extend_from_slice(&sig_mint[..]);
-    v.extend_from_slice(&[0u8; 12][..]);
+/// The topic of the ERC20 event
+/// `Approval(address indexed owner, address indexed spender, uint256 value)`.
+pub const APPROVAL_EVENT: H256 = H256(keccak256(b"Approval(address,address,uint256)"));
+
+/// The inputs of the ERC20 event `Transfer`.
+pub const TRANSFER_EVENT_INPUTS: [(ParamType, bool); 3] = [
+    (ParamType::Address, true),
+    (ParamType::Address, true),
+    (ParamType::Uint, false),
+];
+
+/// The type of a parameter.
+#[derive(Clone, Copy, PartialEq, Eq, RuntimeDebug)]
+pub enum ParamType {
+    Address,
+    Uint,
+    Bytes32,
+    Bytes,
+    String,
+    /// A dynamic array of the given type.
+    Array(&'static ParamType),
+}
+
+impl ParamType {
+    fn is_dynamic(&self) -> bool {
+        !matches!(self, Self::Address | Self::Uint | Self::Bytes32)
+    }
+}
+
+/// A value of a parameter.
+#[derive(Clone, PartialEq, Eq, RuntimeDebug)]
+pub enum Token {
+    Address(H160),
+    Uint(U256),
+    Bytes32(H256),
+    Bytes(Vec<u8>),
+    /// The utf-8 bytes of a string.
+    String(Vec<u8>),
+    Array(Vec<Token>),
+}
+
+impl Token {
+    fn is_dynamic(&self) -> bool {
+        !matches!(self, Self::Address(_) | Self::Uint(_) | Self::Bytes32(_))
+    }
+}
 
-    // second 20-bytes
-    v.extend_from_slice(&account[..]);
+#[derive(Clone, Copy, PartialEq, Eq, RuntimeDebug)]
+pub enum AbiError {
+    /// The data is too short or the offset is out of range.
+    InvalidData,
+    /// The first topic of the log mismatches the event.
+    InvalidTopic,
+}
 
-    // third 32-bytes
-    v.extend_from_slice(&[0u8; 16][..]);
-    v.extend_from_slice(&amount.to_be_bytes()[..]);
+/// Returns the function selector of `signature`, e.g., `transfer(address,uint256)`.
+pub const fn selector(signature: &str) -> [u8; 4] {
+    let hash = keccak256(signature.as_bytes());
+    [hash[0], hash[1], hash[2], hash[3]]
+}
 
+/// Encodes the call of the function with `selector` and the arguments `tokens`.
+pub fn encode_call(selector: [u8; 4], tokens: &[Token]) -> Vec<u8> {
+    let mut v = selector.to_vec();
+    v.extend(encode(tokens));
     v
 }
 
+/// Encodes `tokens` as a tuple.
+pub fn encode(tokens: &[Token]) -> Vec<u8> {
+    let head_len = 32 * tokens.len();
+    let mut head = Vec::with_capacity(head_len);
+    let mut tail = Vec::new();
+    for token in tokens {
+        if token.is_dynamic() {
+            head.extend_from_slice(&uint_word(U256::from(head_len + tail.len())));
+            tail.extend(encode_token(token));
+        } else {
+            head.extend(encode_token(token));
+        }
+    }
+    head.extend(tail);
+    head
+}
+
+fn uint_word(value: U256) -> [u8; 32] {
+    let mut word = [0u8; 32];
+    value.to_big_endian(&mut word);
+    word
+}
+
+fn encode_token(token: &Token) -> Vec<u8> {
+    match token {
+        Token::Address(address) => {
+            let mut word = [0u8; 32];
+            word[12..].copy_from_slice(address.as_bytes());
+            word.to_vec()
+        }
+        Token::Uint(value) => uint_word(*value).to_vec(),
+        Token::Bytes32(bytes) => bytes.as_bytes().to_vec(),
+        Token::Bytes(bytes) | Token::String(bytes) => {
+            let mut v = uint_word(U256::from(bytes.len())).to_vec();
+            v.extend_from_slice(bytes);
+            // right padded to 32 bytes
+            v.resize(32 + (bytes.len() + 31) / 32 * 32, 0);
+            v
+        }
+        Token::Array(tokens) => {
+            let mut v = uint_word(U256::from(tokens.len())).to_vec();
+            v.extend(encode(tokens));
+            v
+        }
+    }
+}
+
+/// Decodes `data` as a tuple of `types`.
+pub fn decode(types: &[ParamType], data: &[u8]) -> Result<Vec<Token>, AbiError> {
+    types
+        .iter()
+        .enumerate()
+        .map(|(i, ty)| decode_param(ty, data, 32 * i))
+        .collect()
+}
+
+fn word(data: &[u8], offset: usize) -> Result<&[u8], AbiError> {
+    data.get(offset..offset.checked_add(32).ok_or(AbiError::InvalidData)?)
+        .ok_or(AbiError::InvalidData)
+}
+
+fn usize_word(data: &[u8], offset: usize) -> Result<usize, AbiError> {
+    let value = U256::from_big_endian(word(data, offset)?);
+    if value > U256::from(u32::MAX) {
+        return Err(AbiError::InvalidData);
+    }
+    Ok(value.low_u64() as usize)
+}
+
+/// Returns the offset of the data following the length word at `start`, the offsets are bounded
+/// by `u32::MAX` only, which can overflow on 32-bit targets.
+fn body_offset(start: usize) -> Result<usize, AbiError> {
+    start.checked_add(32).ok_or(AbiError::InvalidData)
+}
+
+fn decode_param(ty: &ParamType, data: &[u8], offset: usize) -> Result<Token, AbiError> {
+    match ty {
+        ParamType::Address => {
+            let word = word(data, offset)?;
+            if word[..12].iter().any(|&b| b != 0) {
+                return Err(AbiError::InvalidData);
+            }
+            Ok(Token::Address(H160::from_slice(&word[12..])))
+        }
+        ParamType::Uint => Ok(Token::Uint(U256::from_big_endian(word(data, offset)?))),
+        ParamType::Bytes32 => Ok(Token::Bytes32(H256::from_slice(word(data, offset)?))),
+        ParamType::Bytes | ParamType::String => {
+            let start = usize_word(data, offset)?;
+            let len = usize_word(data, start)?;
+            let body = body_offset(start)?;
+            let end = body.checked_add(len).ok_or(AbiError::InvalidData)?;
+            let bytes = data.get(body..end).ok_or(AbiError::InvalidData)?.to_vec();
+            if *ty == ParamType::Bytes {
+                Ok(Token::Bytes(bytes))
+            } else {
+                Ok(Token::String(bytes))
+            }
+        }
+        ParamType::Array(inner) => {
+            let start = usize_word(data, offset)?;
+            let len = usize_word(data, start)?;
+            let body = data
+                .get(body_offset(start)?..)
+                .ok_or(AbiError::InvalidData)?;
+            if len.saturating_mul(32) > body.len() {
+                return Err(AbiError::InvalidData);
+            }
+            (0..len)
+                .map(|i| decode_param(inner, body, 32 * i))
+                .collect::<Result<_, _>>()
+                .map(Token::Array)
+        }
+    }
+}
+
+/// Decodes the log of the event with `topic` and `inputs`, given as the types and whether they
+/// are indexed, into the tokens in the order of `inputs`.
+///
+/// NOTE: the indexed dynamic inputs are only stored as their keccak256 hash in the topics, they
+/// are decoded as the `Token::Bytes` of the hash.
+pub fn decode_log(
+    topic: H256,
+    inputs: &[(ParamType, bool)],
+    topics: &[H256],
+    data: &[u8],
+) -> Result<Vec<Token>, AbiError> {
+    if topics.first() != Some(&topic) {
+        return Err(AbiError::InvalidTopic);
+    }
+
+    let non_indexed = inputs
+        .iter()
+        .filter(|(_, indexed)| !indexed)
+        .map(|(ty, _)| *ty)
+        .collect::<Vec<_>>();
+    let mut values = decode(&non_indexed, data)?.into_iter();
+    let mut indexed_topics = topics[1..].iter();
+
+    inputs
+        .iter()
+        .map(|(ty, indexed)| {
+            if *indexed {
+                let topic = indexed_topics.next().ok_or(AbiError::InvalidTopic)?;
+                if ty.is_dynamic() {
+                    Ok(Token::Bytes(topic.as_bytes().to_vec()))
+                } else {
+                    decode_param(ty, topic.as_bytes(), 0)
+                }
+            } else {
+                values.next().ok_or(AbiError::InvalidData)
+            }
+        })
+        .collect()
+}
+
+/// Returns the revert reason in the `output` of a reverted call, if it is `Error(string)`.
+pub fn decode_revert_reason(output: &[u8]) -> Option<Vec<u8>> {
+    if output.len() < 4 || output[..4] != ERROR_STRING {
+        return None;
+    }
+    match decode(&[ParamType::String], &output[4..]).ok()?.pop()? {
+        Token::String(reason) => Some(reason),
+        _ => None,
+    }
+}
+
+pub fn mint_into_encode(account: H160, amount: u128) -> Vec<u8> {
+    encode_call(
+        MINT_INTO,
+        &[Token::Address(account), Token::Uint(U256::from(amount))],
+    )
+}
+
 pub fn burn_from_encode(account: H160, amount: u128) -> Vec<u8> {
-    // signature ++ account ++ amount
-    let length = 16 + 20 + 32;
-    let mut v = Vec::with_capacity(length);
+    encode_call(
+        BURN_FROM,
+        &[Token::Address(account), Token::Uint(U256::from(amount))],
+    )
+}
 
-    // bytes4(keccak256(bytes("burn_from(address,uint256)"))
-    // 0x0f536f84
-    let sig_burn = [15u8, 83, 111, 132];
+const KECCAK_ROUND_CONSTANTS: [u64; 24] = [
+    0x0000000000000001,
+    0x0000000000008082,
+    0x800000000000808a,
+    0x8000000080008000,
+    0x000000000000808b,
+    0x0000000080000001,
+    0x8000000080008081,
+    0x8000000000008009,
+    0x000000000000008a,
+    0x0000000000000088,
+    0x0000000080008009,
+    0x000000008000000a,
+    0x000000008000808b,
+    0x800000000000008b,
+    0x8000000000008089,
+    0x8000000000008003,
+    0x8000000000008002,
+    0x8000000000000080,
+    0x000000000000800a,
+    0x800000008000000a,
+    0x8000000080008081,
+    0x8000000000008080,
+    0x0000000080000001,
+    0x8000000080008008,
+];
 
-    // first 16-bytes
-    v.extend_from_slice(&sig_burn[..]);
-    v.extend_from_slice(&[0u8; 12][..]);
+const KECCAK_RHO: [u32; 24] = [
+    1, 3, 6, 10, 15, 21, 28, 36, 45, 55, 2, 14, 27, 41, 56, 8, 25, 43, 62, 18, 39, 61, 20, 44,
+];
 
-    // second 20-bytes
-    v.extend_from_slice(&account[..]);
+const KECCAK_PI: [usize; 24] = [
+    10, 7, 11, 17, 18, 3, 5, 16, 8, 21, 24, 4, 15, 23, 19, 13, 12, 2, 20, 14, 22, 9, 6, 1,
+];
 
-    // third 32-bytes
-    v.extend_from_slice(&[0u8; 16][..]);
-    v.extend_from_slice(&amount.to_be_bytes()[..]);
+/// The rate of keccak256 in bytes.
+const KECCAK_RATE: usize = 136;
 
-    v
+const fn keccak_f(mut a: [u64; 25]) -> [u64; 25] {
+    let mut round = 0;
+    while round < 24 {
+        // theta
+        let mut c = [0u64; 5];
+        let mut x = 0;
+        while x < 5 {
+            c[x] = a[x] ^ a[x + 5] ^ a[x + 10] ^ a[x + 15] ^ a[x + 20];
+            x += 1;
+        }
+        x = 0;
+        while x < 5 {
+            let d = c[(x + 4) % 5] ^ c[(x + 1) % 5].rotate_left(1);
+            let mut y = 0;
+            while y < 25 {
+                a[y + x] ^= d;
+                y += 5;
+            }
+            x += 1;
+        }
+
+        // rho and pi
+        let mut last = a[1];
+        let mut i = 0;
+        while i < 24 {
+            let j = KECCAK_PI[i];
+            let tmp = a[j];
+            a[j] = last.rotate_left(KECCAK_RHO[i]);
+            last = tmp;
+            i += 1;
+        }
+
+        // chi
+        let mut y = 0;
+        while y < 25 {
+            let row = [a[y], a[y + 1], a[y + 2], a[y + 3], a[y + 4]];
+            x = 0;
+            while x < 5 {
+                a[y + x] = row[x] ^ (!row[(x + 1) % 5] & row[(x + 2) % 5]);
+                x += 1;
+            }
+            y += 5;
+        }
+
+        // iota
+        a[0] ^= KECCAK_ROUND_CONSTANTS[round];
+        round += 1;
+    }
+    a
+}
+
+const fn absorb(mut state: [u64; 25], block: &[u8], offset: usize) -> [u64; 25] {
+    let mut i = 0;
+    while i < KECCAK_RATE / 8 {
+        let mut lane = 0u64;
+        let mut j = 0;
+        while j < 8 {
+            lane |= (block[offset + 8 * i + j] as u64) << (8 * j);
+            j += 1;
+        }
+        state[i] ^= lane;
+        i += 1;
+    }
+    keccak_f(state)
+}
+
+/// Keccak256 which can be evaluated at compile time, so that the selectors and the event topics
+/// are derived from their signatures without any runtime cost.
+pub const fn keccak256(input: &[u8]) -> [u8; 32] {
+    let mut state = [0u64; 25];
+    let mut offset = 0;
+    while input.len() - offset >= KECCAK_RATE {
+        state = absorb(state, input, offset);
+        offset += KECCAK_RATE;
+    }
+
+    // the last block with the keccak padding
+    let mut block = [0u8; KECCAK_RATE];
+    let mut i = 0;
+    while offset + i < input.len() {
+        block[i] = input[offset + i];
+        i += 1;
+    }
+    block[i] ^= 0x01;
+    block[KECCAK_RATE - 1] ^= 0x80;
+    state = absorb(state, &block, 0);
+
+    let mut out = [0u8; 32];
+    i = 0;
+    while i < 32 {
+        out[i] = (state[i / 8] >> (8 * (i % 8))) as u8;
+        i += 1;
+    }
+    out
 }
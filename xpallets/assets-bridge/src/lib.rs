@@ -27,13 +27,14 @@ use frame_support::{
     traits::{Currency, ExistenceRequirement, IsType, ReservableCurrency, WithdrawReasons},
     transactional,
 };
-use sp_core::{ecdsa, H160, U256};
+use sp_core::{ecdsa, H160, H256, U256};
 use sp_io::{crypto::secp256k1_ecdsa_recover, hashing::keccak_256};
 use sp_runtime::traits::{StaticLookup, UniqueSaturatedInto, Zero};
 use sp_std::vec::Vec;
 
 pub use chainx_primitives::AssetId;
 use pallet_evm::{AddressMapping, CallInfo, ExitReason, Runner};
+use xpallet_assets::AssetLedger;
 
 pub type EcdsaSignature = ecdsa::Signature;
 pub type AddressMappingOf<T> = <T as pallet_evm::Config>::AddressMapping;
@@ -118,6 +119,18 @@ pub mod pallet {
     #[pallet::getter(fn emergencies)]
     pub(super) type Emergencies<T: Config> = StorageValue<_, Vec<AssetId>, ValueQuery>;
 
+    /// Whether the legacy `eth_sign` signature of `claim_account` is accepted.
+    #[pallet::storage]
+    #[pallet::getter(fn legacy_claim_enabled)]
+    pub(super) type LegacyClaimEnabled<T: Config> = StorageValue<_, bool, ValueQuery>;
+
+    /// The nonce of the next EIP-712 account claim of Substrate Accounts
+    ///
+    /// ClaimNonces: map AccountId => u64
+    #[pallet::storage]
+    #[pallet::getter(fn claim_nonce)]
+    pub type ClaimNonces<T: Config> = StorageMap<_, Twox64Concat, T::AccountId, u64, ValueQuery>;
+
     #[pallet::genesis_config]
     pub struct GenesisConfig<T: Config> {
         /// The `AccountId` of the admin key.
@@ -169,6 +182,8 @@ pub mod pallet {
         UnPausedAll,
         // (asset_id, remove)
         BackForeign(AssetId, bool),
+        /// (enabled)
+        LegacyClaim(bool),
     }
 
     /// Error for evm accounts module.
@@ -206,6 +221,16 @@ pub mod pallet {
         ZeroBalance,
         /// Deprecated
         Deprecated,
+        /// The erc20 contract requires to be called by the assets bridge admin address
+        Erc20RequireBridgeAdmin,
+        /// The erc20 balance is too low
+        Erc20InsufficientBalance,
+        /// The erc20 token transfer is paused
+        Erc20Paused,
+        /// The erc20 contract call was reverted
+        Erc20Reverted,
+        /// Nonce overflow
+        NonceOverflow,
     }
 
     #[pallet::call]
@@ -219,40 +244,26 @@ pub mod pallet {
         ///
         /// - `eth_address`: The address to bind to the caller's account
         /// - `eth_signature`: A signature generated by the address to prove ownership
+        ///
+        /// NOTE: the legacy `eth_sign` signature is only accepted if enabled by `set_legacy_claim`,
+        /// use `claim_account_typed` instead.
         #[pallet::weight(100_000_000u64)]
-        // #[transactional]
+        #[transactional]
         pub fn claim_account(
             origin: OriginFor<T>,
             eth_address: H160,
             eth_signature: EcdsaSignature,
         ) -> DispatchResult {
-            Err(Error::<T>::Deprecated.into())
-            // let who = ensure_signed(origin)?;
-            //
-            // // ensure account_id and eth_address has not been mapped
-            // ensure!(
-            //     !EvmAccounts::<T>::contains_key(&who),
-            //     Error::<T>::AccountIdHasMapped
-            // );
-            // ensure!(
-            //     !SubAccounts::<T>::contains_key(eth_address),
-            //     Error::<T>::EthAddressHasMapped
-            // );
-            //
-            // // recover evm address from signature
-            // let address = eth_recover(&eth_signature, &who.using_encoded(to_ascii_hex), &[][..])
-            //     .ok_or(Error::<T>::BadSignature)?;
-            //
-            // ensure!(eth_address == address, Error::<T>::InvalidSignature);
-            //
-            // <T as xpallet_assets::Config>::Currency::reserve(&who, T::ClaimBond::get())?;
-            //
-            // SubAccounts::<T>::insert(eth_address, &who);
-            // EvmAccounts::<T>::insert(&who, eth_address);
-            //
-            // Self::deposit_event(Event::ClaimAccount(who, eth_address));
-            //
-            // Ok(())
+            let who = ensure_signed(origin)?;
+            ensure!(Self::legacy_claim_enabled(), Error::<T>::Deprecated);
+
+            // recover evm address from signature
+            let address = eth_recover(&eth_signature, &who.using_encoded(to_ascii_hex), &[][..])
+                .ok_or(Error::<T>::BadSignature)?;
+
+            ensure!(eth_address == address, Error::<T>::InvalidSignature);
+
+            Self::do_claim_account(who, eth_address)
         }
 
         /// Dissolve substrate accounts and EVM accounts.
@@ -667,6 +678,9 @@ pub mod pallet {
 
         /// Swap XBTC(assets moudle) to BTC(btc ledger module) by 1:1
         /// Note: for user who hold XBTC
+        ///
+        /// Once XBTC is held by the btc ledger, this only transfers XBTC to the mapping
+        /// account of the evm address.
         #[pallet::weight(0u64)]
         pub fn swap_xbtc_to_btc(
             origin: OriginFor<T>,
@@ -682,6 +696,18 @@ pub mod pallet {
             );
             ensure!(amount > 0, Error::<T>::ZeroBalance);
 
+            if <T as xpallet_assets::Config>::AssetLedger::holds(&xbtc_asset_id) {
+                let mapping_account = AddressMappingOf::<T>::into_account_id(eth_address);
+                xpallet_assets::Pallet::<T>::move_usable_balance(
+                    &xbtc_asset_id,
+                    &who,
+                    &mapping_account,
+                    amount.unique_saturated_into(),
+                )
+                .map_err(xpallet_assets::Error::<T>::from)?;
+                return Ok(Pays::No.into());
+            }
+
             // 1. burn useable xbtc from account
             let _ = xpallet_assets::Pallet::<T>::destroy_usable(
                 &xbtc_asset_id,
@@ -727,10 +753,79 @@ pub mod pallet {
 
             Ok(Pays::No.into())
         }
+
+        /// Claim account mapping between Substrate accounts and EVM accounts, with the EIP-712
+        /// typed-data signature of `Claim(bytes account,uint256 nonce)`.
+        /// Note: for general users
+        ///
+        /// - `eth_address`: The address to bind to the caller's account
+        /// - `eth_signature`: A signature of the claim with the caller's current nonce,
+        ///   generated by the address to prove ownership
+        #[pallet::weight(100_000_000u64)]
+        #[transactional]
+        pub fn claim_account_typed(
+            origin: OriginFor<T>,
+            eth_address: H160,
+            eth_signature: EcdsaSignature,
+        ) -> DispatchResult {
+            let who = ensure_signed(origin)?;
+
+            let nonce = Self::claim_nonce(&who);
+            let address = eth_recover_prehashed(&eth_signature, &Self::claim_digest(&who, nonce))
+                .ok_or(Error::<T>::BadSignature)?;
+
+            ensure!(eth_address == address, Error::<T>::InvalidSignature);
+
+            let next_nonce = nonce.checked_add(1).ok_or(Error::<T>::NonceOverflow)?;
+            ClaimNonces::<T>::insert(&who, next_nonce);
+
+            Self::do_claim_account(who, eth_address)
+        }
+
+        /// Enable or disable the legacy `eth_sign` signature of `claim_account`.
+        /// Note: for super admin
+        #[pallet::weight(100_000_000u64)]
+        pub fn set_legacy_claim(origin: OriginFor<T>, enabled: bool) -> DispatchResult {
+            ensure_root(origin)?;
+
+            LegacyClaimEnabled::<T>::put(enabled);
+
+            Self::deposit_event(Event::LegacyClaim(enabled));
+
+            Ok(())
+        }
     }
 }
 
 impl<T: Config> Pallet<T> {
+    /// Returns the EIP-712 digest of the claim of `who` with `nonce` on this chain.
+    pub fn claim_digest(who: &T::AccountId, nonce: u64) -> [u8; 32] {
+        let chain_id = <T as pallet_evm::Config>::ChainId::get();
+        let genesis_hash = frame_system::Pallet::<T>::block_hash(T::BlockNumber::zero());
+        eip712_claim_digest(chain_id, genesis_hash.as_ref(), &who.encode(), nonce)
+    }
+
+    fn do_claim_account(who: T::AccountId, eth_address: H160) -> DispatchResult {
+        // ensure account_id and eth_address has not been mapped
+        ensure!(
+            !EvmAccounts::<T>::contains_key(&who),
+            Error::<T>::AccountIdHasMapped
+        );
+        ensure!(
+            !SubAccounts::<T>::contains_key(eth_address),
+            Error::<T>::EthAddressHasMapped
+        );
+
+        <T as xpallet_assets::Config>::Currency::reserve(&who, T::ClaimBond::get())?;
+
+        SubAccounts::<T>::insert(eth_address, &who);
+        EvmAccounts::<T>::insert(&who, eth_address);
+
+        Self::deposit_event(Event::ClaimAccount(who, eth_address));
+
+        Ok(())
+    }
+
     pub fn set_admin_inner(new_admin: T::AccountId) -> Weight {
         Admin::<T>::mutate(|admin| *admin = Some(new_admin));
         T::DbWeight::get().write
@@ -745,6 +840,15 @@ impl<T: Config> Pallet<T> {
         ensure!(amount > 0, Error::<T>::ZeroBalance);
 
         let mapping_account = AddressMappingOf::<T>::into_account_id(evm_account);
+        // The asset held by the ledger is issued in its own unit, the same as the swaps.
+        if <T as xpallet_assets::Config>::AssetLedger::holds(&asset_id) {
+            return xpallet_assets::Pallet::<T>::issue(
+                &asset_id,
+                &mapping_account,
+                amount.unique_saturated_into(),
+                false,
+            );
+        }
         <T as pallet_evm::Config>::Currency::deposit_creating(
             &mapping_account,
             amount.unique_saturated_into(),
@@ -788,6 +892,9 @@ impl<T: Config> Pallet<T> {
         Ok(())
     }
 
+    /// Swap BTC(btc ledger module) of the evm address `from` to XBTC(assets module) by 1:1.
+    ///
+    /// Once XBTC is held by the btc ledger, the BTC is already the XBTC and nothing is swapped.
     pub fn swap_btc_to_xbtc(from: H160, amount: u128) -> DispatchResult {
         let xbtc_asset_id = 1;
 
@@ -795,6 +902,10 @@ impl<T: Config> Pallet<T> {
             return Err(DispatchError::Other("in emergency"));
         };
 
+        if <T as xpallet_assets::Config>::AssetLedger::holds(&xbtc_asset_id) {
+            return Ok(());
+        }
+
         let mapping_account = AddressMappingOf::<T>::into_account_id(from);
 
         // 1. burn btc in chainx-wasm
@@ -842,10 +953,30 @@ impl<T: Config> Pallet<T> {
                 exit_reason: ExitReason::Succeed(_),
                 ..
             }) => Ok(()),
+            Ok(CallInfo {
+                exit_reason: ExitReason::Revert(_),
+                value,
+                ..
+            }) => Err(Self::revert_error(&value).into()),
             _ => Err(Error::<T>::ExecutedFailed.into()),
         }
     }
 
+    /// Returns the error of the reverted erc20 contract call given its `output`.
+    fn revert_error(output: &[u8]) -> Error<T> {
+        match decode_revert_reason(output).as_deref() {
+            Some(b"AssetsBridge: require called by the assets bridge admin address") => {
+                Error::<T>::Erc20RequireBridgeAdmin
+            }
+            Some(b"ERC20: burn amount exceeds balance")
+            | Some(b"ERC20: transfer amount exceeds balance") => {
+                Error::<T>::Erc20InsufficientBalance
+            }
+            Some(b"ERC20Pausable: token transfer while paused") => Error::<T>::Erc20Paused,
+            _ => Error::<T>::Erc20Reverted,
+        }
+    }
+
     fn is_in_emergency(asset_id: AssetId) -> bool {
         Self::emergencies()
             .iter()
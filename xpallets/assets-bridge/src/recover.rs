@@ -18,9 +18,14 @@ pub fn to_ascii_hex(data: &[u8]) -> Vec<u8> {
 /// using the Ethereum RPC's `personal_sign` and `eth_sign`.
 pub fn eth_recover(s: &EcdsaSignature, what: &[u8], extra: &[u8]) -> Option<H160> {
     let msg = keccak_256(&ethereum_signable_message(what, extra));
+    eth_recover_prehashed(s, &msg)
+}
+
+/// Attempts to recover the Ethereum address from the signature of the message hash `msg`.
+pub fn eth_recover_prehashed(s: &EcdsaSignature, msg: &[u8; 32]) -> Option<H160> {
     let mut res = H160::default();
     res.0
-        .copy_from_slice(&keccak_256(&secp256k1_ecdsa_recover(&s.0, &msg).ok()?[..])[12..]);
+        .copy_from_slice(&keccak_256(&secp256k1_ecdsa_recover(&s.0, msg).ok()?[..])[12..]);
     Some(res)
 }
 
@@ -41,3 +46,53 @@ pub fn ethereum_signable_message(what: &[u8], extra: &[u8]) -> Vec<u8> {
     v.extend_from_slice(extra);
     v
 }
+
+/// The EIP-712 type hash of the domain of the assets bridge.
+pub const EIP712_DOMAIN_TYPEHASH: [u8; 32] =
+    keccak256(b"EIP712Domain(string name,string version,uint256 chainId,bytes32 salt)");
+
+/// The EIP-712 type hash of the account claim, `account` is the SCALE encoded account id.
+pub const EIP712_CLAIM_TYPEHASH: [u8; 32] = keccak256(b"Claim(bytes account,uint256 nonce)");
+
+/// The name of the EIP-712 domain.
+pub const EIP712_DOMAIN_NAME: &[u8] = b"AssetsBridge";
+
+/// The version of the EIP-712 domain.
+pub const EIP712_DOMAIN_VERSION: &[u8] = b"1";
+
+fn u64_word(value: u64) -> [u8; 32] {
+    let mut word = [0u8; 32];
+    word[24..].copy_from_slice(&value.to_be_bytes());
+    word
+}
+
+/// Constructs the EIP-712 digest of the claim of `account` with `nonce`, which Ethereum RPC's
+/// `eth_signTypedData_v4` would sign.
+///
+/// The domain is separated by the ethereum `chain_id` and the `genesis_hash` as the salt, so
+/// that the claim can not be replayed across the chains.
+pub fn eip712_claim_digest(
+    chain_id: u64,
+    genesis_hash: &[u8],
+    account: &[u8],
+    nonce: u64,
+) -> [u8; 32] {
+    let mut salt = [0u8; 32];
+    let len = genesis_hash.len().min(32);
+    salt[..len].copy_from_slice(&genesis_hash[..len]);
+
+    let mut domain = EIP712_DOMAIN_TYPEHASH.to_vec();
+    domain.extend_from_slice(&keccak_256(EIP712_DOMAIN_NAME));
+    domain.extend_from_slice(&keccak_256(EIP712_DOMAIN_VERSION));
+    domain.extend_from_slice(&u64_word(chain_id));
+    domain.extend_from_slice(&salt);
+
+    let mut claim = EIP712_CLAIM_TYPEHASH.to_vec();
+    claim.extend_from_slice(&keccak_256(account));
+    claim.extend_from_slice(&u64_word(nonce));
+
+    let mut v = b"\x19\x01".to_vec();
+    v.extend_from_slice(&keccak_256(&domain));
+    v.extend_from_slice(&keccak_256(&claim));
+    keccak_256(&v)
+}
@@ -2,8 +2,8 @@
 
 use crate::mock::*;
 use crate::{to_ascii_hex, EcdsaSignature};
-use frame_support::{assert_noop, assert_ok};
-use sp_core::{H160, U256};
+use frame_support::{assert_noop, assert_ok, traits::Currency};
+use sp_core::{H160, H256, U256};
 
 use ethabi::{Function, Param, ParamType, Token};
 use hex_literal::hex;
@@ -513,3 +513,207 @@ fn bridge_accounts_should_equal() {
 
     assert_eq!(bridge_admin1, bridge_admin2);
 }
+
+#[test]
+fn abi_selectors_should_be_derived_from_signatures() {
+    assert_eq!(crate::MINT_INTO, mint_into_abi().short_signature());
+    assert_eq!(crate::BURN_FROM, burn_from_abi().short_signature());
+    assert_eq!(crate::ERROR_STRING, hex!("08c379a0"));
+    assert_eq!(
+        crate::APPROVAL_EVENT,
+        H256(hex!(
+            "8c5be1e5ebec7d5bd14f71427d1e84f3dd0314c0f7b2291e5b200ac8c7c3b925"
+        ))
+    );
+    assert_eq!(
+        crate::keccak256(&[0xab; 300]),
+        sp_io::hashing::keccak_256(&[0xab; 300])
+    );
+}
+
+#[test]
+fn abi_encode_and_decode_dynamic_types() {
+    use crate::abi::{self, ParamType};
+
+    let account = H160::from_slice(&EVM_ADDR);
+    let tokens = vec![
+        abi::Token::Address(account),
+        abi::Token::Bytes32(H256::repeat_byte(7)),
+        abi::Token::Bytes(vec![1u8; 33]),
+        abi::Token::String(b"BEVM".to_vec()),
+        abi::Token::Array(vec![
+            abi::Token::Uint(U256::from(1)),
+            abi::Token::Uint(U256::from(2)),
+        ]),
+        abi::Token::Array(vec![abi::Token::String(b"a".to_vec())]),
+    ];
+    let expected = ethabi::encode(&[
+        Token::Address(account),
+        Token::FixedBytes(vec![7u8; 32]),
+        Token::Bytes(vec![1u8; 33]),
+        Token::String("BEVM".into()),
+        Token::Array(vec![Token::Uint(1.into()), Token::Uint(2.into())]),
+        Token::Array(vec![Token::String("a".into())]),
+    ]);
+    let encoded = abi::encode(&tokens);
+    assert_eq!(encoded, expected);
+
+    let types = [
+        ParamType::Address,
+        ParamType::Bytes32,
+        ParamType::Bytes,
+        ParamType::String,
+        ParamType::Array(&ParamType::Uint),
+        ParamType::Array(&ParamType::String),
+    ];
+    assert_eq!(abi::decode(&types, &encoded), Ok(tokens));
+    assert_eq!(
+        abi::decode(&types, &encoded[..encoded.len() - 1]),
+        Err(abi::AbiError::InvalidData)
+    );
+
+    // the offsets and lengths out of the data must not overflow.
+    let max = ethabi::encode(&[Token::Uint(u32::MAX.into())]);
+    let huge_offset = [max.clone(), max.clone()].concat();
+    let huge_len = [ethabi::encode(&[Token::Uint(32.into())]), max].concat();
+    for data in [huge_offset, huge_len] {
+        assert_eq!(
+            abi::decode(&[ParamType::Bytes], &data),
+            Err(abi::AbiError::InvalidData)
+        );
+        assert_eq!(
+            abi::decode(&[ParamType::Array(&ParamType::Uint)], &data),
+            Err(abi::AbiError::InvalidData)
+        );
+    }
+}
+
+#[test]
+fn abi_decode_log_and_revert_reason() {
+    use crate::abi::{self, Token as AbiToken};
+
+    let from = H160::from_slice(&ERC20_1);
+    let to = H160::from_slice(&ERC20_2);
+    let topics = vec![crate::TRANSFER_EVENT, H256::from(from), H256::from(to)];
+    let data = ethabi::encode(&[Token::Uint(100.into())]);
+    assert_eq!(
+        abi::decode_log(
+            crate::TRANSFER_EVENT,
+            &crate::TRANSFER_EVENT_INPUTS,
+            &topics,
+            &data
+        ),
+        Ok(vec![
+            AbiToken::Address(from),
+            AbiToken::Address(to),
+            AbiToken::Uint(U256::from(100))
+        ])
+    );
+    assert_eq!(
+        abi::decode_log(
+            crate::TRANSFER_EVENT,
+            &crate::TRANSFER_EVENT_INPUTS,
+            &topics[1..],
+            &data
+        ),
+        Err(abi::AbiError::InvalidTopic)
+    );
+
+    let mut output = hex!("08c379a0").to_vec();
+    output.extend(ethabi::encode(&[Token::String(
+        "ERC20: burn amount exceeds balance".into(),
+    )]));
+    assert_eq!(
+        abi::decode_revert_reason(&output),
+        Some(b"ERC20: burn amount exceeds balance".to_vec())
+    );
+    assert_eq!(abi::decode_revert_reason(&output[4..]), None);
+}
+
+#[test]
+fn claim_account_with_typed_signature() {
+    use sp_core::Pair;
+
+    new_test_ext().execute_with(|| {
+        assert_eq!(
+            crate::EIP712_CLAIM_TYPEHASH,
+            sp_io::hashing::keccak_256(b"Claim(bytes account,uint256 nonce)")
+        );
+
+        let who: AccountId32 = BOB.into();
+        let pair = sp_core::ecdsa::Pair::from_seed(&[7u8; 32]);
+        let signature = pair.sign_prehashed(&XAssetsBridge::claim_digest(&who, 0));
+        let address =
+            crate::eth_recover_prehashed(&signature, &XAssetsBridge::claim_digest(&who, 0))
+                .unwrap();
+
+        // the legacy claims are disabled by default, the typed claims are not.
+        let legacy_who = AccountId32::from_str(SUB_ACCOUNT).unwrap();
+        let legacy_signature = EcdsaSignature::from_slice(&SIGNATURE).unwrap();
+        let legacy_address = H160::from_slice(&EVM_ADDR);
+        assert!(!XAssetsBridge::legacy_claim_enabled());
+        assert_noop!(
+            XAssetsBridge::claim_account(
+                Origin::signed(legacy_who.clone()),
+                legacy_address,
+                legacy_signature.clone()
+            ),
+            Error::<Test>::Deprecated
+        );
+
+        // the signature is bound to the nonce.
+        assert_noop!(
+            XAssetsBridge::claim_account_typed(
+                Origin::signed(who.clone()),
+                address,
+                pair.sign_prehashed(&XAssetsBridge::claim_digest(&who, 1))
+            ),
+            Error::<Test>::InvalidSignature
+        );
+
+        assert_ok!(XAssetsBridge::claim_account_typed(
+            Origin::signed(who.clone()),
+            address,
+            signature.clone()
+        ));
+        expect_event(XAssetsBridgeEvent::ClaimAccount(who.clone(), address));
+        assert_eq!(XAssetsBridge::evm_accounts(&who), Some(address));
+        assert_eq!(XAssetsBridge::claim_nonce(&who), 1);
+
+        // the claim can not be replayed once dissolved.
+        assert_ok!(XAssetsBridge::dissolve(Origin::signed(who.clone())));
+        assert_noop!(
+            XAssetsBridge::claim_account_typed(Origin::signed(who.clone()), address, signature),
+            Error::<Test>::InvalidSignature
+        );
+
+        // the typed claims do not enable the legacy claims.
+        assert_noop!(
+            XAssetsBridge::claim_account(
+                Origin::signed(legacy_who.clone()),
+                legacy_address,
+                legacy_signature.clone()
+            ),
+            Error::<Test>::Deprecated
+        );
+
+        assert_noop!(
+            XAssetsBridge::set_legacy_claim(Origin::signed(who), true),
+            sp_runtime::DispatchError::BadOrigin
+        );
+        assert_ok!(XAssetsBridge::set_legacy_claim(Origin::root(), true));
+        assert!(XAssetsBridge::legacy_claim_enabled());
+        expect_event(XAssetsBridgeEvent::LegacyClaim(true));
+
+        Balances::make_free_balance_be(&legacy_who, 1000);
+        assert_ok!(XAssetsBridge::claim_account(
+            Origin::signed(legacy_who.clone()),
+            legacy_address,
+            legacy_signature
+        ));
+        assert_eq!(
+            XAssetsBridge::evm_accounts(&legacy_who),
+            Some(legacy_address)
+        );
+    })
+}
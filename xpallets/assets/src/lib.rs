@@ -13,6 +13,7 @@ mod mock;
 #[cfg(test)]
 mod tests;
 
+pub mod migrations;
 pub mod traits;
 mod trigger;
 pub mod types;
@@ -38,7 +39,7 @@ use self::trigger::AssetChangedTrigger;
 use chainx_primitives::AssetId;
 use xpallet_support::traits::TreasuryAccount;
 
-pub use self::traits::{ChainT, OnAssetChanged};
+pub use self::traits::{AssetLedger, ChainT, OnAssetChanged};
 pub use self::types::{
     AssetErr, AssetRestrictions, AssetType, BalanceLock, TotalAssetInfo, WithdrawalLimit,
 };
@@ -77,15 +78,39 @@ pub mod pallet {
         /// The hook triggered whenever the asset balance of an account is changed.
         type OnAssetChanged: OnAssetChanged<Self::AccountId, BalanceOf<Self>>;
 
+        /// The ledger holding the balances of some assets in place of this pallet.
+        type AssetLedger: AssetLedger<Self::AccountId, BalanceOf<Self>>;
+
         /// Weight information for extrinsics in this pallet.
         type WeightInfo: WeightInfo;
     }
 
+    const STORAGE_VERSION: StorageVersion = StorageVersion::new(1);
+
     #[pallet::pallet]
     #[pallet::generate_store(pub(super) trait Store)]
+    #[pallet::storage_version(STORAGE_VERSION)]
     #[pallet::without_storage_info]
     pub struct Pallet<T>(PhantomData<T>);
 
+    #[pallet::hooks]
+    impl<T: Config> Hooks<BlockNumberFor<T>> for Pallet<T> {
+        fn on_runtime_upgrade() -> Weight {
+            if StorageVersion::get::<Pallet<T>>() < 1 {
+                let weight = migrations::migrate_to_asset_ledger::<T>();
+                STORAGE_VERSION.put::<Pallet<T>>();
+                weight.saturating_add(T::DbWeight::get().reads_writes(1, 1))
+            } else {
+                T::DbWeight::get().reads(1)
+            }
+        }
+
+        #[cfg(feature = "try-runtime")]
+        fn post_upgrade() -> Result<(), &'static str> {
+            migrations::check_asset_ledger::<T>()
+        }
+    }
+
     #[pallet::call]
     impl<T: Config> Pallet<T> {
         /// transfer between two accounts
@@ -303,7 +328,7 @@ impl<T: Config> Pallet<T> {
                         id,
                         TotalAssetInfo {
                             info,
-                            balance: Self::total_asset_balance(id),
+                            balance: Self::total_typed_balances(&id),
                             is_online: xpallet_assets_registrar::Pallet::<T>::is_online(&id),
                             restrictions: Self::asset_restrictions_of(id),
                         },
@@ -318,9 +343,24 @@ impl<T: Config> Pallet<T> {
     pub fn valid_assets_of(
         who: &T::AccountId,
     ) -> BTreeMap<AssetId, BTreeMap<AssetType, BalanceOf<T>>> {
-        AssetBalance::<T>::iter_prefix(who)
+        let mut assets = AssetBalance::<T>::iter_prefix(who)
             .filter(|(id, _)| xpallet_assets_registrar::Pallet::<T>::asset_online(id))
-            .collect()
+            .collect::<BTreeMap<_, _>>();
+        // The `Usable` balances of the assets held by the asset ledger are not in `AssetBalance`.
+        for id in xpallet_assets_registrar::Pallet::<T>::asset_ids() {
+            if T::AssetLedger::holds(&id)
+                && xpallet_assets_registrar::Pallet::<T>::asset_online(&id)
+            {
+                let usable = T::AssetLedger::free_balance(who);
+                if !usable.is_zero() {
+                    assets
+                        .entry(id)
+                        .or_default()
+                        .insert(AssetType::Usable, usable);
+                }
+            }
+        }
+        assets
     }
 
     /// Returns whether `restriction` is applied for given asset `id`.
@@ -368,22 +408,46 @@ impl<T: Config> Pallet<T> {
     // Public read functions.
     /// Returns the total issuance of asset `id` by far.
     pub fn total_issuance(id: &AssetId) -> BalanceOf<T> {
+        if T::AssetLedger::holds(id) {
+            return T::AssetLedger::total_issuance();
+        }
         let map = Self::total_asset_balance(id);
         map.values().fold(Zero::zero(), |acc, &x| acc + x)
     }
 
     /// Returns the total balance of asset `id` given the specific asset type `ty`.
     pub fn total_asset_balance_of(id: &AssetId, ty: AssetType) -> BalanceOf<T> {
-        Self::total_asset_balance(id)
+        Self::total_typed_balances(id)
             .get(&ty)
             .copied()
             .unwrap_or_default()
     }
 
+    /// Returns the total balances of asset `id` of all types, including the `Usable` balance
+    /// held by the asset ledger.
+    pub fn total_typed_balances(id: &AssetId) -> BTreeMap<AssetType, BalanceOf<T>> {
+        let mut total = Self::total_asset_balance(id);
+        if T::AssetLedger::holds(id) {
+            let reserved = total
+                .values()
+                .fold(Zero::zero(), |acc: BalanceOf<T>, &x| acc + x);
+            let usable = T::AssetLedger::total_issuance().saturating_sub(reserved);
+            if !usable.is_zero() {
+                total.insert(AssetType::Usable, usable);
+            }
+        }
+        total
+    }
+
     /// Returns the sum of all kinds of `who`'s balances given asset `id`.
     pub fn all_type_asset_balance(who: &T::AccountId, id: &AssetId) -> BalanceOf<T> {
         let map = Self::asset_balance(who, id);
-        map.values().fold(Zero::zero(), |acc, &x| acc + x)
+        let usable = if T::AssetLedger::holds(id) {
+            T::AssetLedger::free_balance(who)
+        } else {
+            Zero::zero()
+        };
+        map.values().fold(usable, |acc, &x| acc + x)
     }
 
     /// Returns the balance of `who` given the asset `id` and type `ty`.
@@ -412,6 +476,14 @@ impl<T: Config> Pallet<T> {
     /// Sets the free balance of `who` without sanity checks and triggering the asset changed hook.
     #[cfg(feature = "std")]
     pub fn force_set_free_balance(id: &AssetId, who: &T::AccountId, value: BalanceOf<T>) {
+        let old = Self::asset_typed_balance(who, id, AssetType::Usable);
+        if let Err(e) = Self::set_ledger_balance(who, id, AssetType::Usable, old, value) {
+            error!(
+                target: "runtime::assets",
+                "[force_set_free_balance] account:{:?}, asset:{}, error:{:?}",
+                who, id, e
+            );
+        }
         Self::make_type_balance_be(who, id, AssetType::Usable, value);
     }
 
@@ -497,6 +569,23 @@ impl<T: Config> Pallet<T> {
 
         AssetChangedTrigger::<T>::on_move_pre(id, from, from_type, to, to_type, value);
 
+        if T::AssetLedger::holds(id) {
+            T::AssetLedger::transfer(
+                from,
+                from_type != AssetType::Usable,
+                to,
+                to_type != AssetType::Usable,
+                value,
+            )
+            .map_err(|e| {
+                error!(
+                    target: "runtime::assets",
+                    "[move_balance] asset ledger transfer failed, id:{}, error:{:?}", id, e
+                );
+                AssetErr::NotEnough
+            })?;
+        }
+
         Self::make_type_balance_be(from, id, from_type, new_from_balance);
         Self::make_type_balance_be(to, id, to_type, new_to_balance);
 
@@ -525,6 +614,7 @@ impl<T: Config> Pallet<T> {
                 continue;
             }
 
+            Self::set_ledger_balance(who, id, type_, old_val, val)?;
             Self::make_type_balance_be(who, id, type_, val);
 
             AssetChangedTrigger::<T>::on_set_balance(id, who, type_, val)?;
@@ -534,12 +624,37 @@ impl<T: Config> Pallet<T> {
 
     /// Returns the balance of `who` given `asset_id` and `ty`.
     fn asset_typed_balance(who: &T::AccountId, asset_id: &AssetId, ty: AssetType) -> BalanceOf<T> {
+        if ty == AssetType::Usable && T::AssetLedger::holds(asset_id) {
+            return T::AssetLedger::free_balance(who);
+        }
         Self::asset_balance(who, asset_id)
             .get(&ty)
             .copied()
             .unwrap_or_default()
     }
 
+    /// Applies the change of `who`'s balance of type `ty` from `old` to `new` to the asset
+    /// ledger, if the asset `id` is held by it.
+    fn set_ledger_balance(
+        who: &T::AccountId,
+        id: &AssetId,
+        ty: AssetType,
+        old: BalanceOf<T>,
+        new: BalanceOf<T>,
+    ) -> DispatchResult {
+        if !T::AssetLedger::holds(id) {
+            return Ok(());
+        }
+        let reserved = ty != AssetType::Usable;
+        if new > old {
+            T::AssetLedger::deposit(who, reserved, new - old)
+        } else if new < old {
+            T::AssetLedger::withdraw(who, reserved, old - new)
+        } else {
+            Ok(())
+        }
+    }
+
     fn new_account(who: &T::AccountId) {
         info!(target: "runtime::assets", "[new_account] account:{:?}", who);
         // FIXME: handle the result properly.
@@ -568,6 +683,11 @@ impl<T: Config> Pallet<T> {
         type_: AssetType,
         new_balance: BalanceOf<T>,
     ) {
+        if type_ == AssetType::Usable && T::AssetLedger::holds(id) {
+            // The `Usable` balance lives in the asset ledger only.
+            return;
+        }
+
         let mut original: BalanceOf<T> = Zero::zero();
         let mut exists = false;
 
@@ -644,6 +764,9 @@ impl<T: Config> Pallet<T> {
 
         AssetChangedTrigger::<T>::on_issue_pre(id, who);
 
+        if T::AssetLedger::holds(id) {
+            T::AssetLedger::deposit(who, type_ != AssetType::Usable, value)?;
+        }
         Self::make_type_balance_be(who, id, type_, new);
 
         AssetChangedTrigger::<T>::on_issue_post(id, who, value, reward_pcx)?;
@@ -670,6 +793,9 @@ impl<T: Config> Pallet<T> {
 
         AssetChangedTrigger::<T>::on_destroy_pre(id, who);
 
+        if T::AssetLedger::holds(id) {
+            T::AssetLedger::withdraw(who, type_ != AssetType::Usable, value)?;
+        }
         Self::make_type_balance_be(who, id, type_, new);
 
         AssetChangedTrigger::<T>::on_destroy_post(id, who, value)?;
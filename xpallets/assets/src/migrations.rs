@@ -0,0 +1,109 @@
+// Copyright 2019-2023 ChainX Project Authors. Licensed under GPL-3.0.
+
+//! All migrations of this pallet.
+
+use frame_support::{log::info, traits::Get, weights::Weight};
+use sp_runtime::traits::Zero;
+use sp_std::prelude::*;
+
+use chainx_primitives::AssetId;
+
+use crate::{AssetBalance, AssetLedger, AssetType, BalanceOf, Config, TotalAssetBalance};
+
+/// Returns the assets held by the asset ledger.
+fn held_assets<T: Config>() -> Vec<AssetId> {
+    xpallet_assets_registrar::Pallet::<T>::asset_ids()
+        .filter(|id| T::AssetLedger::holds(id))
+        .collect()
+}
+
+/// Move the balances of the assets held by the asset ledger into the ledger.
+///
+/// The `Usable` balances become the free balances in the ledger, the balances of other types
+/// are reserved in the ledger and only their breakdown is kept in this pallet.
+pub fn migrate_to_asset_ledger<T: Config>() -> Weight {
+    let held = held_assets::<T>();
+    if held.is_empty() {
+        return T::DbWeight::get().reads(1);
+    }
+
+    let entries = AssetBalance::<T>::iter()
+        .filter(|(_, id, _)| held.contains(id))
+        .collect::<Vec<_>>();
+    let count = entries.len() as Weight;
+
+    for (who, id, mut balances) in entries {
+        let usable = balances.remove(&AssetType::Usable).unwrap_or_default();
+        let reserved = balances
+            .values()
+            .fold(Zero::zero(), |acc: BalanceOf<T>, &x| acc + x);
+
+        for (reserve, value) in [(false, usable), (true, reserved)] {
+            if value.is_zero() {
+                continue;
+            }
+            if let Err(e) = T::AssetLedger::deposit(&who, reserve, value) {
+                frame_support::log::error!(
+                    target: "runtime::assets",
+                    "[migrate_to_asset_ledger] account:{:?}, asset:{}, value:{:?}, error:{:?}",
+                    who, id, value, e
+                );
+            }
+        }
+
+        if balances.is_empty() {
+            AssetBalance::<T>::remove(&who, id);
+            frame_system::Pallet::<T>::dec_consumers(&who);
+        } else {
+            AssetBalance::<T>::insert(&who, id, balances);
+        }
+    }
+
+    for id in &held {
+        TotalAssetBalance::<T>::mutate(id, |total| total.remove(&AssetType::Usable));
+    }
+
+    info!(
+        target: "runtime::assets",
+        "migrated {} asset balances of {:?} into the asset ledger.",
+        count,
+        held,
+    );
+    // Each deposit into the ledger takes a few more reads and writes.
+    T::DbWeight::get().reads_writes(4 * count + 1, 4 * count + held.len() as Weight)
+}
+
+/// Ensure the balances of the assets held by the asset ledger are consistent with the ledger.
+///
+/// The `Usable` balances must be kept in the ledger only, and the reserved balance of each
+/// account in the ledger must equal to the sum of its breakdown in this pallet.
+#[cfg(feature = "try-runtime")]
+pub fn check_asset_ledger<T: Config>() -> Result<(), &'static str> {
+    use frame_support::ensure;
+
+    let held = held_assets::<T>();
+    for id in &held {
+        ensure!(
+            !TotalAssetBalance::<T>::get(id).contains_key(&AssetType::Usable),
+            "the total usable balance of the asset held by the ledger is still in xassets"
+        );
+    }
+
+    for (who, id, balances) in AssetBalance::<T>::iter() {
+        if !held.contains(&id) {
+            continue;
+        }
+        ensure!(
+            !balances.contains_key(&AssetType::Usable),
+            "the usable balance of the asset held by the ledger is still in xassets"
+        );
+        let reserved = balances
+            .values()
+            .fold(Zero::zero(), |acc: BalanceOf<T>, &x| acc + x);
+        ensure!(
+            reserved == T::AssetLedger::reserved_balance(&who),
+            "the reserved balance in the asset ledger mismatches the breakdown in xassets"
+        );
+    }
+    Ok(())
+}
@@ -1,6 +1,6 @@
 // Copyright 2019-2023 ChainX Project Authors. Licensed under GPL-3.0.
 
-use std::collections::BTreeMap;
+use std::{cell::RefCell, collections::BTreeMap};
 
 use sp_core::H256;
 use sp_runtime::{
@@ -8,12 +8,14 @@ use sp_runtime::{
     traits::{BlakeTwo256, IdentityLookup},
 };
 
-use frame_support::{parameter_types, sp_io, traits::GenesisBuild};
+use frame_support::{dispatch::DispatchResult, parameter_types, sp_io, traits::GenesisBuild};
 
 use chainx_primitives::AssetId;
 pub use xp_protocol::X_BTC;
 
-use crate::{self as xpallet_assets, AssetInfo, AssetRestrictions, Chain, Config, Error};
+use crate::{
+    self as xpallet_assets, AssetInfo, AssetLedger, AssetRestrictions, Chain, Config, Error,
+};
 
 /// The AccountId alias in this test module.
 pub(crate) type AccountId = u64;
@@ -100,9 +102,82 @@ impl Config for Test {
     type TreasuryAccount = ();
     type OnCreatedAccount = frame_system::Provider<Test>;
     type OnAssetChanged = ();
+    type AssetLedger = MockLedger;
     type WeightInfo = ();
 }
 
+thread_local! {
+    static LEDGER_HOLDS_X_BTC: RefCell<bool> = RefCell::new(false);
+    static LEDGER: RefCell<BTreeMap<AccountId, (Balance, Balance)>> = RefCell::new(BTreeMap::new());
+}
+
+/// Lets the mock ledger hold the balances of `X_BTC`.
+pub fn hold_x_btc_in_ledger() {
+    LEDGER_HOLDS_X_BTC.with(|v| *v.borrow_mut() = true);
+}
+
+/// Returns the (free, reserved) balance of `who` in the mock ledger.
+pub fn ledger_balance(who: &AccountId) -> (Balance, Balance) {
+    LEDGER.with(|l| l.borrow().get(who).copied().unwrap_or_default())
+}
+
+fn mutate_ledger_balance(
+    who: &AccountId,
+    reserved: bool,
+    f: impl FnOnce(Balance) -> Option<Balance>,
+) -> DispatchResult {
+    LEDGER.with(|l| {
+        let mut ledger = l.borrow_mut();
+        let (free, reserve) = ledger.entry(*who).or_default();
+        let balance = if reserved { reserve } else { free };
+        *balance = f(*balance).ok_or(XAssetsErr::InsufficientBalance)?;
+        Ok(())
+    })
+}
+
+pub struct MockLedger;
+impl AssetLedger<AccountId, Balance> for MockLedger {
+    fn holds(id: &AssetId) -> bool {
+        *id == X_BTC && LEDGER_HOLDS_X_BTC.with(|v| *v.borrow())
+    }
+
+    fn free_balance(who: &AccountId) -> Balance {
+        ledger_balance(who).0
+    }
+
+    fn reserved_balance(who: &AccountId) -> Balance {
+        ledger_balance(who).1
+    }
+
+    fn total_issuance() -> Balance {
+        LEDGER.with(|l| {
+            l.borrow()
+                .values()
+                .map(|(free, reserved)| free + reserved)
+                .sum()
+        })
+    }
+
+    fn deposit(who: &AccountId, reserved: bool, value: Balance) -> DispatchResult {
+        mutate_ledger_balance(who, reserved, |b| b.checked_add(value))
+    }
+
+    fn withdraw(who: &AccountId, reserved: bool, value: Balance) -> DispatchResult {
+        mutate_ledger_balance(who, reserved, |b| b.checked_sub(value))
+    }
+
+    fn transfer(
+        from: &AccountId,
+        from_reserved: bool,
+        to: &AccountId,
+        to_reserved: bool,
+        value: Balance,
+    ) -> DispatchResult {
+        Self::withdraw(from, from_reserved, value)?;
+        Self::deposit(to, to_reserved, value)
+    }
+}
+
 pub struct ExtBuilder;
 
 impl Default for ExtBuilder {
@@ -7,7 +7,7 @@ use xp_protocol::X_BTC;
 
 pub use super::mock::{ExtBuilder, Test};
 use crate::{
-    mock::{Balance, Origin, XAssets, XAssetsErr},
+    mock::{hold_x_btc_in_ledger, ledger_balance, Balance, Origin, XAssets, XAssetsErr},
     AssetBalance, AssetErr, AssetInfo, AssetRestrictions, AssetType, Chain, TotalAssetBalance,
 };
 
@@ -527,3 +527,97 @@ fn test_move() {
         assert_eq!(XAssets::usable_balance(&b, &token), 200 + 100 + 100);
     })
 }
+
+#[test]
+fn test_asset_held_by_ledger() {
+    ExtBuilder::default().build_no_endowed_and_execute(|| {
+        hold_x_btc_in_ledger();
+        let a: u64 = 1; // accountid
+        let b: u64 = 2; // accountid
+        let btc_id = X_BTC;
+
+        XAssets::issue(&btc_id, &a, 100, true).unwrap();
+        assert_eq!(ledger_balance(&a), (100, 0));
+        assert_eq!(XAssets::usable_balance(&a, &btc_id), 100);
+        // The usable balance is only kept in the ledger.
+        assert!(!AssetBalance::<Test>::contains_key(&a, &btc_id));
+
+        XAssets::move_balance(
+            &btc_id,
+            &a,
+            AssetType::Usable,
+            &a,
+            AssetType::ReservedWithdrawal,
+            30,
+        )
+        .unwrap();
+        XAssets::move_usable_balance(&btc_id, &a, &b, 20).unwrap();
+        assert_eq!(ledger_balance(&a), (50, 30));
+        assert_eq!(ledger_balance(&b), (20, 0));
+        assert_eq!(
+            XAssets::asset_balance(&a, &btc_id),
+            vec![(AssetType::ReservedWithdrawal, 30)]
+                .into_iter()
+                .collect()
+        );
+        assert_eq!(XAssets::all_type_asset_balance(&a, &btc_id), 80);
+        assert_eq!(
+            XAssets::move_usable_balance(&btc_id, &b, &a, 21),
+            Err(AssetErr::NotEnough)
+        );
+
+        XAssets::destroy_reserved_withdrawal(&btc_id, &a, 30).unwrap();
+        assert_eq!(ledger_balance(&a), (50, 0));
+        assert_eq!(XAssets::total_issuance(&btc_id), 70);
+        assert_eq!(
+            XAssets::total_asset_balance_of(&btc_id, AssetType::Usable),
+            70
+        );
+        assert!(TotalAssetBalance::<Test>::get(&btc_id).is_empty());
+    })
+}
+
+#[test]
+fn test_migrate_to_asset_ledger() {
+    use frame_support::traits::{Hooks, StorageVersion};
+
+    ExtBuilder::default().build_no_endowed_and_execute(|| {
+        let a: u64 = 1; // accountid
+        let b: u64 = 2; // accountid
+        let btc_id = X_BTC;
+
+        // the balances of the previous storage version, held by xassets.
+        XAssets::issue(&btc_id, &a, 100, true).unwrap();
+        XAssets::issue(&btc_id, &b, 20, true).unwrap();
+        XAssets::move_balance(
+            &btc_id,
+            &a,
+            AssetType::Usable,
+            &a,
+            AssetType::ReservedWithdrawal,
+            30,
+        )
+        .unwrap();
+        XAssets::move_balance(&btc_id, &a, AssetType::Usable, &a, AssetType::Locked, 10).unwrap();
+        StorageVersion::new(0).put::<XAssets>();
+
+        hold_x_btc_in_ledger();
+        XAssets::on_runtime_upgrade();
+
+        assert_eq!(ledger_balance(&a), (60, 40));
+        assert_eq!(ledger_balance(&b), (20, 0));
+        // only the breakdown of the reserved balances is kept in xassets.
+        assert_eq!(
+            XAssets::asset_balance(&a, &btc_id),
+            vec![(AssetType::Locked, 10), (AssetType::ReservedWithdrawal, 30)]
+                .into_iter()
+                .collect()
+        );
+        assert!(!AssetBalance::<Test>::contains_key(&b, &btc_id));
+        assert_eq!(XAssets::usable_balance(&a, &btc_id), 60);
+        assert_eq!(XAssets::all_type_asset_balance(&a, &btc_id), 100);
+        assert_eq!(XAssets::total_issuance(&btc_id), 120);
+        assert!(!TotalAssetBalance::<Test>::get(&btc_id).contains_key(&AssetType::Usable));
+        assert_eq!(StorageVersion::get::<XAssets>(), 1);
+    })
+}
@@ -74,3 +74,78 @@ pub trait OnAssetChanged<AccountId, Balance> {
         Ok(())
     }
 }
+
+/// The ledger holding the balances of an asset in place of this pallet.
+///
+/// The `Usable` balance of an asset held by the ledger is the free balance in the ledger, the
+/// other asset types are reserved in the ledger and only broken down by this pallet. All the
+/// balances are denominated in the asset unit.
+///
+/// The `*_pre` hooks of `OnAssetChanged` are not triggered by this pallet for the held assets,
+/// the ledger is expected to trigger them before each balance change, including the changes
+/// made directly in the ledger.
+pub trait AssetLedger<AccountId, Balance> {
+    /// Returns whether the balances of asset `id` are held by the ledger.
+    fn holds(id: &AssetId) -> bool;
+
+    /// Returns the free balance of `who` in the ledger.
+    fn free_balance(who: &AccountId) -> Balance;
+
+    /// Returns the reserved balance of `who` in the ledger.
+    fn reserved_balance(who: &AccountId) -> Balance;
+
+    /// Returns the total issuance of the ledger.
+    fn total_issuance() -> Balance;
+
+    /// Mints `value` into the free or reserved balance of `who`.
+    fn deposit(who: &AccountId, reserved: bool, value: Balance) -> DispatchResult;
+
+    /// Burns `value` from the free or reserved balance of `who`.
+    fn withdraw(who: &AccountId, reserved: bool, value: Balance) -> DispatchResult;
+
+    /// Moves `value` from the free or reserved balance of `from` to the free or reserved
+    /// balance of `to`.
+    fn transfer(
+        from: &AccountId,
+        from_reserved: bool,
+        to: &AccountId,
+        to_reserved: bool,
+        value: Balance,
+    ) -> DispatchResult;
+}
+
+impl<AccountId, Balance: Default> AssetLedger<AccountId, Balance> for () {
+    fn holds(_id: &AssetId) -> bool {
+        false
+    }
+
+    fn free_balance(_who: &AccountId) -> Balance {
+        Balance::default()
+    }
+
+    fn reserved_balance(_who: &AccountId) -> Balance {
+        Balance::default()
+    }
+
+    fn total_issuance() -> Balance {
+        Balance::default()
+    }
+
+    fn deposit(_who: &AccountId, _reserved: bool, _value: Balance) -> DispatchResult {
+        Err(DispatchError::Other("no asset is held by the ledger"))
+    }
+
+    fn withdraw(_who: &AccountId, _reserved: bool, _value: Balance) -> DispatchResult {
+        Err(DispatchError::Other("no asset is held by the ledger"))
+    }
+
+    fn transfer(
+        _from: &AccountId,
+        _from_reserved: bool,
+        _to: &AccountId,
+        _to_reserved: bool,
+        _value: Balance,
+    ) -> DispatchResult {
+        Err(DispatchError::Other("no asset is held by the ledger"))
+    }
+}
@@ -4,7 +4,7 @@ use frame_support::dispatch::DispatchResult;
 
 use chainx_primitives::AssetId;
 
-use crate::traits::OnAssetChanged;
+use crate::traits::{AssetLedger, OnAssetChanged};
 use crate::types::{AssetErr, AssetType};
 use crate::{BalanceOf, Config, Event, Pallet};
 
@@ -21,6 +21,9 @@ impl<T: Config> AssetChangedTrigger<T> {
         to_type: AssetType,
         value: BalanceOf<T>,
     ) {
+        if T::AssetLedger::holds(id) {
+            return;
+        }
         T::OnAssetChanged::on_move_pre(id, from, from_type, to, to_type, value);
     }
 
@@ -45,6 +48,9 @@ impl<T: Config> AssetChangedTrigger<T> {
     }
 
     pub fn on_issue_pre(id: &AssetId, who: &T::AccountId) {
+        if T::AssetLedger::holds(id) {
+            return;
+        }
         T::OnAssetChanged::on_issue_pre(id, who);
     }
 
@@ -63,6 +69,9 @@ impl<T: Config> AssetChangedTrigger<T> {
     }
 
     pub fn on_destroy_pre(id: &AssetId, who: &T::AccountId) {
+        if T::AssetLedger::holds(id) {
+            return;
+        }
         T::OnAssetChanged::on_destroy_pre(id, who);
     }
 
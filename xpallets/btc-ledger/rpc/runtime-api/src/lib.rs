@@ -2,9 +2,14 @@
 
 #![cfg_attr(not(feature = "std"), no_std)]
 
+use sp_std::prelude::*;
+
 use codec::Codec;
 
+pub use xpallet_btc_ledger::{BalanceChange, BalanceChangeReason, BalanceDelta};
+
 sp_api::decl_runtime_apis! {
+    #[api_version(2)]
     pub trait BtcLedgerApi<AccountId, Balance>
     where
         AccountId: Codec,
@@ -12,5 +17,7 @@ sp_api::decl_runtime_apis! {
     {
         fn get_balance(who: AccountId) -> Balance;
         fn get_total() -> Balance;
+        /// Return the balance changes of the block, derived from the events of the block.
+        fn balance_changes() -> Vec<BalanceChange<AccountId, Balance>>;
     }
 }
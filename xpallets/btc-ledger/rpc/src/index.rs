@@ -0,0 +1,415 @@
+// Copyright 2019-2023 ChainX Project Authors. Licensed under GPL-3.0.
+
+//! An optional node-side index of the BTC balance changes.
+//!
+//! The index is fed by the balance changes of the finalized blocks, which the runtime derives
+//! from the ledger events and the EVM fee events, so that the balance history of an account can
+//! be queried without replaying the blocks. The blocks are indexed with their state, hence the
+//! node has to keep the state of the history (`--pruning archive`) to index the past blocks, the
+//! blocks whose state is pruned are skipped with a warning.
+
+use std::fmt::Display;
+use std::marker::PhantomData;
+use std::path::Path;
+use std::str::FromStr;
+use std::sync::Arc;
+
+use codec::{Codec, Decode, Encode};
+use futures::StreamExt;
+use jsonrpc_derive::rpc;
+use kvdb::{DBTransaction, KeyValueDB};
+use serde::{Deserialize, Serialize};
+
+use sc_client_api::BlockchainEvents;
+use sp_api::{ApiExt, ProvideRuntimeApi};
+use sp_blockchain::HeaderBackend;
+use sp_runtime::{
+    generic::BlockId,
+    traits::{Block as BlockT, UniqueSaturatedInto},
+};
+
+use xp_rpc::{Error, ErrorCode, Result, RpcBalance};
+
+use xpallet_btc_ledger_runtime_api::{
+    BalanceChange, BalanceChangeReason, BalanceDelta, BtcLedgerApi as BtcLedgerRuntimeApi,
+};
+
+/// The maximum number of the balance changes returned in one page.
+pub const MAX_BALANCE_CHANGE_PAGE_SIZE: u32 = 1000;
+
+/// The index query failed.
+const INDEX_ERROR: i64 = 300;
+
+mod columns {
+    pub const NUM_COLUMNS: u32 = 3;
+
+    /// The number of the last indexed block.
+    pub const META: u32 = 0;
+    /// `account ++ block number ++ event index` => the balance change.
+    pub const CHANGES: u32 = 1;
+    /// `account ++ block number` => the total balance at the end of the block.
+    pub const BALANCES: u32 = 2;
+}
+
+const SYNCED_KEY: &[u8] = b"synced";
+
+/// The path of the index database in the frontier database directory.
+pub fn index_database_dir(frontier_database_dir: &Path) -> std::path::PathBuf {
+    frontier_database_dir.join("btc_ledger_index")
+}
+
+/// A balance change stored in the index.
+#[derive(Clone, PartialEq, Eq, Debug, Encode, Decode)]
+pub struct IndexedBalanceChange<Hash, Balance> {
+    pub block_number: u32,
+    pub block_hash: Hash,
+    pub event_index: u32,
+    pub extrinsic_index: Option<u32>,
+    pub reason: BalanceChangeReason,
+    pub delta: BalanceDelta<Balance>,
+    /// The total balance of the account at the end of the block.
+    pub balance: Balance,
+}
+
+/// The position of a balance change of an account in the index.
+#[derive(PartialEq, Eq, Clone, Copy, Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BalanceChangeCursor {
+    pub block_number: u32,
+    pub event_index: u32,
+}
+
+/// The balance change index backed by a database in the frontier database directory.
+pub struct BalanceIndex<Block, AccountId, Balance> {
+    db: Arc<dyn KeyValueDB>,
+    _marker: PhantomData<(Block, AccountId, Balance)>,
+}
+
+impl<Block, AccountId, Balance> BalanceIndex<Block, AccountId, Balance>
+where
+    Block: BlockT,
+    AccountId: Codec,
+    Balance: Codec,
+{
+    /// Open the index database at `path`, creating it if necessary.
+    pub fn open(path: &Path) -> std::result::Result<Self, String> {
+        std::fs::create_dir_all(path).map_err(|e| format!("{}", e))?;
+        let path = path
+            .to_str()
+            .ok_or_else(|| "Invalid BTC ledger index path".to_string())?;
+        let config = kvdb_rocksdb::DatabaseConfig::with_columns(columns::NUM_COLUMNS);
+        let db = kvdb_rocksdb::Database::open(&config, path).map_err(|e| format!("{}", e))?;
+        Ok(Self {
+            db: Arc::new(db),
+            _marker: PhantomData,
+        })
+    }
+
+    /// Return the number of the last indexed block.
+    pub fn synced(&self) -> Option<u32> {
+        self.db
+            .get(columns::META, SYNCED_KEY)
+            .ok()
+            .flatten()
+            .and_then(|value| u32::decode(&mut &value[..]).ok())
+    }
+
+    /// Index the balance changes of the block.
+    fn index_block(
+        &self,
+        number: u32,
+        hash: Block::Hash,
+        changes: Vec<BalanceChange<AccountId, Balance>>,
+    ) -> std::io::Result<()> {
+        let mut transaction = DBTransaction::new();
+        for change in changes {
+            let prefix = change.who.encode();
+            transaction.put(
+                columns::BALANCES,
+                &[&prefix[..], &number.to_be_bytes()].concat(),
+                &change.balance.encode(),
+            );
+            transaction.put_vec(
+                columns::CHANGES,
+                &change_key_of(&prefix, number, change.event_index),
+                IndexedBalanceChange {
+                    block_number: number,
+                    block_hash: hash,
+                    event_index: change.event_index,
+                    extrinsic_index: change.extrinsic_index,
+                    reason: change.reason,
+                    delta: change.delta,
+                    balance: change.balance,
+                }
+                .encode(),
+            );
+        }
+        transaction.put(columns::META, SYNCED_KEY, &number.encode());
+        self.db.write(transaction)
+    }
+
+    /// Index the finalized blocks which are not indexed yet.
+    pub fn sync<C>(&self, client: &C)
+    where
+        C: ProvideRuntimeApi<Block> + HeaderBackend<Block>,
+        C::Api: BtcLedgerRuntimeApi<Block, AccountId, Balance>,
+    {
+        let finalized: u32 = client.info().finalized_number.unique_saturated_into();
+        // The genesis block has no events.
+        let mut next = self.synced().map_or(1, |synced| synced + 1);
+        while next <= finalized {
+            let hash = match client.hash(next.into()) {
+                Ok(Some(hash)) => hash,
+                Ok(None) => return,
+                Err(e) => {
+                    log::error!(
+                        target: "btc-ledger-index",
+                        "Failed to get the block #{}: {:?}", next, e
+                    );
+                    return;
+                }
+            };
+
+            let at = BlockId::hash(hash);
+            let api = client.runtime_api();
+            let changes = match api
+                .api_version::<dyn BtcLedgerRuntimeApi<Block, AccountId, Balance>>(&at)
+                .and_then(|version| match version {
+                    Some(version) if version >= 2 => api.balance_changes(&at),
+                    // The runtime of the block has no balance changes.
+                    _ => Ok(Vec::new()),
+                }) {
+                Ok(changes) => changes,
+                // The state of the block is pruned, the block is skipped rather than retried
+                // forever.
+                Err(e) => {
+                    log::warn!(
+                        target: "btc-ledger-index",
+                        "Skip the block #{}, its balance changes are not available (pruned state?): {:?}",
+                        next,
+                        e
+                    );
+                    Vec::new()
+                }
+            };
+
+            if let Err(e) = self.index_block(next, hash, changes) {
+                log::error!(
+                    target: "btc-ledger-index",
+                    "Failed to index the block #{}: {:?}", next, e
+                );
+                return;
+            }
+            next += 1;
+        }
+    }
+
+    /// Return the total balance of the account at the end of the block, `None` if no balance
+    /// change of the account is indexed up to the block.
+    pub fn balance_at(&self, who: &AccountId, block_number: u32) -> Option<Balance> {
+        let prefix = who.encode();
+        self.db
+            .iter_with_prefix(columns::BALANCES, &prefix)
+            .map(|(key, value)| (block_number_of(&key[prefix.len()..]), value))
+            .take_while(|(number, _)| *number <= block_number)
+            .last()
+            .and_then(|(_, value)| Balance::decode(&mut &value[..]).ok())
+    }
+
+    /// Return at most `limit` balance changes of the account after the cursor `start_after`,
+    /// with the cursor of the next page if there are more.
+    pub fn balance_changes(
+        &self,
+        who: &AccountId,
+        start_after: Option<BalanceChangeCursor>,
+        limit: u32,
+    ) -> (
+        Vec<IndexedBalanceChange<Block::Hash, Balance>>,
+        Option<BalanceChangeCursor>,
+    ) {
+        let prefix = who.encode();
+        let start_after =
+            start_after.map(|c| change_key_of(&prefix, c.block_number, c.event_index));
+        let limit = limit.clamp(1, MAX_BALANCE_CHANGE_PAGE_SIZE) as usize;
+
+        let mut iter = self
+            .db
+            .iter_with_prefix(columns::CHANGES, &prefix)
+            .filter(|(key, _)| {
+                start_after
+                    .as_ref()
+                    .map_or(true, |start| key[..] > start[..])
+            })
+            .filter_map(|(_, value)| {
+                IndexedBalanceChange::<Block::Hash, Balance>::decode(&mut &value[..]).ok()
+            });
+        let changes = iter.by_ref().take(limit).collect::<Vec<_>>();
+        let next = if iter.next().is_some() {
+            changes.last().map(|change| BalanceChangeCursor {
+                block_number: change.block_number,
+                event_index: change.event_index,
+            })
+        } else {
+            None
+        };
+        (changes, next)
+    }
+}
+
+fn change_key_of(prefix: &[u8], block_number: u32, event_index: u32) -> Vec<u8> {
+    [
+        prefix,
+        &block_number.to_be_bytes(),
+        &event_index.to_be_bytes(),
+    ]
+    .concat()
+}
+
+fn block_number_of(key: &[u8]) -> u32 {
+    let mut number = [0u8; 4];
+    number.copy_from_slice(&key[..4]);
+    u32::from_be_bytes(number)
+}
+
+/// Keep the index in sync with the finalized blocks.
+pub async fn sync_balance_index<Block, C, AccountId, Balance>(
+    client: Arc<C>,
+    index: Arc<BalanceIndex<Block, AccountId, Balance>>,
+) where
+    Block: BlockT,
+    C: ProvideRuntimeApi<Block> + HeaderBackend<Block> + BlockchainEvents<Block>,
+    C::Api: BtcLedgerRuntimeApi<Block, AccountId, Balance>,
+    AccountId: Codec,
+    Balance: Codec,
+{
+    let mut finality_notifications = client.finality_notification_stream();
+    index.sync(&*client);
+    while finality_notifications.next().await.is_some() {
+        index.sync(&*client);
+    }
+}
+
+#[rpc]
+pub trait BtcLedgerIndexApi<BlockHash, AccountId, Balance>
+where
+    Balance: Display + FromStr,
+{
+    /// Return the total balance of an account at the end of the block from the index
+    #[rpc(name = "btcledger_getBalanceAt")]
+    fn btcledger_balance_at(
+        &self,
+        who: AccountId,
+        block_number: u32,
+    ) -> Result<Option<RpcBalance<Balance>>>;
+
+    /// Return a page of at most `limit` balance changes of an account from the index, starting
+    /// after the cursor `start_after` (the `next` of the previous page)
+    #[rpc(name = "btcledger_getBalanceChanges")]
+    fn btcledger_balance_changes(
+        &self,
+        who: AccountId,
+        start_after: Option<BalanceChangeCursor>,
+        limit: u32,
+    ) -> Result<RpcBalanceChangePage<BlockHash, Balance>>;
+}
+
+pub struct BtcLedgerIndex<Block, AccountId, Balance> {
+    index: Arc<BalanceIndex<Block, AccountId, Balance>>,
+}
+
+impl<Block, AccountId, Balance> BtcLedgerIndex<Block, AccountId, Balance> {
+    /// Create new `BtcLedgerIndex` with the given reference to the index.
+    pub fn new(index: Arc<BalanceIndex<Block, AccountId, Balance>>) -> Self {
+        Self { index }
+    }
+}
+
+impl<Block, AccountId, Balance> BtcLedgerIndexApi<<Block as BlockT>::Hash, AccountId, Balance>
+    for BtcLedgerIndex<Block, AccountId, Balance>
+where
+    Block: BlockT,
+    AccountId: Codec + Send + Sync + 'static,
+    Balance: Codec + Display + FromStr + Send + Sync + 'static,
+{
+    fn btcledger_balance_at(
+        &self,
+        who: AccountId,
+        block_number: u32,
+    ) -> Result<Option<RpcBalance<Balance>>> {
+        match self.index.synced() {
+            Some(synced) if block_number <= synced => {
+                Ok(self.index.balance_at(&who, block_number).map(Into::into))
+            }
+            synced => Err(Error {
+                code: ErrorCode::ServerError(INDEX_ERROR),
+                message: "Block is not indexed yet".into(),
+                data: Some(format!("last indexed block: {:?}", synced).into()),
+            }),
+        }
+    }
+
+    fn btcledger_balance_changes(
+        &self,
+        who: AccountId,
+        start_after: Option<BalanceChangeCursor>,
+        limit: u32,
+    ) -> Result<RpcBalanceChangePage<<Block as BlockT>::Hash, Balance>> {
+        let (changes, next) = self.index.balance_changes(&who, start_after, limit);
+        Ok(RpcBalanceChangePage {
+            changes: changes.into_iter().map(Into::into).collect(),
+            next,
+        })
+    }
+}
+
+#[derive(PartialEq, Eq, Clone, Serialize, Deserialize)]
+pub enum RpcBalanceDelta<Balance: Display + FromStr> {
+    Credit(RpcBalance<Balance>),
+    Debit(RpcBalance<Balance>),
+    Set(RpcBalance<Balance>),
+}
+
+impl<Balance: Display + FromStr> From<BalanceDelta<Balance>> for RpcBalanceDelta<Balance> {
+    fn from(delta: BalanceDelta<Balance>) -> Self {
+        match delta {
+            BalanceDelta::Credit(amount) => Self::Credit(amount.into()),
+            BalanceDelta::Debit(amount) => Self::Debit(amount.into()),
+            BalanceDelta::Set(amount) => Self::Set(amount.into()),
+        }
+    }
+}
+
+#[derive(PartialEq, Eq, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RpcBalanceChange<Hash, Balance: Display + FromStr> {
+    pub block_number: u32,
+    pub block_hash: Hash,
+    pub event_index: u32,
+    pub extrinsic_index: Option<u32>,
+    pub reason: BalanceChangeReason,
+    pub delta: RpcBalanceDelta<Balance>,
+    pub balance: RpcBalance<Balance>,
+}
+
+impl<Hash, Balance: Display + FromStr> From<IndexedBalanceChange<Hash, Balance>>
+    for RpcBalanceChange<Hash, Balance>
+{
+    fn from(change: IndexedBalanceChange<Hash, Balance>) -> Self {
+        Self {
+            block_number: change.block_number,
+            block_hash: change.block_hash,
+            event_index: change.event_index,
+            extrinsic_index: change.extrinsic_index,
+            reason: change.reason,
+            delta: change.delta.into(),
+            balance: change.balance.into(),
+        }
+    }
+}
+
+#[derive(PartialEq, Eq, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RpcBalanceChangePage<Hash, Balance: Display + FromStr> {
+    pub changes: Vec<RpcBalanceChange<Hash, Balance>>,
+    pub next: Option<BalanceChangeCursor>,
+}
@@ -1,5 +1,7 @@
 // Copyright 2019-2023 ChainX Project Authors. Licensed under GPL-3.0.
 
+pub mod index;
+
 use std::fmt::Display;
 use std::str::FromStr;
 use std::sync::Arc;
@@ -8,6 +8,7 @@
 #![cfg_attr(not(feature = "std"), no_std)]
 #![allow(clippy::comparison_chain)]
 
+pub mod migrations;
 #[cfg(test)]
 mod mock;
 #[cfg(test)]
@@ -21,12 +22,15 @@ use frame_support::{
     ensure,
     pallet_prelude::{DispatchResult, Get},
     traits::{
-        tokens::{fungible, DepositConsequence, WithdrawConsequence},
-        Currency, ExistenceRequirement, Imbalance, SignedImbalance, TryDrop, WithdrawReasons,
+        tokens::{fungible, BalanceStatus, DepositConsequence, WithdrawConsequence},
+        Currency, ExistenceRequirement, Imbalance, LockIdentifier, LockableCurrency, OnUnbalanced,
+        ReservableCurrency, SignedImbalance, TryDrop, WithdrawReasons,
     },
-    PalletId,
+    PalletId, WeakBoundedVec,
 };
 use scale_info::TypeInfo;
+#[cfg(feature = "std")]
+use serde::{Deserialize, Serialize};
 use sp_runtime::{
     traits::{
         AccountIdConversion, AtLeast32BitUnsigned, Bounded, CheckedAdd, CheckedSub,
@@ -34,7 +38,7 @@ use sp_runtime::{
     },
     ArithmeticError, DispatchError, RuntimeDebug,
 };
-use sp_std::{fmt::Debug, mem, prelude::*};
+use sp_std::{cmp, collections::btree_set::BTreeSet, fmt::Debug, mem, prelude::*};
 
 pub use pallet::*;
 
@@ -67,12 +71,69 @@ pub mod pallet {
         /// The btc-ledger's pallet id, used for deriving its sovereign account ID.
         #[pallet::constant]
         type PalletId: Get<PalletId>;
+
+        /// The minimum amount required to keep an account open.
+        #[pallet::constant]
+        type ExistentialDeposit: Get<Self::Balance>;
+
+        /// 1 satoshi in the unit of the ledger, the balances are viewed in satoshi outside the
+        /// ledger and the balances below it are tracked as the remainders. Must not be zero.
+        #[pallet::constant]
+        type Satoshi: Get<Self::Balance>;
+
+        /// Handler for the unbalanced reduction when removing a dust account.
+        type DustRemoval: OnUnbalanced<NegativeImbalance<Self>>;
+
+        /// The hook triggered before the balance of an account is changed.
+        type OnBalanceChange: OnBalanceChange<Self::AccountId>;
+
+        /// The maximum number of locks that should exist on an account.
+        /// Not strictly enforced, but used for weight estimation.
+        #[pallet::constant]
+        type MaxLocks: Get<u32>;
     }
 
     #[pallet::pallet]
     #[pallet::generate_store(pub(super) trait Store)]
     pub struct Pallet<T>(_);
 
+    #[pallet::hooks]
+    impl<T: Config> Hooks<BlockNumberFor<T>> for Pallet<T> {
+        fn on_runtime_upgrade() -> Weight {
+            let mut weight = T::DbWeight::get().reads(1);
+            if StorageVersion::<T>::get() == Releases::V1_0_0 {
+                weight = weight.saturating_add(migrations::migrate_to_v2::<T>());
+                StorageVersion::<T>::put(Releases::V2_0_0);
+                weight = weight.saturating_add(T::DbWeight::get().writes(1));
+            }
+            if StorageVersion::<T>::get() == Releases::V2_0_0 {
+                weight = weight.saturating_add(migrations::reap_dust_accounts::<T>());
+                weight = weight.saturating_add(migrations::init_total_remainder::<T>());
+                StorageVersion::<T>::put(Releases::V3_0_0);
+                weight = weight.saturating_add(T::DbWeight::get().reads_writes(1, 1));
+            }
+            weight
+        }
+
+        fn integrity_test() {
+            assert!(
+                !T::Satoshi::get().is_zero(),
+                "the satoshi of btc ledger is zero"
+            );
+        }
+
+        #[cfg(feature = "try-runtime")]
+        fn pre_upgrade() -> Result<(), &'static str> {
+            migrations::pre_migrate::<T>()
+        }
+
+        #[cfg(feature = "try-runtime")]
+        fn post_upgrade() -> Result<(), &'static str> {
+            Self::check_total_issuance()?;
+            Self::check_total_remainder()
+        }
+    }
+
     #[pallet::call]
     impl<T: Config> Pallet<T> {
         /// Transfer some liquid free balance to another account.
@@ -113,13 +174,24 @@ pub mod pallet {
 
             let who = T::Lookup::lookup(who)?;
 
+            let existential_deposit = T::ExistentialDeposit::get();
+            let can_reap = Self::can_reap(&who);
+            let wipeout = |account: &AccountData<T::Balance>| {
+                can_reap && new_free.saturating_add(account.reserved) < existential_deposit
+            };
+
             // First we try to modify the account's balance to the forced balance.
-            let old_free = Self::mutate_account(&who, |account| {
+            let (old_free, new_free) = Self::mutate_account(&who, |account| {
                 let old_free = account.free;
 
-                account.free = new_free;
+                // the account is reaped if the balance is below the existential deposit.
+                account.free = if wipeout(account) {
+                    Zero::zero()
+                } else {
+                    new_free
+                };
 
-                old_free
+                (old_free, account.free)
             })?;
 
             // This will adjust the total issuance, which was not done by the `mutate_account`
@@ -190,6 +262,35 @@ pub mod pallet {
             who: T::AccountId,
             amount: T::Balance,
         },
+        /// Some balance was reserved (moved from free to reserved).
+        Reserved {
+            who: T::AccountId,
+            amount: T::Balance,
+        },
+        /// Some balance was unreserved (moved from reserved to free).
+        Unreserved {
+            who: T::AccountId,
+            amount: T::Balance,
+        },
+        /// An account was removed whose balance was non-zero but below ExistentialDeposit,
+        /// resulting in an outright loss.
+        DustLost {
+            account: T::AccountId,
+            amount: T::Balance,
+        },
+        /// Some balance was moved from the reserve of the first account to the second account.
+        /// Final argument indicates the destination balance type.
+        ReserveRepatriated {
+            from: T::AccountId,
+            to: T::AccountId,
+            amount: T::Balance,
+            destination_status: BalanceStatus,
+        },
+        /// Some amount was removed from the reserved balance of the account.
+        Slashed {
+            who: T::AccountId,
+            amount: T::Balance,
+        },
     }
 
     #[pallet::error]
@@ -198,6 +299,12 @@ pub mod pallet {
         InsufficientBalance,
         /// Beneficiary account must pre-exist
         DeadAccount,
+        /// Account liquidity restrictions prevent withdrawal
+        LiquidityRestrictions,
+        /// Value too low to create account due to existential deposit
+        ExistentialDeposit,
+        /// Transfer/payment would kill account
+        KeepAlive,
     }
 
     /// The total units issued in the system.
@@ -205,6 +312,13 @@ pub mod pallet {
     #[pallet::getter(fn total_incoming)]
     pub type TotalInComing<T: Config> = StorageValue<_, T::Balance, ValueQuery>;
 
+    /// The sum of the balances below 1 satoshi of all the accounts, i.e. the remainders of the
+    /// free and reserved balances divided by `Satoshi`, which are excluded from the total
+    /// issuance in satoshi.
+    #[pallet::storage]
+    #[pallet::getter(fn total_remainder)]
+    pub type TotalRemainder<T: Config> = StorageValue<_, T::Balance, ValueQuery>;
+
     /// The Balances pallet example of storing the balance of an account.
     #[pallet::storage]
     pub type AccountStore<T: Config> = StorageMap<
@@ -216,6 +330,18 @@ pub mod pallet {
         GetDefault,
     >;
 
+    /// Any liquidity locks on some account balances.
+    /// NOTE: Should only be accessed when setting, changing and freeing a lock.
+    #[pallet::storage]
+    #[pallet::getter(fn locks)]
+    pub type Locks<T: Config> = StorageMap<
+        _,
+        Blake2_128Concat,
+        T::AccountId,
+        WeakBoundedVec<BalanceLock<T::Balance>, T::MaxLocks>,
+        ValueQuery,
+    >;
+
     /// Storage version of the pallet.
     ///
     #[pallet::storage]
@@ -243,8 +369,16 @@ pub mod pallet {
                 .iter()
                 .fold(Zero::zero(), |acc: T::Balance, &(_, n)| acc + n);
 
-            <TotalInComing<T>>::put(total);
-            <StorageVersion<T>>::put(Releases::V1_0_0);
+            let satoshi = T::Satoshi::get();
+            let remainder = self
+                .balances
+                .iter()
+                .fold(Zero::zero(), |acc: T::Balance, &(_, n)| acc + n % satoshi);
+
+            // The balances of the assets held by the ledger may be issued before.
+            <TotalInComing<T>>::mutate(|t| *t = t.saturating_add(total));
+            <TotalRemainder<T>>::mutate(|r| *r = r.saturating_add(remainder));
+            <StorageVersion<T>>::put(Releases::V3_0_0);
 
             // ensure no duplicates exist.
             let endowed_accounts = self
@@ -259,8 +393,21 @@ pub mod pallet {
                 "duplicate balances in genesis."
             );
 
+            for &(_, balance) in self.balances.iter() {
+                assert!(
+                    balance >= T::ExistentialDeposit::get(),
+                    "the balance of any account should always be at least the existential deposit.",
+                )
+            }
+
             for &(ref who, free) in self.balances.iter() {
-                AccountStore::<T>::insert(who, AccountData { free });
+                AccountStore::<T>::insert(
+                    who,
+                    AccountData {
+                        free,
+                        ..Default::default()
+                    },
+                );
             }
         }
     }
@@ -283,26 +430,124 @@ impl<T: Config> GenesisConfig<T> {
     }
 }
 
+/// Hook for doing stuff when the balance of an account changes.
+pub trait OnBalanceChange<AccountId> {
+    /// Triggered before the balance of `who` is changed.
+    fn on_balance_change_pre(_who: &AccountId) {}
+}
+
+impl<AccountId> OnBalanceChange<AccountId> for () {}
+
+/// A single lock on a balance. There can be many of these on an account and they "overlap", so the
+/// same balance is frozen by multiple locks.
+#[derive(Encode, Decode, Clone, PartialEq, Eq, RuntimeDebug, MaxEncodedLen, TypeInfo)]
+pub struct BalanceLock<Balance> {
+    /// An identifier for this lock. Only one lock may be in existence for each identifier.
+    pub id: LockIdentifier,
+    /// The amount which the free balance may not drop below when this lock is in effect.
+    pub amount: Balance,
+}
+
 /// All balance information for an account.
 #[derive(Encode, Decode, Clone, PartialEq, Eq, Default, RuntimeDebug, MaxEncodedLen, TypeInfo)]
 pub struct AccountData<Balance> {
+    /// Non-reserved part of the balance. There may still be restrictions on this, but it is the
+    /// total pool what may in principle be transferred, reserved.
+    ///
     /// This is the only balance that matters in terms of most operations on tokens. It
     /// alone is used to determine the balance when in the contract execution environment.
     pub free: Balance,
+    /// Balance which is reserved and may not be used at all.
+    ///
+    /// This balance is a 'reserve' balance that other subsystems use in order to set aside tokens
+    /// that are still 'owned' by the account holder, but which are suspendable.
+    pub reserved: Balance,
+    /// The amount that `free` may not drop below when withdrawing, i.e. the largest lock.
+    ///
+    /// The locks apply to the withdrawals of all reasons.
+    pub frozen: Balance,
 }
 
 impl<Balance: Saturating + Copy + Ord> AccountData<Balance> {
+    /// How much this account's balance can be reduced.
+    fn usable(&self) -> Balance {
+        self.free.saturating_sub(self.frozen)
+    }
+
+    /// The total balance in this account including any that is reserved and ignoring any frozen.
     fn total(&self) -> Balance {
-        self.free
+        self.free.saturating_add(self.reserved)
+    }
+}
+
+impl<Balance: AtLeast32BitUnsigned + Copy> AccountData<Balance> {
+    /// The balance of this account below `satoshi`, i.e. the remainders of the free and reserved
+    /// balances.
+    fn remainder(&self, satoshi: Balance) -> Balance {
+        (self.free % satoshi).saturating_add(self.reserved % satoshi)
     }
 }
 
+/// What caused a change of the balance of an account.
+#[derive(Encode, Decode, Clone, Copy, PartialEq, Eq, RuntimeDebug, TypeInfo)]
+#[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
+pub enum BalanceChangeReason {
+    /// A transfer between two accounts.
+    Transfer,
+    /// A deposit into the account.
+    Deposit,
+    /// A withdrawal from the account.
+    Withdraw,
+    /// The free balance was set by root.
+    BalanceSet,
+    /// The reserved balance was moved to another account.
+    ReserveRepatriated,
+    /// The account was reaped and the rest of its balance was lost.
+    DustLost,
+    /// The fee charged for an ethereum transaction.
+    EvmFee,
+    /// The refund of the unused fee of an ethereum transaction.
+    EvmFeeRefund,
+    /// The fee of an ethereum transaction paid to the block author.
+    FeeReward,
+    /// The reserved balance was slashed.
+    Slashed,
+}
+
+/// The change of the total balance of an account.
+#[derive(Encode, Decode, Clone, Copy, PartialEq, Eq, RuntimeDebug, TypeInfo)]
+pub enum BalanceDelta<Balance> {
+    /// The balance was increased by the amount.
+    Credit(Balance),
+    /// The balance was decreased by the amount.
+    Debit(Balance),
+    /// The free balance was set to the amount.
+    Set(Balance),
+}
+
+/// A balance change of an account in a block, derived from the ledger events.
+#[derive(Encode, Decode, Clone, PartialEq, Eq, RuntimeDebug, TypeInfo)]
+pub struct BalanceChange<AccountId, Balance> {
+    /// The account whose balance was changed.
+    pub who: AccountId,
+    /// The index of the event in the block.
+    pub event_index: u32,
+    /// The index of the extrinsic emitting the event, `None` for the block hooks.
+    pub extrinsic_index: Option<u32>,
+    pub reason: BalanceChangeReason,
+    pub delta: BalanceDelta<Balance>,
+    /// The total balance of the account at the end of the block.
+    pub balance: Balance,
+}
+
 // A value placed in storage that represents the current version of the Balances storage.
 // This value is used by the `on_runtime_upgrade` logic to determine whether we run
 // storage migration logic. This should match directly with the semantic versions of the Rust crate.
 #[derive(Encode, Decode, Clone, Copy, PartialEq, Eq, RuntimeDebug, MaxEncodedLen, TypeInfo)]
 enum Releases {
     V1_0_0,
+    V2_0_0,
+    V3_0_0,
 }
 
 impl Default for Releases {
@@ -326,6 +571,219 @@ impl<T: Config> Pallet<T> {
         TotalInComing::<T>::get()
     }
 
+    /// Get the free balance of an account in satoshi, without the remainder.
+    pub fn free_satoshi(who: impl sp_std::borrow::Borrow<T::AccountId>) -> T::Balance {
+        Self::account(who.borrow()).free / T::Satoshi::get()
+    }
+
+    /// Get the reserved balance of an account in satoshi, without the remainder.
+    pub fn reserved_satoshi(who: impl sp_std::borrow::Borrow<T::AccountId>) -> T::Balance {
+        Self::account(who.borrow()).reserved / T::Satoshi::get()
+    }
+
+    /// Get the total issuance in satoshi, without the remainders of all the accounts, which is
+    /// the sum of the free and reserved balances in satoshi of all the accounts.
+    pub fn total_issuance_satoshi() -> T::Balance {
+        TotalInComing::<T>::get().saturating_sub(TotalRemainder::<T>::get()) / T::Satoshi::get()
+    }
+
+    /// Ensure the total issuance equals to the sum of all the account balances.
+    #[cfg(any(feature = "try-runtime", test))]
+    pub fn check_total_issuance() -> Result<(), &'static str> {
+        let sum = AccountStore::<T>::iter_values()
+            .fold(Zero::zero(), |acc: T::Balance, account| {
+                acc.saturating_add(account.total())
+            });
+        ensure!(
+            sum == TotalInComing::<T>::get(),
+            "the total issuance of btc ledger mismatches the sum of account balances"
+        );
+        Ok(())
+    }
+
+    /// Ensure the total remainder equals to the sum of the account remainders, so that the total
+    /// issuance in satoshi equals to the sum of the account balances in satoshi.
+    #[cfg(any(feature = "try-runtime", test))]
+    pub fn check_total_remainder() -> Result<(), &'static str> {
+        let satoshi = T::Satoshi::get();
+        let (remainder, balance) = AccountStore::<T>::iter_values().fold(
+            (Zero::zero(), Zero::zero()),
+            |(remainder, balance): (T::Balance, T::Balance), account| {
+                (
+                    remainder.saturating_add(account.remainder(satoshi)),
+                    balance
+                        .saturating_add(account.free / satoshi)
+                        .saturating_add(account.reserved / satoshi),
+                )
+            },
+        );
+        ensure!(
+            remainder == TotalRemainder::<T>::get(),
+            "the total remainder of btc ledger mismatches the sum of account remainders"
+        );
+        ensure!(
+            balance == Self::total_issuance_satoshi(),
+            "the total issuance in satoshi of btc ledger mismatches the sum of account balances"
+        );
+        Ok(())
+    }
+
+    /// Derive the balance changes of the current block from the ledger `events`, which are
+    /// given with their event index and extrinsic index.
+    ///
+    /// The first `Withdraw` of an ethereum transaction (the extrinsics in `evm_extrinsics`) is
+    /// its fee. The fee payments are given as `(event index, extrinsic index, beneficiary,
+    /// amount)` in `fee_paid`, the `Deposit` of the same beneficiary and amount before a fee
+    /// payment of the same extrinsic is the fee reward, and the first other `Deposit` to the fee
+    /// payer before a fee payment of its ethereum transaction, no more than the fee, is the fee
+    /// refund. The changes of the reserved balance only are not included.
+    pub fn balance_changes(
+        events: Vec<(u32, Option<u32>, Event<T>)>,
+        evm_extrinsics: &BTreeSet<u32>,
+        fee_paid: &[(u32, Option<u32>, T::AccountId, T::Balance)],
+    ) -> Vec<BalanceChange<T::AccountId, T::Balance>> {
+        // (extrinsic index, fee payer, fee, refunded)
+        let mut evm_fee_payers = Vec::<(u32, T::AccountId, T::Balance, bool)>::new();
+        // the fee payments not matched by a reward yet.
+        let mut unrewarded = fee_paid.iter().map(|_| true).collect::<Vec<_>>();
+        let mut changes = Vec::new();
+        let mut push = |who: T::AccountId,
+                        event_index: u32,
+                        extrinsic_index: Option<u32>,
+                        reason: BalanceChangeReason,
+                        delta: BalanceDelta<T::Balance>| {
+            changes.push(BalanceChange {
+                balance: Self::account(&who).total(),
+                who,
+                event_index,
+                extrinsic_index,
+                reason,
+                delta,
+            })
+        };
+
+        for (event_index, extrinsic_index, event) in events {
+            let in_evm = extrinsic_index.map_or(false, |i| evm_extrinsics.contains(&i));
+            let fee_payer = extrinsic_index
+                .and_then(|i| evm_fee_payers.iter().position(|(index, ..)| *index == i));
+            // the fee payments of the same extrinsic after the event.
+            let pending_fee_paid = |i: usize| {
+                let (paid_index, paid_extrinsic, ..) = &fee_paid[i];
+                *paid_index > event_index && *paid_extrinsic == extrinsic_index
+            };
+            match event {
+                Event::Transfer { from, to, amount } => {
+                    let reason = BalanceChangeReason::Transfer;
+                    push(
+                        from,
+                        event_index,
+                        extrinsic_index,
+                        reason,
+                        BalanceDelta::Debit(amount),
+                    );
+                    push(
+                        to,
+                        event_index,
+                        extrinsic_index,
+                        reason,
+                        BalanceDelta::Credit(amount),
+                    );
+                }
+                Event::Deposit { who, amount } => {
+                    let reward = (0..fee_paid.len()).find(|&i| {
+                        unrewarded[i]
+                            && pending_fee_paid(i)
+                            && fee_paid[i].2 == who
+                            && fee_paid[i].3 == amount
+                    });
+                    let refund = fee_payer.filter(|&payer| {
+                        let (_, payer_id, fee, refunded) = &evm_fee_payers[payer];
+                        !*refunded
+                            && *payer_id == who
+                            && amount <= *fee
+                            && (0..fee_paid.len()).any(pending_fee_paid)
+                    });
+                    let reason = if let Some(i) = reward {
+                        unrewarded[i] = false;
+                        BalanceChangeReason::FeeReward
+                    } else if let Some(payer) = refund {
+                        evm_fee_payers[payer].3 = true;
+                        BalanceChangeReason::EvmFeeRefund
+                    } else {
+                        BalanceChangeReason::Deposit
+                    };
+                    push(
+                        who,
+                        event_index,
+                        extrinsic_index,
+                        reason,
+                        BalanceDelta::Credit(amount),
+                    );
+                }
+                Event::Withdraw { who, amount } => {
+                    let reason = match extrinsic_index {
+                        Some(i) if in_evm && fee_payer.is_none() => {
+                            evm_fee_payers.push((i, who.clone(), amount, false));
+                            BalanceChangeReason::EvmFee
+                        }
+                        _ => BalanceChangeReason::Withdraw,
+                    };
+                    push(
+                        who,
+                        event_index,
+                        extrinsic_index,
+                        reason,
+                        BalanceDelta::Debit(amount),
+                    );
+                }
+                Event::BalanceSet { who, free } => push(
+                    who,
+                    event_index,
+                    extrinsic_index,
+                    BalanceChangeReason::BalanceSet,
+                    BalanceDelta::Set(free),
+                ),
+                Event::DustLost { account, amount } => push(
+                    account,
+                    event_index,
+                    extrinsic_index,
+                    BalanceChangeReason::DustLost,
+                    BalanceDelta::Debit(amount),
+                ),
+                Event::ReserveRepatriated {
+                    from, to, amount, ..
+                } => {
+                    let reason = BalanceChangeReason::ReserveRepatriated;
+                    push(
+                        from,
+                        event_index,
+                        extrinsic_index,
+                        reason,
+                        BalanceDelta::Debit(amount),
+                    );
+                    push(
+                        to,
+                        event_index,
+                        extrinsic_index,
+                        reason,
+                        BalanceDelta::Credit(amount),
+                    );
+                }
+                Event::Slashed { who, amount } => push(
+                    who,
+                    event_index,
+                    extrinsic_index,
+                    BalanceChangeReason::Slashed,
+                    BalanceDelta::Debit(amount),
+                ),
+                // `Endowed` comes along with the deposit, the others don't change the total.
+                _ => {}
+            }
+        }
+
+        changes
+    }
+
     /// Get both the free balances of an account.
     fn account(who: &T::AccountId) -> AccountData<T::Balance> {
         AccountStore::<T>::get(&who)
@@ -344,16 +802,22 @@ impl<T: Config> Pallet<T> {
             return DepositConsequence::Overflow;
         }
 
-        match account.total().checked_add(&amount) {
-            // NOTE: We assume that we are a self-sufficient,
-            // so don't need to do any checks in the case of account creation.
-            Some(_x) => DepositConsequence::Success,
-            None => DepositConsequence::Overflow,
+        let new_total_balance = match account.total().checked_add(&amount) {
+            Some(x) => x,
+            None => return DepositConsequence::Overflow,
+        };
+
+        if new_total_balance < T::ExistentialDeposit::get() {
+            return DepositConsequence::BelowMinimum;
         }
+
+        // NOTE: We assume that we are a self-sufficient,
+        // so don't need to do any checks in the case of account creation.
+        DepositConsequence::Success
     }
 
     fn withdraw_consequence(
-        _who: &T::AccountId,
+        who: &T::AccountId,
         amount: T::Balance,
         account: &AccountData<T::Balance>,
     ) -> WithdrawConsequence<T::Balance> {
@@ -365,10 +829,23 @@ impl<T: Config> Pallet<T> {
             return WithdrawConsequence::Underflow;
         }
 
-        if account.total().checked_sub(&amount).is_none() {
-            return WithdrawConsequence::NoFunds;
+        let new_free = match account.free.checked_sub(&amount) {
+            Some(x) => x,
+            None => return WithdrawConsequence::NoFunds,
         };
 
+        // Eventual free funds must be no less than the frozen balance.
+        if new_free < account.frozen {
+            return WithdrawConsequence::Frozen;
+        }
+
+        // The account is reaped and the rest is lost if the total balance falls below the
+        // existential deposit.
+        let new_total_balance = new_free.saturating_add(account.reserved);
+        if new_total_balance < T::ExistentialDeposit::get() && Self::can_reap(who) {
+            return WithdrawConsequence::ReducedToZero(new_total_balance);
+        }
+
         // Enough free funds to have them be reduced.
         WithdrawConsequence::Success
     }
@@ -398,24 +875,212 @@ impl<T: Config> Pallet<T> {
         who: &T::AccountId,
         f: impl FnOnce(&mut AccountData<T::Balance>, bool) -> Result<R, E>,
     ) -> Result<R, E> {
-        AccountStore::<T>::try_mutate_exists(who, |maybe_account| {
-            let is_new = maybe_account.is_none();
-            let mut account = maybe_account.take().unwrap_or_default();
-            f(&mut account, is_new).map(move |result| {
-                if is_new {
-                    frame_system::Pallet::<T>::inc_sufficients(who);
-
-                    Self::deposit_event(Event::Endowed {
-                        account: who.clone(),
-                        free_balance: account.free,
-                    });
+        Self::try_mutate_account_with_dust(who, f).map(|(result, dust_cleaner)| {
+            drop(dust_cleaner);
+            result
+        })
+    }
+
+    /// Mutate an account to some new value, or delete it entirely with `None`. Will enforce
+    /// `ExistentialDeposit` law, annulling the account as needed. This will do nothing if the
+    /// result of `f` is an `Err`.
+    ///
+    /// It returns both the result from the closure, and an optional `DustCleaner` instance which
+    /// should be dropped once it is known that all nested mutates that could affect storage items
+    /// that the dust handler touches have completed.
+    ///
+    /// NOTE: LOW-LEVEL: This will not attempt to maintain total issuance.
+    /// It is expected that the caller will do this.
+    fn try_mutate_account_with_dust<R, E: From<DispatchError>>(
+        who: &T::AccountId,
+        f: impl FnOnce(&mut AccountData<T::Balance>, bool) -> Result<R, E>,
+    ) -> Result<(R, DustCleaner<T>), E> {
+        T::OnBalanceChange::on_balance_change_pre(who);
+
+        let satoshi = T::Satoshi::get();
+        let (result, reaped, remainders) =
+            AccountStore::<T>::try_mutate_exists(who, |maybe_account| {
+                let is_new = maybe_account.is_none();
+                let mut account = maybe_account.take().unwrap_or_default();
+                let old_remainder = account.remainder(satoshi);
+                f(&mut account, is_new).map(move |result| {
+                    let total = account.total();
+                    if total < T::ExistentialDeposit::get() && (is_new || Self::can_reap(who)) {
+                        // The account is not kept, the rest of the balance is the dust.
+                        return (result, Some((is_new, total)), (old_remainder, Zero::zero()));
+                    }
+
+                    if is_new {
+                        frame_system::Pallet::<T>::inc_sufficients(who);
+
+                        Self::deposit_event(Event::Endowed {
+                            account: who.clone(),
+                            free_balance: account.free,
+                        });
+                    }
+
+                    let new_remainder = account.remainder(satoshi);
+                    *maybe_account = Some(account);
+
+                    (result, None, (old_remainder, new_remainder))
+                })
+            })?;
+
+        let (old_remainder, new_remainder) = remainders;
+        if old_remainder != new_remainder {
+            TotalRemainder::<T>::mutate(|r| {
+                *r = r
+                    .saturating_add(new_remainder)
+                    .saturating_sub(old_remainder)
+            });
+        }
+
+        let dust_cleaner = match reaped {
+            Some((is_new, dust)) => {
+                if !is_new {
+                    Self::reap_account(who);
+                }
+                if dust.is_zero() {
+                    DustCleaner(None)
+                } else {
+                    DustCleaner(Some((who.clone(), NegativeImbalance::new(dust))))
                 }
+            }
+            None => DustCleaner(None),
+        };
+        Ok((result, dust_cleaner))
+    }
+
+    /// Whether the account can be reaped when its balance falls below the existential deposit.
+    ///
+    /// The account which has sent any transaction and is only kept by the sufficient reference of
+    /// the ledger, e.g. the account mapped from an EVM address, is never reaped, since dropping
+    /// the reference would kill the system account and reset its nonce, which allows replaying its
+    /// transactions.
+    pub(crate) fn can_reap(who: &T::AccountId) -> bool {
+        let account = frame_system::Account::<T>::get(who);
+        account.nonce.is_zero() || account.providers > 0 || account.sufficients > 1
+    }
 
-                *maybe_account = Some(account);
+    /// Clean up the removed account.
+    pub(crate) fn reap_account(who: &T::AccountId) {
+        Locks::<T>::remove(who);
+        // NOTE: the accounts endowed in the genesis hold no sufficient reference.
+        if frame_system::Pallet::<T>::sufficients(who) > 0 {
+            frame_system::Pallet::<T>::dec_sufficients(who);
+        }
+    }
 
-                result
-            })
-        })
+    /// Update the account entry for `who`, given the locks.
+    fn update_locks(who: &T::AccountId, locks: &[BalanceLock<T::Balance>]) {
+        let bounded_locks = WeakBoundedVec::<_, T::MaxLocks>::force_from(
+            locks.to_vec(),
+            Some("BtcLedger Update Locks"),
+        );
+
+        let frozen = bounded_locks
+            .iter()
+            .map(|lock| lock.amount)
+            .max()
+            .unwrap_or_else(Zero::zero);
+        if Self::mutate_account(who, |account| account.frozen = frozen).is_err() {
+            return;
+        }
+
+        if bounded_locks.is_empty() {
+            Locks::<T>::remove(who);
+        } else {
+            Locks::<T>::insert(who, bounded_locks);
+        }
+    }
+
+    /// Move the reserved balance of one account into the balance of another, according to
+    /// `status`.
+    ///
+    /// Is a no-op if the value to be moved is zero.
+    ///
+    /// NOTE: returns actual amount of transferred value in `Ok` case.
+    fn do_transfer_reserved(
+        slashed: &T::AccountId,
+        beneficiary: &T::AccountId,
+        value: T::Balance,
+        best_effort: bool,
+        status: BalanceStatus,
+    ) -> Result<T::Balance, DispatchError> {
+        if value.is_zero() {
+            return Ok(Zero::zero());
+        }
+
+        if slashed == beneficiary {
+            return match status {
+                BalanceStatus::Free => Ok(value
+                    .saturating_sub(<Self as ReservableCurrency<_>>::unreserve(slashed, value))),
+                BalanceStatus::Reserved => {
+                    Ok(value.min(<Self as ReservableCurrency<_>>::reserved_balance(slashed)))
+                }
+            };
+        }
+
+        let ((actual, maybe_dust_1), maybe_dust_2) = Self::try_mutate_account_with_dust(
+            beneficiary,
+            |to_account, is_new| -> Result<(T::Balance, DustCleaner<T>), DispatchError> {
+                ensure!(!is_new, Error::<T>::DeadAccount);
+                Self::try_mutate_account_with_dust(
+                    slashed,
+                    |from_account, _| -> Result<T::Balance, DispatchError> {
+                        let actual = cmp::min(from_account.reserved, value);
+                        ensure!(
+                            best_effort || actual == value,
+                            Error::<T>::InsufficientBalance
+                        );
+                        match status {
+                            BalanceStatus::Free => {
+                                to_account.free = to_account
+                                    .free
+                                    .checked_add(&actual)
+                                    .ok_or(ArithmeticError::Overflow)?
+                            }
+                            BalanceStatus::Reserved => {
+                                to_account.reserved = to_account
+                                    .reserved
+                                    .checked_add(&actual)
+                                    .ok_or(ArithmeticError::Overflow)?
+                            }
+                        }
+                        from_account.reserved -= actual;
+                        Ok(actual)
+                    },
+                )
+            },
+        )?;
+        drop(maybe_dust_1);
+        drop(maybe_dust_2);
+
+        Self::deposit_event(Event::ReserveRepatriated {
+            from: slashed.clone(),
+            to: beneficiary.clone(),
+            amount: actual,
+            destination_status: status,
+        });
+        Ok(actual)
+    }
+}
+
+/// The dust of a reaped account, handed to `T::DustRemoval` once dropped.
+///
+/// NOTE: it should only be dropped after all the nested account mutations have completed, since
+/// the dust handler may touch the same accounts.
+pub struct DustCleaner<T: Config>(Option<(T::AccountId, NegativeImbalance<T>)>);
+
+impl<T: Config> Drop for DustCleaner<T> {
+    fn drop(&mut self) {
+        if let Some((who, dust)) = self.0.take() {
+            Pallet::<T>::deposit_event(Event::DustLost {
+                account: who,
+                amount: dust.peek(),
+            });
+            T::DustRemoval::on_unbalanced(dust);
+        }
     }
 }
 
@@ -426,13 +1091,21 @@ impl<T: Config> fungible::Inspect<T::AccountId> for Pallet<T> {
         TotalInComing::<T>::get()
     }
     fn minimum_balance() -> Self::Balance {
-        Self::Balance::default()
+        T::ExistentialDeposit::get()
     }
     fn balance(who: &T::AccountId) -> Self::Balance {
         Self::account(who).total()
     }
-    fn reducible_balance(who: &T::AccountId, _keep_alive: bool) -> Self::Balance {
-        Self::account(who).total()
+    fn reducible_balance(who: &T::AccountId, keep_alive: bool) -> Self::Balance {
+        let account = Self::account(who);
+        if keep_alive {
+            // The reserved balance also keeps the account alive.
+            let must_remain_to_exist =
+                T::ExistentialDeposit::get().saturating_sub(account.reserved);
+            account.usable().saturating_sub(must_remain_to_exist)
+        } else {
+            account.usable()
+        }
     }
     fn can_deposit(who: &T::AccountId, amount: Self::Balance) -> DepositConsequence {
         Self::deposit_consequence(who, amount, &Self::account(who))
@@ -684,7 +1357,7 @@ where
     }
 
     fn minimum_balance() -> Self::Balance {
-        Self::Balance::default()
+        T::ExistentialDeposit::get()
     }
 
     // Burn funds from the total issuance, returning a positive imbalance for the amount burned.
@@ -722,12 +1395,22 @@ where
         Self::account(who).free
     }
 
+    // Ensure that an account can withdraw from their free balance given any existing withdrawal
+    // restrictions like locks. Is a no-op if amount to be withdrawn is zero.
     fn ensure_can_withdraw(
-        _who: &T::AccountId,
-        _amount: T::Balance,
+        who: &T::AccountId,
+        amount: T::Balance,
         _reasons: WithdrawReasons,
-        _new_balance: T::Balance,
+        new_balance: T::Balance,
     ) -> DispatchResult {
+        if amount.is_zero() {
+            return Ok(());
+        }
+        let min_balance = Self::account(who).frozen;
+        ensure!(
+            new_balance >= min_balance,
+            Error::<T>::LiquidityRestrictions
+        );
         Ok(())
     }
 
@@ -737,35 +1420,55 @@ where
         transactor: &T::AccountId,
         dest: &T::AccountId,
         value: Self::Balance,
-        _existence_requirement: ExistenceRequirement,
+        existence_requirement: ExistenceRequirement,
     ) -> DispatchResult {
         if value.is_zero() || transactor == dest {
             return Ok(());
         }
 
-        Self::try_mutate_account(dest, |to_account, _| -> DispatchResult {
-            Self::try_mutate_account(transactor, |from_account, _| -> DispatchResult {
-                from_account.free = from_account
-                    .free
-                    .checked_sub(&value)
-                    .ok_or(Error::<T>::InsufficientBalance)?;
-
-                // NOTE: total stake being stored in the same type means that this could
-                // never overflow but better to be safe than sorry.
-                to_account.free = to_account
-                    .free
-                    .checked_add(&value)
-                    .ok_or(ArithmeticError::Overflow)?;
-
-                Self::ensure_can_withdraw(
+        Self::try_mutate_account_with_dust(
+            dest,
+            |to_account, _| -> Result<DustCleaner<T>, DispatchError> {
+                Self::try_mutate_account_with_dust(
                     transactor,
-                    value,
-                    WithdrawReasons::TRANSFER,
-                    from_account.free,
-                )?;
-
-                Ok(())
-            })
+                    |from_account, _| -> DispatchResult {
+                        from_account.free = from_account
+                            .free
+                            .checked_sub(&value)
+                            .ok_or(Error::<T>::InsufficientBalance)?;
+
+                        // NOTE: total stake being stored in the same type means that this could
+                        // never overflow but better to be safe than sorry.
+                        to_account.free = to_account
+                            .free
+                            .checked_add(&value)
+                            .ok_or(ArithmeticError::Overflow)?;
+
+                        let ed = T::ExistentialDeposit::get();
+                        ensure!(to_account.total() >= ed, Error::<T>::ExistentialDeposit);
+
+                        Self::ensure_can_withdraw(
+                            transactor,
+                            value,
+                            WithdrawReasons::TRANSFER,
+                            from_account.free,
+                        )?;
+
+                        let allow_death = existence_requirement == ExistenceRequirement::AllowDeath;
+                        ensure!(
+                            allow_death || from_account.total() >= ed,
+                            Error::<T>::KeepAlive
+                        );
+
+                        Ok(())
+                    },
+                )
+                .map(|(_, maybe_dust_cleaner)| maybe_dust_cleaner)
+            },
+        )
+        .map(|(maybe_dust_1, maybe_dust_2)| {
+            drop(maybe_dust_1);
+            drop(maybe_dust_2);
         })?;
 
         // Emit transfer event.
@@ -817,6 +1520,7 @@ where
     ///
     /// This function is a no-op if:
     /// - the `value` to be deposited is zero; or
+    /// - the `value` is below the existential deposit and `who` does not exist; or
     /// - `value` is so large it would cause the balance of `who` to overflow.
     fn deposit_creating(who: &T::AccountId, value: Self::Balance) -> Self::PositiveImbalance {
         if value.is_zero() {
@@ -825,7 +1529,10 @@ where
 
         Self::try_mutate_account(
             who,
-            |account, _| -> Result<Self::PositiveImbalance, DispatchError> {
+            |account, is_new| -> Result<Self::PositiveImbalance, DispatchError> {
+                let ed = T::ExistentialDeposit::get();
+                ensure!(value >= ed || !is_new, Error::<T>::ExistentialDeposit);
+
                 // defensive only: overflow should never happen, however in case it does, then this
                 // operation is a no-op.
                 account.free = match account.free.checked_add(&value) {
@@ -851,7 +1558,7 @@ where
         who: &T::AccountId,
         value: Self::Balance,
         reasons: WithdrawReasons,
-        _liveness: ExistenceRequirement,
+        liveness: ExistenceRequirement,
     ) -> Result<Self::NegativeImbalance, DispatchError> {
         if value.is_zero() {
             return Ok(NegativeImbalance::zero());
@@ -865,6 +1572,15 @@ where
                     .checked_sub(&value)
                     .ok_or(Error::<T>::InsufficientBalance)?;
 
+                // bail if we need to keep the account alive and this would kill it.
+                let ed = T::ExistentialDeposit::get();
+                let would_be_dead = new_free_account.saturating_add(account.reserved) < ed;
+                let would_kill = would_be_dead && account.total() >= ed;
+                ensure!(
+                    liveness == ExistenceRequirement::AllowDeath || !would_kill,
+                    Error::<T>::KeepAlive
+                );
+
                 Self::ensure_can_withdraw(who, value, reasons, new_free_account)?;
 
                 account.free = new_free_account;
@@ -885,8 +1601,16 @@ where
     ) -> SignedImbalance<Self::Balance, Self::PositiveImbalance> {
         Self::try_mutate_account(
             who,
-            |account, _|
+            |account, is_new|
              -> Result<SignedImbalance<Self::Balance, Self::PositiveImbalance>, DispatchError> {
+                // Bypass the entire operation if a new account would be created below the
+                // existential deposit, the created account would be reaped immediately anyway.
+                let ed = T::ExistentialDeposit::get();
+                ensure!(
+                    value.saturating_add(account.reserved) >= ed || !is_new,
+                    Error::<T>::ExistentialDeposit
+                );
+
                 let imbalance = if account.free <= value {
                     SignedImbalance::Positive(PositiveImbalance::new(value - account.free))
                 } else {
@@ -903,3 +1627,292 @@ where
         .unwrap_or_else(|_| SignedImbalance::Positive(Self::PositiveImbalance::zero()))
     }
 }
+
+impl<T: Config> ReservableCurrency<T::AccountId> for Pallet<T>
+where
+    T::Balance: MaybeSerializeDeserialize + Debug,
+{
+    /// Check if `who` can reserve `value` from their free balance.
+    ///
+    /// Always `true` if value to be reserved is zero.
+    fn can_reserve(who: &T::AccountId, value: Self::Balance) -> bool {
+        if value.is_zero() {
+            return true;
+        }
+        Self::account(who)
+            .free
+            .checked_sub(&value)
+            .map_or(false, |new_balance| {
+                Self::ensure_can_withdraw(who, value, WithdrawReasons::RESERVE, new_balance).is_ok()
+            })
+    }
+
+    fn reserved_balance(who: &T::AccountId) -> Self::Balance {
+        Self::account(who).reserved
+    }
+
+    /// Move `value` from the free balance from `who` to their reserved balance.
+    ///
+    /// Is a no-op if value to be reserved is zero.
+    fn reserve(who: &T::AccountId, value: Self::Balance) -> DispatchResult {
+        if value.is_zero() {
+            return Ok(());
+        }
+
+        Self::try_mutate_account(who, |account, _| -> DispatchResult {
+            account.free = account
+                .free
+                .checked_sub(&value)
+                .ok_or(Error::<T>::InsufficientBalance)?;
+            account.reserved = account
+                .reserved
+                .checked_add(&value)
+                .ok_or(ArithmeticError::Overflow)?;
+            Self::ensure_can_withdraw(who, value, WithdrawReasons::RESERVE, account.free)
+        })?;
+
+        Self::deposit_event(Event::Reserved {
+            who: who.clone(),
+            amount: value,
+        });
+        Ok(())
+    }
+
+    /// Unreserve some funds, returning any amount that was unable to be unreserved.
+    ///
+    /// Is a no-op if the value to be unreserved is zero or the account does not exist.
+    fn unreserve(who: &T::AccountId, value: Self::Balance) -> Self::Balance {
+        if value.is_zero() {
+            return Zero::zero();
+        }
+        if Self::total_balance(who).is_zero() {
+            return value;
+        }
+
+        let actual = match Self::mutate_account(who, |account| {
+            let actual = cmp::min(account.reserved, value);
+            account.reserved -= actual;
+            // defensive only: this can never fail since total issuance which is at least
+            // free+reserved fits into the same data type.
+            account.free = account.free.saturating_add(actual);
+            actual
+        }) {
+            Ok(x) => x,
+            Err(_) => {
+                // This should never happen since we don't alter the total amount in the account.
+                // If it ever does, then we should fail gracefully though, indicating that nothing
+                // could be done.
+                return value;
+            }
+        };
+
+        Self::deposit_event(Event::Unreserved {
+            who: who.clone(),
+            amount: actual,
+        });
+        value - actual
+    }
+
+    /// Slash from reserved balance, returning the negative imbalance created,
+    /// and any amount that was unable to be slashed.
+    ///
+    /// Is a no-op if the value to be slashed is zero or the account does not exist.
+    fn slash_reserved(
+        who: &T::AccountId,
+        value: Self::Balance,
+    ) -> (Self::NegativeImbalance, Self::Balance) {
+        if value.is_zero() {
+            return (NegativeImbalance::zero(), Zero::zero());
+        }
+        if Self::total_balance(who).is_zero() {
+            return (NegativeImbalance::zero(), value);
+        }
+
+        match Self::mutate_account(who, |account| {
+            let actual = cmp::min(account.reserved, value);
+            account.reserved -= actual;
+            (NegativeImbalance::new(actual), value - actual)
+        }) {
+            Ok((imbalance, not_slashed)) => {
+                if !imbalance.peek().is_zero() {
+                    Self::deposit_event(Event::Slashed {
+                        who: who.clone(),
+                        amount: imbalance.peek(),
+                    });
+                }
+                (imbalance, not_slashed)
+            }
+            // This should never happen since we don't alter the total amount in the account.
+            Err(_) => (NegativeImbalance::zero(), value),
+        }
+    }
+
+    /// Move the reserved balance of one account into the balance of another, according to
+    /// `status`.
+    ///
+    /// Is a no-op if:
+    /// - the value to be moved is zero; or
+    /// - the `slashed` id equal to `beneficiary` and the `status` is `Reserved`.
+    fn repatriate_reserved(
+        slashed: &T::AccountId,
+        beneficiary: &T::AccountId,
+        value: Self::Balance,
+        status: BalanceStatus,
+    ) -> Result<Self::Balance, DispatchError> {
+        let actual = Self::do_transfer_reserved(slashed, beneficiary, value, true, status)?;
+        Ok(value.saturating_sub(actual))
+    }
+}
+
+impl<T: Config> LockableCurrency<T::AccountId> for Pallet<T>
+where
+    T::Balance: MaybeSerializeDeserialize + Debug,
+{
+    type Moment = T::BlockNumber;
+
+    type MaxLocks = T::MaxLocks;
+
+    // Set a lock on the balance of `who`.
+    // Is a no-op if lock amount is zero or `reasons` `is_none()`.
+    fn set_lock(
+        id: LockIdentifier,
+        who: &T::AccountId,
+        amount: T::Balance,
+        reasons: WithdrawReasons,
+    ) {
+        if amount.is_zero() || reasons.is_empty() {
+            return;
+        }
+        let mut new_lock = Some(BalanceLock { id, amount });
+        let mut locks = Self::locks(who)
+            .into_iter()
+            .filter_map(|lock| {
+                if lock.id == id {
+                    new_lock.take()
+                } else {
+                    Some(lock)
+                }
+            })
+            .collect::<Vec<_>>();
+        if let Some(lock) = new_lock {
+            locks.push(lock)
+        }
+        Self::update_locks(who, &locks[..]);
+    }
+
+    // Extend a lock on the balance of `who`.
+    // Is a no-op if lock amount is zero or `reasons` `is_none()`.
+    fn extend_lock(
+        id: LockIdentifier,
+        who: &T::AccountId,
+        amount: T::Balance,
+        reasons: WithdrawReasons,
+    ) {
+        if amount.is_zero() || reasons.is_empty() {
+            return;
+        }
+        let mut new_lock = Some(BalanceLock { id, amount });
+        let mut locks = Self::locks(who)
+            .into_iter()
+            .filter_map(|lock| {
+                if lock.id == id {
+                    new_lock.take().map(|nl| BalanceLock {
+                        id: lock.id,
+                        amount: lock.amount.max(nl.amount),
+                    })
+                } else {
+                    Some(lock)
+                }
+            })
+            .collect::<Vec<_>>();
+        if let Some(lock) = new_lock {
+            locks.push(lock)
+        }
+        Self::update_locks(who, &locks[..]);
+    }
+
+    fn remove_lock(id: LockIdentifier, who: &T::AccountId) {
+        let locks = Self::locks(who)
+            .into_iter()
+            .filter(|lock| lock.id != id)
+            .collect::<Vec<_>>();
+        Self::update_locks(who, &locks[..]);
+    }
+}
+
+impl<T: Config> fungible::Transfer<T::AccountId> for Pallet<T> {
+    fn transfer(
+        source: &T::AccountId,
+        dest: &T::AccountId,
+        amount: T::Balance,
+        keep_alive: bool,
+    ) -> Result<T::Balance, DispatchError> {
+        let er = if keep_alive {
+            ExistenceRequirement::KeepAlive
+        } else {
+            ExistenceRequirement::AllowDeath
+        };
+        <Self as Currency<T::AccountId>>::transfer(source, dest, amount, er).map(|_| amount)
+    }
+}
+
+impl<T: Config> fungible::InspectHold<T::AccountId> for Pallet<T> {
+    fn balance_on_hold(who: &T::AccountId) -> T::Balance {
+        Self::account(who).reserved
+    }
+    fn can_hold(who: &T::AccountId, amount: T::Balance) -> bool {
+        <Self as ReservableCurrency<T::AccountId>>::can_reserve(who, amount)
+    }
+}
+
+impl<T: Config> fungible::MutateHold<T::AccountId> for Pallet<T> {
+    fn hold(who: &T::AccountId, amount: Self::Balance) -> DispatchResult {
+        <Self as ReservableCurrency<T::AccountId>>::reserve(who, amount)
+    }
+
+    fn release(
+        who: &T::AccountId,
+        amount: Self::Balance,
+        best_effort: bool,
+    ) -> Result<T::Balance, DispatchError> {
+        if amount.is_zero() {
+            return Ok(amount);
+        }
+
+        let actual =
+            Self::try_mutate_account(who, |account, _| -> Result<T::Balance, DispatchError> {
+                let actual = cmp::min(account.reserved, amount);
+                ensure!(
+                    best_effort || actual == amount,
+                    Error::<T>::InsufficientBalance
+                );
+                account.reserved -= actual;
+                account.free = account
+                    .free
+                    .checked_add(&actual)
+                    .ok_or(ArithmeticError::Overflow)?;
+                Ok(actual)
+            })?;
+
+        Self::deposit_event(Event::Unreserved {
+            who: who.clone(),
+            amount: actual,
+        });
+        Ok(actual)
+    }
+
+    fn transfer_held(
+        source: &T::AccountId,
+        dest: &T::AccountId,
+        amount: Self::Balance,
+        best_effort: bool,
+        on_hold: bool,
+    ) -> Result<Self::Balance, DispatchError> {
+        let status = if on_hold {
+            BalanceStatus::Reserved
+        } else {
+            BalanceStatus::Free
+        };
+        Self::do_transfer_reserved(source, dest, amount, best_effort, status)
+    }
+}
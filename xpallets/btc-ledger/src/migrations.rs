@@ -0,0 +1,138 @@
+// Copyright 2019-2023 ChainX Project Authors. Licensed under GPL-3.0.
+
+//! All migrations of this pallet.
+
+use crate::{
+    AccountData, AccountStore, Config, DustCleaner, NegativeImbalance, Pallet, TotalRemainder,
+};
+#[cfg(any(feature = "try-runtime", test))]
+use crate::{Releases, StorageVersion, TotalInComing};
+use codec::{Decode, Encode};
+#[cfg(any(feature = "try-runtime", test))]
+use frame_support::{ensure, storage::unhashed};
+use frame_support::{log::info, traits::Get, weights::Weight};
+use sp_runtime::traits::{Saturating, Zero};
+use sp_std::prelude::*;
+
+/// The account data of `Releases::V1_0_0`, with the free balance only.
+#[derive(Encode, Decode)]
+struct OldAccountData<Balance> {
+    free: Balance,
+}
+
+/// Ensure the balances of `Releases::V1_0_0` are in the unit of the ledger, i.e. 1e-18 BTC as
+/// the EVM shows them, which the migrations keep as they are.
+///
+/// The accounts below the existential deposit, 1 satoshi in the unit of the ledger, are reaped by
+/// the migrations. Had the balances been in satoshi, nearly every account would be below it, so
+/// the balances of these accounts must be negligible, i.e. no more than 0.01% of the total.
+#[cfg(any(feature = "try-runtime", test))]
+pub fn pre_migrate<T: Config>() -> Result<(), &'static str> {
+    if StorageVersion::<T>::get() != Releases::V1_0_0 {
+        return Ok(());
+    }
+    let existential_deposit = T::ExistentialDeposit::get();
+    let (total, dust) = AccountStore::<T>::iter_keys().fold(
+        (Zero::zero(), Zero::zero()),
+        |(total, dust): (T::Balance, T::Balance), who| {
+            let free = unhashed::get::<OldAccountData<T::Balance>>(
+                &AccountStore::<T>::hashed_key_for(&who),
+            )
+            .map(|account| account.free)
+            .unwrap_or_default();
+            if free < existential_deposit {
+                (total.saturating_add(free), dust.saturating_add(free))
+            } else {
+                (total.saturating_add(free), dust)
+            }
+        },
+    );
+    ensure!(
+        total == TotalInComing::<T>::get(),
+        "the total issuance of btc ledger v1 mismatches the sum of account balances"
+    );
+    ensure!(
+        dust.saturating_mul(10_000u32.into()) <= total,
+        "the balances of btc ledger v1 are not in the unit of the ledger"
+    );
+    Ok(())
+}
+
+/// Migrate the account data from `Releases::V1_0_0` to `Releases::V2_0_0`, adding the empty
+/// reserved and frozen balances.
+pub fn migrate_to_v2<T: Config>() -> Weight {
+    let mut count: Weight = 0;
+    AccountStore::<T>::translate::<OldAccountData<T::Balance>, _>(|_, old| {
+        count += 1;
+        Some(AccountData {
+            free: old.free,
+            reserved: Zero::zero(),
+            frozen: Zero::zero(),
+        })
+    });
+    info!(
+        target: "runtime::btc_ledger",
+        "migrated {} accounts to the btc ledger v2.",
+        count,
+    );
+    <T as frame_system::Config>::DbWeight::get().reads_writes(count, count)
+}
+
+/// Reap the accounts which were endowed below the existential deposit before it was introduced,
+/// the dust is handled the same as a reaped account. The accounts which can't be reaped are kept.
+pub fn reap_dust_accounts<T: Config>() -> Weight {
+    let existential_deposit = T::ExistentialDeposit::get();
+    let mut reads: Weight = 0;
+    let dust_accounts = AccountStore::<T>::iter()
+        .filter(|(_, account)| {
+            reads += 1;
+            account.total() < existential_deposit
+        })
+        .collect::<Vec<_>>();
+    let mut count: Weight = 0;
+    for (who, account) in dust_accounts.iter() {
+        if !Pallet::<T>::can_reap(who) {
+            continue;
+        }
+        count += 1;
+        AccountStore::<T>::remove(who);
+        Pallet::<T>::reap_account(who);
+        if !account.total().is_zero() {
+            drop(DustCleaner::<T>(Some((
+                who.clone(),
+                NegativeImbalance::new(account.total()),
+            ))));
+        }
+    }
+    info!(
+        target: "runtime::btc_ledger",
+        "reaped {} of {} accounts below the existential deposit.",
+        count,
+        dust_accounts.len(),
+    );
+    // the system account is read for each dust account, the account, locks and the system
+    // account are written for each reaped account.
+    <T as frame_system::Config>::DbWeight::get().reads_writes(
+        reads.saturating_add(dust_accounts.len() as Weight),
+        count.saturating_mul(3),
+    )
+}
+
+/// Sum up the remainders below 1 satoshi of all the accounts into `TotalRemainder`, which is
+/// maintained by the ledger afterwards.
+pub fn init_total_remainder<T: Config>() -> Weight {
+    let satoshi = T::Satoshi::get();
+    let mut count: Weight = 0;
+    let remainder =
+        AccountStore::<T>::iter_values().fold(Zero::zero(), |acc: T::Balance, account| {
+            count += 1;
+            acc.saturating_add(account.remainder(satoshi))
+        });
+    TotalRemainder::<T>::put(remainder);
+    info!(
+        target: "runtime::btc_ledger",
+        "summed up the remainders of {} accounts in the btc ledger.",
+        count,
+    );
+    <T as frame_system::Config>::DbWeight::get().reads_writes(count, 1)
+}
@@ -1,8 +1,10 @@
 // Copyright 2019-2023 ChainX Project Authors. Licensed under GPL-3.0.
 
+use std::cell::RefCell;
+
 use frame_support::{
     construct_runtime, parameter_types,
-    traits::{ConstU32, ConstU64},
+    traits::{ConstU128, ConstU32, ConstU64, Get},
     PalletId,
 };
 use frame_system::EnsureRoot;
@@ -65,11 +67,31 @@ impl frame_system::Config for Test {
     type MaxConsumers = ConstU32<16>;
 }
 
+thread_local! {
+    static EXISTENTIAL_DEPOSIT: RefCell<Balance> = RefCell::new(0);
+}
+
+pub struct ExistentialDeposit;
+impl Get<Balance> for ExistentialDeposit {
+    fn get() -> Balance {
+        EXISTENTIAL_DEPOSIT.with(|v| *v.borrow())
+    }
+}
+
+pub fn set_existential_deposit(value: Balance) {
+    EXISTENTIAL_DEPOSIT.with(|v| *v.borrow_mut() = value);
+}
+
 impl crate::Config for Test {
     type Balance = Balance;
     type Event = Event;
     type CouncilOrigin = EnsureRoot<AccountId>;
     type PalletId = BtcLedgerPalletId;
+    type MaxLocks = ConstU32<50>;
+    type ExistentialDeposit = ExistentialDeposit;
+    type Satoshi = ConstU128<10>;
+    type DustRemoval = ();
+    type OnBalanceChange = ();
 }
 
 pub const ALICE: [u8; 32] = [1u8; 32];
@@ -1,13 +1,18 @@
 // Copyright 2019-2023 ChainX Project Authors. Licensed under GPL-3.0.
 
-use crate::mock::*;
+use crate::{mock::*, BalanceChangeReason, BalanceDelta};
 
+use codec::Encode;
 use frame_support::{
     assert_err, assert_noop, assert_ok,
     traits::{
+        tokens::{
+            fungible::{self, InspectHold, MutateHold},
+            BalanceStatus, DepositConsequence, WithdrawConsequence,
+        },
         Currency,
         ExistenceRequirement::{AllowDeath, KeepAlive},
-        WithdrawReasons,
+        LockIdentifier, LockableCurrency, OnRuntimeUpgrade, ReservableCurrency, WithdrawReasons,
     },
 };
 use frame_system::RawOrigin;
@@ -282,3 +287,555 @@ fn set_balance_handles_total_issuance() {
         assert_eq!(BtcLedger::free_balance(&CHARLIE.into()), 69);
     });
 }
+
+const ID_1: LockIdentifier = *b"1       ";
+const ID_2: LockIdentifier = *b"2       ";
+
+#[test]
+fn reserve_and_unreserve_should_work() {
+    new_test_ext().execute_with(|| {
+        let alice: AccountId = ALICE.into();
+        let bob: AccountId = BOB.into();
+
+        assert_ok!(BtcLedger::reserve(&alice, 4));
+        System::assert_last_event(Event::BtcLedger(crate::Event::Reserved {
+            who: alice.clone(),
+            amount: 4,
+        }));
+        assert_eq!(BtcLedger::free_balance(&alice), 6);
+        assert_eq!(BtcLedger::reserved_balance(&alice), 4);
+        assert_eq!(BtcLedger::total_balance(&alice), 10);
+        assert!(!BtcLedger::can_reserve(&alice, 7));
+        assert_noop!(
+            BtcLedger::reserve(&alice, 7),
+            crate::Error::<Test>::InsufficientBalance
+        );
+
+        // unreserve more than reserved
+        assert_eq!(BtcLedger::unreserve(&alice, 5), 1);
+        assert_eq!(BtcLedger::free_balance(&alice), 10);
+        assert_eq!(BtcLedger::reserved_balance(&alice), 0);
+
+        // slash the reserved balance
+        let total_issuance = BtcLedger::total_issuance();
+        assert_ok!(BtcLedger::reserve(&alice, 4));
+        let (imbalance, remaining) = BtcLedger::slash_reserved(&alice, 5);
+        assert_eq!(remaining, 1);
+        System::assert_last_event(Event::BtcLedger(crate::Event::Slashed {
+            who: alice.clone(),
+            amount: 4,
+        }));
+        drop(imbalance);
+        assert_eq!(BtcLedger::reserved_balance(&alice), 0);
+        assert_eq!(BtcLedger::total_balance(&alice), 6);
+        assert_eq!(BtcLedger::total_issuance(), total_issuance - 4);
+
+        // repatriate the reserved balance
+        assert_ok!(BtcLedger::reserve(&alice, 6));
+        assert_eq!(
+            BtcLedger::repatriate_reserved(&alice, &bob, 2, BalanceStatus::Free),
+            Ok(0)
+        );
+        assert_eq!(BtcLedger::free_balance(&bob), 22);
+        assert_eq!(
+            BtcLedger::repatriate_reserved(&alice, &bob, 3, BalanceStatus::Reserved),
+            Ok(0)
+        );
+        assert_eq!(BtcLedger::reserved_balance(&bob), 3);
+        assert_eq!(
+            BtcLedger::repatriate_reserved(&alice, &bob, 5, BalanceStatus::Free),
+            Ok(4)
+        );
+        assert_eq!(BtcLedger::total_balance(&alice), 0);
+        assert_eq!(BtcLedger::total_balance(&bob), 26);
+    });
+}
+
+#[test]
+fn lock_should_restrict_withdrawal() {
+    new_test_ext().execute_with(|| {
+        let alice: AccountId = ALICE.into();
+        let bob: AccountId = BOB.into();
+
+        BtcLedger::set_lock(ID_1, &alice, 6, WithdrawReasons::all());
+        assert_eq!(btc_ledger::AccountStore::<Test>::get(&alice).frozen, 6);
+        assert_eq!(
+            <BtcLedger as fungible::Inspect<_>>::reducible_balance(&alice, false),
+            4
+        );
+        assert_noop!(
+            <BtcLedger as Currency<_>>::transfer(&alice, &bob, 5, AllowDeath),
+            crate::Error::<Test>::LiquidityRestrictions
+        );
+        assert!(!BtcLedger::can_reserve(&alice, 5));
+        assert_ok!(<BtcLedger as Currency<_>>::transfer(
+            &alice, &bob, 4, AllowDeath
+        ));
+
+        // the locks overlap
+        BtcLedger::extend_lock(ID_1, &alice, 3, WithdrawReasons::all());
+        assert_eq!(btc_ledger::AccountStore::<Test>::get(&alice).frozen, 6);
+        BtcLedger::set_lock(ID_2, &alice, 8, WithdrawReasons::all());
+        assert_eq!(btc_ledger::AccountStore::<Test>::get(&alice).frozen, 8);
+        assert_eq!(BtcLedger::locks(&alice).len(), 2);
+
+        BtcLedger::remove_lock(ID_2, &alice);
+        assert_eq!(btc_ledger::AccountStore::<Test>::get(&alice).frozen, 6);
+        BtcLedger::remove_lock(ID_1, &alice);
+        assert_eq!(btc_ledger::AccountStore::<Test>::get(&alice).frozen, 0);
+        assert!(!btc_ledger::Locks::<Test>::contains_key(&alice));
+        assert_ok!(<BtcLedger as Currency<_>>::transfer(
+            &alice, &bob, 6, AllowDeath
+        ));
+    });
+}
+
+#[test]
+fn hold_and_release_should_work() {
+    new_test_ext().execute_with(|| {
+        let alice: AccountId = ALICE.into();
+        let bob: AccountId = BOB.into();
+
+        assert_ok!(<BtcLedger as MutateHold<_>>::hold(&alice, 4));
+        assert_eq!(BtcLedger::balance_on_hold(&alice), 4);
+        assert!(!BtcLedger::can_hold(&alice, 7));
+        assert_noop!(
+            BtcLedger::release(&alice, 5, false),
+            crate::Error::<Test>::InsufficientBalance
+        );
+        assert_eq!(BtcLedger::release(&alice, 5, true), Ok(4));
+        assert_eq!(BtcLedger::balance_on_hold(&alice), 0);
+        assert_eq!(BtcLedger::free_balance(&alice), 10);
+
+        assert_ok!(<BtcLedger as MutateHold<_>>::hold(&alice, 4));
+        assert_eq!(
+            BtcLedger::transfer_held(&alice, &bob, 3, false, true),
+            Ok(3)
+        );
+        assert_eq!(BtcLedger::balance_on_hold(&alice), 1);
+        assert_eq!(BtcLedger::balance_on_hold(&bob), 3);
+        assert_noop!(
+            BtcLedger::transfer_held(&alice, &bob, 3, false, false),
+            crate::Error::<Test>::InsufficientBalance
+        );
+    });
+}
+
+#[test]
+fn migrate_account_data_from_v1() {
+    new_test_ext().execute_with(|| {
+        let alice: AccountId = ALICE.into();
+        frame_support::storage::unhashed::put_raw(
+            &btc_ledger::AccountStore::<Test>::hashed_key_for(&alice),
+            &15u128.encode(),
+        );
+        btc_ledger::StorageVersion::<Test>::put(btc_ledger::Releases::V1_0_0);
+
+        BtcLedger::on_runtime_upgrade();
+
+        assert_eq!(
+            btc_ledger::AccountStore::<Test>::get(&alice),
+            btc_ledger::AccountData {
+                free: 15,
+                reserved: 0,
+                frozen: 0,
+            }
+        );
+        assert_eq!(BtcLedger::free_balance(AccountId32::from(BOB)), 20);
+        assert_eq!(BtcLedger::total_remainder(), 5);
+        assert_eq!(
+            btc_ledger::StorageVersion::<Test>::get(),
+            btc_ledger::Releases::V3_0_0
+        );
+    });
+}
+
+#[test]
+fn pre_migrate_should_prove_the_unit_of_v1_balances() {
+    new_test_ext().execute_with(|| {
+        let alice: AccountId = ALICE.into();
+        let bob: AccountId = BOB.into();
+        for who in [&alice, &bob] {
+            let free = BtcLedger::free_balance(who);
+            frame_support::storage::unhashed::put_raw(
+                &btc_ledger::AccountStore::<Test>::hashed_key_for(who),
+                &free.encode(),
+            );
+        }
+        btc_ledger::StorageVersion::<Test>::put(btc_ledger::Releases::V1_0_0);
+
+        // the balances would be reaped as the dust if they were in another unit.
+        set_existential_deposit(50);
+        assert_err!(
+            crate::migrations::pre_migrate::<Test>(),
+            "the balances of btc ledger v1 are not in the unit of the ledger"
+        );
+
+        set_existential_deposit(5);
+        assert_ok!(crate::migrations::pre_migrate::<Test>());
+        let total = BtcLedger::total_issuance();
+        BtcLedger::on_runtime_upgrade();
+
+        // the balances are kept as they are.
+        assert_eq!(BtcLedger::free_balance(&alice), 10);
+        assert_eq!(BtcLedger::free_balance(&bob), 20);
+        assert_eq!(BtcLedger::total_issuance(), total);
+        assert_ok!(BtcLedger::check_total_issuance());
+        assert_ok!(BtcLedger::check_total_remainder());
+    });
+}
+
+#[test]
+fn total_remainder_should_track_sub_satoshi_balances() {
+    new_test_ext().execute_with(|| {
+        let alice: AccountId = ALICE.into();
+        let bob: AccountId = BOB.into();
+        // 1 satoshi is 10 in the mock.
+        assert_eq!(BtcLedger::total_remainder(), 0);
+        assert_eq!(BtcLedger::total_issuance_satoshi(), 3);
+
+        assert_ok!(<BtcLedger as Currency<_>>::transfer(
+            &alice, &bob, 3, AllowDeath
+        ));
+        assert_eq!(BtcLedger::total_remainder(), 7 + 3);
+        assert_eq!(BtcLedger::free_satoshi(&alice), 0);
+        assert_eq!(BtcLedger::free_satoshi(&bob), 2);
+        assert_eq!(BtcLedger::total_issuance_satoshi(), 2);
+        assert_ok!(BtcLedger::check_total_remainder());
+
+        assert_ok!(BtcLedger::reserve(&bob, 5));
+        assert_eq!(BtcLedger::total_remainder(), 7 + 8 + 5);
+        assert_eq!(BtcLedger::free_satoshi(&bob), 1);
+        assert_eq!(BtcLedger::reserved_satoshi(&bob), 0);
+        assert_eq!(BtcLedger::total_issuance_satoshi(), 1);
+        assert_ok!(BtcLedger::check_total_remainder());
+
+        // the remainder of the reaped account is lost with the dust.
+        set_existential_deposit(8);
+        assert_ok!(<BtcLedger as Currency<_>>::transfer(
+            &alice, &bob, 1, AllowDeath
+        ));
+        assert!(!btc_ledger::AccountStore::<Test>::contains_key(&alice));
+        assert_eq!(BtcLedger::total_issuance(), 24);
+        assert_eq!(BtcLedger::total_remainder(), 9 + 5);
+        assert_eq!(BtcLedger::total_issuance_satoshi(), 1);
+        assert_ok!(BtcLedger::check_total_issuance());
+        assert_ok!(BtcLedger::check_total_remainder());
+    });
+}
+
+#[test]
+fn account_with_nonce_should_not_be_reaped() {
+    new_test_ext().execute_with(|| {
+        set_existential_deposit(5);
+        let bob: AccountId = BOB.into();
+        let charlie: AccountId = CHARLIE.into();
+
+        // the account is only kept by the ledger, e.g. mapped from an EVM address.
+        let _ = BtcLedger::deposit_creating(&charlie, 10);
+        assert_eq!(System::sufficients(&charlie), 1);
+        System::inc_account_nonce(&charlie);
+
+        assert_eq!(
+            <BtcLedger as fungible::Inspect<_>>::can_withdraw(&charlie, 8),
+            WithdrawConsequence::Success
+        );
+        assert_ok!(<BtcLedger as Currency<_>>::transfer(
+            &charlie, &bob, 8, AllowDeath
+        ));
+        assert_eq!(BtcLedger::free_balance(&charlie), 2);
+        assert_eq!(System::sufficients(&charlie), 1);
+        assert_eq!(System::account_nonce(&charlie), 1);
+        assert!(!System::events().iter().any(|record| matches!(
+            record.event,
+            Event::BtcLedger(crate::Event::DustLost { .. })
+        )));
+
+        assert_ok!(<BtcLedger as Currency<_>>::transfer(
+            &charlie, &bob, 2, AllowDeath
+        ));
+        assert!(btc_ledger::AccountStore::<Test>::contains_key(&charlie));
+        assert_eq!(System::account_nonce(&charlie), 1);
+        assert_ok!(BtcLedger::check_total_issuance());
+    });
+}
+
+#[test]
+fn migrate_dust_accounts_from_v2() {
+    new_test_ext().execute_with(|| {
+        let alice: AccountId = ALICE.into();
+        let bob: AccountId = BOB.into();
+        let charlie: AccountId = CHARLIE.into();
+        let _ = BtcLedger::deposit_creating(&charlie, 3);
+        System::inc_account_nonce(&charlie);
+        assert_ok!(<BtcLedger as Currency<_>>::transfer(
+            &alice, &bob, 6, AllowDeath
+        ));
+
+        set_existential_deposit(5);
+        btc_ledger::StorageVersion::<Test>::put(btc_ledger::Releases::V2_0_0);
+        BtcLedger::on_runtime_upgrade();
+
+        // the account kept by the providers is reaped, the dust is lost.
+        assert!(!btc_ledger::AccountStore::<Test>::contains_key(&alice));
+        System::assert_has_event(Event::BtcLedger(crate::Event::DustLost {
+            account: alice,
+            amount: 4,
+        }));
+        // the account only kept by the ledger with a nonce is not reaped.
+        assert_eq!(BtcLedger::free_balance(&charlie), 3);
+        assert_eq!(System::account_nonce(&charlie), 1);
+        assert_eq!(BtcLedger::free_balance(&bob), 26);
+        assert_eq!(BtcLedger::total_issuance(), 29);
+        assert_ok!(BtcLedger::check_total_issuance());
+        assert_eq!(BtcLedger::total_remainder(), 6 + 3);
+        assert_ok!(BtcLedger::check_total_remainder());
+        assert_eq!(
+            btc_ledger::StorageVersion::<Test>::get(),
+            btc_ledger::Releases::V3_0_0
+        );
+    });
+}
+
+#[test]
+fn dust_account_should_be_reaped() {
+    new_test_ext().execute_with(|| {
+        set_existential_deposit(5);
+        let alice: AccountId = ALICE.into();
+        let bob: AccountId = BOB.into();
+        let charlie: AccountId = CHARLIE.into();
+
+        // the new account must be endowed with at least the existential deposit.
+        assert_noop!(
+            <BtcLedger as Currency<_>>::transfer(&alice, &charlie, 4, AllowDeath),
+            btc_ledger::Error::<Test>::ExistentialDeposit
+        );
+        // the sender must be kept alive.
+        assert_noop!(
+            <BtcLedger as Currency<_>>::transfer(&alice, &bob, 7, KeepAlive),
+            btc_ledger::Error::<Test>::KeepAlive
+        );
+
+        let _ = BtcLedger::deposit_creating(&charlie, 4);
+        assert!(!btc_ledger::AccountStore::<Test>::contains_key(&charlie));
+        assert_eq!(BtcLedger::total_issuance(), 30);
+
+        BtcLedger::set_lock(ID_1, &alice, 2, WithdrawReasons::all());
+        assert_ok!(<BtcLedger as Currency<_>>::transfer(
+            &alice, &bob, 7, AllowDeath
+        ));
+        System::assert_has_event(Event::BtcLedger(crate::Event::DustLost {
+            account: alice.clone(),
+            amount: 3,
+        }));
+        assert!(!btc_ledger::AccountStore::<Test>::contains_key(&alice));
+        assert!(BtcLedger::locks(&alice).is_empty());
+        assert_eq!(BtcLedger::free_balance(&bob), 27);
+        assert_eq!(BtcLedger::total_issuance(), 27);
+        assert_ok!(BtcLedger::check_total_issuance());
+
+        // the account below the existential deposit is wiped out by `set_balance`.
+        assert_ok!(BtcLedger::set_balance(Origin::root(), bob.clone(), 4));
+        assert!(!btc_ledger::AccountStore::<Test>::contains_key(&bob));
+        assert_eq!(BtcLedger::total_issuance(), 0);
+        assert_ok!(BtcLedger::check_total_issuance());
+    });
+}
+
+#[test]
+fn consequences_should_respect_existential_deposit() {
+    new_test_ext().execute_with(|| {
+        set_existential_deposit(5);
+        let alice: AccountId = ALICE.into();
+        let charlie: AccountId = CHARLIE.into();
+
+        assert_eq!(<BtcLedger as fungible::Inspect<_>>::minimum_balance(), 5);
+        assert_eq!(
+            <BtcLedger as fungible::Inspect<_>>::can_deposit(&charlie, 4),
+            DepositConsequence::BelowMinimum
+        );
+        assert_eq!(
+            <BtcLedger as fungible::Inspect<_>>::can_deposit(&charlie, 5),
+            DepositConsequence::Success
+        );
+        assert_eq!(
+            <BtcLedger as fungible::Inspect<_>>::can_withdraw(&alice, 5),
+            WithdrawConsequence::Success
+        );
+        assert_eq!(
+            <BtcLedger as fungible::Inspect<_>>::can_withdraw(&alice, 6),
+            WithdrawConsequence::ReducedToZero(4)
+        );
+        assert_eq!(
+            <BtcLedger as fungible::Inspect<_>>::reducible_balance(&alice, true),
+            5
+        );
+        assert_eq!(
+            <BtcLedger as fungible::Inspect<_>>::reducible_balance(&alice, false),
+            10
+        );
+
+        // the reserved balance keeps the account alive as well.
+        assert_ok!(BtcLedger::reserve(&alice, 3));
+        assert_eq!(
+            <BtcLedger as fungible::Inspect<_>>::can_withdraw(&alice, 2),
+            WithdrawConsequence::Success
+        );
+        assert_eq!(
+            <BtcLedger as fungible::Inspect<_>>::can_withdraw(&alice, 6),
+            WithdrawConsequence::ReducedToZero(4)
+        );
+        assert_eq!(
+            <BtcLedger as fungible::Inspect<_>>::reducible_balance(&alice, true),
+            5
+        );
+    });
+}
+
+#[test]
+fn balance_changes_should_classify_evm_fees() {
+    new_test_ext().execute_with(|| {
+        let alice: AccountId = ALICE.into();
+        let bob: AccountId = BOB.into();
+        let charlie: AccountId = CHARLIE.into();
+
+        let events = vec![
+            // the ethereum transaction of alice in the extrinsic 1.
+            (
+                0,
+                Some(1),
+                btc_ledger::Event::<Test>::Withdraw {
+                    who: alice.clone(),
+                    amount: 5,
+                },
+            ),
+            (
+                1,
+                Some(1),
+                btc_ledger::Event::<Test>::Transfer {
+                    from: alice.clone(),
+                    to: bob.clone(),
+                    amount: 1,
+                },
+            ),
+            (
+                2,
+                Some(1),
+                btc_ledger::Event::<Test>::Deposit {
+                    who: alice.clone(),
+                    amount: 2,
+                },
+            ),
+            (
+                3,
+                Some(1),
+                btc_ledger::Event::<Test>::Deposit {
+                    who: bob.clone(),
+                    amount: 3,
+                },
+            ),
+            (
+                4,
+                Some(1),
+                btc_ledger::Event::<Test>::Deposit {
+                    who: charlie.clone(),
+                    amount: 3,
+                },
+            ),
+            // the events of index 5 and the fee payment event of index 6 are not ledger events.
+            (
+                7,
+                Some(2),
+                btc_ledger::Event::<Test>::Withdraw {
+                    who: bob.clone(),
+                    amount: 1,
+                },
+            ),
+            (
+                8,
+                Some(2),
+                btc_ledger::Event::<Test>::Reserved {
+                    who: bob.clone(),
+                    amount: 1,
+                },
+            ),
+            (
+                9,
+                Some(2),
+                btc_ledger::Event::<Test>::Slashed {
+                    who: bob.clone(),
+                    amount: 1,
+                },
+            ),
+        ];
+        let changes = BtcLedger::balance_changes(
+            events,
+            &[1].into_iter().collect(),
+            &[(6, Some(1), charlie.clone(), 3)],
+        );
+
+        assert_eq!(
+            changes
+                .iter()
+                .map(|change| (
+                    change.who.clone(),
+                    change.event_index,
+                    change.reason,
+                    change.delta
+                ))
+                .collect::<Vec<_>>(),
+            vec![
+                (
+                    alice.clone(),
+                    0,
+                    BalanceChangeReason::EvmFee,
+                    BalanceDelta::Debit(5)
+                ),
+                (
+                    alice.clone(),
+                    1,
+                    BalanceChangeReason::Transfer,
+                    BalanceDelta::Debit(1)
+                ),
+                (
+                    bob.clone(),
+                    1,
+                    BalanceChangeReason::Transfer,
+                    BalanceDelta::Credit(1)
+                ),
+                (
+                    alice,
+                    2,
+                    BalanceChangeReason::EvmFeeRefund,
+                    BalanceDelta::Credit(2)
+                ),
+                (
+                    bob.clone(),
+                    3,
+                    BalanceChangeReason::Deposit,
+                    BalanceDelta::Credit(3)
+                ),
+                (
+                    charlie,
+                    4,
+                    BalanceChangeReason::FeeReward,
+                    BalanceDelta::Credit(3)
+                ),
+                (
+                    bob.clone(),
+                    7,
+                    BalanceChangeReason::Withdraw,
+                    BalanceDelta::Debit(1)
+                ),
+                (
+                    bob.clone(),
+                    9,
+                    BalanceChangeReason::Slashed,
+                    BalanceDelta::Debit(1)
+                ),
+            ]
+        );
+        assert_eq!(changes[6].extrinsic_index, Some(2));
+        assert_eq!(changes[6].balance, BtcLedger::total_balance(&bob));
+    });
+}
@@ -126,6 +126,7 @@ impl xpallet_assets::Config for Test {
     type TreasuryAccount = ();
     type OnCreatedAccount = frame_system::Provider<Test>;
     type OnAssetChanged = ();
+    type AssetLedger = ();
     type WeightInfo = ();
 }
 
@@ -6,7 +6,10 @@
 
 use sp_runtime::DispatchError;
 use sp_std::vec::Vec;
-pub use xpallet_gateway_bitcoin::{types::BtcHeaderInfo, BtcHeader, BtcWithdrawalProposal, H256};
+pub use xpallet_gateway_bitcoin::{
+    types::{BtcHeaderInfo, BtcReserveReport},
+    BtcHeader, BtcWithdrawalProposal, H256,
+};
 
 sp_api::decl_runtime_apis! {
     pub trait XGatewayBitcoinApi<AccountId>
@@ -23,5 +26,7 @@ sp_api::decl_runtime_apis! {
         fn get_genesis_info() -> (BtcHeader, u32);
 
         fn get_btc_block_header(txid: H256) -> Option<BtcHeaderInfo>;
+
+        fn get_reserve_report() -> BtcReserveReport;
     }
 }
@@ -12,7 +12,7 @@ use sp_runtime::{generic::BlockId, traits::Block as BlockT};
 
 use xp_rpc::{runtime_error_into_rpc_err, Result};
 use xpallet_gateway_bitcoin_rpc_runtime_api::{
-    BtcHeader, BtcHeaderInfo, BtcWithdrawalProposal,
+    BtcHeader, BtcHeaderInfo, BtcReserveReport, BtcWithdrawalProposal,
     XGatewayBitcoinApi as XGatewayBitcoinRuntimeApi, H256,
 };
 
@@ -61,6 +61,10 @@ pub trait XGatewayBitcoinApi<BlockHash, AccountId> {
         txid: H256,
         at: Option<BlockHash>,
     ) -> Result<Option<BtcHeaderInfo>>;
+
+    /// Get the liabilities of the bridge against the tracked trustee utxos
+    #[rpc(name = "xgatewaybitcoin_getReserveReport")]
+    fn get_reserve_report(&self, at: Option<BlockHash>) -> Result<BtcReserveReport>;
 }
 
 impl<C, Block, AccountId> XGatewayBitcoinApi<<Block as BlockT>::Hash, AccountId>
@@ -121,4 +125,13 @@ where
             .map_err(runtime_error_into_rpc_err)?;
         Ok(reslut)
     }
+
+    fn get_reserve_report(&self, at: Option<<Block as BlockT>::Hash>) -> Result<BtcReserveReport> {
+        let api = self.client.runtime_api();
+        let at = BlockId::hash(at.unwrap_or_else(|| self.client.info().best_hash));
+        let result = api
+            .get_reserve_report(&at)
+            .map_err(runtime_error_into_rpc_err)?;
+        Ok(result)
+    }
 }
@@ -1,7 +1,8 @@
 // Copyright 2019-2023 ChainX Project Authors. Licensed under GPL-3.0.
 
 use codec::{Decode, Encode};
-use frame_benchmarking::{benchmarks, whitelisted_caller};
+use frame_benchmarking::{benchmarks_instance_pallet, whitelisted_caller};
+use frame_support::traits::Hooks;
 use frame_system::RawOrigin;
 use sp_runtime::AccountId32;
 use sp_std::{collections::btree_map::BTreeMap, prelude::*};
@@ -18,14 +19,18 @@ use light_bitcoin::{
     serialization::{self, Reader, SERIALIZE_TRANSACTION_WITNESS},
 };
 
-use crate::{types::*, Call, Config, Pallet, PendingDeposits, TxState, WithdrawalProposal};
+use crate::{
+    trustee::MAX_EXPIRED_PROPOSALS, types::*, Call, Config, ExpiredProposalHashes, Pallet,
+    PendingDepositBalance, PendingDeposits, TxState, WithdrawalFeeReserve, WithdrawalProposal,
+    WithdrawalProposalDeadline,
+};
 
 fn generate_blocks_63290_63310() -> BTreeMap<u32, BlockHeader> {
     let bytes = include_bytes!("./res/headers-63290-63310.raw");
     Decode::decode(&mut &bytes[..]).unwrap()
 }
 
-fn account<T: Config>(pubkey: &str) -> T::AccountId {
+fn account<T: Config<I>, I: 'static>(pubkey: &str) -> T::AccountId {
     let pubkey = hex::decode(pubkey).unwrap();
     let mut public = [0u8; 32];
     public.copy_from_slice(pubkey.as_slice());
@@ -33,14 +38,14 @@ fn account<T: Config>(pubkey: &str) -> T::AccountId {
     Decode::decode(&mut account.as_slice()).unwrap()
 }
 
-fn alice<T: Config>() -> T::AccountId {
+fn alice<T: Config<I>, I: 'static>() -> T::AccountId {
     // sr25519 Alice
-    account::<T>("d43593c715fdd31c61141abd04a99fd6822c8558854ccde39a5684e7a56da27d")
+    account::<T, I>("d43593c715fdd31c61141abd04a99fd6822c8558854ccde39a5684e7a56da27d")
 }
 
-// fn bob<T: Config>() -> T::AccountId {
+// fn bob<T: Config<I>, I: 'static>() -> T::AccountId {
 //     // sr25519 Bob
-//     account::<T>("8eaf04151687736326c9fea17e25fc5287613693c912909cb226aa4794f26a48")
+//     account::<T, I>("8eaf04151687736326c9fea17e25fc5287613693c912909cb226aa4794f26a48")
 // }
 
 fn withdraw_tx() -> (Transaction, Vec<u8>, Transaction) {
@@ -66,7 +71,7 @@ fn withdraw_tx() -> (Transaction, Vec<u8>, Transaction) {
 }
 
 // push header 63290 - 63310
-fn prepare_headers<T: Config>(caller: &T::AccountId) {
+fn prepare_headers<T: Config<I>, I: 'static>(caller: &T::AccountId) {
     for (height, header) in generate_blocks_63290_63310() {
         if height == 63290 {
             continue;
@@ -75,11 +80,11 @@ fn prepare_headers<T: Config>(caller: &T::AccountId) {
             break;
         }
         let header = serialization::serialize(&header).into();
-        Pallet::<T>::push_header(RawOrigin::Signed(caller.clone()).into(), header).unwrap();
+        Pallet::<T, I>::push_header(RawOrigin::Signed(caller.clone()).into(), header).unwrap();
     }
 }
 
-benchmarks! {
+benchmarks_instance_pallet! {
     push_header {
         let receiver: T::AccountId = whitelisted_caller();
         let insert_height = 63290 + 1;
@@ -88,15 +93,15 @@ benchmarks! {
         let header_raw = serialization::serialize(&header).into();
     }: _(RawOrigin::Signed(receiver), header_raw)
     verify {
-        assert!(Pallet::<T>::headers(&hash).is_some());
+        assert!(Pallet::<T, I>::headers(&hash).is_some());
     }
 
     push_transaction {
         let n = 1024 * 1024 * 500; // 500KB length
         let l = 1024 * 1024 * 500; // 500KB length
 
-        let caller: T::AccountId = alice::<T>();
-        prepare_headers::<T>(&caller);
+        let caller: T::AccountId = alice::<T, I>();
+        prepare_headers::<T, I>(&caller);
         let (tx, info, prev_tx) = withdraw_tx();
         let tx_hash = tx.hash();
         let tx_raw = serialization::serialize_with_flags(&tx, SERIALIZE_TRANSACTION_WITNESS).into();
@@ -116,13 +121,13 @@ benchmarks! {
             tx,
             trustee_list: vec![],
         };
-        WithdrawalProposal::<T>::put(proposal);
+        WithdrawalProposal::<T, I>::put(proposal);
 
     }: _(RawOrigin::Signed(caller), tx_raw, info, Some(prev_tx_raw))
     verify {
-        assert!(WithdrawalProposal::<T>::get().is_none());
+        assert!(WithdrawalProposal::<T, I>::get().is_none());
         assert_eq!(
-            TxState::<T>::get(tx_hash),
+            TxState::<T, I>::get(tx_hash),
             Some(BtcTxState {
                 tx_type: BtcTxType::Withdrawal,
                 result: BtcTxResult::Success,
@@ -133,7 +138,7 @@ benchmarks! {
     create_taproot_withdraw_tx {
         let n = 100;                // 100 withdrawal count
         let l = 1024 * 1024 * 500;  // 500KB length
-        let caller = alice::<T>();
+        let caller = alice::<T, I>();
 
         let (tx, info, prev_tx) = withdraw_tx();
         let tx_hash = tx.hash();
@@ -153,17 +158,33 @@ benchmarks! {
 
     }: _(RawOrigin::Signed(caller), vec![0], tx_raw)
     verify {
-        assert_eq!(WithdrawalProposal::<T>::get().unwrap().sig_state, VoteResult::Finish);
+        assert_eq!(WithdrawalProposal::<T, I>::get().unwrap().sig_state, VoteResult::Finish);
     }
 
-    set_best_index {
-        let best = BtcHeaderIndex {
-            hash: H256::repeat_byte(1),
-            height: 100,
-        };
-    }: _(RawOrigin::Root, best)
+    rollback_headers {
+        let n in 1 .. 15;
+
+        let caller: T::AccountId = alice::<T, I>();
+        prepare_headers::<T, I>(&caller);
+        let hash = generate_blocks_63290_63310()[&(63306 - n)].hash();
+    }: _(RawOrigin::Root, hash, true)
+    verify {
+        assert_eq!(Pallet::<T, I>::best_index().hash, hash);
+    }
+
+    set_checkpoint {
+        let n in 1 .. 15;
+
+        let caller: T::AccountId = alice::<T, I>();
+        prepare_headers::<T, I>(&caller);
+        // a header not relayed yet, anchored below the best height to drop `n` headers
+        let header = generate_blocks_63290_63310()[&63309];
+        let hash = header.hash();
+        let header_raw = serialization::serialize(&header).into();
+        let height = 63307 - n;
+    }: _(RawOrigin::Root, header_raw, height, 0)
     verify {
-        assert_eq!(Pallet::<T>::best_index(), best);
+        assert_eq!(Pallet::<T, I>::best_index(), BtcHeaderIndex { hash, height });
     }
 
     set_confirmed_index {
@@ -173,7 +194,7 @@ benchmarks! {
         };
     }: _(RawOrigin::Root, confirmed)
     verify {
-        assert_eq!(Pallet::<T>::confirmed_index(), Some(confirmed));
+        assert_eq!(Pallet::<T, I>::confirmed_index(), Some(confirmed));
     }
 
     remove_pending {
@@ -192,16 +213,18 @@ benchmarks! {
                 balance: 300000000,
             },
         ];
-        PendingDeposits::<T>::insert(&addr, v);
+        PendingDeposits::<T, I>::insert(&addr, v);
+        PendingDepositBalance::<T, I>::put(600000000);
         let receiver: T::AccountId = whitelisted_caller();
     }: _(RawOrigin::Root, addr.clone(), Some(OpReturnAccount::Wasm(receiver)))
     verify {
-        assert!(Pallet::<T>::pending_deposits(&addr).is_empty());
+        assert!(Pallet::<T, I>::pending_deposits(&addr).is_empty());
+        assert_eq!(Pallet::<T, I>::pending_deposit_balance(), 0);
         // assert_eq!(XAssets::<T>::usable_balance(&receiver, &AssetId::default()), (100000000u32 + 200000000u32 + 300000000u32).into());
     }
 
     remove_proposal {
-        let caller = alice::<T>();
+        let caller = alice::<T, I>();
         let amount: BalanceOf<T> = 1_000_000_000u32.into();
         let withdrawal: BalanceOf<T> = 10000u32.into();
 
@@ -220,29 +243,151 @@ benchmarks! {
             trustee_list: vec![],
         };
 
-        WithdrawalProposal::<T>::put(proposal);
+        WithdrawalProposal::<T, I>::put(proposal);
     }: _(RawOrigin::Root)
     verify {
-        assert!(WithdrawalProposal::<T>::get().is_none());
+        assert!(WithdrawalProposal::<T, I>::get().is_none());
     }
 
     set_btc_withdrawal_fee {
-        let caller = alice::<T>();
+        let caller = alice::<T, I>();
     }: _(RawOrigin::Root,  2000000)
     verify {
     }
 
     set_btc_deposit_limit {
-        let caller = alice::<T>();
+        let caller = alice::<T, I>();
     }: _(RawOrigin::Root,  2000000)
     verify {
     }
 
     set_coming_bot {
-        let caller = alice::<T>();
+        let caller = alice::<T, I>();
     }: _(RawOrigin::Root,  Some(caller))
     verify {
     }
+
+    set_withdrawal_fee_reserve {
+        let caller = alice::<T, I>();
+    }: _(RawOrigin::Root, Some(caller.clone()))
+    verify {
+        assert_eq!(Pallet::<T, I>::withdrawal_fee_reserve(), Some(caller));
+    }
+
+    bump_withdrawal_fee {
+        let caller = alice::<T, I>();
+
+        let (tx, _, _) = withdraw_tx();
+        // the rbf replacement pays 10000 satoshi more out of the change
+        let mut replacement = tx.clone();
+        replacement.outputs[1].value -= 10000;
+        let replacement_hash = replacement.hash();
+        let tx_raw: Vec<u8> = serialization::serialize_with_flags(&replacement, SERIALIZE_TRANSACTION_WITNESS).into();
+
+        let amount: BalanceOf<T> = 1_000_000_000u32.into();
+
+        let withdrawal: BalanceOf<T> = 50000u32.into();
+
+        #[cfg(feature = "runtime-benchmarks")]
+        let withdrawal: BalanceOf<T> = 550000u32.into();
+
+        XGatewayRecords::<T>::deposit(&caller, X_BTC, amount).unwrap();
+        XGatewayRecords::<T>::withdraw(&caller, X_BTC, withdrawal, b"tb1pexff2s7l58sthpyfrtx500ax234stcnt0gz2lr4kwe0ue95a2e0srxsc68".to_vec(), b"".to_vec().into()).unwrap();
+
+        XGatewayRecords::<T>::withdrawal_state_insert(0, WithdrawalState::Processing);
+
+        let proposal = BtcWithdrawalProposal::<T::AccountId> {
+            sig_state: VoteResult::Unfinish,
+            withdrawal_id_list: vec![0],
+            tx,
+            trustee_list: vec![],
+        };
+        WithdrawalProposal::<T, I>::put(proposal);
+        WithdrawalFeeReserve::<T, I>::put(caller.clone());
+    }: _(RawOrigin::Signed(caller), BtcFeeBumpKind::Rbf, tx_raw)
+    verify {
+        assert_eq!(WithdrawalProposal::<T, I>::get().unwrap().tx.hash(), replacement_hash);
+        assert_eq!(Pallet::<T, I>::withdrawal_fee_bump().unwrap().rbf_fee, 10000);
+    }
+
+    sign_withdrawal_proposal {
+        let caller = alice::<T, I>();
+        let (tx, _, _) = withdraw_tx();
+        let proposal = BtcWithdrawalProposal::<T::AccountId> {
+            sig_state: VoteResult::Unfinish,
+            withdrawal_id_list: vec![0],
+            tx,
+            trustee_list: vec![],
+        };
+        WithdrawalProposal::<T, I>::put(proposal);
+    }: _(RawOrigin::Signed(caller.clone()))
+    verify {
+        assert_eq!(WithdrawalProposal::<T, I>::get().unwrap().trustee_list, vec![(caller, true)]);
+    }
+
+    expire_withdrawal_proposal {
+        let caller = alice::<T, I>();
+        let amount: BalanceOf<T> = 1_000_000_000u32.into();
+        let withdrawal: BalanceOf<T> = 10000u32.into();
+
+        XGatewayRecords::<T>::deposit(&caller, X_BTC, amount).unwrap();
+        XGatewayRecords::<T>::withdraw(&caller, X_BTC, withdrawal, b"tb1pexff2s7l58sthpyfrtx500ax234stcnt0gz2lr4kwe0ue95a2e0srxsc68".to_vec(), b"".to_vec().into()).unwrap();
+        XGatewayRecords::<T>::withdrawal_state_insert(0, WithdrawalState::Processing);
+
+        let (tx, _, _) = withdraw_tx();
+        let tx_hash = tx.hash();
+        let proposal = BtcWithdrawalProposal::<T::AccountId> {
+            sig_state: VoteResult::Unfinish,
+            withdrawal_id_list: vec![0],
+            tx,
+            trustee_list: vec![],
+        };
+        WithdrawalProposal::<T, I>::put(proposal);
+
+        // the oldest expired proposal is pruned
+        let hashes = (0..MAX_EXPIRED_PROPOSALS as u8).map(H256::repeat_byte).collect::<Vec<_>>();
+        ExpiredProposalHashes::<T, I>::put(hashes);
+        let deadline: T::BlockNumber = 10u32.into();
+        WithdrawalProposalDeadline::<T, I>::put(deadline);
+    }: {
+        Pallet::<T, I>::on_initialize(deadline);
+    }
+    verify {
+        assert!(WithdrawalProposal::<T, I>::get().is_none());
+        assert!(Pallet::<T, I>::expired_proposals(tx_hash).is_some());
+    }
+
+    set_withdrawal_proposal_timeout {
+        let timeout: T::BlockNumber = 14400u32.into();
+    }: _(RawOrigin::Root, timeout)
+    verify {
+        assert_eq!(Pallet::<T, I>::withdrawal_proposal_timeout(), timeout);
+    }
+
+    set_reserve_check {
+        let interval: T::BlockNumber = 600u32.into();
+    }: _(RawOrigin::Root, interval, 9_500)
+    verify {
+        assert_eq!(Pallet::<T, I>::reserve_check_interval(), interval);
+        assert_eq!(Pallet::<T, I>::reserve_alarm_threshold(), 9_500);
+    }
+
+    set_trustee_utxos {
+        let n in 1 .. 1000;
+        let utxos = (0..n).map(|i| (H256::repeat_byte(1), i, 100_000, i % 2 == 0)).collect::<Vec<_>>();
+    }: _(RawOrigin::Root, utxos)
+    verify {
+        assert_eq!(Pallet::<T, I>::trustee_utxo_balance(), 100_000 * n as u64);
+    }
+    set_rebalance_config {
+        let config = BtcRebalanceConfig {
+            target_hot_ratio: 1_000,
+            threshold: 500,
+        };
+    }: _(RawOrigin::Root, Some(config))
+    verify {
+        assert_eq!(Pallet::<T, I>::rebalance_config(), Some(config));
+    }
 }
 
 #[cfg(test)]
@@ -257,12 +402,21 @@ mod tests {
             assert_ok!(Pallet::<Test>::test_benchmark_push_header());
             assert_ok!(Pallet::<Test>::test_benchmark_push_transaction());
             assert_ok!(Pallet::<Test>::test_benchmark_create_taproot_withdraw_tx());
-            assert_ok!(Pallet::<Test>::test_benchmark_set_best_index());
+            assert_ok!(Pallet::<Test>::test_benchmark_rollback_headers());
+            assert_ok!(Pallet::<Test>::test_benchmark_set_checkpoint());
             assert_ok!(Pallet::<Test>::test_benchmark_set_confirmed_index());
             assert_ok!(Pallet::<Test>::test_benchmark_remove_pending());
             assert_ok!(Pallet::<Test>::test_benchmark_set_btc_withdrawal_fee());
             assert_ok!(Pallet::<Test>::test_benchmark_set_btc_deposit_limit());
             assert_ok!(Pallet::<Test>::test_benchmark_set_coming_bot());
+            assert_ok!(Pallet::<Test>::test_benchmark_set_withdrawal_fee_reserve());
+            assert_ok!(Pallet::<Test>::test_benchmark_bump_withdrawal_fee());
+            assert_ok!(Pallet::<Test>::test_benchmark_sign_withdrawal_proposal());
+            assert_ok!(Pallet::<Test>::test_benchmark_expire_withdrawal_proposal());
+            assert_ok!(Pallet::<Test>::test_benchmark_set_withdrawal_proposal_timeout());
+            assert_ok!(Pallet::<Test>::test_benchmark_set_reserve_check());
+            assert_ok!(Pallet::<Test>::test_benchmark_set_trustee_utxos());
+            assert_ok!(Pallet::<Test>::test_benchmark_set_rebalance_config());
         });
     }
 }
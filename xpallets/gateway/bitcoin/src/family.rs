@@ -0,0 +1,98 @@
+// Copyright 2019-2023 ChainX Project Authors. Licensed under GPL-3.0.
+
+//! The chain-specific rules of the bitcoin-family chains bridged by the instances of this pallet.
+
+use sp_std::cmp;
+
+use light_bitcoin::{
+    chain::BlockHeader as BtcHeader,
+    primitives::{Compact, H256, U256},
+};
+
+use chainx_primitives::AssetId;
+use xpallet_assets::Chain;
+
+use crate::types::BtcParams;
+
+/// The rules of a bitcoin-family chain (e.g. bitcoin, litecoin, dogecoin) which differ from
+/// bitcoin, the defaults are the bitcoin ones.
+///
+/// The retarget interval, timespan and max bits are the `BtcParams` of each instance. Only
+/// bitcoin is implemented for now, the other chains need their own `Chain` first.
+pub trait BitcoinFamily {
+    /// The chain bridged by the instance.
+    const CHAIN: Chain;
+    /// The asset issued for the deposits of the chain.
+    const ASSET_ID: AssetId;
+
+    /// Convert the base58 address version of the chain to the bitcoin one, so that the
+    /// addresses could be parsed as bitcoin addresses, `None` if it's not a valid version.
+    fn base58_version(version: u8) -> Option<u8> {
+        Some(version)
+    }
+
+    /// Whether the bech32 (segwit and taproot) addresses of bitcoin are accepted.
+    fn accept_bech32() -> bool {
+        true
+    }
+
+    /// The hash compared with the target of the header.
+    fn pow_hash(header: &BtcHeader) -> H256 {
+        header.hash()
+    }
+
+    /// Whether the work required changes at the height.
+    fn is_retarget_height(height: u32, params: &BtcParams) -> bool {
+        height % params.retargeting_interval() == 0
+    }
+
+    /// The work required at the retarget height, by the bits of the last block, the time of
+    /// the last block and the time of the block at the last retarget height.
+    fn retarget(
+        last_bits: Compact,
+        retarget_timestamp: u32,
+        last_timestamp: u32,
+        params: &BtcParams,
+    ) -> Compact {
+        let mut retarget: U256 = last_bits.into();
+        let maximum: U256 = params.max_bits().into();
+
+        retarget *= U256::from(retarget_timespan(
+            retarget_timestamp,
+            last_timestamp,
+            params,
+        ));
+        retarget /= U256::from(params.target_timespan_seconds());
+
+        if retarget > maximum {
+            params.max_bits()
+        } else {
+            retarget.into()
+        }
+    }
+}
+
+/// Bitcoin
+pub struct Bitcoin;
+
+impl BitcoinFamily for Bitcoin {
+    const CHAIN: Chain = Chain::Bitcoin;
+    const ASSET_ID: AssetId = xp_protocol::X_BTC;
+}
+
+/// Returns constrained number of seconds since last retarget
+pub fn retarget_timespan(retarget_timestamp: u32, last_timestamp: u32, params: &BtcParams) -> u32 {
+    // TODO i64??
+    // subtract unsigned 32 bit numbers in signed 64 bit space in
+    // order to prevent underflow before applying the range constraint.
+    let timespan = last_timestamp as i64 - i64::from(retarget_timestamp);
+    range_constrain(
+        timespan,
+        i64::from(params.min_timespan()),
+        i64::from(params.max_timespan()),
+    ) as u32
+}
+
+fn range_constrain(value: i64, min: i64, max: i64) -> i64 {
+    cmp::min(cmp::max(value, min), max)
+}
@@ -6,7 +6,7 @@ use frame_support::{
     traits::UnixTime,
 };
 use sp_runtime::RuntimeDebug;
-use sp_std::{cmp, convert::TryFrom};
+use sp_std::convert::TryFrom;
 
 use light_bitcoin::{
     chain::BlockHeader as BtcHeader,
@@ -15,6 +15,7 @@ use light_bitcoin::{
 };
 
 use crate::{
+    family::BitcoinFamily,
     types::{BtcHeaderInfo, BtcParams},
     Config, Error, Pallet,
 };
@@ -26,7 +27,7 @@ pub struct HeaderVerifier<'a> {
 }
 
 impl<'a> HeaderVerifier<'a> {
-    pub fn new<T: Config>(header_info: &'a BtcHeaderInfo) -> Self {
+    pub fn new<T: Config<I>, I: 'static>(header_info: &'a BtcHeaderInfo) -> Self {
         let now = T::UnixTime::now();
         // if convert from u64 to u32 failed (unix timestamp should not be greater than u32::MAX),
         // ignore timestamp check, timestamp check are not important
@@ -39,16 +40,16 @@ impl<'a> HeaderVerifier<'a> {
         }
     }
 
-    pub fn check<T: Config>(&self) -> DispatchResult {
-        let params: BtcParams = Pallet::<T>::params_info();
-        let network_id: Network = Pallet::<T>::network_id();
+    pub fn check<T: Config<I>, I: 'static>(&self) -> DispatchResult {
+        let params: BtcParams = Pallet::<T, I>::params_info();
+        let network_id: Network = Pallet::<T, I>::network_id();
         if let Network::Mainnet = network_id {
-            self.work.check::<T>(&params)?;
+            self.work.check::<T, I>(&params)?;
         }
-        self.proof_of_work.check::<T>(&params)?;
+        self.proof_of_work.check::<T, I>(&params)?;
         // ignore this in benchmarks
         #[cfg(not(feature = "runtime-benchmarks"))]
-        self.timestamp.check::<T>(&params)?;
+        self.timestamp.check::<T, I>(&params)?;
 
         Ok(())
     }
@@ -69,9 +70,9 @@ impl<'a> HeaderWork<'a> {
         HeaderWork { info }
     }
 
-    fn check<T: Config>(&self, params: &BtcParams) -> DispatchResult {
+    fn check<T: Config<I>, I: 'static>(&self, params: &BtcParams) -> DispatchResult {
         let previous_header_hash = self.info.header.previous_header_hash;
-        let work = work_required::<T>(previous_header_hash, self.info.height, params);
+        let work = work_required::<T, I>(previous_header_hash, self.info.height, params);
         match work {
             RequiredWork::Value(work) => {
                 if work != self.info.header.bits {
@@ -80,7 +81,7 @@ impl<'a> HeaderWork<'a> {
                         "[check_header_work] nBits do not match difficulty rules, work:{:?}, header bits:{:?}, height:{}",
                         work, self.info.header.bits, self.info.height
                     );
-                    return Err(Error::<T>::HeaderNBitsNotMatch.into());
+                    return Err(Error::<T, I>::HeaderNBitsNotMatch.into());
                 }
                 Ok(())
             }
@@ -89,7 +90,7 @@ impl<'a> HeaderWork<'a> {
     }
 }
 
-pub fn work_required<T: Config>(
+pub fn work_required<T: Config<I>, I: 'static>(
     parent_hash: H256,
     height: u32,
     params: &BtcParams,
@@ -99,12 +100,12 @@ pub fn work_required<T: Config>(
         return RequiredWork::Value(max_bits);
     }
 
-    let parent_header: BtcHeader = Pallet::<T>::headers(&parent_hash)
+    let parent_header: BtcHeader = Pallet::<T, I>::headers(&parent_hash)
         .expect("pre header must exist here")
         .header;
 
-    if is_retarget_height(height, params) {
-        let new_work = work_required_retarget::<T>(parent_header, height, params);
+    if T::Family::is_retarget_height(height, params) {
+        let new_work = work_required_retarget::<T, I>(parent_header, height, params);
         info!(
             target: "runtime::bitcoin",
             "[work_required] Retarget new work required, height:{}, retargeting_interval:{}, new_work:{:?}",
@@ -120,12 +121,8 @@ pub fn work_required<T: Config>(
     RequiredWork::Value(parent_header.bits)
 }
 
-fn is_retarget_height(height: u32, params: &BtcParams) -> bool {
-    height % params.retargeting_interval() == 0
-}
-
 /// Algorithm used for retargeting work every 2 weeks
-fn work_required_retarget<T: Config>(
+fn work_required_retarget<T: Config<I>, I: 'static>(
     parent_header: BtcHeader,
     height: u32,
     params: &BtcParams,
@@ -137,64 +134,42 @@ fn work_required_retarget<T: Config>(
     // bits of last block
     let last_bits = parent_header.bits;
 
-    let (_, genesis_height) = Pallet::<T>::genesis_info();
+    let (_, genesis_height) = Pallet::<T, I>::genesis_info();
     let mut retarget_header = parent_header;
-    if retarget_num < genesis_height {
-        // retarget_header = genesis_header;
-        return RequiredWork::NotCheck;
+    // timestamp of block(height - RETARGETING_INTERVAL)
+    let retarget_timestamp = if retarget_num < genesis_height {
+        // the block is before the checkpoint, use the retarget context of the checkpoint
+        match Pallet::<T, I>::checkpoint() {
+            Some(checkpoint)
+                if checkpoint.height - checkpoint.height % params.retargeting_interval()
+                    == retarget_num =>
+            {
+                checkpoint.retarget_timestamp
+            }
+            // retarget_header = genesis_header;
+            _ => return RequiredWork::NotCheck,
+        }
     } else {
-        let hash_list = Pallet::<T>::block_hash_for(&retarget_num);
+        let hash_list = Pallet::<T, I>::block_hash_for(&retarget_num);
         for h in hash_list {
             // look up in main chain
-            if Pallet::<T>::main_chain(h) {
-                let info = Pallet::<T>::headers(h).expect("block header must exist at here.");
+            if Pallet::<T, I>::main_chain(h) {
+                let info = Pallet::<T, I>::headers(h).expect("block header must exist at here.");
                 retarget_header = info.header;
                 break;
             };
         }
-    }
-
-    // timestamp of block(height - RETARGETING_INTERVAL)
-    let retarget_timestamp = retarget_header.time;
-
-    let mut retarget: U256 = last_bits.into();
-    let maximum: U256 = params.max_bits().into();
-
-    retarget *= U256::from(retarget_timespan(
-        retarget_timestamp,
-        last_timestamp,
-        params,
-    ));
-    retarget /= U256::from(params.target_timespan_seconds());
+        retarget_header.time
+    };
 
+    let new_bits = T::Family::retarget(last_bits, retarget_timestamp, last_timestamp, params);
     debug!(
         target: "runtime::bitcoin",
-        "[work_required_retarget] retarget:{}, maximum:{:?}",
-        retarget, maximum
+        "[work_required_retarget] retarget:{:?}, maximum:{:?}",
+        new_bits, params.max_bits()
     );
 
-    RequiredWork::Value(if retarget > maximum {
-        params.max_bits()
-    } else {
-        retarget.into()
-    })
-}
-
-/// Returns constrained number of seconds since last retarget
-fn retarget_timespan(retarget_timestamp: u32, last_timestamp: u32, params: &BtcParams) -> u32 {
-    // TODO i64??
-    // subtract unsigned 32 bit numbers in signed 64 bit space in
-    // order to prevent underflow before applying the range constraint.
-    let timespan = last_timestamp as i64 - i64::from(retarget_timestamp);
-    range_constrain(
-        timespan,
-        i64::from(params.min_timespan()),
-        i64::from(params.max_timespan()),
-    ) as u32
-}
-
-fn range_constrain(value: i64, min: i64, max: i64) -> i64 {
-    cmp::min(cmp::max(value, min), max)
+    RequiredWork::Value(new_bits)
 }
 
 pub struct HeaderProofOfWork<'a> {
@@ -206,11 +181,15 @@ impl<'a> HeaderProofOfWork<'a> {
         Self { header }
     }
 
-    fn check<T: Config>(&self, params: &BtcParams) -> DispatchResult {
-        if is_valid_proof_of_work(params.max_bits(), self.header.bits, self.header.hash()) {
+    fn check<T: Config<I>, I: 'static>(&self, params: &BtcParams) -> DispatchResult {
+        if is_valid_proof_of_work(
+            params.max_bits(),
+            self.header.bits,
+            T::Family::pow_hash(self.header),
+        ) {
             Ok(())
         } else {
-            Err(Error::<T>::InvalidPoW.into())
+            Err(Error::<T, I>::InvalidPoW.into())
         }
     }
 }
@@ -239,7 +218,7 @@ impl<'a> HeaderTimestamp<'a> {
     }
 
     #[allow(unused)]
-    fn check<T: Config>(&self, params: &BtcParams) -> DispatchResult {
+    fn check<T: Config<I>, I: 'static>(&self, params: &BtcParams) -> DispatchResult {
         if let Some(current_time) = self.current_time {
             if self.header.time > current_time + params.block_max_future() {
                 error!(
@@ -249,7 +228,7 @@ impl<'a> HeaderTimestamp<'a> {
                     current_time,
                     params.block_max_future()
                 );
-                Err(Error::<T>::HeaderFuturisticTimestamp.into())
+                Err(Error::<T, I>::HeaderFuturisticTimestamp.into())
             } else {
                 Ok(())
             }
@@ -2,17 +2,25 @@
 
 mod header_proof;
 
-use frame_support::log::{error, info};
+use frame_support::{
+    dispatch::DispatchError,
+    ensure,
+    log::{error, info},
+};
 use sp_runtime::DispatchResult;
 use sp_std::{cmp::Ordering, prelude::*};
 
 use light_bitcoin::primitives::{hash_rev, H256};
 
 use crate::{
-    types::{BtcHeaderIndex, BtcHeaderInfo},
-    Config, ConfirmedIndex, Error, MainChain, Pallet,
+    types::{BtcCheckpoint, BtcHeaderIndex, BtcHeaderInfo},
+    BestIndex, BlockHashFor, BlockProcessedTxs, Checkpoint, Config, ConfirmedIndex, Error,
+    GenesisInfo, Headers, MainChain, Pallet, TxState,
 };
 
+/// The max number of the main chain headers removed by a rollback, about one day of bitcoin.
+pub const MAX_ROLLBACK_DEPTH: u32 = 144;
+
 pub use self::header_proof::HeaderVerifier;
 
 /// Look back the headers to pick the confirmed index,
@@ -25,10 +33,10 @@ pub use self::header_proof::HeaderVerifier;
 ///           4              3     2    1       (confirmations)
 ///           97             98    99   100     (height)
 ///
-fn look_back_confirmed_header<T: Config>(
+fn look_back_confirmed_header<T: Config<I>, I: 'static>(
     header_info: &BtcHeaderInfo,
 ) -> (Option<BtcHeaderIndex>, Vec<BtcHeaderIndex>) {
-    let confirmations = Pallet::<T>::confirmation_number();
+    let confirmations = Pallet::<T, I>::confirmation_number();
     let mut chain = Vec::with_capacity(confirmations as usize);
     let mut prev_hash = header_info.header.previous_header_hash;
 
@@ -39,7 +47,7 @@ fn look_back_confirmed_header<T: Config>(
     });
     // e.g. when confirmations is 4, loop 3 times max
     for cnt in 1..confirmations {
-        if let Some(current_info) = Pallet::<T>::headers(&prev_hash) {
+        if let Some(current_info) = Pallet::<T, I>::headers(&prev_hash) {
             chain.push(BtcHeaderIndex {
                 hash: prev_hash,
                 height: current_info.height,
@@ -66,35 +74,39 @@ fn look_back_confirmed_header<T: Config>(
     }
 }
 
-pub fn update_confirmed_header<T: Config>(header_info: &BtcHeaderInfo) -> Option<BtcHeaderIndex> {
-    let (confirmed, chain) = look_back_confirmed_header::<T>(header_info);
+pub fn update_confirmed_header<T: Config<I>, I: 'static>(
+    header_info: &BtcHeaderInfo,
+) -> Option<BtcHeaderIndex> {
+    let (confirmed, chain) = look_back_confirmed_header::<T, I>(header_info);
     for index in chain {
-        set_main_chain::<T>(index.height, index.hash);
+        set_main_chain::<T, I>(index.height, index.hash);
     }
     confirmed.map(|index| {
-        ConfirmedIndex::<T>::put(index);
+        ConfirmedIndex::<T, I>::put(index);
         index
     })
 }
 
-fn set_main_chain<T: Config>(height: u32, main_hash: H256) {
-    let hashes = Pallet::<T>::block_hash_for(&height);
+fn set_main_chain<T: Config<I>, I: 'static>(height: u32, main_hash: H256) {
+    let hashes = Pallet::<T, I>::block_hash_for(&height);
     if hashes.len() == 1 {
-        MainChain::<T>::insert(&hashes[0], true);
+        MainChain::<T, I>::insert(&hashes[0], true);
         return;
     }
     for hash in hashes {
         if hash == main_hash {
-            MainChain::<T>::insert(&hash, true);
+            MainChain::<T, I>::insert(&hash, true);
         } else {
-            MainChain::<T>::remove(&hash);
+            MainChain::<T, I>::remove(&hash);
         }
     }
 }
 
-pub fn check_confirmed_header<T: Config>(header_info: &BtcHeaderInfo) -> DispatchResult {
-    let (confirmed, _) = look_back_confirmed_header::<T>(header_info);
-    if let Some(current_confirmed) = ConfirmedIndex::<T>::get() {
+pub fn check_confirmed_header<T: Config<I>, I: 'static>(
+    header_info: &BtcHeaderInfo,
+) -> DispatchResult {
+    let (confirmed, _) = look_back_confirmed_header::<T, I>(header_info);
+    if let Some(current_confirmed) = ConfirmedIndex::<T, I>::get() {
         if let Some(now_confirmed) = confirmed {
             return match current_confirmed.height.cmp(&now_confirmed.height) {
                 Ordering::Greater => {
@@ -122,7 +134,7 @@ pub fn check_confirmed_header<T: Config>(header_info: &BtcHeaderInfo) -> Dispatc
                         //  | --------- b(now_confirmed) --- b  ------ b --- b(now)
                         // 99              100       101  102    103
                         // current_confirmed = now_confirmed
-                        Err(Error::<T>::AncientFork.into())
+                        Err(Error::<T, I>::AncientFork.into())
                     }
                 }
                 Ordering::Less => {
@@ -132,7 +144,7 @@ pub fn check_confirmed_header<T: Config>(header_info: &BtcHeaderInfo) -> Dispatc
                         "[check_confirmed_header] Should not happen, current confirmed is less than confirmed for this header, \
                         current:{:?}, now:{:?}", current_confirmed, now_confirmed
                     );
-                    Err(Error::<T>::AncientFork.into())
+                    Err(Error::<T, I>::AncientFork.into())
                 }
             };
         }
@@ -140,3 +152,179 @@ pub fn check_confirmed_header<T: Config>(header_info: &BtcHeaderInfo) -> Dispatc
     // do not have confirmed yet.
     Ok(())
 }
+
+/// Roll the light client back to the main chain header `target`.
+///
+/// The main chain headers above it and the forks on them are removed, so that they could be
+/// relayed again. The main chain and the confirmed index are recomputed from `target`.
+///
+/// Return the new confirmed index, the number of removed headers and the processed deposit and
+/// withdrawal txs of the removed blocks, which are only allowed when `force` is set. The state of
+/// the rolled back txs is cleared, so that they could be processed again on the new main chain.
+pub fn rollback_to<T: Config<I>, I: 'static>(
+    target: &BtcHeaderInfo,
+    force: bool,
+) -> Result<(Option<BtcHeaderIndex>, u32, Vec<H256>), DispatchError> {
+    let target_hash = target.header.hash();
+    let best = Pallet::<T, I>::best_index();
+    ensure!(
+        Pallet::<T, I>::main_chain(&target_hash) && target.height <= best.height,
+        Error::<T, I>::NotMainChainHeader
+    );
+    ensure!(
+        best.height - target.height <= MAX_ROLLBACK_DEPTH,
+        Error::<T, I>::RollbackTooDeep
+    );
+    ensure!(
+        force || target.height >= Pallet::<T, I>::untracked_processed_height(),
+        Error::<T, I>::RollbackUntrackedBlocks
+    );
+
+    let mut removed: Vec<BtcHeaderIndex> = Vec::new();
+    let mut processed_txs = Vec::new();
+    for height in (target.height + 1)..=best.height {
+        for hash in Pallet::<T, I>::block_hash_for(&height) {
+            let on_removed_chain = Pallet::<T, I>::main_chain(&hash)
+                || Pallet::<T, I>::headers(&hash).map_or(true, |info| {
+                    removed
+                        .iter()
+                        .any(|index| index.hash == info.header.previous_header_hash)
+                });
+            if on_removed_chain {
+                processed_txs.extend(Pallet::<T, I>::block_processed_txs(&hash));
+                removed.push(BtcHeaderIndex { hash, height });
+            }
+        }
+    }
+    if !processed_txs.is_empty() && !force {
+        error!(
+            target: "runtime::bitcoin",
+            "[rollback_to] Reject to roll back the processed txs:{:?}",
+            processed_txs
+        );
+        return Err(Error::<T, I>::RollbackProcessedTxs.into());
+    }
+
+    for tx_hash in processed_txs.iter() {
+        TxState::<T, I>::remove(tx_hash);
+    }
+    for index in removed.iter() {
+        Headers::<T, I>::remove(&index.hash);
+        MainChain::<T, I>::remove(&index.hash);
+        BlockProcessedTxs::<T, I>::remove(&index.hash);
+        BlockHashFor::<T, I>::mutate_exists(index.height, |hashes| {
+            if let Some(v) = hashes {
+                v.retain(|hash| hash != &index.hash);
+                if v.is_empty() {
+                    *hashes = None;
+                }
+            }
+        });
+    }
+
+    BestIndex::<T, I>::put(BtcHeaderIndex {
+        hash: target_hash,
+        height: target.height,
+    });
+    let confirmed = update_confirmed_header::<T, I>(target);
+    if confirmed.is_none() {
+        ConfirmedIndex::<T, I>::kill();
+    }
+    info!(
+        target: "runtime::bitcoin",
+        "[rollback_to] Roll back to height:{}, hash:{:?}, confirm:{:?}, removed headers:{}",
+        target.height,
+        hash_rev(target_hash),
+        confirmed,
+        removed.len()
+    );
+
+    Ok((confirmed, removed.len() as u32, processed_txs))
+}
+
+/// Anchor the light client at the `checkpoint`, which replaces the genesis header.
+///
+/// If the checkpoint is already on the main chain, only the confirmed index is moved up to it.
+/// Otherwise the main chain headers from its height are dropped from the main chain, and it
+/// becomes the best and the confirmed header. The dropped headers must not contain any processed
+/// deposit or withdrawal.
+///
+/// Return the number of the headers dropped from the main chain.
+pub fn install_checkpoint<T: Config<I>, I: 'static>(
+    checkpoint: &BtcCheckpoint,
+) -> Result<u32, DispatchError> {
+    let hash = checkpoint.header.hash();
+    let index = BtcHeaderIndex {
+        hash,
+        height: checkpoint.height,
+    };
+    if let Some(info) = Pallet::<T, I>::headers(&hash) {
+        ensure!(
+            info.height == checkpoint.height,
+            Error::<T, I>::InvalidCheckpoint
+        );
+    }
+
+    let best = Pallet::<T, I>::best_index();
+    let mut depth = 0;
+    if Pallet::<T, I>::main_chain(&hash) {
+        if Pallet::<T, I>::confirmed_index()
+            .map_or(true, |confirmed| confirmed.height < index.height)
+        {
+            ConfirmedIndex::<T, I>::put(index);
+        }
+    } else {
+        if best.height >= checkpoint.height {
+            depth = best.height - checkpoint.height + 1;
+            ensure!(depth <= MAX_ROLLBACK_DEPTH, Error::<T, I>::RollbackTooDeep);
+            ensure!(
+                checkpoint.height > Pallet::<T, I>::untracked_processed_height(),
+                Error::<T, I>::RollbackUntrackedBlocks
+            );
+        }
+        let mut dropped = Vec::with_capacity(depth as usize);
+        let mut current = best.hash;
+        for _ in 0..depth {
+            ensure!(
+                Pallet::<T, I>::block_processed_txs(&current).is_empty(),
+                Error::<T, I>::RollbackProcessedTxs
+            );
+            dropped.push(current);
+            match Pallet::<T, I>::headers(&current) {
+                Some(info) => current = info.header.previous_header_hash,
+                None => break,
+            }
+        }
+        for hash in dropped.iter() {
+            MainChain::<T, I>::remove(hash);
+        }
+
+        Headers::<T, I>::insert(
+            &hash,
+            BtcHeaderInfo {
+                header: checkpoint.header,
+                height: checkpoint.height,
+            },
+        );
+        BlockHashFor::<T, I>::mutate(checkpoint.height, |v| {
+            if !v.contains(&hash) {
+                v.push(hash);
+            }
+        });
+        MainChain::<T, I>::insert(&hash, true);
+        BestIndex::<T, I>::put(index);
+        ConfirmedIndex::<T, I>::put(index);
+    }
+
+    GenesisInfo::<T, I>::put((checkpoint.header, checkpoint.height));
+    Checkpoint::<T, I>::put(checkpoint);
+    info!(
+        target: "runtime::bitcoin",
+        "[install_checkpoint] Anchor at height:{}, hash:{:?}, dropped main chain headers:{}",
+        checkpoint.height,
+        hash_rev(hash),
+        depth
+    );
+
+    Ok(depth)
+}
@@ -4,7 +4,10 @@
 
 #![cfg_attr(not(feature = "std"), no_std)]
 
+pub mod family;
 mod header;
+pub mod migrations;
+pub mod taproot;
 pub mod trustee;
 mod tx;
 pub mod types;
@@ -30,13 +33,14 @@ pub use light_bitcoin::{
 };
 use light_bitcoin::{
     chain::Transaction,
+    crypto::checksum,
     keys::{Address, DisplayLayout},
-    serialization::{deserialize, Reader},
+    serialization::{deserialize, serialize, Reader},
 };
 
 use chainx_primitives::{AssetId, ReferralId};
-use xp_gateway_common::AccountExtractor;
-use xpallet_assets::{BalanceOf, Chain, ChainT, WithdrawalLimit};
+use xp_gateway_common::{AccountExtractor, DstChain};
+use xpallet_assets::{AssetLedger, BalanceOf, Chain, ChainT, WithdrawalLimit};
 use xpallet_gateway_common::{
     traits::{
         AddressBinding, ProposalProvider, ReferralBinding, TotalSupply, TrusteeInfoUpdate,
@@ -50,17 +54,22 @@ use self::{
     trustee::{get_current_trustee_address_pair, get_last_trustee_address_pair},
     tx::remove_pending_deposit,
     types::{
-        BtcDepositCache, BtcHeaderIndex, BtcHeaderInfo, BtcRelayedTx, BtcRelayedTxInfo,
-        BtcTxResult, BtcTxState,
+        BtcCheckpoint, BtcDepositCache, BtcExpiredProposal, BtcFeeBump, BtcFeeBumpKind,
+        BtcHeaderIndex, BtcHeaderInfo, BtcRebalanceConfig, BtcRebalanceProposal, BtcRelayedTx,
+        BtcRelayedTxInfo, BtcReserveReport, BtcTxResult, BtcTxState,
     },
 };
 
 pub use self::{
+    family::{Bitcoin, BitcoinFamily},
     types::{BtcAddress, BtcParams, BtcTxVerifier, BtcWithdrawalProposal},
     weights::WeightInfo,
 };
 pub use pallet::*;
 
+/// 1 satoshi in the evm currency, as BTC has 8 decimals and the evm currency has 18.
+const EVM_SATOSHI: u128 = 10_000_000_000;
+
 // syntactic sugar for native log.
 #[macro_export]
 macro_rules! log {
@@ -77,29 +86,36 @@ pub mod pallet {
     use sp_std::marker::PhantomData;
 
     use frame_support::{
-        dispatch::DispatchResult, pallet_prelude::*, traits::UnixTime, transactional,
+        dispatch::DispatchResult,
+        pallet_prelude::*,
+        storage::{with_transaction, TransactionOutcome},
+        traits::{Currency, UnixTime},
+        transactional,
     };
     use frame_system::pallet_prelude::*;
     use sp_core::H160;
-    use sp_runtime::traits::Saturating;
-    use xp_gateway_bitcoin::OpReturnAccount;
+    use sp_runtime::traits::{Saturating, Zero};
+    use xp_gateway_bitcoin::{BtcTxType, OpReturnAccount};
 
     use super::*;
 
+    const STORAGE_VERSION: StorageVersion = StorageVersion::new(2);
+
     #[pallet::pallet]
     #[pallet::generate_store(pub(crate) trait Store)]
+    #[pallet::storage_version(STORAGE_VERSION)]
     #[pallet::without_storage_info]
-    pub struct Pallet<T>(PhantomData<T>);
+    pub struct Pallet<T, I = ()>(PhantomData<(T, I)>);
 
     #[pallet::config]
-    pub trait Config:
+    pub trait Config<I: 'static = ()>:
         frame_system::Config
         + xpallet_assets::Config
         + xpallet_gateway_records::Config
         + xpallet_assets_bridge::Config
     {
         /// The overarching event type.
-        type Event: From<Event<Self>> + IsType<<Self as frame_system::Config>::Event>;
+        type Event: From<Event<Self, I>> + IsType<<Self as frame_system::Config>::Event>;
 
         /// The unix time type.
         type UnixTime: UnixTime;
@@ -118,7 +134,7 @@ pub mod pallet {
         >;
 
         /// Update information about the trustee.
-        type TrusteeInfoUpdate: TrusteeInfoUpdate;
+        type TrusteeInfoUpdate: TrusteeInfoUpdate<Self::AccountId>;
 
         /// Handle referral of assets across chains.
         type ReferralBinding: ReferralBinding<Self::AccountId>;
@@ -126,21 +142,73 @@ pub mod pallet {
         /// Handle address binding about pending deposit.
         type AddressBinding: AddressBinding<Self::AccountId, BtcAddress>;
 
+        /// The rules of the bitcoin-family chain bridged by this instance.
+        type Family: BitcoinFamily;
+
         /// Weight information for extrinsics in this pallet.
         type WeightInfo: WeightInfo;
     }
 
     #[pallet::hooks]
-    impl<T: Config> Hooks<BlockNumberFor<T>> for Pallet<T> {}
+    impl<T: Config<I>, I: 'static> Hooks<BlockNumberFor<T>> for Pallet<T, I> {
+        fn on_initialize(now: T::BlockNumber) -> Weight {
+            let mut weight = match Self::withdrawal_proposal_deadline() {
+                Some(deadline) if now >= deadline => {
+                    let result = with_transaction(|| match Self::apply_expire_proposal(deadline) {
+                        Ok(()) => TransactionOutcome::Commit(Ok(())),
+                        Err(err) => TransactionOutcome::Rollback(Err(err)),
+                    });
+                    if let Err(err) = result {
+                        log!(
+                            error,
+                            "[on_initialize] Expire withdrawal proposal failed, error:{:?}, must use root to fix it",
+                            err
+                        );
+                        // do not retry in every block
+                        WithdrawalProposalDeadline::<T, I>::kill();
+                    }
+                    <T as Config<I>>::WeightInfo::expire_withdrawal_proposal()
+                }
+                _ => T::DbWeight::get().reads(1),
+            }
+            .saturating_add(T::DbWeight::get().reads(1));
+
+            let interval = Self::reserve_check_interval();
+            if !interval.is_zero() && (now % interval).is_zero() {
+                Self::check_reserves();
+                Self::check_rebalance(now);
+                weight = weight
+                    .saturating_add(<T as Config<I>>::WeightInfo::check_reserves())
+                    .saturating_add(<T as Config<I>>::WeightInfo::check_rebalance());
+            }
+            weight
+        }
+
+        fn on_runtime_upgrade() -> Weight {
+            let version = StorageVersion::get::<Pallet<T, I>>();
+            if version >= STORAGE_VERSION {
+                return T::DbWeight::get().reads(1);
+            }
+            let mut weight = T::DbWeight::get().reads_writes(1, 1);
+            if version < 1 {
+                weight = weight.saturating_add(migrations::processed_txs::apply::<T, I>());
+            }
+            if version < 2 {
+                weight = weight.saturating_add(migrations::pending_deposits::apply::<T, I>());
+            }
+            STORAGE_VERSION.put::<Pallet<T, I>>();
+            weight
+        }
+    }
 
     #[pallet::call]
-    impl<T: Config> Pallet<T> {
+    impl<T: Config<I>, I: 'static> Pallet<T, I> {
         /// if use `BtcHeader` struct would export in metadata, cause complex in front-end
-        #[pallet::weight(<T as Config>::WeightInfo::push_header())]
+        #[pallet::weight(<T as Config<I>>::WeightInfo::push_header())]
         pub fn push_header(origin: OriginFor<T>, header: Vec<u8>) -> DispatchResultWithPostInfo {
             let from = ensure_signed(origin)?;
             let header: BtcHeader =
-                deserialize(header.as_slice()).map_err(|_| Error::<T>::DeserializeErr)?;
+                deserialize(header.as_slice()).map_err(|_| Error::<T, I>::DeserializeErr)?;
             log!(debug, "[push_header] from:{:?}, header:{:?}", from, header);
 
             Self::apply_push_header(header)?;
@@ -150,7 +218,7 @@ pub mod pallet {
         }
 
         /// if use `RelayTx` struct would export in metadata, cause complex in front-end
-        #[pallet::weight(<T as Config>::WeightInfo::push_transaction())]
+        #[pallet::weight(<T as Config<I>>::WeightInfo::push_transaction())]
         pub fn push_transaction(
             origin: OriginFor<T>,
             raw_tx: Vec<u8>,
@@ -159,8 +227,8 @@ pub mod pallet {
         ) -> DispatchResultWithPostInfo {
             let _from = ensure_signed(origin)?;
             let raw_tx = Self::deserialize_tx(raw_tx.as_slice())?;
-            let relayed_info: BtcRelayedTxInfo =
-                Decode::decode(&mut &relayed_info[..]).map_err(|_| Error::<T>::DeserializeErr)?;
+            let relayed_info: BtcRelayedTxInfo = Decode::decode(&mut &relayed_info[..])
+                .map_err(|_| Error::<T, I>::DeserializeErr)?;
             let prev_tx = if let Some(prev_tx) = prev_tx {
                 Some(Self::deserialize_tx(prev_tx.as_slice())?)
             } else {
@@ -181,7 +249,7 @@ pub mod pallet {
         }
 
         /// Trustee create a proposal for a withdrawal list. `tx` is the proposal withdrawal transaction.
-        #[pallet::weight(<T as Config>::WeightInfo::create_taproot_withdraw_tx())]
+        #[pallet::weight(<T as Config<I>>::WeightInfo::create_taproot_withdraw_tx())]
         pub fn create_taproot_withdraw_tx(
             origin: OriginFor<T>,
             withdrawal_id_list: Vec<u32>,
@@ -191,7 +259,7 @@ pub mod pallet {
 
             ensure!(
                 !T::TrusteeSessionProvider::trustee_transition_state(),
-                Error::<T>::TrusteeTransitionPeriod
+                Error::<T, I>::TrusteeTransitionPeriod
             );
 
             // committer must be in the trustee list
@@ -213,7 +281,7 @@ pub mod pallet {
         /// Allow root or trustees could remove pending deposits for an address and decide whether
         /// deposit to an account id. if pass `None` to `who`, would just remove pending, if pass
         /// Some, would deposit to this account id.
-        #[pallet::weight(<T as Config>::WeightInfo::remove_pending())]
+        #[pallet::weight(<T as Config<I>>::WeightInfo::remove_pending())]
         pub fn remove_pending(
             origin: OriginFor<T>,
             addr: BtcAddress,
@@ -224,17 +292,18 @@ pub mod pallet {
                 .or_else(ensure_root)?;
 
             if let Some(w) = who {
-                remove_pending_deposit::<T>(&addr, &w);
+                remove_pending_deposit::<T, I>(&addr, &w);
             } else {
                 log!(info, "[remove_pending] Release pending deposit directly, not deposit to someone, addr:{:?}", try_addr(&addr));
-                PendingDeposits::<T>::remove(&addr);
+                let records = PendingDeposits::<T, I>::take(&addr);
+                Self::sub_pending_deposit_balance(&records);
             }
             Ok(())
         }
 
         /// Dangerous! remove current withdrawal proposal directly. Please check business logic before
         /// do this operation.
-        #[pallet::weight(<T as Config>::WeightInfo::remove_proposal())]
+        #[pallet::weight(<T as Config<I>>::WeightInfo::remove_proposal())]
         #[transactional]
         pub fn remove_proposal(origin: OriginFor<T>) -> DispatchResult {
             T::CouncilOrigin::try_origin(origin)
@@ -243,19 +312,19 @@ pub mod pallet {
             Self::apply_remove_proposal()
         }
 
-        /// Dangerous! Be careful to set BestIndex
-        #[pallet::weight(<T as Config>::WeightInfo::set_best_index())]
-        pub fn set_best_index(origin: OriginFor<T>, index: BtcHeaderIndex) -> DispatchResult {
+        /// Deprecated, use `rollback_headers` instead, as setting the best index directly leaves
+        /// the main chain inconsistent.
+        #[pallet::weight(1_000_000u64)]
+        pub fn set_best_index(origin: OriginFor<T>, _index: BtcHeaderIndex) -> DispatchResult {
             ensure_root(origin)?;
-            BestIndex::<T>::put(index);
-            Ok(())
+            Err(Error::<T, I>::Deprecated.into())
         }
 
         /// Dangerous! Be careful to set ConfirmedIndex
-        #[pallet::weight(<T as Config>::WeightInfo::set_confirmed_index())]
+        #[pallet::weight(<T as Config<I>>::WeightInfo::set_confirmed_index())]
         pub fn set_confirmed_index(origin: OriginFor<T>, index: BtcHeaderIndex) -> DispatchResult {
             ensure_root(origin)?;
-            ConfirmedIndex::<T>::put(index);
+            ConfirmedIndex::<T, I>::put(index);
             Ok(())
         }
 
@@ -265,12 +334,12 @@ pub mod pallet {
             T::CouncilOrigin::try_origin(origin)
                 .map(|_| ())
                 .or_else(ensure_root)?;
-            ConfirmationNumber::<T>::put(number);
+            ConfirmationNumber::<T, I>::put(number);
             Ok(())
         }
 
         /// Set bitcoin withdrawal fee
-        #[pallet::weight(<T as Config>::WeightInfo::set_btc_withdrawal_fee())]
+        #[pallet::weight(<T as Config<I>>::WeightInfo::set_btc_withdrawal_fee())]
         pub fn set_btc_withdrawal_fee(
             origin: OriginFor<T>,
             #[pallet::compact] fee: u64,
@@ -278,12 +347,12 @@ pub mod pallet {
             T::CouncilOrigin::try_origin(origin)
                 .map(|_| ())
                 .or_else(ensure_root)?;
-            BtcWithdrawalFee::<T>::put(fee);
+            BtcWithdrawalFee::<T, I>::put(fee);
             Ok(())
         }
 
         /// Set bitcoin deposit limit
-        #[pallet::weight(<T as Config>::WeightInfo::set_btc_deposit_limit())]
+        #[pallet::weight(<T as Config<I>>::WeightInfo::set_btc_deposit_limit())]
         pub fn set_btc_deposit_limit(
             origin: OriginFor<T>,
             #[pallet::compact] value: u64,
@@ -291,27 +360,215 @@ pub mod pallet {
             T::CouncilOrigin::try_origin(origin)
                 .map(|_| ())
                 .or_else(ensure_root)?;
-            BtcMinDeposit::<T>::put(value);
+            BtcMinDeposit::<T, I>::put(value);
             Ok(())
         }
 
         /// Set coming bot
-        #[pallet::weight(<T as Config>::WeightInfo::set_coming_bot())]
+        #[pallet::weight(<T as Config<I>>::WeightInfo::set_coming_bot())]
         pub fn set_coming_bot(origin: OriginFor<T>, bot: Option<T::AccountId>) -> DispatchResult {
             T::CouncilOrigin::try_origin(origin)
                 .map(|_| ())
                 .or_else(ensure_root)?;
             match bot {
-                None => ComingBot::<T>::kill(),
-                Some(n) => ComingBot::<T>::put(n),
+                None => ComingBot::<T, I>::kill(),
+                Some(n) => ComingBot::<T, I>::put(n),
+            }
+            Ok(())
+        }
+
+        /// Set the account whose X-BTC pays the extra fee of bumped withdrawal transactions,
+        /// `None` disables the fee bumping. It can't be changed while a fee bump is pending.
+        #[pallet::weight(<T as Config<I>>::WeightInfo::set_withdrawal_fee_reserve())]
+        pub fn set_withdrawal_fee_reserve(
+            origin: OriginFor<T>,
+            who: Option<T::AccountId>,
+        ) -> DispatchResult {
+            T::CouncilOrigin::try_origin(origin)
+                .map(|_| ())
+                .or_else(ensure_root)?;
+            ensure!(
+                Self::withdrawal_fee_bump().is_none() && Self::pending_cpfp_fee().is_none(),
+                Error::<T, I>::FeeBumpPending
+            );
+            match who {
+                None => WithdrawalFeeReserve::<T, I>::kill(),
+                Some(who) => WithdrawalFeeReserve::<T, I>::put(who),
+            }
+            Ok(())
+        }
+
+        /// Set the blocks that trustees have to finish a withdrawal proposal, 0 means no deadline
+        #[pallet::weight(<T as Config<I>>::WeightInfo::set_withdrawal_proposal_timeout())]
+        pub fn set_withdrawal_proposal_timeout(
+            origin: OriginFor<T>,
+            timeout: T::BlockNumber,
+        ) -> DispatchResult {
+            T::CouncilOrigin::try_origin(origin)
+                .map(|_| ())
+                .or_else(ensure_root)?;
+            WithdrawalProposalTimeout::<T, I>::put(timeout);
+            Ok(())
+        }
+
+        /// Set the blocks between two reserve checks (0 means no check), and the reserve ratio
+        /// in basis points below which the alarm fires
+        #[pallet::weight(<T as Config<I>>::WeightInfo::set_reserve_check())]
+        pub fn set_reserve_check(
+            origin: OriginFor<T>,
+            interval: T::BlockNumber,
+            alarm_threshold: u32,
+        ) -> DispatchResult {
+            T::CouncilOrigin::try_origin(origin)
+                .map(|_| ())
+                .or_else(ensure_root)?;
+            ReserveCheckInterval::<T, I>::put(interval);
+            ReserveAlarmThreshold::<T, I>::put(alarm_threshold);
+            Ok(())
+        }
+
+        /// Reset the tracked trustee utxos, used to import the utxos received before the
+        /// tracking, [txid, index, value, is_hot]
+        #[pallet::weight(<T as Config<I>>::WeightInfo::set_trustee_utxos(utxos.len() as u32))]
+        pub fn set_trustee_utxos(
+            origin: OriginFor<T>,
+            utxos: Vec<(H256, u32, u64, bool)>,
+        ) -> DispatchResult {
+            ensure_root(origin)?;
+            let _ = TrusteeUtxos::<T, I>::remove_all(None);
+            let _ = TrusteeHotUtxos::<T, I>::remove_all(None);
+            let mut balance = 0u64;
+            let mut hot_balance = 0u64;
+            for (txid, index, value, is_hot) in utxos {
+                if TrusteeUtxos::<T, I>::contains_key(&txid, index) {
+                    continue;
+                }
+                TrusteeUtxos::<T, I>::insert(&txid, index, value);
+                balance = balance.saturating_add(value);
+                if is_hot {
+                    TrusteeHotUtxos::<T, I>::insert(&txid, index, value);
+                    hot_balance = hot_balance.saturating_add(value);
+                }
+            }
+            TrusteeUtxoBalance::<T, I>::put(balance);
+            TrusteeHotUtxoBalance::<T, I>::put(hot_balance);
+            Ok(())
+        }
+
+        /// Anchor the light client at the trusted checkpoint header, which replaces the genesis
+        /// header.
+        ///
+        /// If the header is not on the main chain, it becomes the best and confirmed header, and
+        /// the main chain headers from its height are dropped from the main chain, which must not
+        /// contain any processed deposit or withdrawal.
+        #[pallet::weight(<T as Config<I>>::WeightInfo::set_checkpoint(header::MAX_ROLLBACK_DEPTH))]
+        pub fn set_checkpoint(
+            origin: OriginFor<T>,
+            header: Vec<u8>,
+            height: u32,
+            retarget_timestamp: u32,
+        ) -> DispatchResultWithPostInfo {
+            T::CouncilOrigin::try_origin(origin)
+                .map(|_| ())
+                .or_else(ensure_root)?;
+            let header: BtcHeader =
+                deserialize(header.as_slice()).map_err(|_| Error::<T, I>::DeserializeErr)?;
+            let checkpoint = BtcCheckpoint {
+                header,
+                height,
+                retarget_timestamp,
+            };
+            let depth = Self::apply_set_checkpoint(checkpoint)?;
+            Ok(Some(<T as Config<I>>::WeightInfo::set_checkpoint(depth)).into())
+        }
+
+        /// Set the target share of the reserves in the hot address, `None` disables the
+        /// rebalancing. The pending rebalance proposal is dropped.
+        #[pallet::weight(<T as Config<I>>::WeightInfo::set_rebalance_config())]
+        pub fn set_rebalance_config(
+            origin: OriginFor<T>,
+            config: Option<BtcRebalanceConfig>,
+        ) -> DispatchResult {
+            T::CouncilOrigin::try_origin(origin)
+                .map(|_| ())
+                .or_else(ensure_root)?;
+            if let Some(config) = config {
+                ensure!(
+                    config.target_hot_ratio.saturating_add(config.threshold) <= 10_000,
+                    Error::<T, I>::InvalidRebalanceConfig
+                );
+                RebalanceConfig::<T, I>::put(config);
+            } else {
+                RebalanceConfig::<T, I>::kill();
             }
+            RebalanceProposal::<T, I>::kill();
             Ok(())
         }
+
+        /// Trustee bump the fee of the current withdrawal proposal. `tx` is either the rbf
+        /// replacement of the proposal transaction or the cpfp child spending its change output.
+        /// The extra fee is charged to the X-BTC of the `WithdrawalFeeReserve` account.
+        #[pallet::weight(<T as Config<I>>::WeightInfo::bump_withdrawal_fee())]
+        #[transactional]
+        pub fn bump_withdrawal_fee(
+            origin: OriginFor<T>,
+            kind: BtcFeeBumpKind,
+            tx: Vec<u8>,
+        ) -> DispatchResultWithPostInfo {
+            let from = ensure_signed(origin)?;
+
+            ensure!(
+                !T::TrusteeSessionProvider::trustee_transition_state(),
+                Error::<T, I>::TrusteeTransitionPeriod
+            );
+
+            // committer must be in the trustee list
+            Self::ensure_trustee_or_bot(&from)?;
+
+            let tx = Self::deserialize_tx(tx.as_slice())?;
+            log!(
+                debug,
+                "[bump_withdrawal_fee] from:{:?}, kind:{:?}, tx:{:?}",
+                from,
+                kind,
+                tx
+            );
+
+            Self::apply_bump_withdrawal_fee(from, kind, tx)?;
+            Ok(Pays::No.into())
+        }
+
+        /// Trustee declares that the current withdrawal proposal tx has been signed by the trustee.
+        #[pallet::weight(<T as Config<I>>::WeightInfo::sign_withdrawal_proposal())]
+        pub fn sign_withdrawal_proposal(origin: OriginFor<T>) -> DispatchResultWithPostInfo {
+            let from = ensure_signed(origin)?;
+
+            Self::apply_sign_withdrawal_proposal(from)?;
+            Ok(Pays::No.into())
+        }
+
+        /// Roll the light client back to the main chain header `hash`.
+        ///
+        /// The headers above it are removed and the main chain and the confirmed index are
+        /// recomputed. It's rejected if any removed block contains a processed deposit or
+        /// withdrawal, unless `force` is set.
+        #[pallet::weight(<T as Config<I>>::WeightInfo::rollback_headers(header::MAX_ROLLBACK_DEPTH))]
+        pub fn rollback_headers(
+            origin: OriginFor<T>,
+            hash: H256,
+            force: bool,
+        ) -> DispatchResultWithPostInfo {
+            T::CouncilOrigin::try_origin(origin)
+                .map(|_| ())
+                .or_else(ensure_root)?;
+            let depth = Self::apply_rollback_headers(hash, force)?;
+            Ok(Some(<T as Config<I>>::WeightInfo::rollback_headers(depth)).into())
+        }
     }
 
     /// Error for the XBridge Bitcoin module
     #[pallet::error]
-    pub enum Error<T> {
+    pub enum Error<T, I = ()> {
         /// parse base58 addr error
         InvalidBase58,
         /// load addr from bytes error
@@ -370,11 +627,45 @@ pub mod pallet {
         TxOutputNotColdAddr,
         /// The total amount of the trust must be transferred out in full
         TxNotFullAmount,
+        /// The withdrawal tx does not signal replaceability
+        TxNotReplaceable,
+        /// The fee bump tx does not spend the expected inputs
+        FeeBumpInputsNotMatch,
+        /// The fee bump tx does not pay a higher fee
+        FeeNotIncreased,
+        /// The withdrawal fee reserve can't pay the extra fee
+        InsufficientFeeReserve,
+        /// Invalid recovery keys, threshold or timelock
+        InvalidRecoveryConfig,
+        /// The header is not on the main chain
+        NotMainChainHeader,
+        /// Roll back too many headers at once
+        RollbackTooDeep,
+        /// The rolled back blocks contain processed deposits or withdrawals
+        RollbackProcessedTxs,
+        /// The checkpoint conflicts with the stored header
+        InvalidCheckpoint,
+        /// The target hot ratio plus the threshold exceeds 100%
+        InvalidRebalanceConfig,
+        /// The rebalance tx moves less than the proposed amount to the cold address
+        TxNoColdOutput,
+        /// The withdrawal is still delayed
+        WithdrawalDelayed,
+        /// The header is not stored by the light client
+        UnknownHeader,
+        /// The withdrawal fee reserve can't be changed while a fee bump is pending
+        FeeBumpPending,
+        /// The rolled back blocks may contain the processed txs which are not tracked
+        RollbackUntrackedBlocks,
+        /// The tx of 64 bytes can be forged from an inner node of the merkle tree
+        InvalidTxLength,
+        /// The call is deprecated
+        Deprecated,
     }
 
     #[pallet::event]
     #[pallet::generate_deposit(pub(crate) fn deposit_event)]
-    pub enum Event<T: Config> {
+    pub enum Event<T: Config<I>, I: 'static = ()> {
         /// A Bitcoin header was validated and inserted. [btc_header_hash]
         HeaderInserted(H256),
         /// A Bitcoin transaction was processed. [tx_hash, block_hash, tx_state]
@@ -405,103 +696,264 @@ pub mod pallet {
         DepositedNamed(H256, Vec<u8>, Vec<u8>, BalanceOf<T>),
         /// A unclaimed deposit record was removed for named address. [prefix, depositor, deposit_amount, tx_hash, btc_address]
         PendingDepositNamedRemoved(Vec<u8>, Vec<u8>, BalanceOf<T>, H256, BtcAddress),
+        /// The fee of the withdrawal proposal was bumped. [trustee, kind, tx_hash, extra_fee]
+        WithdrawalFeeBumped(T::AccountId, BtcFeeBumpKind, H256, u64),
+        /// The withdrawal proposal was not finished before the deadline, the withdrawal
+        /// applications were rolled back. [tx_hash, withdrawal_ids, missing_signers]
+        WithdrawalProposalExpired(H256, Vec<u32>, Vec<T::AccountId>),
+        /// The reserves of the trustees were checked against the liabilities of the bridge.
+        /// [reserves, total_liabilities, reserve_ratio]
+        ReserveRatio(u64, u64, u32),
+        /// The reserve ratio is below the alarm threshold. [reserve_ratio, alarm_threshold]
+        ReserveAlarm(u32, u32),
+        /// The light client was rolled back.
+        /// [old_best, new_best, new_confirmed, removed_headers, processed_txs]
+        HeadersRolledBack(
+            BtcHeaderIndex,
+            BtcHeaderIndex,
+            Option<BtcHeaderIndex>,
+            u32,
+            Vec<H256>,
+        ),
+        /// The light client was anchored at a new checkpoint. [checkpoint, old_best, new_best]
+        CheckpointSet(BtcHeaderIndex, BtcHeaderIndex, BtcHeaderIndex),
+        /// The hot balance exceeds the target, a rebalance proposal was opened.
+        /// [hot_balance, reserves, amount]
+        RebalanceProposed(u64, u64, u64),
+        /// The rebalance proposal was finished by a hot and cold tx. [tx_hash, cold_amount]
+        Rebalanced(H256, u64),
     }
 
     /// best header info
     #[pallet::storage]
     #[pallet::getter(fn best_index)]
-    pub(crate) type BestIndex<T: Config> = StorageValue<_, BtcHeaderIndex, ValueQuery>;
+    pub(crate) type BestIndex<T: Config<I>, I: 'static = ()> =
+        StorageValue<_, BtcHeaderIndex, ValueQuery>;
 
     /// confirmed header info
     #[pallet::storage]
     #[pallet::getter(fn confirmed_index)]
-    pub(crate) type ConfirmedIndex<T: Config> = StorageValue<_, BtcHeaderIndex>;
+    pub(crate) type ConfirmedIndex<T: Config<I>, I: 'static = ()> = StorageValue<_, BtcHeaderIndex>;
+
+    /// the processed deposit and withdrawal txs of a block, which protect it from a rollback
+    #[pallet::storage]
+    #[pallet::getter(fn block_processed_txs)]
+    pub(crate) type BlockProcessedTxs<T: Config<I>, I: 'static = ()> =
+        StorageMap<_, Identity, H256, Vec<H256>, ValueQuery>;
+
+    /// the blocks up to this height may contain the processed txs processed before
+    /// `BlockProcessedTxs` was introduced, so they are only rolled back by force
+    #[pallet::storage]
+    #[pallet::getter(fn untracked_processed_height)]
+    pub(crate) type UntrackedProcessedHeight<T: Config<I>, I: 'static = ()> =
+        StorageValue<_, u32, ValueQuery>;
 
     /// block hash list for a height, include forked header hash
     #[pallet::storage]
     #[pallet::getter(fn block_hash_for)]
-    pub(crate) type BlockHashFor<T: Config> =
+    pub(crate) type BlockHashFor<T: Config<I>, I: 'static = ()> =
         StorageMap<_, Twox64Concat, u32, Vec<H256>, ValueQuery>;
 
     /// mark this blockhash is in mainchain
     #[pallet::storage]
     #[pallet::getter(fn main_chain)]
-    pub(crate) type MainChain<T: Config> = StorageMap<_, Identity, H256, bool, ValueQuery>;
+    pub(crate) type MainChain<T: Config<I>, I: 'static = ()> =
+        StorageMap<_, Identity, H256, bool, ValueQuery>;
 
     /// all valid blockheader (include forked blockheader)
     #[pallet::storage]
     #[pallet::getter(fn headers)]
-    pub(crate) type Headers<T: Config> = StorageMap<_, Identity, H256, BtcHeaderInfo>;
+    pub(crate) type Headers<T: Config<I>, I: 'static = ()> =
+        StorageMap<_, Identity, H256, BtcHeaderInfo>;
 
     /// mark tx has been handled, in case re-handle this tx, and log handle result
     #[pallet::storage]
     #[pallet::getter(fn tx_state)]
-    pub(crate) type TxState<T: Config> = StorageMap<_, Identity, H256, BtcTxState>;
+    pub(crate) type TxState<T: Config<I>, I: 'static = ()> =
+        StorageMap<_, Identity, H256, BtcTxState>;
 
     /// unclaimed deposit info, addr => tx_hash, btc value,
     #[pallet::storage]
     #[pallet::getter(fn pending_deposits)]
-    pub(crate) type PendingDeposits<T: Config> =
+    pub(crate) type PendingDeposits<T: Config<I>, I: 'static = ()> =
         StorageMap<_, Blake2_128Concat, BtcAddress, Vec<BtcDepositCache>, ValueQuery>;
 
+    /// The total value of `PendingDeposits`
+    #[pallet::storage]
+    #[pallet::getter(fn pending_deposit_balance)]
+    pub(crate) type PendingDepositBalance<T: Config<I>, I: 'static = ()> =
+        StorageValue<_, u64, ValueQuery>;
+
     /// withdrawal tx outs for account, tx_hash => outs ( out index => withdrawal account )
     #[pallet::storage]
     #[pallet::getter(fn withdrawal_proposal)]
-    pub(crate) type WithdrawalProposal<T: Config> =
+    pub(crate) type WithdrawalProposal<T: Config<I>, I: 'static = ()> =
         StorageValue<_, BtcWithdrawalProposal<T::AccountId>>;
 
     /// get GenesisInfo (header, height)
     #[pallet::storage]
     #[pallet::getter(fn genesis_info)]
-    pub(crate) type GenesisInfo<T: Config> = StorageValue<_, (BtcHeader, u32), ValueQuery>;
+    pub(crate) type GenesisInfo<T: Config<I>, I: 'static = ()> =
+        StorageValue<_, (BtcHeader, u32), ValueQuery>;
+
+    /// the checkpoint which replaced the genesis header, if any
+    #[pallet::storage]
+    #[pallet::getter(fn checkpoint)]
+    pub(crate) type Checkpoint<T: Config<I>, I: 'static = ()> = StorageValue<_, BtcCheckpoint>;
 
     /// get ParamsInfo from genesis_config
     #[pallet::storage]
     #[pallet::getter(fn params_info)]
-    pub(crate) type ParamsInfo<T: Config> = StorageValue<_, BtcParams, ValueQuery>;
+    pub(crate) type ParamsInfo<T: Config<I>, I: 'static = ()> =
+        StorageValue<_, BtcParams, ValueQuery>;
 
     ///  NetworkId for testnet or mainnet
     #[pallet::storage]
     #[pallet::getter(fn network_id)]
-    pub(crate) type NetworkId<T: Config> = StorageValue<_, BtcNetwork, ValueQuery>;
+    pub(crate) type NetworkId<T: Config<I>, I: 'static = ()> =
+        StorageValue<_, BtcNetwork, ValueQuery>;
 
     /// get ConfirmationNumber from genesis_config
     #[pallet::storage]
     #[pallet::getter(fn confirmation_number)]
-    pub(crate) type ConfirmationNumber<T: Config> = StorageValue<_, u32, ValueQuery>;
+    pub(crate) type ConfirmationNumber<T: Config<I>, I: 'static = ()> =
+        StorageValue<_, u32, ValueQuery>;
 
     /// get BtcWithdrawalFee from genesis_config
     #[pallet::storage]
     #[pallet::getter(fn btc_withdrawal_fee)]
-    pub(crate) type BtcWithdrawalFee<T: Config> = StorageValue<_, u64, ValueQuery>;
+    pub(crate) type BtcWithdrawalFee<T: Config<I>, I: 'static = ()> =
+        StorageValue<_, u64, ValueQuery>;
 
     #[pallet::type_value]
-    pub fn DefaultForMinDeposit<T: Config>() -> u64 {
+    pub fn DefaultForMinDeposit<T: Config<I>, I: 'static>() -> u64 {
         100000
     }
 
     /// min deposit value limit, default is 10w sotashi(0.001 BTC)
     #[pallet::storage]
     #[pallet::getter(fn btc_min_deposit)]
-    pub(crate) type BtcMinDeposit<T: Config> =
-        StorageValue<_, u64, ValueQuery, DefaultForMinDeposit<T>>;
+    pub(crate) type BtcMinDeposit<T: Config<I>, I: 'static = ()> =
+        StorageValue<_, u64, ValueQuery, DefaultForMinDeposit<T, I>>;
 
     /// max withdraw account count in bitcoin withdrawal transaction
     #[pallet::storage]
     #[pallet::getter(fn max_withdrawal_count)]
-    pub(crate) type MaxWithdrawalCount<T: Config> = StorageValue<_, u32, ValueQuery>;
+    pub(crate) type MaxWithdrawalCount<T: Config<I>, I: 'static = ()> =
+        StorageValue<_, u32, ValueQuery>;
 
     #[pallet::storage]
     #[pallet::getter(fn verifier)]
-    pub(crate) type Verifier<T: Config> = StorageValue<_, BtcTxVerifier, ValueQuery>;
+    pub(crate) type Verifier<T: Config<I>, I: 'static = ()> =
+        StorageValue<_, BtcTxVerifier, ValueQuery>;
 
     /// Coming bot helps update btc withdrawal transaction status
     #[pallet::storage]
     #[pallet::getter(fn coming_bot)]
-    pub(crate) type ComingBot<T: Config> = StorageValue<_, T::AccountId, OptionQuery>;
+    pub(crate) type ComingBot<T: Config<I>, I: 'static = ()> =
+        StorageValue<_, T::AccountId, OptionQuery>;
+
+    /// The account whose X-BTC pays the extra fee of bumped withdrawal transactions. The fee is
+    /// reserved when charged, and destroyed once the withdrawal tx paying it is packed.
+    #[pallet::storage]
+    #[pallet::getter(fn withdrawal_fee_reserve)]
+    pub(crate) type WithdrawalFeeReserve<T: Config<I>, I: 'static = ()> =
+        StorageValue<_, T::AccountId, OptionQuery>;
+
+    /// The fee bumps applied to the current withdrawal proposal
+    #[pallet::storage]
+    #[pallet::getter(fn withdrawal_fee_bump)]
+    pub(crate) type WithdrawalFeeBump<T: Config<I>, I: 'static = ()> = StorageValue<_, BtcFeeBump>;
+
+    /// The cpfp child of the packed withdrawal tx and its fee, which is held by the withdrawal
+    /// fee reserve until a tx spending the change output of the withdrawal tx is relayed.
+    #[pallet::storage]
+    #[pallet::getter(fn pending_cpfp_fee)]
+    pub(crate) type PendingCpfpFee<T: Config<I>, I: 'static = ()> =
+        StorageValue<_, (Transaction, u64)>;
+
+    /// The blocks that trustees have to finish a withdrawal proposal, 0 means no deadline
+    #[pallet::storage]
+    #[pallet::getter(fn withdrawal_proposal_timeout)]
+    pub(crate) type WithdrawalProposalTimeout<T: Config<I>, I: 'static = ()> =
+        StorageValue<_, T::BlockNumber, ValueQuery>;
+
+    /// The block at which the current withdrawal proposal expires. It's removed once a tx
+    /// spending the inputs of the proposal tx is relayed.
+    #[pallet::storage]
+    #[pallet::getter(fn withdrawal_proposal_deadline)]
+    pub(crate) type WithdrawalProposalDeadline<T: Config<I>, I: 'static = ()> =
+        StorageValue<_, T::BlockNumber>;
+
+    /// The expired withdrawal proposals, tx_hash => expired proposal
+    ///
+    /// Only the latest `MAX_EXPIRED_PROPOSALS` ones are kept.
+    #[pallet::storage]
+    #[pallet::getter(fn expired_proposals)]
+    pub(crate) type ExpiredProposals<T: Config<I>, I: 'static = ()> =
+        StorageMap<_, Identity, H256, BtcExpiredProposal<T::AccountId, T::BlockNumber>>;
+
+    /// The tx hashes of the kept expired withdrawal proposals, the oldest first
+    #[pallet::storage]
+    #[pallet::getter(fn expired_proposal_hashes)]
+    pub(crate) type ExpiredProposalHashes<T: Config<I>, I: 'static = ()> =
+        StorageValue<_, Vec<H256>, ValueQuery>;
+
+    /// The unspent outputs held by the current and last trustees, txid => index => value
+    #[pallet::storage]
+    #[pallet::getter(fn trustee_utxos)]
+    pub(crate) type TrusteeUtxos<T: Config<I>, I: 'static = ()> =
+        StorageDoubleMap<_, Identity, H256, Twox64Concat, u32, u64>;
+
+    /// The total value of `TrusteeUtxos`
+    #[pallet::storage]
+    #[pallet::getter(fn trustee_utxo_balance)]
+    pub(crate) type TrusteeUtxoBalance<T: Config<I>, I: 'static = ()> =
+        StorageValue<_, u64, ValueQuery>;
+
+    /// The blocks between two reserve checks, 0 means no check
+    #[pallet::storage]
+    #[pallet::getter(fn reserve_check_interval)]
+    pub(crate) type ReserveCheckInterval<T: Config<I>, I: 'static = ()> =
+        StorageValue<_, T::BlockNumber, ValueQuery>;
+
+    #[pallet::type_value]
+    pub fn DefaultReserveAlarmThreshold() -> u32 {
+        10_000
+    }
+
+    /// The reserve ratio in basis points below which the alarm fires, default is 100%
+    #[pallet::storage]
+    #[pallet::getter(fn reserve_alarm_threshold)]
+    pub(crate) type ReserveAlarmThreshold<T: Config<I>, I: 'static = ()> =
+        StorageValue<_, u32, ValueQuery, DefaultReserveAlarmThreshold>;
+
+    /// The tracked trustee utxos held by the current hot address, txid => index => value
+    #[pallet::storage]
+    #[pallet::getter(fn trustee_hot_utxos)]
+    pub(crate) type TrusteeHotUtxos<T: Config<I>, I: 'static = ()> =
+        StorageDoubleMap<_, Identity, H256, Twox64Concat, u32, u64>;
+
+    /// The total value of `TrusteeHotUtxos`
+    #[pallet::storage]
+    #[pallet::getter(fn trustee_hot_utxo_balance)]
+    pub(crate) type TrusteeHotUtxoBalance<T: Config<I>, I: 'static = ()> =
+        StorageValue<_, u64, ValueQuery>;
+
+    /// The target share of the reserves in the hot address, no rebalancing if none
+    #[pallet::storage]
+    #[pallet::getter(fn rebalance_config)]
+    pub(crate) type RebalanceConfig<T: Config<I>, I: 'static = ()> =
+        StorageValue<_, BtcRebalanceConfig>;
+
+    /// The pending proposal to move the hot balance above the target to the cold address
+    #[pallet::storage]
+    #[pallet::getter(fn rebalance_proposal)]
+    pub(crate) type RebalanceProposal<T: Config<I>, I: 'static = ()> =
+        StorageValue<_, BtcRebalanceProposal<T::BlockNumber>>;
 
     #[pallet::genesis_config]
-    pub struct GenesisConfig<T: Config> {
+    pub struct GenesisConfig<T: Config<I>, I: 'static = ()> {
         pub genesis_hash: H256,
         pub genesis_info: (BtcHeader, u32),
         pub genesis_trustees: Vec<T::AccountId>,
@@ -511,10 +963,11 @@ pub mod pallet {
         pub btc_withdrawal_fee: u64,
         pub max_withdrawal_count: u32,
         pub verifier: BtcTxVerifier,
+        pub phantom: PhantomData<I>,
     }
 
     #[cfg(feature = "std")]
-    impl<T: Config> Default for GenesisConfig<T> {
+    impl<T: Config<I>, I: 'static> Default for GenesisConfig<T, I> {
         fn default() -> Self {
             Self {
                 genesis_hash: Default::default(),
@@ -526,13 +979,14 @@ pub mod pallet {
                 btc_withdrawal_fee: Default::default(),
                 max_withdrawal_count: Default::default(),
                 verifier: Default::default(),
+                phantom: Default::default(),
             }
         }
     }
 
     #[pallet::genesis_build]
     #[cfg(feature = "std")]
-    impl<T: Config> GenesisBuild<T> for GenesisConfig<T> {
+    impl<T: Config<I>, I: 'static> GenesisBuild<T, I> for GenesisConfig<T, I> {
         fn build(&self) {
             let genesis_hash = &self.genesis_hash.clone();
             let (genesis_header, genesis_height) = &self.genesis_info.clone();
@@ -545,33 +999,39 @@ pub mod pallet {
                 height: *genesis_height,
             };
 
-            Headers::<T>::insert(&genesis_hash, header_info);
-            BlockHashFor::<T>::insert(&genesis_index.height, vec![genesis_hash]);
-            MainChain::<T>::insert(&genesis_hash, true);
-            BestIndex::<T>::put(genesis_index);
-            GenesisInfo::<T>::put(self.genesis_info);
-            ParamsInfo::<T>::put(self.params_info);
-            NetworkId::<T>::put(self.network_id);
-            ConfirmationNumber::<T>::put(self.confirmation_number);
-            BtcWithdrawalFee::<T>::put(self.btc_withdrawal_fee);
-            MaxWithdrawalCount::<T>::put(self.max_withdrawal_count);
-            Verifier::<T>::put(self.verifier);
+            Headers::<T, I>::insert(&genesis_hash, header_info);
+            BlockHashFor::<T, I>::insert(&genesis_index.height, vec![genesis_hash]);
+            MainChain::<T, I>::insert(&genesis_hash, true);
+            BestIndex::<T, I>::put(genesis_index);
+            GenesisInfo::<T, I>::put(self.genesis_info);
+            ParamsInfo::<T, I>::put(self.params_info);
+            NetworkId::<T, I>::put(self.network_id);
+            ConfirmationNumber::<T, I>::put(self.confirmation_number);
+            BtcWithdrawalFee::<T, I>::put(self.btc_withdrawal_fee);
+            MaxWithdrawalCount::<T, I>::put(self.max_withdrawal_count);
+            Verifier::<T, I>::put(self.verifier);
 
             // init trustee (not this action should ha)
             if !self.genesis_trustees.is_empty() {
                 T::TrusteeSessionProvider::genesis_trustee(
-                    Pallet::<T>::chain(),
+                    Pallet::<T, I>::chain(),
                     &self.genesis_trustees,
                 );
             }
         }
     }
 
-    impl<T: Config> ChainT<BalanceOf<T>> for Pallet<T> {
-        const ASSET_ID: AssetId = xp_protocol::X_BTC;
+    impl<T: Config<I>, I: 'static> xpallet_gateway_common::traits::ChainProvider for Pallet<T, I> {
+        fn chain() -> Chain {
+            T::Family::CHAIN
+        }
+    }
+
+    impl<T: Config<I>, I: 'static> ChainT<BalanceOf<T>> for Pallet<T, I> {
+        const ASSET_ID: AssetId = T::Family::ASSET_ID;
 
         fn chain() -> Chain {
-            Chain::Bitcoin
+            T::Family::CHAIN
         }
 
         fn check_addr(addr: &[u8], _: &[u8]) -> DispatchResult {
@@ -585,11 +1045,11 @@ pub mod pallet {
                 err
             })?;
 
-            match get_current_trustee_address_pair::<T>() {
+            match get_current_trustee_address_pair::<T, I>() {
                 Ok((hot_addr, cold_addr)) => {
                     // do not allow withdraw from trustee address
                     if address == hot_addr || address == cold_addr {
-                        return Err(Error::<T>::InvalidAddress.into());
+                        return Err(Error::<T, I>::InvalidAddress.into());
                     }
                 }
                 Err(err) => {
@@ -615,35 +1075,27 @@ pub mod pallet {
         }
     }
 
-    impl<T: Config> TotalSupply<BalanceOf<T>> for Pallet<T> {
+    impl<T: Config<I>, I: 'static> TotalSupply<BalanceOf<T>> for Pallet<T, I> {
         fn total_supply() -> BalanceOf<T> {
-            let pending_deposits: BalanceOf<T> = PendingDeposits::<T>::iter_values()
-                .map(|deposits| {
-                    deposits
-                        .into_iter()
-                        .map(|deposit| deposit.balance)
-                        .sum::<u64>()
-                })
-                .sum::<u64>()
-                .saturated_into();
+            let pending_deposits: BalanceOf<T> = Self::pending_deposit_balance().saturated_into();
 
-            let asset_supply = xpallet_assets::Pallet::<T>::total_issuance(&xp_protocol::X_BTC);
+            let asset_supply = xpallet_assets::Pallet::<T>::total_issuance(&Self::ASSET_ID);
             asset_supply.saturating_add(pending_deposits)
         }
     }
 
-    impl<T: Config> ProposalProvider for Pallet<T> {
+    impl<T: Config<I>, I: 'static> ProposalProvider for Pallet<T, I> {
         type WithdrawalProposal = BtcWithdrawalProposal<T::AccountId>;
         fn get_withdrawal_proposal() -> Option<Self::WithdrawalProposal> {
             Self::withdrawal_proposal()
         }
     }
 
-    impl<T: Config> Pallet<T> {
+    impl<T: Config<I>, I: 'static> Pallet<T, I> {
         /// Helper function for deserializing the slice of raw tx.
         #[inline]
-        pub(crate) fn deserialize_tx(input: &[u8]) -> Result<Transaction, Error<T>> {
-            deserialize(Reader::new(input)).map_err(|_| Error::<T>::DeserializeErr)
+        pub(crate) fn deserialize_tx(input: &[u8]) -> Result<Transaction, Error<T, I>> {
+            deserialize(Reader::new(input)).map_err(|_| Error::<T, I>::DeserializeErr)
         }
 
         #[transactional]
@@ -655,7 +1107,7 @@ pub mod pallet {
                     "[apply_push_header] The BTC header already exists, hash:{:?}",
                     header.hash()
                 );
-                return Err(Error::<T>::ExistingHeader.into());
+                return Err(Error::<T, I>::ExistingHeader.into());
             }
             // prev header should exist, thus we reject orphan block
             let prev_info = Self::headers(header.previous_header_hash).ok_or_else(|| {
@@ -664,7 +1116,7 @@ pub mod pallet {
                     "[check_prev_and_convert] Can not find prev header, current header:{:?}",
                     header
                 );
-                Error::<T>::PrevHeaderNotExisted
+                Error::<T, I>::PrevHeaderNotExisted
             })?;
 
             // convert btc header to self header info
@@ -673,15 +1125,15 @@ pub mod pallet {
                 height: prev_info.height + 1,
             };
             // verify header
-            let header_verifier = header::HeaderVerifier::new::<T>(&header_info);
-            header_verifier.check::<T>()?;
+            let header_verifier = header::HeaderVerifier::new::<T, I>(&header_info);
+            header_verifier.check::<T, I>()?;
 
             // insert into storage
             let hash = header_info.header.hash();
             // insert valid header into storage
-            Headers::<T>::insert(&hash, header_info.clone());
+            Headers::<T, I>::insert(&hash, header_info.clone());
             // storage height => block list (contains forked header hash)
-            BlockHashFor::<T>::mutate(header_info.height, |v| {
+            BlockHashFor::<T, I>::mutate(header_info.height, |v| {
                 if !v.contains(&hash) {
                     v.push(hash);
                 }
@@ -698,7 +1150,7 @@ pub mod pallet {
 
             if header_info.height > best_index.height {
                 // note update_confirmed_header would mutate other storage depend on BlockHashFor
-                let confirmed_index = header::update_confirmed_header::<T>(&header_info);
+                let confirmed_index = header::update_confirmed_header::<T, I>(&header_info);
                 log!(
                     info,
                     "[apply_push_header] Update new height:{}, hash:{:?}, confirm:{:?}",
@@ -712,7 +1164,7 @@ pub mod pallet {
                     hash,
                     height: header_info.height,
                 };
-                BestIndex::<T>::put(new_best_index);
+                BestIndex::<T, I>::put(new_best_index);
             } else {
                 // forked chain
                 log!(
@@ -721,9 +1173,9 @@ pub mod pallet {
                     best_index.height,
                     header_info.height
                 );
-                header::check_confirmed_header::<T>(&header_info)?;
+                header::check_confirmed_header::<T, I>(&header_info)?;
             };
-            Self::deposit_event(Event::<T>::HeaderInserted(hash));
+            Self::deposit_event(Event::<T, I>::HeaderInserted(hash));
             Ok(())
         }
 
@@ -733,7 +1185,7 @@ pub mod pallet {
         ) -> DispatchResult {
             let tx_hash = tx.raw.hash();
             let block_hash = tx.block_hash;
-            let header_info = Pallet::<T>::headers(&tx.block_hash).ok_or_else(|| {
+            let header_info = Pallet::<T, I>::headers(&tx.block_hash).ok_or_else(|| {
                 log!(
                     error,
                     "[apply_push_transaction] Tx's block header ({:?}) must exist before",
@@ -743,21 +1195,24 @@ pub mod pallet {
             })?;
             let merkle_root = header_info.header.merkle_root_hash;
             // verify, check merkle proof
-            tx::validate_transaction::<T>(&tx, merkle_root, prev_tx.as_ref())?;
+            tx::validate_transaction::<T, I>(&tx, merkle_root, prev_tx.as_ref())?;
 
             // ensure the tx should belong to the main chain, means should submit main chain tx,
             // e.g. a tx may be packed in main chain block, and forked chain block, only submit main chain tx
             // could pass the verify.
-            ensure!(Self::main_chain(&tx.block_hash), Error::<T>::UnconfirmedTx);
+            ensure!(
+                Self::main_chain(&tx.block_hash),
+                Error::<T, I>::UnconfirmedTx
+            );
             // if ConfirmedIndex not set, due to confirm height not beyond genesis height
-            let confirmed = Self::confirmed_index().ok_or(Error::<T>::UnconfirmedTx)?;
+            let confirmed = Self::confirmed_index().ok_or(Error::<T, I>::UnconfirmedTx)?;
             let height = header_info.height;
             if height > confirmed.height {
                 log!(error,
                 "[apply_push_transaction] Receive an unconfirmed tx (height:{}, hash:{:?}), confirmed index (height:{}, hash:{:?})",
                 height, tx_hash, confirmed.height, confirmed.hash
             );
-                return Err(Error::<T>::UnconfirmedTx.into());
+                return Err(Error::<T, I>::UnconfirmedTx.into());
             }
             // check whether replayed tx has been processed, just process failed and not processed tx;
             match Self::tx_state(&tx_hash) {
@@ -768,16 +1223,16 @@ pub mod pallet {
                         "[apply_push_transaction] Reject processed tx (hash:{:?}, type:{:?}, result:{:?})",
                         tx_hash, state.tx_type, state.result
                     );
-                        return Err(Error::<T>::ReplayedTx.into());
+                        return Err(Error::<T, I>::ReplayedTx.into());
                     }
                 }
             }
 
-            let network = Pallet::<T>::network_id();
-            let min_deposit = Pallet::<T>::btc_min_deposit();
-            let current_trustee_pair = get_current_trustee_address_pair::<T>()?;
-            let last_trustee_pair = get_last_trustee_address_pair::<T>().ok();
-            let state = tx::process_tx::<T>(
+            let network = Pallet::<T, I>::network_id();
+            let min_deposit = Pallet::<T, I>::btc_min_deposit();
+            let current_trustee_pair = get_current_trustee_address_pair::<T, I>()?;
+            let last_trustee_pair = get_last_trustee_address_pair::<T, I>().ok();
+            let state = tx::process_tx::<T, I>(
                 tx.raw,
                 prev_tx,
                 network,
@@ -785,16 +1240,59 @@ pub mod pallet {
                 current_trustee_pair,
                 last_trustee_pair,
             );
-            TxState::<T>::insert(&tx_hash, state);
-            Self::deposit_event(Event::<T>::TxProcessed(tx_hash, block_hash, state));
+            TxState::<T, I>::insert(&tx_hash, state);
+            if state.result == BtcTxResult::Success
+                && matches!(state.tx_type, BtcTxType::Deposit | BtcTxType::Withdrawal)
+            {
+                BlockProcessedTxs::<T, I>::append(&block_hash, tx_hash);
+            }
+            Self::deposit_event(Event::<T, I>::TxProcessed(tx_hash, block_hash, state));
             match state.result {
                 BtcTxResult::Success => Ok(()),
-                BtcTxResult::Failure => Err(Error::<T>::ProcessTxFailed.into()),
+                BtcTxResult::Failure => Err(Error::<T, I>::ProcessTxFailed.into()),
             }
         }
 
+        pub(crate) fn apply_rollback_headers(
+            hash: H256,
+            force: bool,
+        ) -> Result<u32, DispatchError> {
+            let target = Self::headers(&hash).ok_or(Error::<T, I>::NotMainChainHeader)?;
+            let old_best = Self::best_index();
+            let (confirmed, removed, processed_txs) = header::rollback_to::<T, I>(&target, force)?;
+            let new_best = Self::best_index();
+            Self::deposit_event(Event::<T, I>::HeadersRolledBack(
+                old_best,
+                new_best,
+                confirmed,
+                removed,
+                processed_txs,
+            ));
+            Ok(old_best.height - new_best.height)
+        }
+
+        pub(crate) fn apply_set_checkpoint(
+            checkpoint: BtcCheckpoint,
+        ) -> Result<u32, DispatchError> {
+            let old_best = Self::best_index();
+            let depth = header::install_checkpoint::<T, I>(&checkpoint)?;
+            let index = BtcHeaderIndex {
+                hash: checkpoint.header.hash(),
+                height: checkpoint.height,
+            };
+            Self::deposit_event(Event::<T, I>::CheckpointSet(
+                index,
+                old_best,
+                Self::best_index(),
+            ));
+            Ok(depth)
+        }
+
         pub(crate) fn apply_remove_proposal() -> DispatchResult {
-            if let Some(proposal) = WithdrawalProposal::<T>::take() {
+            // nothing is settled, return all the charged fee to the reserve
+            trustee::settle_fee_bump::<T, I>(None);
+            WithdrawalProposalDeadline::<T, I>::kill();
+            if let Some(proposal) = WithdrawalProposal::<T, I>::take() {
                 for id in proposal.withdrawal_id_list.iter() {
                     xpallet_gateway_records::Pallet::<T>::set_withdrawal_state_by_root(
                         *id,
@@ -806,17 +1304,33 @@ pub mod pallet {
         }
     }
 
-    impl<T: Config> Pallet<T> {
+    impl<T: Config<I>, I: 'static> Pallet<T, I> {
         pub fn verify_bech32_address(data: &[u8]) -> Result<Address, DispatchError> {
-            let addr = core::str::from_utf8(data).map_err(|_| Error::<T>::InvalidAddr)?;
-            Address::from_str(addr).map_err(|_| Error::<T>::InvalidAddr.into())
+            ensure!(T::Family::accept_bech32(), Error::<T, I>::InvalidAddr);
+            let addr = core::str::from_utf8(data).map_err(|_| Error::<T, I>::InvalidAddr)?;
+            Address::from_str(addr).map_err(|_| Error::<T, I>::InvalidAddr.into())
         }
 
         pub fn verify_bs58_address(data: &[u8]) -> Result<Address, DispatchError> {
-            let r = bs58::decode(data)
+            let mut r = bs58::decode(data)
                 .into_vec()
-                .map_err(|_| Error::<T>::InvalidBase58)?;
-            let addr = Address::from_layout(&r).map_err(|_| Error::<T>::InvalidAddr)?;
+                .map_err(|_| Error::<T, I>::InvalidBase58)?;
+            let version = *r.first().ok_or(Error::<T, I>::InvalidAddr)?;
+            let btc_version =
+                T::Family::base58_version(version).ok_or(Error::<T, I>::InvalidAddr)?;
+            if btc_version != version {
+                // verify the checksum with the version of the chain, then rebuild it with the
+                // version of bitcoin
+                let payload_len = r.len().checked_sub(4).ok_or(Error::<T, I>::InvalidAddr)?;
+                ensure!(
+                    checksum(&r[..payload_len]).as_bytes() == &r[payload_len..],
+                    Error::<T, I>::InvalidAddr
+                );
+                r[0] = btc_version;
+                let sum = checksum(&r[..payload_len]);
+                r[payload_len..].copy_from_slice(sum.as_bytes());
+            }
+            let addr = Address::from_layout(&r).map_err(|_| Error::<T, I>::InvalidAddr)?;
             Ok(addr)
         }
 
@@ -835,12 +1349,12 @@ pub mod pallet {
         ) -> Result<bool, DispatchError> {
             let tx = Self::deserialize_tx(raw_tx.as_slice())?;
 
-            let current_trustee_pair = get_current_trustee_address_pair::<T>()?;
+            let current_trustee_pair = get_current_trustee_address_pair::<T, I>()?;
             let all_outputs_is_trustee = tx
                 .outputs
                 .iter()
                 .map(|output| {
-                    xp_gateway_bitcoin::extract_output_addr(output, NetworkId::<T>::get())
+                    xp_gateway_bitcoin::extract_output_addr(output, NetworkId::<T, I>::get())
                         .unwrap_or_default()
                 })
                 .all(|addr| xp_gateway_bitcoin::is_trustee_addr(addr, current_trustee_pair));
@@ -849,12 +1363,12 @@ pub mod pallet {
             if T::TrusteeSessionProvider::trustee_transition_state() {
                 // check trustee transition tx
                 // tx output address = new hot address
-                let prev_trustee_pair = get_last_trustee_address_pair::<T>()?;
+                let prev_trustee_pair = get_last_trustee_address_pair::<T, I>()?;
                 let all_outputs_is_current_cold_address = tx
                     .outputs
                     .iter()
                     .map(|output| {
-                        xp_gateway_bitcoin::extract_output_addr(output, NetworkId::<T>::get())
+                        xp_gateway_bitcoin::extract_output_addr(output, NetworkId::<T, I>::get())
                             .unwrap_or_default()
                     })
                     .all(|addr| addr.hash == current_trustee_pair.1.hash);
@@ -863,7 +1377,7 @@ pub mod pallet {
                     .outputs
                     .iter()
                     .map(|output| {
-                        xp_gateway_bitcoin::extract_output_addr(output, NetworkId::<T>::get())
+                        xp_gateway_bitcoin::extract_output_addr(output, NetworkId::<T, I>::get())
                             .unwrap_or_default()
                     })
                     .all(|addr| addr.hash == prev_trustee_pair.1.hash);
@@ -871,29 +1385,215 @@ pub mod pallet {
                 // Ensure that all outputs are cold addresses
                 ensure!(
                     all_outputs_is_current_cold_address || all_outputs_is_prev_cold_address,
-                    Error::<T>::TxOutputNotColdAddr
+                    Error::<T, I>::TxOutputNotColdAddr
                 );
                 // Ensure that all amounts are sent
-                ensure!(full_amount, Error::<T>::TxNotFullAmount);
+                ensure!(full_amount, Error::<T, I>::TxNotFullAmount);
 
+                Ok(true)
+            } else if let (true, Some(proposal)) =
+                (withdrawal_id_list.is_empty(), Self::rebalance_proposal())
+            {
+                // check rebalance tx
+                // non-change output address = current cold address
+                let mut cold_amount = 0u64;
+                for output in tx.outputs.iter() {
+                    let addr =
+                        xp_gateway_bitcoin::extract_output_addr(output, NetworkId::<T, I>::get())
+                            .unwrap_or_default();
+                    if addr.hash == current_trustee_pair.1.hash {
+                        cold_amount = cold_amount.saturating_add(output.value);
+                    } else {
+                        ensure!(
+                            addr.hash == current_trustee_pair.0.hash,
+                            Error::<T, I>::TxOutputNotColdAddr
+                        );
+                    }
+                }
+                ensure!(
+                    cold_amount >= proposal.amount,
+                    Error::<T, I>::TxNoColdOutput
+                );
                 Ok(true)
             } else if all_outputs_is_trustee {
                 Ok(true)
             } else {
                 // check normal withdrawal tx
-                trustee::check_withdraw_tx::<T>(&tx, &withdrawal_id_list)?;
+                trustee::check_withdraw_tx::<T, I>(&tx, &withdrawal_id_list)?;
                 Ok(true)
             }
         }
     }
 
+    /// Proof of reserves
+    impl<T: Config<I>, I: 'static> Pallet<T, I> {
+        /// Record the reserve ratio and fire the alarm if it is below the threshold.
+        ///
+        /// Only the running totals are read, the breakdown of the liabilities is left to
+        /// `get_reserve_report`.
+        pub(crate) fn check_reserves() {
+            let reserves = Self::trustee_utxo_balance();
+            let total_liabilities = Self::total_liabilities();
+            let reserve_ratio = Self::reserve_ratio(reserves, total_liabilities);
+            Self::deposit_event(Event::<T, I>::ReserveRatio(
+                reserves,
+                total_liabilities,
+                reserve_ratio,
+            ));
+
+            let threshold = Self::reserve_alarm_threshold();
+            if reserve_ratio < threshold {
+                log!(
+                    warn,
+                    "[check_reserves] Reserve ratio {} is below the alarm threshold {}, reserves:{}, total_liabilities:{}",
+                    reserve_ratio,
+                    threshold,
+                    reserves,
+                    total_liabilities
+                );
+                Self::deposit_event(Event::<T, I>::ReserveAlarm(reserve_ratio, threshold));
+            }
+        }
+
+        /// The total liabilities of the bridge, i.e. the X-BTC issuance, which includes the
+        /// balances of the proxy accounts, the BTC issued to the evm accounts which is not
+        /// accounted as X-BTC, and the unclaimed deposits.
+        fn total_liabilities() -> u64 {
+            xpallet_assets::Pallet::<T>::total_issuance(&Self::ASSET_ID)
+                .saturated_into::<u64>()
+                .saturating_add(Self::evm_liabilities())
+                .saturating_add(Self::pending_deposit_balance())
+        }
+
+        /// reserves / total_liabilities, in basis points
+        fn reserve_ratio(reserves: u64, total_liabilities: u64) -> u32 {
+            if total_liabilities == 0 {
+                10_000
+            } else {
+                (reserves as u128 * 10_000 / total_liabilities as u128).saturated_into()
+            }
+        }
+
+        /// Add the value of the deposits newly cached in `PendingDeposits`.
+        pub(crate) fn add_pending_deposit_balance(balance: u64) {
+            PendingDepositBalance::<T, I>::mutate(|total| *total = total.saturating_add(balance));
+        }
+
+        /// Subtract the value of the deposits taken from `PendingDeposits`.
+        pub(crate) fn sub_pending_deposit_balance(records: &[BtcDepositCache]) {
+            let balance = records
+                .iter()
+                .fold(0u64, |acc, record| acc.saturating_add(record.balance));
+            PendingDepositBalance::<T, I>::mutate(|total| *total = total.saturating_sub(balance));
+        }
+
+        /// Open a rebalance proposal if the hot balance exceeds the target share of the reserves
+        /// by more than the threshold.
+        pub(crate) fn check_rebalance(now: T::BlockNumber) {
+            let config = match Self::rebalance_config() {
+                Some(config) => config,
+                None => return,
+            };
+            if Self::rebalance_proposal().is_some()
+                || T::TrusteeSessionProvider::trustee_transition_state()
+            {
+                return;
+            }
+
+            let hot_balance = Self::trustee_hot_utxo_balance();
+            let reserves = Self::trustee_utxo_balance();
+            let hot_limit = reserves as u128
+                * config.target_hot_ratio.saturating_add(config.threshold) as u128
+                / 10_000;
+            if (hot_balance as u128) <= hot_limit {
+                return;
+            }
+
+            let target: u64 =
+                (reserves as u128 * config.target_hot_ratio as u128 / 10_000).saturated_into();
+            let amount = hot_balance.saturating_sub(target);
+            log!(
+                info,
+                "[check_rebalance] Hot balance {} exceeds the target {}, propose to move {} to the cold address",
+                hot_balance,
+                target,
+                amount
+            );
+            RebalanceProposal::<T, I>::put(BtcRebalanceProposal {
+                hot_balance,
+                reserves,
+                amount,
+                opened_at: now,
+            });
+            Self::deposit_event(Event::<T, I>::RebalanceProposed(
+                hot_balance,
+                reserves,
+                amount,
+            ));
+        }
+
+        /// Track the trustee utxos spent and created by the tx.
+        pub(crate) fn update_trustee_utxos(
+            tx: &Transaction,
+            network: BtcNetwork,
+            current_trustee_pair: (Address, Address),
+            last_trustee_pair: Option<(Address, Address)>,
+        ) {
+            for input in tx.inputs() {
+                let outpoint = &input.previous_output;
+                if let Some(value) = TrusteeUtxos::<T, I>::take(&outpoint.txid, outpoint.index) {
+                    TrusteeUtxoBalance::<T, I>::mutate(|balance| {
+                        *balance = balance.saturating_sub(value)
+                    });
+                }
+                if let Some(value) = TrusteeHotUtxos::<T, I>::take(&outpoint.txid, outpoint.index) {
+                    TrusteeHotUtxoBalance::<T, I>::mutate(|balance| {
+                        *balance = balance.saturating_sub(value)
+                    });
+                }
+            }
+
+            let txid = tx.hash();
+            for (index, output) in tx.outputs().iter().enumerate() {
+                let addr = xp_gateway_bitcoin::extract_output_addr(output, network);
+                let is_trustee_output = match addr {
+                    Some(addr) => {
+                        xp_gateway_bitcoin::is_trustee_addr(addr, current_trustee_pair)
+                            || last_trustee_pair.map_or(false, |pair| {
+                                xp_gateway_bitcoin::is_trustee_addr(addr, pair)
+                            })
+                    }
+                    None => false,
+                };
+                let index = index as u32;
+                if is_trustee_output && !TrusteeUtxos::<T, I>::contains_key(&txid, index) {
+                    TrusteeUtxos::<T, I>::insert(&txid, index, output.value);
+                    TrusteeUtxoBalance::<T, I>::mutate(|balance| {
+                        *balance = balance.saturating_add(output.value)
+                    });
+                    if addr.map_or(false, |addr| addr.hash == current_trustee_pair.0.hash) {
+                        TrusteeHotUtxos::<T, I>::insert(&txid, index, output.value);
+                        TrusteeHotUtxoBalance::<T, I>::mutate(|balance| {
+                            *balance = balance.saturating_add(output.value)
+                        });
+                    }
+                }
+            }
+        }
+    }
+
     /// Storage Query RPCs
-    impl<T: Config> Pallet<T> {
+    impl<T: Config<I>, I: 'static> Pallet<T, I> {
         /// Get withdrawal proposal
         pub fn get_withdrawal_proposal() -> Option<BtcWithdrawalProposal<T::AccountId>> {
             Self::withdrawal_proposal()
         }
 
+        /// Get the fee bumps of the withdrawal proposal
+        pub fn get_withdrawal_fee_bump() -> Option<BtcFeeBump> {
+            Self::withdrawal_fee_bump()
+        }
+
         /// Get genesis info
         pub fn get_genesis_info() -> (BtcHeader, u32) {
             Self::genesis_info()
@@ -903,5 +1603,114 @@ pub mod pallet {
         pub fn get_btc_block_header(txid: H256) -> Option<BtcHeaderInfo> {
             Self::headers(txid)
         }
+
+        /// Get the liabilities of the bridge against the tracked trustee utxos
+        pub fn get_reserve_report() -> BtcReserveReport {
+            let mut proxy_balance = 0u64;
+            let mut aptos = 0u64;
+            let mut named = Vec::new();
+            for (dst_chain, proxy) in T::AddressBinding::dst_chain_proxy_addresses() {
+                let balance: u64 =
+                    xpallet_assets::Pallet::<T>::all_type_asset_balance(&proxy, &Self::ASSET_ID)
+                        .saturated_into();
+                match dst_chain {
+                    DstChain::Aptos => aptos = aptos.saturating_add(balance),
+                    DstChain::Named(prefix) => named.push((prefix, balance)),
+                    _ => continue,
+                }
+                proxy_balance = proxy_balance.saturating_add(balance);
+            }
+
+            let evm = Self::evm_liabilities();
+            let wasm = xpallet_assets::Pallet::<T>::total_issuance(&Self::ASSET_ID)
+                .saturated_into::<u64>()
+                .saturating_sub(proxy_balance);
+            let pending = Self::pending_deposit_balance();
+
+            let total_liabilities = wasm
+                .saturating_add(evm)
+                .saturating_add(proxy_balance)
+                .saturating_add(pending);
+            let reserves = Self::trustee_utxo_balance();
+            let reserve_ratio = Self::reserve_ratio(reserves, total_liabilities);
+
+            BtcReserveReport {
+                wasm,
+                evm,
+                aptos,
+                named,
+                pending,
+                total_liabilities,
+                reserves,
+                reserve_ratio,
+                best_height: Self::best_index().height,
+                confirmed_height: Self::confirmed_index().map(|index| index.height),
+            }
+        }
+
+        /// The BTC issued to the evm accounts which is not accounted as X-BTC, in satoshi.
+        ///
+        /// The X-BTC issuance is authoritative. Once the X-BTC balances are held by the btc
+        /// ledger, which is the currency of the evm as well, the evm balances are part of it.
+        fn evm_liabilities() -> u64 {
+            if <T as xpallet_assets::Config>::AssetLedger::holds(&Self::ASSET_ID) {
+                0
+            } else {
+                Self::evm_issuance()
+            }
+        }
+
+        /// The total issuance of the evm currency in satoshi.
+        fn evm_issuance() -> u64 {
+            let issuance: u128 =
+                <T as pallet_evm::Config>::Currency::total_issuance().saturated_into();
+            issuance
+                .checked_div(EVM_SATOSHI)
+                .unwrap_or_default()
+                .saturated_into()
+        }
+    }
+
+    /// Light client queries
+    impl<T: Config<I>, I: 'static> Pallet<T, I> {
+        /// Returns the main chain header at `height`.
+        pub fn main_chain_header_at(height: u32) -> Option<BtcHeaderInfo> {
+            Self::block_hash_for(height)
+                .into_iter()
+                .find(|hash| Self::main_chain(hash))
+                .and_then(Self::headers)
+        }
+
+        /// Returns the confirmations of the stored header `hash`, i.e., 1 for the best header
+        /// and 0 for a header not on the main chain.
+        pub fn confirmations_of(hash: &H256) -> Option<u32> {
+            let info = Self::headers(hash)?;
+            if !Self::main_chain(hash) {
+                return Some(0);
+            }
+            Some(Self::best_index().height.saturating_sub(info.height) + 1)
+        }
+
+        /// Verifies that `raw_tx` is included in the stored header `block_hash` by the partial
+        /// merkle tree `proof`, both in bitcoin serialization, and returns the tx hash.
+        pub fn verify_tx_inclusion(
+            raw_tx: &[u8],
+            proof: &[u8],
+            block_hash: H256,
+        ) -> Result<H256, DispatchError> {
+            let raw = Self::deserialize_tx(raw_tx)?;
+            // The concatenation of two hashes is 64 bytes as well, so the inner nodes of the
+            // merkle tree could be proved as the tx (CVE-2017-12842).
+            ensure!(serialize(&raw).len() != 64, Error::<T, I>::InvalidTxLength);
+            let header_info = Self::headers(&block_hash).ok_or(Error::<T, I>::UnknownHeader)?;
+            let tx = BtcRelayedTx {
+                block_hash,
+                raw,
+                merkle_proof: deserialize(Reader::new(proof))
+                    .map_err(|_| Error::<T, I>::DeserializeErr)?,
+            };
+            tx::validate_transaction::<T, I>(&tx, header_info.header.merkle_root_hash, None)?;
+            Ok(tx.raw.hash())
+        }
     }
 }
@@ -0,0 +1,6 @@
+// Copyright 2019-2023 ChainX Project Authors. Licensed under GPL-3.0.
+
+//! All migrations of this pallet.
+
+pub mod pending_deposits;
+pub mod processed_txs;
@@ -0,0 +1,39 @@
+// Copyright 2019-2023 ChainX Project Authors. Licensed under GPL-3.0.
+
+use frame_support::{log::info, traits::Get, weights::Weight};
+
+use crate::{Config, PendingDepositBalance, PendingDeposits};
+
+/// Apply all of the migrations due to the running total of the pending deposits.
+///
+/// ### Warning
+///
+/// Use with care and run at your own risk.
+pub fn apply<T: Config<I>, I: 'static>() -> Weight {
+    info!(
+        target: "runtime::bitcoin",
+        "Running migration for the total value of the pending deposits"
+    );
+
+    init_pending_deposit_balance::<T, I>()
+}
+
+/// Sum up the value of the deposits cached before into `PendingDepositBalance`, which is
+/// maintained wherever the deposits are cached or taken afterwards.
+pub fn init_pending_deposit_balance<T: Config<I>, I: 'static>() -> Weight {
+    let mut count: Weight = 0;
+    let balance = PendingDeposits::<T, I>::iter_values()
+        .flatten()
+        .fold(0u64, |acc, deposit| {
+            count += 1;
+            acc.saturating_add(deposit.balance)
+        });
+    PendingDepositBalance::<T, I>::put(balance);
+    info!(
+        target: "runtime::bitcoin",
+        "the total value of {} pending deposits is {}.",
+        count,
+        balance,
+    );
+    T::DbWeight::get().reads_writes(count, 1)
+}
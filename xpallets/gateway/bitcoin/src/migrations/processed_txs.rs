@@ -0,0 +1,35 @@
+// Copyright 2019-2023 ChainX Project Authors. Licensed under GPL-3.0.
+
+use frame_support::{log::info, traits::Get, weights::Weight};
+
+use crate::{Config, Pallet, UntrackedProcessedHeight};
+
+/// Apply all of the migrations due to the processed txs of the blocks.
+///
+/// ### Warning
+///
+/// Use with care and run at your own risk.
+pub fn apply<T: Config<I>, I: 'static>() -> Weight {
+    info!(
+        target: "runtime::bitcoin",
+        "Running migration for the processed txs of the blocks"
+    );
+
+    protect_untracked_blocks::<T, I>()
+}
+
+/// The processed txs of the confirmed blocks were not recorded, and the txs are only processed
+/// in the confirmed blocks, so the blocks up to the confirmed height are protected from the
+/// rollback without force.
+pub fn protect_untracked_blocks<T: Config<I>, I: 'static>() -> Weight {
+    let height = Pallet::<T, I>::confirmed_index()
+        .map(|index| index.height)
+        .unwrap_or_default();
+    UntrackedProcessedHeight::<T, I>::put(height);
+    info!(
+        target: "runtime::bitcoin",
+        "the blocks up to height {} are protected from the rollback without force.",
+        height,
+    );
+    T::DbWeight::get().reads_writes(1, 1)
+}
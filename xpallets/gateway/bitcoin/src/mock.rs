@@ -186,6 +186,7 @@ impl xpallet_assets::Config for Test {
     type TreasuryAccount = SimpleTreasuryAccount;
     type OnCreatedAccount = frame_system::Provider<Test>;
     type OnAssetChanged = ();
+    type AssetLedger = ();
     type WeightInfo = ();
 }
 
@@ -196,6 +197,7 @@ parameter_types! {
 
 impl xpallet_gateway_records::Config for Test {
     type Event = ();
+    type CouncilOrigin = EnsureSigned<AccountId>;
     type WeightInfo = ();
 }
 
@@ -207,6 +209,10 @@ impl MultisigAddressFor<AccountId> for MultisigAddr {
     }
 }
 
+parameter_types! {
+    pub const TrusteeInsurancePalletId: PalletId = PalletId(*b"pcx/trin");
+}
+
 impl xpallet_gateway_common::Config for Test {
     type Event = ();
     type Validator = ();
@@ -217,6 +223,7 @@ impl xpallet_gateway_common::Config for Test {
     type BitcoinTrusteeSessionProvider = trustees::bitcoin::BtcTrusteeSessionManager<Test>;
     type BitcoinTotalSupply = XGatewayBitcoin;
     type BitcoinWithdrawalProposal = XGatewayBitcoin;
+    type InsurancePalletId = TrusteeInsurancePalletId;
     type WeightInfo = ();
 }
 
@@ -284,6 +291,7 @@ impl xpallet_assets_bridge::Config for Test {
 impl Config for Test {
     type Event = ();
     type UnixTime = CustomTimestamp;
+    type Family = crate::Bitcoin;
     type AccountExtractor = xp_gateway_bitcoin::OpReturnExtractor;
     type TrusteeSessionProvider =
         xpallet_gateway_common::trustees::bitcoin::BtcTrusteeSessionManager<Test>;
@@ -367,6 +375,7 @@ impl ExtBuilder {
                 4,                    // retargeting_factor
             ), // retargeting_factor
             verifier: BtcTxVerifier::Recover,
+            phantom: Default::default(),
             confirmation_number: 4,
             btc_withdrawal_fee: 0,
             max_withdrawal_count: 100,
@@ -450,6 +459,7 @@ impl ExtBuilder {
                 4,                    // retargeting_factor
             ), // retargeting_factor
             verifier: BtcTxVerifier::Recover,
+            phantom: Default::default(),
             confirmation_number: 4,
             btc_withdrawal_fee: 0,
             max_withdrawal_count: 100,
@@ -528,6 +538,7 @@ fn trustees_info() -> Vec<(
     let btc_config = TrusteeInfoConfig {
         min_trustee_count: 3,
         max_trustee_count: 15,
+        recovery: None,
     };
     vec![(Chain::Bitcoin, btc_config, btc_trustees)]
 }
@@ -0,0 +1,146 @@
+// Copyright 2019-2023 ChainX Project Authors. Licensed under GPL-3.0.
+
+//! Taproot (BIP341) hashes of the tapscript tree, BIP340 schnorr signatures and the x-only
+//! keys of the taproot outputs.
+
+extern crate alloc;
+
+use alloc::string::ToString;
+use frame_support::dispatch::DispatchError;
+use sp_std::{
+    convert::{TryFrom, TryInto},
+    prelude::*,
+};
+
+use light_bitcoin::{
+    keys::{Public, SchnorrSignature, XOnly},
+    mast::{generate_btc_address, key::PublicKey, tweak_pubkey, MerkleNode},
+    primitives::H256,
+};
+
+use crate::{Config, Error, Pallet};
+
+/// The leaf version of tapscript.
+pub const TAPSCRIPT_LEAF_VERSION: u8 = 0xc0;
+
+/// BIP340 tagged hash, `sha256(sha256(tag) || sha256(tag) || msg)`.
+pub fn tagged_hash(tag: &[u8], msg: &[u8]) -> [u8; 32] {
+    let tag_hash = sp_io::hashing::sha2_256(tag);
+    let mut data = Vec::with_capacity(64 + msg.len());
+    data.extend_from_slice(&tag_hash);
+    data.extend_from_slice(&tag_hash);
+    data.extend_from_slice(msg);
+    sp_io::hashing::sha2_256(&data)
+}
+
+/// The hash of a tapscript leaf.
+pub fn tap_leaf_hash(script: &[u8]) -> [u8; 32] {
+    let mut data = Vec::with_capacity(script.len() + 10);
+    data.push(TAPSCRIPT_LEAF_VERSION);
+    data.extend_from_slice(&compact_size(script.len() as u64));
+    data.extend_from_slice(script);
+    tagged_hash(b"TapLeaf", &data)
+}
+
+/// The hash of a tapscript branch, the children are sorted.
+pub fn tap_branch_hash(a: &[u8; 32], b: &[u8; 32]) -> [u8; 32] {
+    let (left, right) = if a <= b { (a, b) } else { (b, a) };
+    let mut data = [0u8; 64];
+    data[..32].copy_from_slice(left);
+    data[32..].copy_from_slice(right);
+    tagged_hash(b"TapBranch", &data)
+}
+
+/// The root of the tapscript tree built on `leaves`.
+///
+/// The adjacent nodes are paired level by level, the last node of an odd level is promoted to
+/// the next level directly.
+pub fn tap_tree_root(leaves: Vec<[u8; 32]>) -> Option<[u8; 32]> {
+    let mut level = leaves;
+    while level.len() > 1 {
+        level = level
+            .chunks(2)
+            .map(|pair| match pair {
+                [a, b] => tap_branch_hash(a, b),
+                [a] => *a,
+                _ => unreachable!("chunks(2) yields 1 or 2 items; qed"),
+            })
+            .collect();
+    }
+    level.pop()
+}
+
+/// BIP340 verification of the schnorr signature `sig` of `msg` by the x-only `pubkey`.
+pub fn verify_schnorr(pubkey: &[u8; 32], msg: &[u8; 32], sig: &[u8; 64]) -> bool {
+    let pubkey = match XOnly::try_from(&pubkey[..]) {
+        Ok(pubkey) => pubkey,
+        Err(_) => return false,
+    };
+    let sig = match SchnorrSignature::try_from(&sig[..]) {
+        Ok(sig) => sig,
+        Err(_) => return false,
+    };
+    pubkey
+        .verify(&H256::from_slice(msg), &sig)
+        .unwrap_or_default()
+}
+
+/// Taproot utilities
+impl<T: Config<I>, I: 'static> Pallet<T, I> {
+    /// Tweaks the x-only `internal_key` with the tapscript `merkle_root` in the same way as the
+    /// trustee addresses, returns the x-only output key and whether its y coordinate is odd.
+    pub fn taproot_tweak(
+        internal_key: &[u8; 32],
+        merkle_root: &[u8; 32],
+    ) -> Result<([u8; 32], bool), DispatchError> {
+        let internal = x_only_pubkey::<T, I>(internal_key)?;
+        let root =
+            MerkleNode::from_slice(merkle_root).map_err(|_| Error::<T, I>::InvalidAddress)?;
+        let output = tweak_pubkey(&internal, &root).map_err(|_| Error::<T, I>::InvalidPublicKey)?;
+
+        let mut output_key = [0u8; 32];
+        output_key.copy_from_slice(&output.x_coor()[..]);
+        Ok((output_key, output.serialize_compressed()[0] == 3))
+    }
+
+    /// Returns the bech32m address of the taproot x-only `output_key` on the current network.
+    pub fn taproot_address(output_key: &[u8; 32]) -> Result<Vec<u8>, DispatchError> {
+        let output = x_only_pubkey::<T, I>(output_key)?;
+        let network = Self::network_id().to_string();
+        generate_btc_address(&output, &network)
+            .map(|addr| addr.into_bytes())
+            .map_err(|_| Error::<T, I>::InvalidAddress.into())
+    }
+}
+
+/// Lifts the x-only `key` to the public key with the even y coordinate.
+fn x_only_pubkey<T: Config<I>, I: 'static>(key: &[u8; 32]) -> Result<PublicKey, DispatchError> {
+    let mut compressed = [2u8; 33];
+    compressed[1..].copy_from_slice(key);
+    let public = Public::from_slice(&compressed).map_err(|_| Error::<T, I>::InvalidPublicKey)?;
+    public
+        .try_into()
+        .map_err(|_| Error::<T, I>::InvalidPublicKey.into())
+}
+
+/// Bitcoin compact size encoding.
+fn compact_size(n: u64) -> Vec<u8> {
+    match n {
+        0..=0xfc => vec![n as u8],
+        0xfd..=0xffff => {
+            let mut v = vec![0xfd];
+            v.extend_from_slice(&(n as u16).to_le_bytes());
+            v
+        }
+        0x10000..=0xffff_ffff => {
+            let mut v = vec![0xfe];
+            v.extend_from_slice(&(n as u32).to_le_bytes());
+            v
+        }
+        _ => {
+            let mut v = vec![0xff];
+            v.extend_from_slice(&n.to_le_bytes());
+            v
+        }
+    }
+}
@@ -1,20 +1,26 @@
 // Copyright 2019-2023 ChainX Project Authors. Licensed under GPL-3.0.
 
-use frame_support::{assert_noop, assert_ok};
+use frame_support::{
+    assert_noop, assert_ok,
+    traits::{Hooks, StorageVersion},
+};
+use xp_gateway_bitcoin::BtcTxType;
 
 use light_bitcoin::{
     chain::{h256, BlockHeader},
     keys::Network,
+    primitives::H256,
     serialization,
 };
 
 use crate::mock::alice;
 use crate::{
     mock::{
-        generate_blocks_478557_478563, generate_blocks_63290_63310, ExtBuilder, XGatewayBitcoin,
-        XGatewayBitcoinErr,
+        generate_blocks_478557_478563, generate_blocks_63290_63310, ExtBuilder, Test,
+        XGatewayBitcoin, XGatewayBitcoinErr,
     },
-    types::BtcHeaderIndex,
+    types::{BtcHeaderIndex, BtcTxResult, BtcTxState},
+    BlockProcessedTxs, TxState, UntrackedProcessedHeight,
 };
 
 #[test]
@@ -219,3 +225,163 @@ fn test_call() {
         assert_ok!(XGatewayBitcoin::push_header(origin, v));
     })
 }
+
+#[test]
+fn test_rollback_headers() {
+    ExtBuilder::default().build_and_execute(|| {
+        let headers = generate_blocks_63290_63310();
+        for i in 63291..=63300 {
+            assert_ok!(XGatewayBitcoin::apply_push_header(headers[&i]));
+        }
+        let root = || frame_system::RawOrigin::Root.into();
+        assert_eq!(XGatewayBitcoin::best_index().height, 63300);
+        assert_eq!(XGatewayBitcoin::confirmed_index().unwrap().height, 63297);
+
+        // the best index can only be changed by the rollback.
+        assert_noop!(
+            XGatewayBitcoin::set_best_index(root(), XGatewayBitcoin::best_index()),
+            XGatewayBitcoinErr::Deprecated
+        );
+        assert_noop!(
+            XGatewayBitcoin::rollback_headers(root(), H256::repeat_byte(1), false),
+            XGatewayBitcoinErr::NotMainChainHeader
+        );
+
+        let target = headers[&63295].hash();
+        assert_ok!(XGatewayBitcoin::rollback_headers(root(), target, false));
+        assert_eq!(
+            XGatewayBitcoin::best_index(),
+            BtcHeaderIndex {
+                hash: target,
+                height: 63295
+            }
+        );
+        assert_eq!(
+            XGatewayBitcoin::confirmed_index(),
+            Some(BtcHeaderIndex {
+                hash: headers[&63292].hash(),
+                height: 63292
+            })
+        );
+        for i in 63296..=63300 {
+            assert!(XGatewayBitcoin::headers(&headers[&i].hash()).is_none());
+            assert!(!XGatewayBitcoin::main_chain(&headers[&i].hash()));
+            assert!(XGatewayBitcoin::block_hash_for(i).is_empty());
+        }
+
+        // the removed headers could be relayed again
+        assert_ok!(XGatewayBitcoin::apply_push_header(headers[&63296]));
+        assert_eq!(XGatewayBitcoin::best_index().height, 63296);
+
+        // the block with processed txs is only rolled back by force
+        BlockProcessedTxs::<Test>::insert(headers[&63296].hash(), vec![H256::repeat_byte(2)]);
+        TxState::<Test>::insert(
+            H256::repeat_byte(2),
+            BtcTxState {
+                tx_type: BtcTxType::Deposit,
+                result: BtcTxResult::Success,
+            },
+        );
+        let target = headers[&63292].hash();
+        assert_noop!(
+            XGatewayBitcoin::rollback_headers(root(), target, false),
+            XGatewayBitcoinErr::RollbackProcessedTxs
+        );
+        assert_ok!(XGatewayBitcoin::rollback_headers(root(), target, true));
+        assert_eq!(XGatewayBitcoin::best_index().height, 63292);
+        // not enough confirmations above the genesis
+        assert_eq!(XGatewayBitcoin::confirmed_index(), None);
+        assert!(XGatewayBitcoin::block_processed_txs(headers[&63296].hash()).is_empty());
+        assert!(XGatewayBitcoin::tx_state(H256::repeat_byte(2)).is_none());
+
+        // the blocks processed before the txs were tracked are only rolled back by force
+        UntrackedProcessedHeight::<Test>::put(63292);
+        let target = headers[&63291].hash();
+        assert_noop!(
+            XGatewayBitcoin::rollback_headers(root(), target, false),
+            XGatewayBitcoinErr::RollbackUntrackedBlocks
+        );
+        assert_ok!(XGatewayBitcoin::rollback_headers(root(), target, true));
+        assert_eq!(XGatewayBitcoin::best_index().height, 63291);
+    })
+}
+
+#[test]
+fn test_untracked_processed_height_migration() {
+    ExtBuilder::default().build_and_execute(|| {
+        let headers = generate_blocks_63290_63310();
+        for i in 63291..=63300 {
+            assert_ok!(XGatewayBitcoin::apply_push_header(headers[&i]));
+        }
+        StorageVersion::new(0).put::<XGatewayBitcoin>();
+        XGatewayBitcoin::on_runtime_upgrade();
+        assert_eq!(StorageVersion::get::<XGatewayBitcoin>(), 1);
+        assert_eq!(XGatewayBitcoin::untracked_processed_height(), 63297);
+    })
+}
+
+#[test]
+fn test_set_checkpoint() {
+    ExtBuilder::default().build_and_execute(|| {
+        let headers = generate_blocks_63290_63310();
+        for i in 63291..=63300 {
+            assert_ok!(XGatewayBitcoin::apply_push_header(headers[&i]));
+        }
+        let root = || frame_system::RawOrigin::Root.into();
+        let raw = |height: u32| serialization::serialize(&headers[&height]).take();
+        let index = |height: u32| BtcHeaderIndex {
+            hash: headers[&height].hash(),
+            height,
+        };
+
+        assert_noop!(
+            XGatewayBitcoin::set_checkpoint(root(), raw(63299), 63298, 0),
+            XGatewayBitcoinErr::InvalidCheckpoint
+        );
+
+        // a checkpoint on the main chain only moves the confirmed index
+        assert_ok!(XGatewayBitcoin::set_checkpoint(
+            root(),
+            raw(63299),
+            63299,
+            0
+        ));
+        assert_eq!(XGatewayBitcoin::genesis_info(), (headers[&63299], 63299));
+        assert_eq!(XGatewayBitcoin::best_index(), index(63300));
+        assert_eq!(XGatewayBitcoin::confirmed_index(), Some(index(63299)));
+        should_in_mainchain(&[headers[&63299], headers[&63300]], true);
+
+        // the main chain headers with processed txs are not dropped by a checkpoint
+        BlockProcessedTxs::<Test>::insert(headers[&63300].hash(), vec![H256::repeat_byte(2)]);
+        assert_noop!(
+            XGatewayBitcoin::set_checkpoint(root(), raw(63305), 63300, 0),
+            XGatewayBitcoinErr::RollbackProcessedTxs
+        );
+        BlockProcessedTxs::<Test>::remove(headers[&63300].hash());
+        UntrackedProcessedHeight::<Test>::put(63300);
+        assert_noop!(
+            XGatewayBitcoin::set_checkpoint(root(), raw(63305), 63300, 0),
+            XGatewayBitcoinErr::RollbackUntrackedBlocks
+        );
+        UntrackedProcessedHeight::<Test>::kill();
+
+        // a checkpoint beyond the best header becomes the best and confirmed header
+        assert_ok!(XGatewayBitcoin::set_checkpoint(
+            root(),
+            raw(63305),
+            63305,
+            headers[&63290].time
+        ));
+        assert_eq!(XGatewayBitcoin::best_index(), index(63305));
+        assert_eq!(XGatewayBitcoin::confirmed_index(), Some(index(63305)));
+        assert_eq!(
+            XGatewayBitcoin::checkpoint().map(|c| c.retarget_timestamp),
+            Some(headers[&63290].time)
+        );
+        for i in 63306..=63310 {
+            assert_ok!(XGatewayBitcoin::apply_push_header(headers[&i]));
+        }
+        assert_eq!(XGatewayBitcoin::best_index(), index(63310));
+        assert_eq!(XGatewayBitcoin::confirmed_index(), Some(index(63307)));
+    })
+}
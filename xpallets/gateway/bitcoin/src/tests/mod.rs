@@ -10,9 +10,11 @@ use xp_gateway_common::AccountExtractor;
 
 use light_bitcoin::script::Script;
 
+use xpallet_assets::{Chain, ChainT};
+
 use crate::{
-    mock::{Test, XGatewayBitcoin},
-    Config,
+    mock::{Test, XGatewayBitcoin, X_BTC},
+    Bitcoin, BitcoinFamily, Config,
 };
 
 #[test]
@@ -32,3 +34,11 @@ fn test_account_ss58_version() {
     let data = script.to_bytes();
     assert!(<Test as Config>::AccountExtractor::extract_account(&data).is_some());
 }
+
+#[test]
+fn test_bitcoin_family() {
+    assert_eq!(XGatewayBitcoin::chain(), Chain::Bitcoin);
+    assert_eq!(XGatewayBitcoin::ASSET_ID, X_BTC);
+    assert_eq!(Bitcoin::base58_version(111), Some(111));
+    assert!(Bitcoin::accept_bech32());
+}
@@ -7,14 +7,19 @@ use sp_std::convert::TryInto;
 use light_bitcoin::{
     crypto::dhash160,
     keys::{Address, AddressTypes, Network, Public, Type},
-    mast::Mast,
+    mast::{key::PublicKey, Mast},
     script::{Builder, Opcode},
 };
 
-use xpallet_gateway_common::traits::TrusteeForChain;
+use xp_gateway_bitcoin::recovery_script;
+use xpallet_gateway_common::{
+    traits::TrusteeForChain,
+    types::{TrusteeInfoConfig, TrusteeIntentionProps, TrusteeRecoveryConfig},
+};
 
 use crate::{
-    mock::{ExtBuilder, Test, XGatewayBitcoin, XGatewayBitcoinErr},
+    mock::{trustees, ExtBuilder, Test, XGatewayBitcoin, XGatewayBitcoinErr},
+    taproot::{self, verify_schnorr},
     trustee::create_multi_address,
 };
 
@@ -91,8 +96,8 @@ fn test_create_multi_address() {
     cold_keys.push(Public::from_slice(&pubkey8_bytes).unwrap());
 
     ExtBuilder::default().build_and_execute(|| {
-        let hot_info = create_multi_address::<Test>(&hot_keys, 3).unwrap();
-        let cold_info = create_multi_address::<Test>(&cold_keys, 3).unwrap();
+        let hot_info = create_multi_address::<Test, ()>(&hot_keys, 3).unwrap();
+        let cold_info = create_multi_address::<Test, ()>(&cold_keys, 3).unwrap();
         let real_hot_addr = b"2N6mJFLkjN9muneSeHCsMCxWXVZ4ruLKfFo".to_vec();
         let real_cold_addr = b"2NEf17iYn2Lj2AdnAd1C7A9i8a5PpfPjaFk".to_vec();
         assert_eq!(hot_info.addr, real_hot_addr);
@@ -153,6 +158,124 @@ fn test_create_taproot_address() {
         assert_eq!(
             threshold_addr.to_string(),
             "tb1psaktm6w6nrh5xs8umla9qaw6zjarr4yuqk3m4x8pzc6ekve93v7ss20kuq"
+        );
+
+        // the same address is derived from the x-only internal key and the merkle root.
+        let mut internal_key = [0u8; 32];
+        internal_key.copy_from_slice(&mast.inner_pubkey.x_coor()[..]);
+        let mut merkle_root = [0u8; 32];
+        merkle_root.copy_from_slice(&mast.calc_root().unwrap()[..]);
+        let (output_key, _) = XGatewayBitcoin::taproot_tweak(&internal_key, &merkle_root).unwrap();
+        assert_eq!(
+            XGatewayBitcoin::taproot_address(&output_key).unwrap(),
+            threshold_addr.to_string().into_bytes()
+        );
+    })
+}
+
+#[test]
+fn test_create_taproot_address_with_recovery() {
+    ExtBuilder::default().build_and_execute(|| {
+        let props = trustees()
+            .into_iter()
+            .map(|(who, about, hot, cold)| {
+                let props = TrusteeIntentionProps {
+                    proxy_account: None,
+                    about,
+                    hot_entity: XGatewayBitcoin::check_trustee_entity(&hot).unwrap(),
+                    cold_entity: XGatewayBitcoin::check_trustee_entity(&cold).unwrap(),
+                };
+                (who, props)
+            })
+            .collect::<Vec<_>>();
+        let config = |recovery| TrusteeInfoConfig {
+            min_trustee_count: 3,
+            max_trustee_count: 15,
+            recovery,
+        };
+        let recovery_key =
+            hex!("0237322a5008a1b26ac72778167e770e1fa2272cfd9f9fe0f2c20bd41fe051da6c");
+        let recovery = TrusteeRecoveryConfig {
+            keys: vec![recovery_key.to_vec()],
+            threshold: 1,
+            csv_delay: 144,
+        };
+
+        let (info, _) =
+            XGatewayBitcoin::generate_trustee_session_info(props.clone(), config(None)).unwrap();
+        let (recovery_info, _) = XGatewayBitcoin::generate_trustee_session_info(
+            props.clone(),
+            config(Some(recovery.clone())),
         )
+        .unwrap();
+        assert_eq!(info.threshold, recovery_info.threshold);
+        assert_ne!(info.hot_address.addr, recovery_info.hot_address.addr);
+        assert_ne!(info.cold_address.addr, recovery_info.cold_address.addr);
+
+        // hot address = tweak(internal key, TapBranch(threshold root, recovery leaf))
+        let hot_pks = trustees()
+            .iter()
+            .map(|(_, _, hot, _)| Public::from_slice(hot).unwrap().try_into().unwrap())
+            .collect::<Vec<_>>();
+        let mast = Mast::new(hot_pks, info.threshold as u32).unwrap();
+        let mut threshold_root = [0u8; 32];
+        threshold_root.copy_from_slice(&mast.calc_root().unwrap()[..]);
+        let recovery_pk: PublicKey = Public::from_slice(&recovery_key)
+            .unwrap()
+            .try_into()
+            .unwrap();
+        let recovery_leaf = taproot::tap_leaf_hash(&recovery_script(144, &recovery_pk.x_coor()));
+        let merkle_root = taproot::tap_branch_hash(&threshold_root, &recovery_leaf);
+        let mut internal_key = [0u8; 32];
+        internal_key.copy_from_slice(&mast.inner_pubkey.x_coor()[..]);
+        let (output_key, _) = XGatewayBitcoin::taproot_tweak(&internal_key, &merkle_root).unwrap();
+        assert_eq!(
+            XGatewayBitcoin::taproot_address(&output_key).unwrap(),
+            recovery_info.hot_address.addr
+        );
+
+        // the timelock must be a valid BIP68 relative locktime
+        for csv_delay in [0, 0x10000] {
+            let recovery = TrusteeRecoveryConfig {
+                csv_delay,
+                ..recovery.clone()
+            };
+            assert_noop!(
+                XGatewayBitcoin::generate_trustee_session_info(
+                    props.clone(),
+                    config(Some(recovery))
+                ),
+                XGatewayBitcoinErr::InvalidRecoveryConfig
+            );
+        }
+        // the threshold could not exceed the recovery keys
+        let recovery = TrusteeRecoveryConfig {
+            threshold: 2,
+            ..recovery
+        };
+        assert_noop!(
+            XGatewayBitcoin::generate_trustee_session_info(props, config(Some(recovery))),
+            XGatewayBitcoinErr::InvalidRecoveryConfig
+        );
     })
 }
+
+#[test]
+fn test_verify_schnorr() {
+    // BIP340 test vectors
+    let pubkey = hex!("f9308a019258c31049344f85f89d5229b531c845836f99b08601f113bce036f9");
+    let sig = hex!("e907831f80848d1069a5371b402410364bdf1c5f8307b0084c55f1ce2dca821525f66a4a85ea8b71e482a74f382d2ce5ebeee8fdb2172f477df4900d310536c0");
+    assert!(verify_schnorr(&pubkey, &[0u8; 32], &sig));
+
+    let pubkey = hex!("dff1d77f2a671c5f36183726db2341be58feae1da2deced843240f7b502ba659");
+    let msg = hex!("243f6a8885a308d313198a2e03707344a4093822299f31d0082efa98ec4e6c89");
+    let mut sig = hex!("6896bd60eeae296db48a229ff71dfe071bde413e6d43f917dc8dcf8c78de33418906d11ac976abccb20b091292bff4ea897efcb639ea871cfa95f6de339e4b0a");
+    assert!(verify_schnorr(&pubkey, &msg, &sig));
+    assert!(!verify_schnorr(&pubkey, &[0u8; 32], &sig));
+
+    sig[63] ^= 1;
+    assert!(!verify_schnorr(&pubkey, &msg, &sig));
+    // s is not less than the curve order.
+    sig[32..].copy_from_slice(&[0xff; 32]);
+    assert!(!verify_schnorr(&pubkey, &msg, &sig));
+}
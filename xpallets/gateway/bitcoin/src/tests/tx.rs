@@ -3,29 +3,39 @@
 #![allow(non_upper_case_globals)]
 
 use codec::Encode;
-use frame_support::{assert_noop, assert_ok};
+use frame_support::{
+    assert_noop, assert_ok,
+    storage::with_transaction,
+    traits::{Currency, Hooks},
+};
+use pallet_evm::AddressMapping;
 use sp_core::{
     crypto::{set_default_ss58_version, Ss58AddressFormatRegistry},
     H160,
 };
+use sp_runtime::TransactionOutcome;
 
 use light_bitcoin::{
-    chain::Transaction,
+    chain::{OutPoint, Transaction, TransactionOutput},
     keys::{Address, Network},
     merkle::PartialMerkleTree,
     serialization::{self, Reader},
 };
 
 use xp_gateway_bitcoin::{AccountExtractor, BtcTxMetaType, BtcTxType, BtcTxTypeDetector};
+use xpallet_assets::{AssetType, Chain};
+use xpallet_gateway_records::WithdrawalState;
 
 use crate::{
     mock::*,
+    trustee::{check_cpfp_child_relayed, settle_fee_bump, MAX_EXPIRED_PROPOSALS},
     tx::process_tx,
     types::{
-        BtcDepositCache, BtcRelayedTxInfo, BtcTxResult, BtcTxState, BtcWithdrawalProposal,
-        VoteResult,
+        BtcDepositCache, BtcFeeBump, BtcFeeBumpKind, BtcRebalanceConfig, BtcRelayedTxInfo,
+        BtcTxResult, BtcTxState, BtcWithdrawalProposal, VoteResult,
     },
-    Config, WithdrawalProposal,
+    Config, ExpiredProposalHashes, ExpiredProposals, RebalanceProposal, WithdrawalFeeBump,
+    WithdrawalProposal, WithdrawalProposalDeadline, H256,
 };
 
 // Tyoe is p2tr. Address farmat is Mainnet.:
@@ -198,7 +208,7 @@ fn mock_process_tx<T: Config>(tx: Transaction, prev_tx: Option<Transaction>) ->
         DEPOSIT_COLD_ADDR.parse::<Address>().unwrap(),
     );
     let previous_trustee_pair = None;
-    process_tx::<T>(
+    process_tx::<T, ()>(
         tx,
         prev_tx,
         network,
@@ -234,6 +244,9 @@ fn test_process_tx() {
                 balance: 100000,
             }]
         );
+        // the unclaimed deposits are counted by the running total.
+        assert_eq!(XGatewayBitcoin::pending_deposit_balance(), 100000);
+        assert_eq!(XGatewayBitcoin::get_reserve_report().pending, 100000);
 
         // withdraw
         WithdrawalProposal::<Test>::put(BtcWithdrawalProposal {
@@ -309,6 +322,17 @@ fn test_process_tx() {
         let r = mock_process_tx::<Test>(hot_to_cold.clone(), Some(hot_to_cold_prev.clone()));
         assert_eq!(r.tx_type, BtcTxType::HotAndCold);
         assert_eq!(r.result, BtcTxResult::Success);
+
+        // the released deposits are taken from the running total.
+        assert_ok!(XGatewayBitcoin::remove_pending(
+            Origin::root(),
+            deposit_taproot1_input_account.to_vec(),
+            None
+        ));
+        assert!(
+            XGatewayBitcoin::pending_deposits(&deposit_taproot1_input_account.to_vec()).is_empty()
+        );
+        assert_eq!(XGatewayBitcoin::pending_deposit_balance(), 0);
     })
 }
 
@@ -355,3 +379,451 @@ fn test_push_tx_call() {
         );
     });
 }
+
+#[test]
+fn test_verify_tx_inclusion() {
+    // https://blockchain.info/rawtx/f1a9161a045a01db7ae02b8c0531e2fe2e9740efe30afe6d84a12e3cac251344?format=hex
+    let normal_deposit: Transaction = "02000000000101aeee49e0bbf7a36f78ea4321b5c8bae0b8c72bdf2c024d2484b137fa7d0f8e1f01000000000000000003a0860100000000002251209a9ea267884f5549c206b2aec2bd56d98730f90532ea7f7154d4d4f923b7e3bb0000000000000000326a3035516a706f3772516e7751657479736167477a6334526a376f737758534c6d4d7141754332416255364c464646476a38801a060000000000225120c9929543dfa1e0bb84891acd47bfa6546b05e26b7a04af8eb6765fcc969d565f01409e325889515ed47099fdd7098e6fafdc880b21456d3f368457de923f4229286e34cef68816348a0581ae5885ede248a35ac4b09da61a7b9b90f34c200872d2e300000000".parse().unwrap();
+    let tx = serialization::serialize(&normal_deposit);
+    let headers = generate_blocks_63290_63310();
+    let block_hash = headers[&63299].hash();
+    let raw_proof = hex::decode("0a000000050a59b195a68a29037580798ca0414941eb46eaf7607db2d0da1ff89e9570ce455fea22ec1a3e3e7e1167fa220cc8376225f07bd20aa194e7f3c4ac68c7375d8e0a35e47541de7d0aa7312dabcf3bc9f06603e832427b8e4fe9a97a309f8cd7141687d11a3fd8f21e2105a52a3c36a17ea870e326ecddb23221d4cc0398b6c44bdcce3f191919a31f4cfaca5a786cc8315db76683ad6b8008f2ed9b348df76a0d022f00").unwrap();
+
+    ExtBuilder::default().build_and_execute(|| {
+        assert_noop!(
+            XGatewayBitcoin::verify_tx_inclusion(&tx, &raw_proof, block_hash),
+            XGatewayBitcoinErr::UnknownHeader
+        );
+
+        for i in 63291..=63300 {
+            assert_ok!(XGatewayBitcoin::apply_push_header(headers[&i]));
+        }
+        assert_eq!(
+            XGatewayBitcoin::main_chain_header_at(63299).map(|info| info.header.hash()),
+            Some(block_hash)
+        );
+        assert_eq!(XGatewayBitcoin::confirmations_of(&block_hash), Some(2));
+
+        assert_eq!(
+            XGatewayBitcoin::verify_tx_inclusion(&tx, &raw_proof, block_hash),
+            Ok(normal_deposit.hash())
+        );
+        // the proof doesn't match another header.
+        assert_noop!(
+            XGatewayBitcoin::verify_tx_inclusion(&tx, &raw_proof, headers[&63298].hash()),
+            XGatewayBitcoinErr::BadMerkleProof
+        );
+        assert_noop!(
+            XGatewayBitcoin::verify_tx_inclusion(
+                &tx,
+                &raw_proof[..raw_proof.len() - 1],
+                block_hash
+            ),
+            XGatewayBitcoinErr::DeserializeErr
+        );
+
+        // a tx of 64 bytes without the witness is refused, whatever the proof is.
+        let tx_64_bytes: Transaction = "010000000100000000000000000000000000000000000000000000000000000000000000000000000000ffffffff010000000000000000045151515100000000".parse().unwrap();
+        let raw_64_bytes = serialization::serialize(&tx_64_bytes);
+        assert_eq!(raw_64_bytes.len(), 64);
+        assert_noop!(
+            XGatewayBitcoin::verify_tx_inclusion(&raw_64_bytes, &raw_proof, block_hash),
+            XGatewayBitcoinErr::InvalidTxLength
+        );
+    });
+}
+
+#[test]
+fn test_withdrawal_proposal_expired() {
+    ExtBuilder::default().build_and_execute(|| {
+        assert_ok!(XGatewayRecords::deposit(&alice(), X_BTC, 1_000_000));
+        assert_ok!(XGatewayRecords::withdraw(
+            &alice(),
+            X_BTC,
+            100_000,
+            b"bc1pexff2s7l58sthpyfrtx500ax234stcnt0gz2lr4kwe0ue95a2e0s5wxhqg".to_vec(),
+            b"".to_vec().into()
+        ));
+        assert_ok!(XGatewayRecords::process_withdrawals(&[0], Chain::Bitcoin));
+
+        WithdrawalProposal::<Test>::put(BtcWithdrawalProposal {
+            sig_state: VoteResult::Finish,
+            withdrawal_id_list: vec![0],
+            tx: withdraw_taproot1.clone(),
+            trustee_list: vec![],
+        });
+        WithdrawalProposalDeadline::<Test>::put(10);
+
+        XGatewayBitcoin::on_initialize(9);
+        assert!(XGatewayBitcoin::withdrawal_proposal().is_some());
+
+        XGatewayBitcoin::on_initialize(10);
+        assert!(XGatewayBitcoin::withdrawal_proposal().is_none());
+        assert!(XGatewayBitcoin::withdrawal_proposal_deadline().is_none());
+        assert_eq!(
+            XGatewayRecords::state_of(0),
+            Some(WithdrawalState::Applying)
+        );
+        let expired = XGatewayBitcoin::expired_proposals(withdraw_taproot1.hash()).unwrap();
+        assert_eq!(expired.withdrawal_id_list, vec![0]);
+        assert_eq!(expired.missing_signers.len(), trustees().len());
+        assert_eq!(
+            XGatewayBitcoin::expired_proposal_hashes(),
+            vec![withdraw_taproot1.hash()]
+        );
+
+        // only the latest expired proposals are kept
+        let hashes = (0..MAX_EXPIRED_PROPOSALS as u8)
+            .map(H256::repeat_byte)
+            .collect::<Vec<_>>();
+        for hash in hashes.iter() {
+            ExpiredProposals::<Test>::insert(hash, expired.clone());
+        }
+        ExpiredProposalHashes::<Test>::put(hashes.clone());
+        WithdrawalProposal::<Test>::put(BtcWithdrawalProposal {
+            sig_state: VoteResult::Unfinish,
+            withdrawal_id_list: vec![],
+            tx: withdraw_taproot2.clone(),
+            trustee_list: vec![],
+        });
+        WithdrawalProposalDeadline::<Test>::put(20);
+        XGatewayBitcoin::on_initialize(20);
+        let kept = XGatewayBitcoin::expired_proposal_hashes();
+        assert_eq!(kept.len(), MAX_EXPIRED_PROPOSALS);
+        assert_eq!(kept[..MAX_EXPIRED_PROPOSALS - 1], hashes[1..]);
+        assert_eq!(kept.last(), Some(&withdraw_taproot2.hash()));
+        assert!(XGatewayBitcoin::expired_proposals(hashes[0]).is_none());
+        assert!(XGatewayBitcoin::expired_proposals(withdraw_taproot2.hash()).is_some());
+    });
+}
+
+#[test]
+fn test_bump_withdrawal_fee() {
+    ExtBuilder::default().build_and_execute(|| {
+        assert_ok!(XGatewayRecords::deposit(&alice(), X_BTC, 1_000_000));
+        assert_ok!(XGatewayRecords::withdraw(
+            &alice(),
+            X_BTC,
+            50_000,
+            b"bc1pexff2s7l58sthpyfrtx500ax234stcnt0gz2lr4kwe0ue95a2e0s5wxhqg".to_vec(),
+            b"".to_vec().into()
+        ));
+        assert_ok!(XGatewayRecords::process_withdrawals(&[0], Chain::Bitcoin));
+        WithdrawalProposal::<Test>::put(BtcWithdrawalProposal {
+            sig_state: VoteResult::Finish,
+            withdrawal_id_list: vec![0],
+            tx: withdraw_taproot1.clone(),
+            trustee_list: vec![(alice(), true)],
+        });
+
+        // the replacement pays 1000 more out of the change to the hot address
+        let mut rbf = withdraw_taproot1.clone();
+        rbf.outputs[1].value -= 1_000;
+        let rbf_raw: Vec<u8> = serialization::serialize(&rbf).into();
+
+        assert_noop!(
+            XGatewayBitcoin::bump_withdrawal_fee(
+                Origin::signed(alice()),
+                BtcFeeBumpKind::Rbf,
+                rbf_raw.clone()
+            ),
+            XGatewayBitcoinErr::InsufficientFeeReserve
+        );
+        assert_ok!(XGatewayBitcoin::set_withdrawal_fee_reserve(
+            Origin::root(),
+            Some(bob())
+        ));
+        assert_ok!(XGatewayRecords::deposit(&bob(), X_BTC, 10_000));
+
+        // the withdrawal outputs can't be changed
+        let mut invalid = withdraw_taproot1.clone();
+        invalid.outputs[0].value -= 1_000;
+        assert_noop!(
+            XGatewayBitcoin::bump_withdrawal_fee(
+                Origin::signed(alice()),
+                BtcFeeBumpKind::Rbf,
+                serialization::serialize(&invalid).into()
+            ),
+            XGatewayBitcoinErr::TxOutputsNotMatch
+        );
+
+        assert_ok!(XGatewayBitcoin::bump_withdrawal_fee(
+            Origin::signed(alice()),
+            BtcFeeBumpKind::Rbf,
+            rbf_raw
+        ));
+        let proposal = XGatewayBitcoin::withdrawal_proposal().unwrap();
+        assert_eq!(proposal.tx, rbf);
+        assert!(proposal.trustee_list.is_empty());
+        assert_eq!(XAssets::usable_balance(&bob(), &X_BTC), 9_000);
+        assert_eq!(
+            XAssets::asset_balance_of(&bob(), &X_BTC, AssetType::ReservedWithdrawal),
+            1_000
+        );
+
+        // the child spends the change output and pays 2000
+        let mut cpfp = withdraw_taproot1.clone();
+        cpfp.inputs.truncate(1);
+        cpfp.inputs[0].previous_output = OutPoint {
+            txid: rbf.hash(),
+            index: 1,
+        };
+        cpfp.outputs = vec![TransactionOutput {
+            value: rbf.outputs[1].value - 2_000,
+            script_pubkey: rbf.outputs[1].script_pubkey.clone(),
+        }];
+        assert_ok!(XGatewayBitcoin::bump_withdrawal_fee(
+            Origin::signed(alice()),
+            BtcFeeBumpKind::Cpfp,
+            serialization::serialize(&cpfp).into()
+        ));
+        assert_eq!(XAssets::usable_balance(&bob(), &X_BTC), 7_000);
+        let bump = XGatewayBitcoin::withdrawal_fee_bump().unwrap();
+        assert_eq!(bump.charged(), 3_000);
+
+        assert_noop!(
+            XGatewayBitcoin::set_withdrawal_fee_reserve(Origin::root(), None),
+            XGatewayBitcoinErr::FeeBumpPending
+        );
+
+        // the original tx is packed, nothing is spent
+        settle_fee_bump::<Test, ()>(Some(&withdraw_taproot1.hash()));
+        assert_eq!(XAssets::usable_balance(&bob(), &X_BTC), 10_000);
+        assert_eq!(
+            XAssets::asset_balance_of(&bob(), &X_BTC, AssetType::ReservedWithdrawal),
+            0
+        );
+
+        let charge = |bump: &BtcFeeBump| {
+            WithdrawalFeeBump::<Test>::put(bump);
+            assert_ok!(XAssets::move_balance(
+                &X_BTC,
+                &bob(),
+                AssetType::Usable,
+                &bob(),
+                AssetType::ReservedWithdrawal,
+                3_000
+            ));
+            XAssets::total_issuance(&X_BTC)
+        };
+
+        // the replacement is packed, the rbf fee is destroyed and the child fee is held
+        let issuance = charge(&bump);
+        settle_fee_bump::<Test, ()>(Some(&rbf.hash()));
+        assert_eq!(XAssets::usable_balance(&bob(), &X_BTC), 7_000);
+        assert_eq!(
+            XAssets::asset_balance_of(&bob(), &X_BTC, AssetType::ReservedWithdrawal),
+            2_000
+        );
+        assert_eq!(XAssets::total_issuance(&X_BTC), issuance - 1_000);
+        assert_eq!(
+            XGatewayBitcoin::pending_cpfp_fee(),
+            Some((cpfp.clone(), 2_000))
+        );
+        assert_noop!(
+            XGatewayBitcoin::set_withdrawal_fee_reserve(Origin::root(), None),
+            XGatewayBitcoinErr::FeeBumpPending
+        );
+
+        // the child is relayed, its fee is destroyed
+        check_cpfp_child_relayed::<Test, ()>(&cpfp);
+        assert_eq!(XGatewayBitcoin::pending_cpfp_fee(), None);
+        assert_eq!(XAssets::usable_balance(&bob(), &X_BTC), 7_000);
+        assert_eq!(
+            XAssets::asset_balance_of(&bob(), &X_BTC, AssetType::ReservedWithdrawal),
+            0
+        );
+        assert_eq!(XAssets::total_issuance(&X_BTC), issuance - 3_000);
+
+        // the change output is spent by another tx, the child fee is given back
+        let issuance = charge(&bump);
+        settle_fee_bump::<Test, ()>(Some(&rbf.hash()));
+        let mut other = cpfp.clone();
+        other.outputs[0].value -= 1_000;
+        check_cpfp_child_relayed::<Test, ()>(&other);
+        assert_eq!(XGatewayBitcoin::pending_cpfp_fee(), None);
+        assert_eq!(XAssets::usable_balance(&bob(), &X_BTC), 6_000);
+        assert_eq!(
+            XAssets::asset_balance_of(&bob(), &X_BTC, AssetType::ReservedWithdrawal),
+            0
+        );
+        assert_eq!(XAssets::total_issuance(&X_BTC), issuance - 1_000);
+
+        // the child is relayed before the replacement, the whole fee is spent with it
+        let issuance = charge(&bump);
+        check_cpfp_child_relayed::<Test, ()>(&cpfp);
+        assert!(XGatewayBitcoin::withdrawal_fee_bump().unwrap().cpfp_relayed);
+        settle_fee_bump::<Test, ()>(Some(&rbf.hash()));
+        assert_eq!(XGatewayBitcoin::pending_cpfp_fee(), None);
+        assert_eq!(XAssets::usable_balance(&bob(), &X_BTC), 3_000);
+        assert_eq!(
+            XAssets::asset_balance_of(&bob(), &X_BTC, AssetType::ReservedWithdrawal),
+            0
+        );
+        assert_eq!(XAssets::total_issuance(&X_BTC), issuance - 3_000);
+    });
+}
+
+#[test]
+fn test_reserve_report() {
+    set_default_ss58_version(Ss58AddressFormatRegistry::ChainxAccount.into());
+    ExtBuilder::default().build_and_execute(|| {
+        let r = mock_process_tx::<Test>(deposit_taproot2.clone(), None);
+        assert_eq!(r.result, BtcTxResult::Success);
+        assert_eq!(XGatewayBitcoin::trustee_utxo_balance(), 100000);
+
+        // the utxo of a replayed tx is only tracked once
+        let r = mock_process_tx::<Test>(deposit_taproot2.clone(), None);
+        assert_eq!(r.result, BtcTxResult::Success);
+        assert_eq!(XGatewayBitcoin::trustee_utxo_balance(), 100000);
+
+        let report = XGatewayBitcoin::get_reserve_report();
+        assert_eq!(report.wasm, 200000);
+        assert_eq!(report.reserves, 100000);
+        assert_eq!(
+            report.total_liabilities,
+            report.wasm + report.evm + report.aptos + report.pending
+        );
+        assert!(report.reserve_ratio < 10_000);
+        assert_eq!(report.best_height, XGatewayBitcoin::best_index().height);
+
+        // the 18-decimal evm balances are reported in satoshi
+        let evm_account =
+            <Test as pallet_evm::Config>::AddressMapping::into_account_id(H160::repeat_byte(1));
+        let _ = Balances::deposit_creating(&evm_account, 50_000 * 10_000_000_000 + 1);
+        let evm_report = XGatewayBitcoin::get_reserve_report();
+        assert_eq!(
+            evm_report.evm as u128,
+            Balances::total_issuance() / 10_000_000_000
+        );
+        assert!(evm_report.evm >= report.evm + 50_000);
+        assert_eq!(
+            evm_report.total_liabilities,
+            report.total_liabilities + evm_report.evm - report.evm
+        );
+
+        assert_noop!(
+            XGatewayBitcoin::set_trustee_utxos(Origin::signed(alice()), vec![]),
+            sp_runtime::DispatchError::BadOrigin
+        );
+        assert_ok!(XGatewayBitcoin::set_trustee_utxos(
+            Origin::root(),
+            vec![
+                (deposit_taproot2.hash(), 1, 100000, true),
+                (deposit_taproot1.hash(), 0, 300000, false)
+            ]
+        ));
+        assert_eq!(XGatewayBitcoin::trustee_utxo_balance(), 400000);
+        assert_eq!(XGatewayBitcoin::trustee_hot_utxo_balance(), 100000);
+        assert_eq!(
+            XGatewayBitcoin::trustee_hot_utxos(deposit_taproot2.hash(), 1),
+            Some(100000)
+        );
+        assert_eq!(
+            XGatewayBitcoin::trustee_hot_utxos(deposit_taproot1.hash(), 0),
+            None
+        );
+
+        assert_ok!(XGatewayBitcoin::set_reserve_check(
+            Origin::root(),
+            5,
+            10_000
+        ));
+        XGatewayBitcoin::on_initialize(4);
+        assert!(!System::events().iter().any(|record| matches!(
+            record.event,
+            Event::XGatewayBitcoin(crate::Event::ReserveRatio(..))
+        )));
+
+        XGatewayBitcoin::on_initialize(5);
+        let report = XGatewayBitcoin::get_reserve_report();
+        assert!(System::events().iter().any(|record| record.event
+            == Event::XGatewayBitcoin(crate::Event::ReserveRatio(
+                400000,
+                report.total_liabilities,
+                report.reserve_ratio
+            ))));
+        assert!(System::events().iter().any(|record| record.event
+            == Event::XGatewayBitcoin(crate::Event::ReserveAlarm(report.reserve_ratio, 10_000))));
+    });
+}
+
+#[test]
+fn test_rebalance_proposal() {
+    set_default_ss58_version(Ss58AddressFormatRegistry::ChainxAccount.into());
+    ExtBuilder::default().build_and_execute(|| {
+        let r = mock_process_tx::<Test>(deposit_taproot2.clone(), None);
+        assert_eq!(r.result, BtcTxResult::Success);
+        assert_eq!(XGatewayBitcoin::trustee_utxo_balance(), 100000);
+        assert_eq!(XGatewayBitcoin::trustee_hot_utxo_balance(), 100000);
+
+        assert_noop!(
+            XGatewayBitcoin::set_rebalance_config(
+                Origin::signed(alice()),
+                Some(BtcRebalanceConfig {
+                    target_hot_ratio: 9_500,
+                    threshold: 1_000,
+                })
+            ),
+            XGatewayBitcoinErr::InvalidRebalanceConfig
+        );
+        assert_ok!(XGatewayBitcoin::set_rebalance_config(
+            Origin::signed(alice()),
+            Some(BtcRebalanceConfig {
+                target_hot_ratio: 1_000,
+                threshold: 500,
+            })
+        ));
+        assert_ok!(XGatewayBitcoin::set_reserve_check(Origin::root(), 5, 0));
+
+        // hot balance 100% > 10% + 5%, move 90% to the cold address
+        XGatewayBitcoin::on_initialize(5);
+        let proposal = XGatewayBitcoin::rebalance_proposal().unwrap();
+        assert_eq!(proposal.amount, 90000);
+        assert_eq!(proposal.opened_at, 5);
+        assert!(System::events().iter().any(|record| record.event
+            == Event::XGatewayBitcoin(crate::Event::RebalanceProposed(100000, 100000, 90000))));
+
+        // moving less than the proposed amount does not finish the proposal
+        with_transaction(|| {
+            let r = mock_process_tx::<Test>(hot_to_cold.clone(), Some(hot_to_cold_prev.clone()));
+            assert_eq!(r.result, BtcTxResult::Success);
+            assert_eq!(
+                XGatewayBitcoin::rebalance_proposal(),
+                Some(proposal.clone())
+            );
+            assert!(!System::events().iter().any(|record| matches!(
+                record.event,
+                Event::XGatewayBitcoin(crate::Event::Rebalanced(..))
+            )));
+            TransactionOutcome::Rollback(())
+        });
+
+        // the hot and cold tx finishes the proposal, the change stays in the hot address
+        RebalanceProposal::<Test>::mutate(|proposal| {
+            if let Some(proposal) = proposal {
+                proposal.amount = 20000;
+            }
+        });
+        let r = mock_process_tx::<Test>(hot_to_cold.clone(), Some(hot_to_cold_prev.clone()));
+        assert_eq!(r.tx_type, BtcTxType::HotAndCold);
+        assert_eq!(r.result, BtcTxResult::Success);
+        assert!(XGatewayBitcoin::rebalance_proposal().is_none());
+        assert!(System::events().iter().any(|record| record.event
+            == Event::XGatewayBitcoin(crate::Event::Rebalanced(hot_to_cold.hash(), 20000))));
+        assert_eq!(XGatewayBitcoin::trustee_utxo_balance(), 130000);
+        assert_eq!(XGatewayBitcoin::trustee_hot_utxo_balance(), 110000);
+
+        XGatewayBitcoin::on_initialize(10);
+        assert_eq!(XGatewayBitcoin::rebalance_proposal().unwrap().amount, 97000);
+
+        // disable the rebalancing
+        assert_ok!(XGatewayBitcoin::set_rebalance_config(
+            Origin::signed(alice()),
+            None
+        ));
+        assert!(XGatewayBitcoin::rebalance_proposal().is_none());
+        XGatewayBitcoin::on_initialize(15);
+        assert!(XGatewayBitcoin::rebalance_proposal().is_none());
+    });
+}
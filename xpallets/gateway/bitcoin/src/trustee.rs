@@ -2,8 +2,11 @@
 extern crate alloc;
 
 use alloc::string::ToString;
-use frame_support::dispatch::{DispatchError, DispatchResult};
-use sp_runtime::SaturatedConversion;
+use frame_support::{
+    dispatch::{DispatchError, DispatchResult},
+    ensure,
+};
+use sp_runtime::{traits::Zero, SaturatedConversion};
 use sp_std::{
     cmp::max,
     convert::{TryFrom, TryInto},
@@ -11,82 +14,97 @@ use sp_std::{
 };
 
 use light_bitcoin::{
-    chain::Transaction,
+    chain::{OutPoint, Transaction, TransactionOutput},
     crypto::dhash160,
     keys::{Address, AddressTypes, Public, Type},
-    mast::{compute_min_threshold, key::PublicKey, Mast},
-    primitives::Bytes,
+    mast::{
+        compute_min_threshold, generate_btc_address, key::PublicKey, tweak_pubkey, Mast, MerkleNode,
+    },
+    primitives::{Bytes, H256},
     script::{Builder, Opcode},
 };
 
-use xp_gateway_bitcoin::extract_output_addr;
-use xpallet_assets::Chain;
+use xp_gateway_bitcoin::{extract_output_addr, recovery_script};
+use xpallet_assets::{AssetType, ChainT};
 use xpallet_gateway_common::{
-    traits::{TrusteeForChain, TrusteeSession},
+    traits::{TrusteeForChain, TrusteeInfoUpdate, TrusteeSession},
     trustees::bitcoin::{BtcTrusteeAddrInfo, BtcTrusteeType},
-    types::{ScriptInfo, TrusteeInfoConfig, TrusteeIntentionProps, TrusteeSessionInfo},
+    types::{
+        ScriptInfo, TrusteeInfoConfig, TrusteeIntentionProps, TrusteeRecoveryConfig,
+        TrusteeSessionInfo,
+    },
     utils::two_thirds_unsafe,
 };
 
 use crate::{
-    log,
-    types::{BtcWithdrawalProposal, VoteResult},
-    Config, Error, Event, Pallet, WithdrawalProposal,
+    log, taproot,
+    types::{BtcExpiredProposal, BtcFeeBumpKind, BtcWithdrawalProposal, VoteResult},
+    Config, Error, Event, ExpiredProposalHashes, ExpiredProposals, Pallet, PendingCpfpFee,
+    WithdrawalFeeBump, WithdrawalFeeReserve, WithdrawalProposal, WithdrawalProposalDeadline,
 };
 
-pub fn current_trustee_session<T: Config>(
+/// The max sequence of an input which signals the replaceability (BIP125).
+const MAX_RBF_SEQUENCE: u32 = 0xffff_fffd;
+
+/// The max number of the expired withdrawal proposals kept in the storage.
+pub const MAX_EXPIRED_PROPOSALS: usize = 100;
+
+pub fn current_trustee_session<T: Config<I>, I: 'static>(
 ) -> Result<TrusteeSessionInfo<T::AccountId, T::BlockNumber, BtcTrusteeAddrInfo>, DispatchError> {
     T::TrusteeSessionProvider::current_trustee_session()
 }
 
-pub fn current_proxy_account<T: Config>() -> Result<Vec<T::AccountId>, DispatchError> {
+pub fn current_proxy_account<T: Config<I>, I: 'static>() -> Result<Vec<T::AccountId>, DispatchError>
+{
     T::TrusteeSessionProvider::current_proxy_account()
 }
 
 #[inline]
-fn current_trustee_addr_pair<T: Config>(
+fn current_trustee_addr_pair<T: Config<I>, I: 'static>(
 ) -> Result<(BtcTrusteeAddrInfo, BtcTrusteeAddrInfo), DispatchError> {
     T::TrusteeSessionProvider::current_trustee_session()
         .map(|session_info| (session_info.hot_address, session_info.cold_address))
 }
 
-pub fn get_hot_trustee_address<T: Config>() -> Result<Address, DispatchError> {
-    current_trustee_addr_pair::<T>()
-        .and_then(|(addr_info, _)| Pallet::<T>::verify_btc_address(&addr_info.addr))
+pub fn get_hot_trustee_address<T: Config<I>, I: 'static>() -> Result<Address, DispatchError> {
+    current_trustee_addr_pair::<T, I>()
+        .and_then(|(addr_info, _)| Pallet::<T, I>::verify_btc_address(&addr_info.addr))
 }
 
 #[inline]
-pub fn get_current_trustee_address_pair<T: Config>() -> Result<(Address, Address), DispatchError> {
-    current_trustee_addr_pair::<T>().map(|(hot_info, cold_info)| {
+pub fn get_current_trustee_address_pair<T: Config<I>, I: 'static>(
+) -> Result<(Address, Address), DispatchError> {
+    current_trustee_addr_pair::<T, I>().map(|(hot_info, cold_info)| {
         (
-            Pallet::<T>::verify_btc_address(&hot_info.addr)
+            Pallet::<T, I>::verify_btc_address(&hot_info.addr)
                 .expect("should not parse error from storage data; qed"),
-            Pallet::<T>::verify_btc_address(&cold_info.addr)
+            Pallet::<T, I>::verify_btc_address(&cold_info.addr)
                 .expect("should not parse error from storage data; qed"),
         )
     })
 }
 
 #[inline]
-pub fn get_last_trustee_address_pair<T: Config>() -> Result<(Address, Address), DispatchError> {
+pub fn get_last_trustee_address_pair<T: Config<I>, I: 'static>(
+) -> Result<(Address, Address), DispatchError> {
     T::TrusteeSessionProvider::last_trustee_session().map(|session_info| {
         (
-            Pallet::<T>::verify_btc_address(&session_info.hot_address.addr)
+            Pallet::<T, I>::verify_btc_address(&session_info.hot_address.addr)
                 .expect("should not parse error from storage data; qed"),
-            Pallet::<T>::verify_btc_address(&session_info.cold_address.addr)
+            Pallet::<T, I>::verify_btc_address(&session_info.cold_address.addr)
                 .expect("should not parse error from storage data; qed"),
         )
     })
 }
 
-pub fn check_keys<T: Config>(keys: &[Public]) -> DispatchResult {
+pub fn check_keys<T: Config<I>, I: 'static>(keys: &[Public]) -> DispatchResult {
     let has_duplicate = (1..keys.len()).any(|i| keys[i..].contains(&keys[i - 1]));
     if has_duplicate {
         log!(
             error,
             "[generate_new_trustees] Keys contains duplicate pubkey"
         );
-        return Err(Error::<T>::DuplicatedKeys.into());
+        return Err(Error::<T, I>::DuplicatedKeys.into());
     }
     let has_normal_pubkey = keys
         .iter()
@@ -107,33 +125,108 @@ const ZERO_P: [u8; 32] = [0; 32];
 
 const MAX_TAPROOT_NODES: u32 = 250;
 
-impl<T: Config> TrusteeForChain<T::AccountId, T::BlockNumber, BtcTrusteeType, BtcTrusteeAddrInfo>
-    for Pallet<T>
+/// The max relative timelock in blocks (BIP68).
+const MAX_CSV_BLOCKS: u32 = 0xffff;
+
+/// The root of the tapscript subtree of the timelocked recovery leaves, one leaf for each
+/// combination of `threshold` recovery keys.
+fn recovery_tree_root<T: Config<I>, I: 'static>(
+    recovery: &TrusteeRecoveryConfig,
+) -> Result<[u8; 32], DispatchError> {
+    if recovery.csv_delay == 0
+        || recovery.csv_delay > MAX_CSV_BLOCKS
+        || recovery.threshold == 0
+        || recovery.threshold as usize > recovery.keys.len()
+    {
+        log!(
+            error,
+            "[recovery_tree_root] Invalid recovery config:{:?}",
+            recovery
+        );
+        return Err(Error::<T, I>::InvalidRecoveryConfig.into());
+    }
+
+    let keys = recovery
+        .keys
+        .iter()
+        .map(|key| Public::from_slice(key).map_err(|_| Error::<T, I>::InvalidPublicKey))
+        .collect::<Result<Vec<_>, Error<T, I>>>()?;
+    check_keys::<T, I>(&keys)?;
+    let pks = keys
+        .into_iter()
+        .map(|k| k.try_into().map_err(|_| Error::<T, I>::InvalidPublicKey))
+        .collect::<Result<Vec<PublicKey>, Error<T, I>>>()?;
+
+    let leaf_keys = if pks.len() == 1 {
+        pks
+    } else {
+        Mast::new(pks, recovery.threshold)
+            .map_err(|_| Error::<T, I>::InvalidRecoveryConfig)?
+            .pubkeys
+    };
+    let leaves = leaf_keys
+        .iter()
+        .map(|p| taproot::tap_leaf_hash(&recovery_script(recovery.csv_delay, &p.x_coor())))
+        .collect::<Vec<_>>();
+    taproot::tap_tree_root(leaves).ok_or_else(|| Error::<T, I>::InvalidRecoveryConfig.into())
+}
+
+/// Generate the taproot address of the threshold `mast`.
+///
+/// With the `recovery_root`, the tapscript tree is `TapBranch(threshold_root, recovery_root)`.
+fn generate_taproot_address<T: Config<I>, I: 'static>(
+    mast: &Mast,
+    recovery_root: Option<&[u8; 32]>,
+) -> Result<Address, DispatchError> {
+    let network = Pallet::<T, I>::network_id().to_string();
+    let addr = match recovery_root {
+        None => mast.generate_address(&network),
+        Some(recovery_root) => {
+            let threshold_root = mast
+                .calc_root()
+                .map_err(|_| Error::<T, I>::InvalidAddress)?;
+            let mut threshold = [0u8; 32];
+            threshold.copy_from_slice(&threshold_root[..]);
+            let root = taproot::tap_branch_hash(&threshold, recovery_root);
+            let root = MerkleNode::from_slice(&root).map_err(|_| Error::<T, I>::InvalidAddress)?;
+            tweak_pubkey(&mast.inner_pubkey, &root)
+                .and_then(|tweaked| generate_btc_address(&tweaked, &network))
+        }
+    }
+    .map_err(|_| Error::<T, I>::InvalidAddress)?;
+
+    addr.parse()
+        .map_err(|_| Error::<T, I>::InvalidAddress.into())
+}
+
+impl<T: Config<I>, I: 'static>
+    TrusteeForChain<T::AccountId, T::BlockNumber, BtcTrusteeType, BtcTrusteeAddrInfo>
+    for Pallet<T, I>
 {
     fn check_trustee_entity(raw_addr: &[u8]) -> Result<BtcTrusteeType, DispatchError> {
         let trustee_type = BtcTrusteeType::try_from(raw_addr.to_vec())
-            .map_err(|_| Error::<T>::InvalidPublicKey)?;
+            .map_err(|_| Error::<T, I>::InvalidPublicKey)?;
         let public = trustee_type.0;
         let public: PublicKey = public
             .try_into()
-            .map_err(|_| Error::<T>::InvalidPublicKey)?;
+            .map_err(|_| Error::<T, I>::InvalidPublicKey)?;
 
         let raw_addr = public.serialize_compressed();
-        let public = Public::from_slice(&raw_addr).map_err(|_| Error::<T>::InvalidPublicKey)?;
+        let public = Public::from_slice(&raw_addr).map_err(|_| Error::<T, I>::InvalidPublicKey)?;
 
         if 2 != raw_addr[0] && 3 != raw_addr[0] {
             log!(error, "Not Compressed Public(prefix not 2|3)");
-            return Err(Error::<T>::InvalidPublicKey.into());
+            return Err(Error::<T, I>::InvalidPublicKey.into());
         }
 
         if ZERO_P == raw_addr[1..33] {
             log!(error, "Not Compressed Public(Zero32)");
-            return Err(Error::<T>::InvalidPublicKey.into());
+            return Err(Error::<T, I>::InvalidPublicKey.into());
         }
 
         if raw_addr[1..33].to_vec() >= EC_P.to_vec() {
             log!(error, "Not Compressed Public(EC_P)");
-            return Err(Error::<T>::InvalidPublicKey.into());
+            return Err(Error::<T, I>::InvalidPublicKey.into());
         }
 
         Ok(BtcTrusteeType(public))
@@ -164,8 +257,8 @@ impl<T: Config> TrusteeForChain<T::AccountId, T::BlockNumber, BtcTrusteeType, Bt
             .unzip();
 
         // judge all props has different pubkey
-        check_keys::<T>(&hot_keys)?;
-        check_keys::<T>(&cold_keys)?;
+        check_keys::<T, I>(&hot_keys)?;
+        check_keys::<T, I>(&cold_keys)?;
 
         // [min, max] e.g. bitcoin min is 4, max is 15
         if (trustees.len() as u32) < config.min_trustee_count
@@ -177,7 +270,7 @@ impl<T: Config> TrusteeForChain<T::AccountId, T::BlockNumber, BtcTrusteeType, Bt
                 can't generate trustee addr",
                 trustees, config.min_trustee_count, config.max_trustee_count
             );
-            return Err(Error::<T>::InvalidTrusteeCount.into());
+            return Err(Error::<T, I>::InvalidTrusteeCount.into());
         }
 
         #[cfg(feature = "std")]
@@ -211,30 +304,31 @@ impl<T: Config> TrusteeForChain<T::AccountId, T::BlockNumber, BtcTrusteeType, Bt
         // Set hot address for taproot threshold address
         let hot_pks = hot_keys
             .into_iter()
-            .map(|k| k.try_into().map_err(|_| Error::<T>::InvalidPublicKey))
-            .collect::<Result<Vec<_>, Error<T>>>()?;
+            .map(|k| k.try_into().map_err(|_| Error::<T, I>::InvalidPublicKey))
+            .collect::<Result<Vec<_>, Error<T, I>>>()?;
 
-        let hot_mast = Mast::new(hot_pks, sig_num).map_err(|_| Error::<T>::InvalidAddress)?;
+        // Timelocked recovery leaves shared by the hot and cold address
+        let recovery_root = config
+            .recovery
+            .as_ref()
+            .map(recovery_tree_root::<T, I>)
+            .transpose()?;
 
-        let hot_threshold_addr: Address = hot_mast
-            .generate_address(&Pallet::<T>::network_id().to_string())
-            .map_err(|_| Error::<T>::InvalidAddress)?
-            .parse()
-            .map_err(|_| Error::<T>::InvalidAddress)?;
+        let hot_mast = Mast::new(hot_pks, sig_num).map_err(|_| Error::<T, I>::InvalidAddress)?;
+
+        let hot_threshold_addr: Address =
+            generate_taproot_address::<T, I>(&hot_mast, recovery_root.as_ref())?;
 
         // Set cold address for taproot threshold address
         let cold_pks = cold_keys
             .into_iter()
-            .map(|k| k.try_into().map_err(|_| Error::<T>::InvalidPublicKey))
-            .collect::<Result<Vec<_>, Error<T>>>()?;
+            .map(|k| k.try_into().map_err(|_| Error::<T, I>::InvalidPublicKey))
+            .collect::<Result<Vec<_>, Error<T, I>>>()?;
 
-        let cold_mast = Mast::new(cold_pks, sig_num).map_err(|_| Error::<T>::InvalidAddress)?;
+        let cold_mast = Mast::new(cold_pks, sig_num).map_err(|_| Error::<T, I>::InvalidAddress)?;
 
-        let cold_threshold_addr: Address = cold_mast
-            .generate_address(&Pallet::<T>::network_id().to_string())
-            .map_err(|_| Error::<T>::InvalidAddress)?
-            .parse()
-            .map_err(|_| Error::<T>::InvalidAddress)?;
+        let cold_threshold_addr: Address =
+            generate_taproot_address::<T, I>(&cold_mast, recovery_root.as_ref())?;
 
         // Aggregate public key script and corresponding personal public key index
         let mut agg_pubkeys: Vec<Vec<u8>> = vec![];
@@ -292,18 +386,18 @@ impl<T: Config> TrusteeForChain<T::AccountId, T::BlockNumber, BtcTrusteeType, Bt
     }
 }
 
-impl<T: Config> Pallet<T> {
+impl<T: Config<I>, I: 'static> Pallet<T, I> {
     pub fn ensure_trustee_or_bot(who: &T::AccountId) -> DispatchResult {
         match Self::coming_bot() {
             Some(n) if &n == who => return Ok(()),
             _ => (),
         }
 
-        if current_proxy_account::<T>()?.iter().any(|n| n == who) {
+        if current_proxy_account::<T, I>()?.iter().any(|n| n == who) {
             return Ok(());
         }
 
-        let trustee_session_info = current_trustee_session::<T>()?;
+        let trustee_session_info = current_trustee_session::<T, I>()?;
         if trustee_session_info
             .trustee_list
             .iter()
@@ -317,7 +411,7 @@ impl<T: Config> Pallet<T> {
                 who,
                 trustee_session_info.trustee_list
             );
-            Err(Error::<T>::NotTrustee.into())
+            Err(Error::<T, I>::NotTrustee.into())
         }
     }
 
@@ -333,14 +427,14 @@ impl<T: Config> Pallet<T> {
                 "[apply_create_withdraw] Current list (len:{}) exceeding the max withdrawal amount {}",
                 withdrawal_id_list.len(), withdraw_amount
             );
-            return Err(Error::<T>::WrongWithdrawalCount.into());
+            return Err(Error::<T, I>::WrongWithdrawalCount.into());
         }
         // remove duplicate
         let mut withdrawal_id_list = withdrawal_id_list;
         withdrawal_id_list.sort_unstable();
         withdrawal_id_list.dedup();
 
-        check_withdraw_tx::<T>(&tx, &withdrawal_id_list)?;
+        check_withdraw_tx::<T, I>(&tx, &withdrawal_id_list)?;
         log!(
             info,
             "[apply_create_withdraw] Create new withdraw, id_list:{:?}",
@@ -349,7 +443,7 @@ impl<T: Config> Pallet<T> {
 
         xpallet_gateway_records::Pallet::<T>::process_withdrawals(
             &withdrawal_id_list,
-            Chain::Bitcoin,
+            Pallet::<T, I>::chain(),
         )?;
 
         let proposal = BtcWithdrawalProposal::new(
@@ -364,22 +458,461 @@ impl<T: Config> Pallet<T> {
             "[apply_create_withdraw] Pass the legality check of withdrawal"
         );
 
-        Self::deposit_event(Event::<T>::WithdrawalProposalCreated(
+        Self::deposit_event(Event::<T, I>::WithdrawalProposalCreated(
             who,
             withdrawal_id_list,
         ));
 
-        WithdrawalProposal::<T>::put(proposal);
+        WithdrawalProposal::<T, I>::put(proposal);
+        reset_proposal_deadline::<T, I>();
+
+        Ok(())
+    }
+
+    pub fn apply_sign_withdrawal_proposal(who: T::AccountId) -> DispatchResult {
+        let mut proposal = Self::withdrawal_proposal().ok_or(Error::<T, I>::NoProposal)?;
 
+        let trustee_session_info = current_trustee_session::<T, I>()?;
+        ensure!(
+            trustee_session_info.trustee_list.iter().any(|n| n.0 == who),
+            Error::<T, I>::NotTrustee
+        );
+        ensure!(
+            !proposal.trustee_list.iter().any(|(n, _)| n == &who),
+            Error::<T, I>::DuplicateVote
+        );
+
+        proposal.trustee_list.push((who.clone(), true));
+        WithdrawalProposal::<T, I>::put(proposal);
+
+        Self::deposit_event(Event::<T, I>::WithdrawalProposalVoted(who, true));
         Ok(())
     }
+
+    /// Roll back the withdrawal proposal which was not finished before the `deadline`.
+    pub(crate) fn apply_expire_proposal(deadline: T::BlockNumber) -> DispatchResult {
+        let proposal = WithdrawalProposal::<T, I>::take().ok_or(Error::<T, I>::NoProposal)?;
+        WithdrawalProposalDeadline::<T, I>::kill();
+        // the proposal tx was never packed, return all the charged fee to the reserve
+        settle_fee_bump::<T, I>(None);
+
+        for id in proposal.withdrawal_id_list.iter() {
+            xpallet_gateway_records::Pallet::<T>::set_withdrawal_state_by_root(
+                *id,
+                xpallet_gateway_records::WithdrawalState::Applying,
+            )?;
+        }
+
+        let (signers, missing_signers): (Vec<_>, Vec<_>) = current_trustee_session::<T, I>()?
+            .trustee_list
+            .into_iter()
+            .map(|(trustee, _)| trustee)
+            .partition(|trustee| {
+                proposal
+                    .trustee_list
+                    .iter()
+                    .any(|(n, signed)| n == trustee && *signed)
+            });
+        T::TrusteeInfoUpdate::update_trustee_liveness(Pallet::<T, I>::chain(), &signers);
+
+        let tx_hash = proposal.tx.hash();
+        log!(
+            warn,
+            "[apply_expire_proposal] Withdrawal proposal expired, tx_hash:{:?}, id_list:{:?}, missing signers:{:?}",
+            tx_hash,
+            proposal.withdrawal_id_list,
+            missing_signers
+        );
+
+        ExpiredProposals::<T, I>::insert(
+            tx_hash,
+            BtcExpiredProposal {
+                withdrawal_id_list: proposal.withdrawal_id_list.clone(),
+                deadline,
+                missing_signers: missing_signers.clone(),
+            },
+        );
+        ExpiredProposalHashes::<T, I>::mutate(|hashes| {
+            // the same tx may expire again after it's proposed again
+            hashes.retain(|hash| hash != &tx_hash);
+            hashes.push(tx_hash);
+            if hashes.len() > MAX_EXPIRED_PROPOSALS {
+                let pruned = hashes.remove(0);
+                ExpiredProposals::<T, I>::remove(pruned);
+            }
+        });
+        Self::deposit_event(Event::<T, I>::WithdrawalProposalExpired(
+            tx_hash,
+            proposal.withdrawal_id_list,
+            missing_signers,
+        ));
+        Ok(())
+    }
+
+    pub fn apply_bump_withdrawal_fee(
+        who: T::AccountId,
+        kind: BtcFeeBumpKind,
+        tx: Transaction,
+    ) -> DispatchResult {
+        let mut proposal = Self::withdrawal_proposal().ok_or(Error::<T, I>::NoProposal)?;
+        let mut bump = Self::withdrawal_fee_bump().unwrap_or_default();
+        let proposal_hash = proposal.tx.hash();
+        let tx_hash = tx.hash();
+
+        let extra_fee = match kind {
+            BtcFeeBumpKind::Rbf => {
+                let extra_fee =
+                    check_rbf_tx::<T, I>(&proposal.tx, &tx, &proposal.withdrawal_id_list)?;
+                // the cpfp child of the replaced tx would never be valid
+                let child_fee = bump.cpfp.take().map(|(_, fee)| fee).unwrap_or_default();
+                bump.cpfp_relayed = false;
+                charge_fee_reserve::<T, I>(extra_fee, child_fee)?;
+
+                bump.replaced.push((proposal_hash, bump.rbf_fee));
+                bump.rbf_fee = bump.rbf_fee.saturating_add(extra_fee);
+                // the replacement has to be signed again
+                proposal.tx = tx;
+                proposal.trustee_list.clear();
+                WithdrawalProposal::<T, I>::put(proposal);
+                reset_proposal_deadline::<T, I>();
+                extra_fee
+            }
+            BtcFeeBumpKind::Cpfp => {
+                let fee = check_cpfp_tx::<T, I>(&proposal.tx, &tx)?;
+                // a new child replaces the previous one, only charge the difference
+                let child_fee = bump.cpfp.as_ref().map(|(_, fee)| *fee).unwrap_or_default();
+                ensure!(fee > child_fee, Error::<T, I>::FeeNotIncreased);
+                charge_fee_reserve::<T, I>(fee - child_fee, 0)?;
+
+                bump.cpfp = Some((tx, fee));
+                fee - child_fee
+            }
+        };
+
+        log!(
+            info,
+            "[apply_bump_withdrawal_fee] Bump withdrawal fee, kind:{:?}, proposal:{:?}, tx:{:?}, extra fee:{}",
+            kind,
+            proposal_hash,
+            tx_hash,
+            extra_fee
+        );
+
+        WithdrawalFeeBump::<T, I>::put(bump);
+        Self::deposit_event(Event::<T, I>::WithdrawalFeeBumped(
+            who, kind, tx_hash, extra_fee,
+        ));
+        Ok(())
+    }
+}
+
+/// Start the countdown of the current withdrawal proposal.
+fn reset_proposal_deadline<T: Config<I>, I: 'static>() {
+    let timeout = Pallet::<T, I>::withdrawal_proposal_timeout();
+    if timeout.is_zero() {
+        WithdrawalProposalDeadline::<T, I>::kill();
+    } else {
+        let now = frame_system::Pallet::<T>::block_number();
+        WithdrawalProposalDeadline::<T, I>::put(now + timeout);
+    }
+}
+
+/// Stop the countdown of the withdrawal proposal once a tx spending the inputs of the proposal
+/// tx is relayed, the withdrawal applications can't be rolled back any more.
+pub(crate) fn check_proposal_inputs_spent<T: Config<I>, I: 'static>(tx: &Transaction) {
+    if Pallet::<T, I>::withdrawal_proposal_deadline().is_none() {
+        return;
+    }
+    if let Some(proposal) = Pallet::<T, I>::withdrawal_proposal() {
+        let spent = tx.inputs.iter().any(|input| {
+            proposal
+                .tx
+                .inputs
+                .iter()
+                .any(|n| n.previous_output == input.previous_output)
+        });
+        if spent {
+            log!(
+                warn,
+                "[check_proposal_inputs_spent] Tx {:?} spends the inputs of the withdrawal proposal {:?}",
+                tx.hash(),
+                proposal.tx.hash()
+            );
+            WithdrawalProposalDeadline::<T, I>::kill();
+        }
+    }
+}
+
+/// Charge `fee` to the withdrawal fee reserve after giving back the `refund`.
+///
+/// The charged X-BTC of the reserve account is moved to `ReservedWithdrawal` until the
+/// withdrawal tx is packed.
+fn charge_fee_reserve<T: Config<I>, I: 'static>(fee: u64, refund: u64) -> DispatchResult {
+    let reserve =
+        Pallet::<T, I>::withdrawal_fee_reserve().ok_or(Error::<T, I>::InsufficientFeeReserve)?;
+    let id = <Pallet<T, I> as ChainT<_>>::ASSET_ID;
+    let (from_type, to_type, value) = if fee >= refund {
+        (
+            AssetType::Usable,
+            AssetType::ReservedWithdrawal,
+            fee - refund,
+        )
+    } else {
+        (
+            AssetType::ReservedWithdrawal,
+            AssetType::Usable,
+            refund - fee,
+        )
+    };
+    xpallet_assets::Pallet::<T>::move_balance(
+        &id,
+        &reserve,
+        from_type,
+        &reserve,
+        to_type,
+        value.saturated_into(),
+    )
+    .map_err(|_| Error::<T, I>::InsufficientFeeReserve)?;
+    Ok(())
+}
+
+/// Clear the fee bumps of the withdrawal proposal, destroy the fee really spent and give the
+/// rest back to the withdrawal fee reserve. `settled` is the withdrawal tx packed into the block,
+/// if any.
+///
+/// The fee of a cpfp child not relayed yet is held in `PendingCpfpFee`, as the child may be
+/// packed after the withdrawal tx.
+pub(crate) fn settle_fee_bump<T: Config<I>, I: 'static>(settled: Option<&H256>) {
+    let bump = match WithdrawalFeeBump::<T, I>::take() {
+        Some(bump) => bump,
+        None => return,
+    };
+    let (spent, held) = settled
+        .map(|txid| (bump.spent(txid), bump.held(txid)))
+        .unwrap_or_default();
+    let refund = bump.charged().saturating_sub(spent).saturating_sub(held);
+
+    if held > 0 {
+        if let Some(child) = bump.cpfp {
+            // the child of an earlier withdrawal tx would never be packed
+            if let Some((_, fee)) = PendingCpfpFee::<T, I>::take() {
+                release_fee_reserve::<T, I>(0, fee);
+            }
+            PendingCpfpFee::<T, I>::put(child);
+        }
+    }
+    release_fee_reserve::<T, I>(spent, refund);
+}
+
+/// Charge the fee of the cpfp child once a tx spending the change output of the withdrawal tx is
+/// relayed, the fee is given back to the withdrawal fee reserve if the tx is not the child.
+///
+/// The child may be relayed before or after the withdrawal tx.
+pub(crate) fn check_cpfp_child_relayed<T: Config<I>, I: 'static>(tx: &Transaction) {
+    let tx_hash = tx.hash();
+    let spends = |child: &Transaction| {
+        tx.inputs.iter().any(|input| {
+            child
+                .inputs
+                .iter()
+                .any(|n| n.previous_output == input.previous_output)
+        })
+    };
+
+    if let Some(mut bump) = Pallet::<T, I>::withdrawal_fee_bump() {
+        let relayed = match bump.cpfp.as_ref() {
+            Some((child, _)) if !bump.cpfp_relayed && spends(child) => Some(child.hash()),
+            _ => None,
+        };
+        if let Some(child_hash) = relayed {
+            if child_hash == tx_hash {
+                bump.cpfp_relayed = true;
+            } else {
+                log!(
+                    warn,
+                    "[check_cpfp_child_relayed] Tx {:?} spends the change output instead of the cpfp child {:?}",
+                    tx_hash,
+                    child_hash
+                );
+                let fee = bump.cpfp_fee();
+                bump.cpfp = None;
+                release_fee_reserve::<T, I>(0, fee);
+            }
+            WithdrawalFeeBump::<T, I>::put(bump);
+        }
+    }
+
+    if let Some((child, fee)) = Pallet::<T, I>::pending_cpfp_fee() {
+        if spends(&child) {
+            PendingCpfpFee::<T, I>::kill();
+            if child.hash() == tx_hash {
+                release_fee_reserve::<T, I>(fee, 0);
+            } else {
+                release_fee_reserve::<T, I>(0, fee);
+            }
+        }
+    }
+}
+
+/// Destroy the `spent` fee and give the `refund` back to the withdrawal fee reserve, both are
+/// held as `ReservedWithdrawal` of the reserve account.
+fn release_fee_reserve<T: Config<I>, I: 'static>(spent: u64, refund: u64) {
+    let reserve = match Pallet::<T, I>::withdrawal_fee_reserve() {
+        Some(reserve) => reserve,
+        None => {
+            log!(
+                error,
+                "[release_fee_reserve] No withdrawal fee reserve for the spent fee {} and the refund {}",
+                spent,
+                refund
+            );
+            return;
+        }
+    };
+    let id = <Pallet<T, I> as ChainT<_>>::ASSET_ID;
+
+    if spent > 0 {
+        if let Err(err) = xpallet_assets::Pallet::<T>::destroy_reserved_withdrawal(
+            &id,
+            &reserve,
+            spent.saturated_into(),
+        ) {
+            log!(
+                error,
+                "[release_fee_reserve] Destroy the spent fee {} failed:{:?}",
+                spent,
+                err
+            );
+        }
+    }
+    if refund > 0 {
+        if let Err(err) = xpallet_assets::Pallet::<T>::move_balance(
+            &id,
+            &reserve,
+            AssetType::ReservedWithdrawal,
+            &reserve,
+            AssetType::Usable,
+            refund.saturated_into(),
+        ) {
+            log!(
+                error,
+                "[release_fee_reserve] Refund the fee {} failed:{:?}",
+                refund,
+                err
+            );
+        }
+    }
+}
+
+/// Check the rbf replacement of the withdrawal tx, return the extra fee.
+///
+/// The replacement must spend the same inputs, keep the withdrawal outputs unchanged and pay the
+/// extra fee out of the change.
+fn check_rbf_tx<T: Config<I>, I: 'static>(
+    origin: &Transaction,
+    tx: &Transaction,
+    withdrawal_id_list: &[u32],
+) -> Result<u64, DispatchError> {
+    // the original tx must opt in the replaceability, or it would never be replaced
+    if !origin
+        .inputs
+        .iter()
+        .any(|input| input.sequence <= MAX_RBF_SEQUENCE)
+    {
+        return Err(Error::<T, I>::TxNotReplaceable.into());
+    }
+
+    let mut origin_inputs = origin
+        .inputs
+        .iter()
+        .map(|input| input.previous_output.clone())
+        .collect::<Vec<_>>();
+    let mut inputs = tx
+        .inputs
+        .iter()
+        .map(|input| input.previous_output.clone())
+        .collect::<Vec<_>>();
+    origin_inputs.sort_unstable_by_key(outpoint_key);
+    inputs.sort_unstable_by_key(outpoint_key);
+    if origin_inputs != inputs {
+        log!(
+            error,
+            "[check_rbf_tx] Inputs not match, origin:{:?}, replacement:{:?}",
+            origin_inputs,
+            inputs
+        );
+        return Err(Error::<T, I>::FeeBumpInputsNotMatch.into());
+    }
+
+    check_withdraw_tx_impl::<T, I>(tx, withdrawal_id_list)?;
+
+    let origin_out = origin.total_spends();
+    let out = tx.total_spends();
+    match origin_out.checked_sub(out) {
+        Some(extra_fee) if extra_fee > 0 => Ok(extra_fee),
+        _ => Err(Error::<T, I>::FeeNotIncreased.into()),
+    }
+}
+
+/// Check the cpfp child of the withdrawal tx, return the fee paid by the child.
+///
+/// The child must only spend the change output of the withdrawal tx and send the rest back to the
+/// hot address.
+fn check_cpfp_tx<T: Config<I>, I: 'static>(
+    parent: &Transaction,
+    tx: &Transaction,
+) -> Result<u64, DispatchError> {
+    let hot_trustee_address: Address = get_hot_trustee_address::<T, I>()?;
+    let btc_network = Pallet::<T, I>::network_id();
+    let is_hot = |output: &TransactionOutput| {
+        extract_output_addr(output, btc_network)
+            .map(|addr| addr.hash == hot_trustee_address.hash)
+            .unwrap_or(false)
+    };
+
+    let parent_hash = parent.hash();
+    let (change_index, change) = parent
+        .outputs
+        .iter()
+        .enumerate()
+        .find(|(_, output)| is_hot(*output))
+        .ok_or(Error::<T, I>::FeeBumpInputsNotMatch)?;
+
+    let change_outpoint = OutPoint {
+        txid: parent_hash,
+        index: change_index as u32,
+    };
+    if tx.inputs.len() != 1 || tx.inputs[0].previous_output != change_outpoint {
+        log!(
+            error,
+            "[check_cpfp_tx] Child must only spend the change output {:?}, child inputs:{:?}",
+            change_outpoint,
+            tx.inputs
+        );
+        return Err(Error::<T, I>::FeeBumpInputsNotMatch.into());
+    }
+
+    ensure!(
+        tx.outputs.iter().all(is_hot),
+        Error::<T, I>::TxOutputsNotMatch
+    );
+
+    match change.value.checked_sub(tx.total_spends()) {
+        Some(fee) if fee > 0 => Ok(fee),
+        _ => Err(Error::<T, I>::FeeNotIncreased.into()),
+    }
+}
+
+#[inline]
+fn outpoint_key(outpoint: &OutPoint) -> (H256, u32) {
+    (outpoint.txid, outpoint.index)
 }
 
 /// Get the required number of signatures
 /// sig_num: Number of signatures required
 /// trustee_num: Total number of multiple signatures
 /// NOTE: Signature ratio greater than 2/3
-pub fn get_sig_num<T: Config>() -> (u32, u32) {
+pub fn get_sig_num<T: Config<I>, I: 'static>() -> (u32, u32) {
     let trustee_list = T::TrusteeSessionProvider::current_trustee_session()
         .map(|session_info| session_info.trustee_list)
         .expect("the trustee_list must exist; qed");
@@ -388,7 +921,7 @@ pub fn get_sig_num<T: Config>() -> (u32, u32) {
 }
 
 #[allow(dead_code)]
-pub(crate) fn create_multi_address<T: Config>(
+pub(crate) fn create_multi_address<T: Config<I>, I: 'static>(
     pubkeys: &[Public],
     sig_num: u32,
 ) -> Option<BtcTrusteeAddrInfo> {
@@ -428,7 +961,7 @@ pub(crate) fn create_multi_address<T: Config>(
 
     let addr = Address {
         kind: Type::P2SH,
-        network: Pallet::<T>::network_id(),
+        network: Pallet::<T, I>::network_id(),
         hash: AddressTypes::Legacy(dhash160(&redeem_script)),
     };
     let script_bytes: Bytes = redeem_script.into();
@@ -439,17 +972,17 @@ pub(crate) fn create_multi_address<T: Config>(
 }
 
 /// Check that the cash withdrawal transaction is correct
-pub fn check_withdraw_tx<T: Config>(
+pub fn check_withdraw_tx<T: Config<I>, I: 'static>(
     tx: &Transaction,
     withdrawal_id_list: &[u32],
 ) -> DispatchResult {
-    match Pallet::<T>::withdrawal_proposal() {
-        Some(_) => Err(Error::<T>::NotFinishProposal.into()),
-        None => check_withdraw_tx_impl::<T>(tx, withdrawal_id_list),
+    match Pallet::<T, I>::withdrawal_proposal() {
+        Some(_) => Err(Error::<T, I>::NotFinishProposal.into()),
+        None => check_withdraw_tx_impl::<T, I>(tx, withdrawal_id_list),
     }
 }
 
-fn check_withdraw_tx_impl<T: Config>(
+fn check_withdraw_tx_impl<T: Config<I>, I: 'static>(
     tx: &Transaction,
     withdrawal_id_list: &[u32],
 ) -> DispatchResult {
@@ -457,18 +990,24 @@ fn check_withdraw_tx_impl<T: Config>(
     let mut appl_withdrawal_list: Vec<(Address, u64)> = Vec::new();
     for withdraw_index in withdrawal_id_list.iter() {
         let record = xpallet_gateway_records::Pallet::<T>::pending_withdrawals(withdraw_index)
-            .ok_or(Error::<T>::NoWithdrawalRecord)?;
+            .ok_or(Error::<T, I>::NoWithdrawalRecord)?;
+        // the delayed withdrawals can't be batched until the delay ends
+        ensure!(
+            xpallet_gateway_records::Pallet::<T>::state_of(withdraw_index)
+                != Some(xpallet_gateway_records::WithdrawalState::Delayed),
+            Error::<T, I>::WithdrawalDelayed
+        );
         // record.addr() is base58
         // verify btc address would conveRelayedTx a base58 addr to Address
-        let addr: Address = Pallet::<T>::verify_btc_address(record.addr())?;
+        let addr: Address = Pallet::<T, I>::verify_btc_address(record.addr())?;
 
         appl_withdrawal_list.push((addr, record.balance().saturated_into::<u64>()));
     }
     // not allow deposit directly to cold address, only hot address allow
-    let hot_trustee_address: Address = get_hot_trustee_address::<T>()?;
+    let hot_trustee_address: Address = get_hot_trustee_address::<T, I>()?;
     // withdrawal addr list for tx outputs
-    let btc_withdrawal_fee = Pallet::<T>::btc_withdrawal_fee();
-    let btc_network = Pallet::<T>::network_id();
+    let btc_withdrawal_fee = Pallet::<T, I>::btc_withdrawal_fee();
+    let btc_network = Pallet::<T, I>::network_id();
     let mut tx_withdraw_list = Vec::new();
     for output in &tx.outputs {
         let addr = extract_output_addr(output, btc_network).ok_or("not found addr in this out")?;
@@ -495,7 +1034,7 @@ fn check_withdraw_tx_impl<T: Config>(
                 .zip(appl_withdrawal_list)
                 .collect::<Vec<_>>()
         );
-        return Err(Error::<T>::TxOutputsNotMatch.into());
+        return Err(Error::<T, I>::TxOutputsNotMatch.into());
     }
 
     let count = appl_withdrawal_list
@@ -518,7 +1057,7 @@ fn check_withdraw_tx_impl<T: Config>(
         .count();
 
     if count != appl_withdrawal_list.len() {
-        return Err(Error::<T>::TxOutputsNotMatch.into());
+        return Err(Error::<T, I>::TxOutputsNotMatch.into());
     }
 
     Ok(())
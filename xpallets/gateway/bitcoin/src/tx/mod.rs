@@ -28,11 +28,12 @@ use xpallet_support::try_str;
 
 pub use self::validator::validate_transaction;
 use crate::{
+    trustee::{check_cpfp_child_relayed, check_proposal_inputs_spent, settle_fee_bump},
     types::{AccountInfo, BtcAddress, BtcDepositCache, BtcTxResult, BtcTxState},
-    BalanceOf, Config, Event, Pallet, PendingDeposits, WithdrawalProposal,
+    BalanceOf, Config, Event, Pallet, PendingDeposits, RebalanceProposal, WithdrawalProposal,
 };
 
-pub fn process_tx<T: Config>(
+pub fn process_tx<T: Config<I>, I: 'static>(
     tx: Transaction,
     prev_tx: Option<Transaction>,
     network: Network,
@@ -49,12 +50,25 @@ pub fn process_tx<T: Config>(
         last_trustee_pair,
     );
 
+    // must be checked before the withdrawal proposal is taken
+    check_proposal_inputs_spent::<T, I>(&tx);
+    check_cpfp_child_relayed::<T, I>(&tx);
+    Pallet::<T, I>::update_trustee_utxos(&tx, network, current_trustee_pair, last_trustee_pair);
+
     let tx_type = meta_type.ref_into();
     let result = match meta_type {
-        BtcTxMetaType::<_>::Deposit(deposit_info) => deposit::<T>(tx.hash(), deposit_info),
-        BtcTxMetaType::<_>::Withdrawal => withdraw::<T>(tx),
-        BtcTxMetaType::TrusteeTransition => trustee_transition::<T>(tx),
-        BtcTxMetaType::HotAndCold => BtcTxResult::Success,
+        BtcTxMetaType::<_>::Deposit(deposit_info) => deposit::<T, I>(tx.hash(), deposit_info),
+        BtcTxMetaType::<_>::Withdrawal => withdraw::<T, I>(tx),
+        BtcTxMetaType::TrusteeTransition => trustee_transition::<T, I>(tx),
+        BtcTxMetaType::HotAndCold => hot_and_cold::<T, I>(tx, network, current_trustee_pair),
+        BtcTxMetaType::TrusteeRecovery => {
+            warn!(
+                target: "runtime::bitcoin",
+                "[process_tx] Trustee utxo is spent by the recovery path, tx_hash:{:?}",
+                hash_rev(tx.hash())
+            );
+            BtcTxResult::Success
+        }
         // mark `Irrelevance` be `Failure` so that it could be replayed in the future
         BtcTxMetaType::<_>::Irrelevance => BtcTxResult::Failure,
     };
@@ -62,24 +76,71 @@ pub fn process_tx<T: Config>(
     BtcTxState { tx_type, result }
 }
 
-fn trustee_transition<T: Config>(tx: Transaction) -> BtcTxResult {
+fn trustee_transition<T: Config<I>, I: 'static>(tx: Transaction) -> BtcTxResult {
     let amount = tx.outputs().iter().map(|output| output.value).sum::<u64>();
 
-    T::TrusteeInfoUpdate::update_transition_status(Pallet::<T>::chain(), false, Some(amount));
+    // Record the liveness of the last trustees before the session is archived
+    if let Some(input) = tx.inputs().get(0) {
+        if input.script_witness.len() == 3 {
+            T::TrusteeInfoUpdate::update_transition_sig_record(
+                Pallet::<T, I>::chain(),
+                input.script_witness[1].as_slice(),
+            );
+        }
+    }
+
+    T::TrusteeInfoUpdate::update_transition_status(Pallet::<T, I>::chain(), false, Some(amount));
+    // the hot balance moves to the new trustees
+    RebalanceProposal::<T, I>::kill();
+
+    BtcTxResult::Success
+}
+
+fn hot_and_cold<T: Config<I>, I: 'static>(
+    tx: Transaction,
+    network: Network,
+    current_trustee_pair: (Address, Address),
+) -> BtcTxResult {
+    let proposal = match RebalanceProposal::<T, I>::get() {
+        Some(proposal) => proposal,
+        None => return BtcTxResult::Success,
+    };
 
+    let cold_amount = tx
+        .outputs()
+        .iter()
+        .filter(|output| {
+            xp_gateway_bitcoin::extract_output_addr(output, network)
+                .map_or(false, |addr| addr.hash == current_trustee_pair.1.hash)
+        })
+        .map(|output| output.value)
+        .fold(0u64, |acc, value| acc.saturating_add(value));
+    if cold_amount >= proposal.amount {
+        info!(
+            target: "runtime::bitcoin",
+            "[hot_and_cold] Rebalance proposal finished, tx_hash:{:?}, cold_amount:{}",
+            hash_rev(tx.hash()),
+            cold_amount
+        );
+        RebalanceProposal::<T, I>::kill();
+        Pallet::<T, I>::deposit_event(Event::<T, I>::Rebalanced(tx.hash(), cold_amount));
+    }
     BtcTxResult::Success
 }
 
-fn deposit<T: Config>(txid: H256, deposit_info: BtcDepositInfo<T::AccountId>) -> BtcTxResult {
+fn deposit<T: Config<I>, I: 'static>(
+    txid: H256,
+    deposit_info: BtcDepositInfo<T::AccountId>,
+) -> BtcTxResult {
     // check address in op_return whether allow binding
     let deposit_info = T::AddressBinding::check_allowed_binding(deposit_info);
     let account_info = match (deposit_info.op_return, deposit_info.input_addr) {
         (Some((account, referral)), Some(input_addr)) => {
             let input_addr = input_addr.to_string().into_bytes();
             // remove old unbinding deposit info
-            remove_pending_deposit::<T>(&input_addr, &account);
+            remove_pending_deposit::<T, I>(&input_addr, &account);
             // update or override binding info
-            T::AddressBinding::update_binding(Pallet::<T>::chain(), input_addr, account.clone());
+            T::AddressBinding::update_binding(Pallet::<T, I>::chain(), input_addr, account.clone());
             AccountInfo::<T::AccountId>::Account((account, referral))
         }
         (Some((account, referral)), None) => {
@@ -95,7 +156,7 @@ fn deposit<T: Config>(txid: H256, deposit_info: BtcDepositInfo<T::AccountId>) ->
         (None, Some(input_addr)) => {
             // no opreturn but have input addr, use input addr to get accountid
             let addr_bytes = input_addr.to_string().into_bytes();
-            match T::AddressBinding::address(Pallet::<T>::chain(), addr_bytes) {
+            match T::AddressBinding::address(Pallet::<T, I>::chain(), addr_bytes) {
                 Some(account) => AccountInfo::Account((account, None)),
                 None => AccountInfo::Address(input_addr),
             }
@@ -114,13 +175,13 @@ fn deposit<T: Config>(txid: H256, deposit_info: BtcDepositInfo<T::AccountId>) ->
         AccountInfo::<_>::Account((account, referral)) => {
             if let OpReturnAccount::Wasm(w) = account.clone() {
                 T::ReferralBinding::update_binding(
-                    &<Pallet<T> as ChainT<_>>::ASSET_ID,
+                    &<Pallet<T, I> as ChainT<_>>::ASSET_ID,
                     &w,
                     referral,
                 );
             }
 
-            match deposit_token::<T>(txid, &account, deposit_info.deposit_value) {
+            match deposit_token::<T, I>(txid, &account, deposit_info.deposit_value) {
                 Ok(_) => {
                     info!(
                         target: "runtime::bitcoin",
@@ -135,7 +196,7 @@ fn deposit<T: Config>(txid: H256, deposit_info: BtcDepositInfo<T::AccountId>) ->
             }
         }
         AccountInfo::<_>::Address(input_addr) => {
-            insert_pending_deposit::<T>(&input_addr, txid, deposit_info.deposit_value);
+            insert_pending_deposit::<T, I>(&input_addr, txid, deposit_info.deposit_value);
             info!(
                 target: "runtime::bitcoin",
                 "[deposit] Deposit tx ({:?}) into pending, addr:{:?}, balance:{}",
@@ -148,26 +209,32 @@ fn deposit<T: Config>(txid: H256, deposit_info: BtcDepositInfo<T::AccountId>) ->
     }
 }
 
-fn deposit_token<T: Config>(
+fn deposit_token<T: Config<I>, I: 'static>(
     txid: H256,
     who: &OpReturnAccount<T::AccountId>,
     balance: u64,
 ) -> DispatchResult {
     match who {
-        OpReturnAccount::Evm(w) => deposit_evm::<T>(txid, w, balance),
-        OpReturnAccount::Wasm(w) => deposit_wasm::<T>(txid, w, balance),
-        OpReturnAccount::Aptos(w) => deposit_aptos::<T>(txid, w, balance),
-        OpReturnAccount::Named(w1, w2) => deposit_named::<T>(txid, w1.clone(), w2.clone(), balance),
+        OpReturnAccount::Evm(w) => deposit_evm::<T, I>(txid, w, balance),
+        OpReturnAccount::Wasm(w) => deposit_wasm::<T, I>(txid, w, balance),
+        OpReturnAccount::Aptos(w) => deposit_aptos::<T, I>(txid, w, balance),
+        OpReturnAccount::Named(w1, w2) => {
+            deposit_named::<T, I>(txid, w1.clone(), w2.clone(), balance)
+        }
     }
 }
 
-fn deposit_wasm<T: Config>(txid: H256, who: &T::AccountId, balance: u64) -> DispatchResult {
-    let id: AssetId = <Pallet<T> as ChainT<_>>::ASSET_ID;
+fn deposit_wasm<T: Config<I>, I: 'static>(
+    txid: H256,
+    who: &T::AccountId,
+    balance: u64,
+) -> DispatchResult {
+    let id: AssetId = <Pallet<T, I> as ChainT<_>>::ASSET_ID;
 
     let value: BalanceOf<T> = balance.saturated_into();
     match <xpallet_gateway_records::Pallet<T>>::deposit(who, id, value) {
         Ok(()) => {
-            Pallet::<T>::deposit_event(Event::<T>::Deposited(txid, who.clone(), value));
+            Pallet::<T, I>::deposit_event(Event::<T, I>::Deposited(txid, who.clone(), value));
             Ok(())
         }
         Err(err) => {
@@ -181,12 +248,12 @@ fn deposit_wasm<T: Config>(txid: H256, who: &T::AccountId, balance: u64) -> Disp
     }
 }
 
-fn deposit_evm<T: Config>(txid: H256, who: &H160, balance: u64) -> DispatchResult {
-    let id: AssetId = <Pallet<T> as ChainT<_>>::ASSET_ID;
+fn deposit_evm<T: Config<I>, I: 'static>(txid: H256, who: &H160, balance: u64) -> DispatchResult {
+    let id: AssetId = <Pallet<T, I> as ChainT<_>>::ASSET_ID;
 
     match xpallet_assets_bridge::Pallet::<T>::apply_direct_deposit(*who, id, balance as u128) {
         Ok(_) => {
-            Pallet::<T>::deposit_event(Event::<T>::DepositedEvm(
+            Pallet::<T, I>::deposit_event(Event::<T, I>::DepositedEvm(
                 txid,
                 *who,
                 balance.saturated_into(),
@@ -204,14 +271,14 @@ fn deposit_evm<T: Config>(txid: H256, who: &H160, balance: u64) -> DispatchResul
     }
 }
 
-fn deposit_aptos<T: Config>(txid: H256, who: &H256, balance: u64) -> DispatchResult {
-    let id: AssetId = <Pallet<T> as ChainT<_>>::ASSET_ID;
+fn deposit_aptos<T: Config<I>, I: 'static>(txid: H256, who: &H256, balance: u64) -> DispatchResult {
+    let id: AssetId = <Pallet<T, I> as ChainT<_>>::ASSET_ID;
     let value: BalanceOf<T> = balance.saturated_into();
 
     if let Some(proxy_address) = T::AddressBinding::dst_chain_proxy_address(DstChain::Aptos) {
         match <xpallet_gateway_records::Pallet<T>>::deposit(&proxy_address, id, value) {
             Ok(()) => {
-                Pallet::<T>::deposit_event(Event::<T>::DepositedAptos(txid, *who, value));
+                Pallet::<T, I>::deposit_event(Event::<T, I>::DepositedAptos(txid, *who, value));
             }
             Err(err) => {
                 error!(
@@ -226,13 +293,13 @@ fn deposit_aptos<T: Config>(txid: H256, who: &H256, balance: u64) -> DispatchRes
     Ok(())
 }
 
-fn deposit_named<T: Config>(
+fn deposit_named<T: Config<I>, I: 'static>(
     txid: H256,
     prefix: Vec<u8>,
     who: Vec<u8>,
     balance: u64,
 ) -> DispatchResult {
-    let id: AssetId = <Pallet<T> as ChainT<_>>::ASSET_ID;
+    let id: AssetId = <Pallet<T, I> as ChainT<_>>::ASSET_ID;
     let value: BalanceOf<T> = balance.saturated_into();
 
     if let Some(proxy_address) =
@@ -240,7 +307,9 @@ fn deposit_named<T: Config>(
     {
         match <xpallet_gateway_records::Pallet<T>>::deposit(&proxy_address, id, value) {
             Ok(()) => {
-                Pallet::<T>::deposit_event(Event::<T>::DepositedNamed(txid, prefix, who, value));
+                Pallet::<T, I>::deposit_event(Event::<T, I>::DepositedNamed(
+                    txid, prefix, who, value,
+                ));
             }
             Err(err) => {
                 error!(
@@ -255,15 +324,16 @@ fn deposit_named<T: Config>(
     Ok(())
 }
 
-pub fn remove_pending_deposit<T: Config>(
+pub fn remove_pending_deposit<T: Config<I>, I: 'static>(
     input_address: &BtcAddress,
     who: &OpReturnAccount<T::AccountId>,
 ) {
     // notice this would delete this cache
-    let records = PendingDeposits::<T>::take(input_address);
+    let records = PendingDeposits::<T, I>::take(input_address);
+    Pallet::<T, I>::sub_pending_deposit_balance(&records);
     for record in records {
         // ignore error
-        let _ = deposit_token::<T>(record.txid, who, record.balance);
+        let _ = deposit_token::<T, I>(record.txid, who, record.balance);
         info!(
             target: "runtime::bitcoin",
             "[remove_pending_deposit] Use pending info to re-deposit, who:{:?}, balance:{}, cached_tx:{:?}",
@@ -272,7 +342,7 @@ pub fn remove_pending_deposit<T: Config>(
 
         match who.clone() {
             OpReturnAccount::Evm(w) => {
-                Pallet::<T>::deposit_event(Event::<T>::PendingDepositEvmRemoved(
+                Pallet::<T, I>::deposit_event(Event::<T, I>::PendingDepositEvmRemoved(
                     w,
                     record.balance.saturated_into(),
                     record.txid,
@@ -280,7 +350,7 @@ pub fn remove_pending_deposit<T: Config>(
                 ));
             }
             OpReturnAccount::Wasm(w) => {
-                Pallet::<T>::deposit_event(Event::<T>::PendingDepositRemoved(
+                Pallet::<T, I>::deposit_event(Event::<T, I>::PendingDepositRemoved(
                     w,
                     record.balance.saturated_into(),
                     record.txid,
@@ -288,7 +358,7 @@ pub fn remove_pending_deposit<T: Config>(
                 ));
             }
             OpReturnAccount::Aptos(w) => {
-                Pallet::<T>::deposit_event(Event::<T>::PendingDepositAptosRemoved(
+                Pallet::<T, I>::deposit_event(Event::<T, I>::PendingDepositAptosRemoved(
                     w,
                     record.balance.saturated_into(),
                     record.txid,
@@ -296,7 +366,7 @@ pub fn remove_pending_deposit<T: Config>(
                 ));
             }
             OpReturnAccount::Named(w1, w2) => {
-                Pallet::<T>::deposit_event(Event::<T>::PendingDepositNamedRemoved(
+                Pallet::<T, I>::deposit_event(Event::<T, I>::PendingDepositNamedRemoved(
                     w1.clone(),
                     w2.clone(),
                     record.balance.saturated_into(),
@@ -308,12 +378,16 @@ pub fn remove_pending_deposit<T: Config>(
     }
 }
 
-fn insert_pending_deposit<T: Config>(input_addr: &Address, txid: H256, balance: u64) {
+fn insert_pending_deposit<T: Config<I>, I: 'static>(
+    input_addr: &Address,
+    txid: H256,
+    balance: u64,
+) {
     let addr_bytes = input_addr.to_string().into_bytes();
 
     let cache = BtcDepositCache { txid, balance };
 
-    PendingDeposits::<T>::mutate(&addr_bytes, |list| {
+    PendingDeposits::<T, I>::mutate(&addr_bytes, |list| {
         if !list.contains(&cache) {
             log::debug!(
                 target: "runtime::bitcoin",
@@ -323,14 +397,18 @@ fn insert_pending_deposit<T: Config>(input_addr: &Address, txid: H256, balance:
                 balance
             );
             list.push(cache);
+            Pallet::<T, I>::add_pending_deposit_balance(balance);
 
-            Pallet::<T>::deposit_event(Event::<T>::UnclaimedDeposit(txid, addr_bytes.clone()));
+            Pallet::<T, I>::deposit_event(Event::<T, I>::UnclaimedDeposit(
+                txid,
+                addr_bytes.clone(),
+            ));
         }
     });
 }
 
-fn withdraw<T: Config>(tx: Transaction) -> BtcTxResult {
-    if let Some(proposal) = WithdrawalProposal::<T>::take() {
+fn withdraw<T: Config<I>, I: 'static>(tx: Transaction) -> BtcTxResult {
+    if let Some(proposal) = WithdrawalProposal::<T, I>::take() {
         log::debug!(
             target: "runtime::bitcoin",
             "[withdraw] Withdraw tx {:?}, proposal:{:?}",
@@ -339,8 +417,12 @@ fn withdraw<T: Config>(tx: Transaction) -> BtcTxResult {
         );
         let proposal_hash = proposal.tx.hash();
         let tx_hash = tx.hash();
+        // a withdrawal tx replaced by rbf may still be packed into the block
+        let is_replaced = Pallet::<T, I>::withdrawal_fee_bump()
+            .map(|bump| bump.replaced.iter().any(|(txid, _)| *txid == tx_hash))
+            .unwrap_or(false);
 
-        if proposal_hash == tx_hash {
+        if proposal_hash == tx_hash || is_replaced {
             // Check if the transaction is normal witness
             let input = &tx.inputs()[0];
             if input.script_witness.len() != 3 {
@@ -376,19 +458,27 @@ fn withdraw<T: Config>(tx: Transaction) -> BtcTxResult {
                 }
             }
 
-            let btc_withdrawal_fee = Pallet::<T>::btc_withdrawal_fee();
+            let btc_withdrawal_fee = Pallet::<T, I>::btc_withdrawal_fee();
             // real withdraw value would reduce withdraw_fee
             total -=
                 (proposal.withdrawal_id_list.len() as u64 * btc_withdrawal_fee).saturated_into();
 
+            xpallet_gateway_records::Pallet::<T>::settle_archived_withdrawals(
+                &proposal.withdrawal_id_list,
+                tx_hash,
+            );
+
+            // give the fee not spent back to the reserve
+            settle_fee_bump::<T, I>(Some(&tx_hash));
+
             // Record trustee signature
             T::TrusteeInfoUpdate::update_trustee_sig_record(
-                Pallet::<T>::chain(),
+                Pallet::<T, I>::chain(),
                 input.script_witness[1].as_slice(),
                 total.saturated_into(),
             );
 
-            Pallet::<T>::deposit_event(Event::<T>::Withdrawn(
+            Pallet::<T, I>::deposit_event(Event::<T, I>::Withdrawn(
                 tx_hash,
                 proposal.withdrawal_id_list,
                 total,
@@ -401,9 +491,12 @@ fn withdraw<T: Config>(tx: Transaction) -> BtcTxResult {
                 tx_hash, proposal_hash, proposal.withdrawal_id_list
             );
             // re-store proposal into storage.
-            WithdrawalProposal::<T>::put(proposal);
+            WithdrawalProposal::<T, I>::put(proposal);
 
-            Pallet::<T>::deposit_event(Event::<T>::WithdrawalFatalErr(proposal_hash, tx_hash));
+            Pallet::<T, I>::deposit_event(Event::<T, I>::WithdrawalFatalErr(
+                proposal_hash,
+                tx_hash,
+            ));
             BtcTxResult::Failure
         }
     } else {
@@ -413,7 +506,7 @@ fn withdraw<T: Config>(tx: Transaction) -> BtcTxResult {
             tx.hash()
         );
         // no proposal, but find a withdraw tx, it's a fatal error in withdrawal
-        Pallet::<T>::deposit_event(Event::<T>::WithdrawalFatalErr(
+        Pallet::<T, I>::deposit_event(Event::<T, I>::WithdrawalFatalErr(
             tx.hash(),
             Default::default(),
         ));
@@ -10,7 +10,7 @@ use light_bitcoin::{chain::Transaction, primitives::H256};
 
 use crate::{types::BtcRelayedTx, Config, Error};
 
-pub fn validate_transaction<T: Config>(
+pub fn validate_transaction<T: Config<I>, I: 'static>(
     tx: &BtcRelayedTx,
     merkle_root: H256,
     prev_tx: Option<&Transaction>,
@@ -28,21 +28,21 @@ pub fn validate_transaction<T: Config>(
     let hash = tx
         .merkle_proof
         .extract_matches(&mut matches, &mut _indexes)
-        .map_err(|_| Error::<T>::BadMerkleProof)?;
+        .map_err(|_| Error::<T, I>::BadMerkleProof)?;
     if merkle_root != hash {
         error!(
             target: "runtime::bitcoin",
             "[validate_transaction] Check merkle tree proof error, merkle_root:{:?}, hash:{:?}",
             merkle_root, hash
         );
-        return Err(Error::<T>::BadMerkleProof.into());
+        return Err(Error::<T, I>::BadMerkleProof.into());
     }
     if !matches.iter().any(|h| *h == tx_hash) {
         error!(
             target: "runtime::bitcoin",
             "[validate_transaction] Tx hash should in matches of partial merkle tree"
         );
-        return Err(Error::<T>::BadMerkleProof.into());
+        return Err(Error::<T, I>::BadMerkleProof.into());
     }
 
     if let Some(prev) = prev_tx {
@@ -56,7 +56,7 @@ pub fn validate_transaction<T: Config>(
                 "[validate_transaction] Relay previous tx's hash not equal to relay tx first input, expected_id:{:?}, prev:{:?}",
                 expected_id, previous_txid
             );
-            return Err(Error::<T>::InvalidPrevTx.into());
+            return Err(Error::<T, I>::InvalidPrevTx.into());
         }
     }
     Ok(())
@@ -53,6 +53,18 @@ pub struct BtcHeaderInfo {
     pub height: u32,
 }
 
+/// A trusted header which the light client is anchored at, instead of the genesis header.
+#[derive(PartialEq, Eq, Clone, Encode, Decode, RuntimeDebug, TypeInfo)]
+#[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "std", serde(rename_all = "camelCase"))]
+pub struct BtcCheckpoint {
+    pub header: BtcHeader,
+    pub height: u32,
+    /// The time of the block at the last retarget height (not above the header), which the
+    /// first retarget after the header is computed from
+    pub retarget_timestamp: u32,
+}
+
 #[derive(PartialEq, Eq, Clone, Copy, Default, Encode, Decode, RuntimeDebug, TypeInfo)]
 #[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
 pub struct BtcHeaderIndex {
@@ -110,6 +122,125 @@ impl<AccountId> BtcWithdrawalProposal<AccountId> {
     }
 }
 
+/// The withdrawal proposal which was not finished before its deadline.
+#[derive(PartialEq, Clone, Encode, Decode, RuntimeDebug, TypeInfo)]
+#[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
+pub struct BtcExpiredProposal<AccountId, BlockNumber> {
+    pub withdrawal_id_list: Vec<u32>,
+    pub deadline: BlockNumber,
+    /// The trustees who did not sign the proposal tx.
+    pub missing_signers: Vec<AccountId>,
+}
+
+/// The way to raise the fee of a stuck withdrawal transaction.
+#[derive(PartialEq, Eq, Clone, Copy, Encode, Decode, RuntimeDebug, TypeInfo)]
+#[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
+pub enum BtcFeeBumpKind {
+    /// Replace the withdrawal tx by a tx with the same inputs and withdrawal outputs, but a
+    /// smaller change output.
+    Rbf,
+    /// Spend the change output of the withdrawal tx by a child tx paying a higher fee.
+    Cpfp,
+}
+
+/// The fee bumps applied to the current withdrawal proposal.
+#[derive(PartialEq, Clone, Encode, Decode, Default, RuntimeDebug, TypeInfo)]
+#[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
+pub struct BtcFeeBump {
+    /// The withdrawal txs replaced by rbf, with the extra fee already paid by each of them.
+    pub replaced: Vec<(H256, u64)>,
+    /// The extra fee paid by the current withdrawal tx.
+    pub rbf_fee: u64,
+    /// The cpfp child of the current withdrawal tx and its fee.
+    pub cpfp: Option<(BtcTransaction, u64)>,
+    /// Whether the cpfp child has been relayed, its fee is only spent then.
+    pub cpfp_relayed: bool,
+}
+
+impl BtcFeeBump {
+    /// The fee of the cpfp child.
+    pub fn cpfp_fee(&self) -> u64 {
+        self.cpfp.as_ref().map(|(_, fee)| *fee).unwrap_or_default()
+    }
+
+    /// The total fee charged to the fee reserve.
+    pub fn charged(&self) -> u64 {
+        self.rbf_fee.saturating_add(self.cpfp_fee())
+    }
+
+    /// The fee really spent if the withdrawal tx `txid` is the one packed into the block.
+    pub fn spent(&self, txid: &H256) -> u64 {
+        match self.replaced.iter().find(|(hash, _)| hash == txid) {
+            Some((_, fee)) => *fee,
+            None if self.cpfp_relayed => self.charged(),
+            None => self.rbf_fee,
+        }
+    }
+
+    /// The fee of the cpfp child which is still held after the withdrawal tx `txid` is packed,
+    /// until the child is relayed.
+    pub fn held(&self, txid: &H256) -> u64 {
+        if self.cpfp_relayed || self.replaced.iter().any(|(hash, _)| hash == txid) {
+            0
+        } else {
+            self.cpfp_fee()
+        }
+    }
+}
+
+/// The liabilities of the btc bridge against the btc held by the trustees, all in satoshi.
+#[derive(PartialEq, Eq, Clone, Default, Encode, Decode, RuntimeDebug, TypeInfo)]
+#[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "std", serde(rename_all = "camelCase"))]
+pub struct BtcReserveReport {
+    /// The XBTC issued to the wasm accounts, the evm accounts are included once the XBTC
+    /// balances are held by the btc ledger
+    pub wasm: u64,
+    /// The BTC issued to the evm accounts which is not accounted as XBTC
+    pub evm: u64,
+    /// The XBTC held by the proxy account of aptos
+    pub aptos: u64,
+    /// The XBTC held by the proxy accounts of the named chains, [prefix, amount]
+    pub named: Vec<(Vec<u8>, u64)>,
+    /// The unclaimed deposits
+    pub pending: u64,
+    /// The sum of all the liabilities above
+    pub total_liabilities: u64,
+    /// The value of the tracked trustee utxos
+    pub reserves: u64,
+    /// reserves / total_liabilities, in basis points
+    pub reserve_ratio: u32,
+    /// The best btc header height when the report was made
+    pub best_height: u32,
+    /// The confirmed btc header height when the report was made
+    pub confirmed_height: Option<u32>,
+}
+
+/// The share of the reserves the trustees should keep in the hot address, all in basis points.
+#[derive(PartialEq, Eq, Clone, Copy, Default, Encode, Decode, RuntimeDebug, TypeInfo)]
+#[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "std", serde(rename_all = "camelCase"))]
+pub struct BtcRebalanceConfig {
+    /// The target hot balance / reserves
+    pub target_hot_ratio: u32,
+    /// How far the hot balance / reserves may exceed the target before a rebalance is proposed
+    pub threshold: u32,
+}
+
+/// The proposal to move the hot balance above the target to the cold address.
+#[derive(PartialEq, Eq, Clone, Default, Encode, Decode, RuntimeDebug, TypeInfo)]
+#[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "std", serde(rename_all = "camelCase"))]
+pub struct BtcRebalanceProposal<BlockNumber> {
+    /// The hot balance when the proposal was opened
+    pub hot_balance: u64,
+    /// The reserves when the proposal was opened
+    pub reserves: u64,
+    /// The amount to move to the cold address
+    pub amount: u64,
+    pub opened_at: BlockNumber,
+}
+
 #[derive(PartialEq, Clone, Copy, Eq, Encode, Decode, RuntimeDebug, TypeInfo)]
 #[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
 pub enum VoteResult {
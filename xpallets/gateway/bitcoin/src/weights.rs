@@ -34,13 +34,24 @@ pub trait WeightInfo {
     fn push_header() -> Weight;
     fn push_transaction() -> Weight;
     fn create_taproot_withdraw_tx() -> Weight;
-    fn set_best_index() -> Weight;
+    fn rollback_headers(n: u32) -> Weight;
     fn set_confirmed_index() -> Weight;
     fn remove_pending() -> Weight;
     fn remove_proposal() -> Weight;
     fn set_btc_withdrawal_fee() -> Weight;
     fn set_btc_deposit_limit() -> Weight;
     fn set_coming_bot() -> Weight;
+    fn bump_withdrawal_fee() -> Weight;
+    fn set_withdrawal_fee_reserve() -> Weight;
+    fn sign_withdrawal_proposal() -> Weight;
+    fn set_withdrawal_proposal_timeout() -> Weight;
+    fn expire_withdrawal_proposal() -> Weight;
+    fn set_reserve_check() -> Weight;
+    fn set_trustee_utxos(n: u32) -> Weight;
+    fn check_reserves() -> Weight;
+    fn set_checkpoint(n: u32) -> Weight;
+    fn set_rebalance_config() -> Weight;
+    fn check_rebalance() -> Weight;
 }
 
 /// Weights for xpallet_gateway_bitcoin using the Substrate node and recommended hardware.
@@ -61,8 +72,13 @@ impl<T: frame_system::Config> WeightInfo for SubstrateWeight<T> {
             .saturating_add(T::DbWeight::get().reads(14 as Weight))
             .saturating_add(T::DbWeight::get().writes(2 as Weight))
     }
-    fn set_best_index() -> Weight {
-        (3_180_000 as Weight).saturating_add(T::DbWeight::get().writes(1 as Weight))
+    fn rollback_headers(n: u32) -> Weight {
+        (18_420_000 as Weight)
+            .saturating_add((6_315_000 as Weight).saturating_mul(n as Weight))
+            .saturating_add(T::DbWeight::get().reads(4 as Weight))
+            .saturating_add(T::DbWeight::get().reads((4 as Weight).saturating_mul(n as Weight)))
+            .saturating_add(T::DbWeight::get().writes(2 as Weight))
+            .saturating_add(T::DbWeight::get().writes((4 as Weight).saturating_mul(n as Weight)))
     }
     fn set_confirmed_index() -> Weight {
         (3_334_000 as Weight).saturating_add(T::DbWeight::get().writes(1 as Weight))
@@ -86,6 +102,60 @@ impl<T: frame_system::Config> WeightInfo for SubstrateWeight<T> {
     fn set_coming_bot() -> Weight {
         (2_887_000 as Weight).saturating_add(T::DbWeight::get().writes(1 as Weight))
     }
+    fn bump_withdrawal_fee() -> Weight {
+        (118_420_000 as Weight)
+            .saturating_add(T::DbWeight::get().reads(16 as Weight))
+            .saturating_add(T::DbWeight::get().writes(5 as Weight))
+    }
+    fn set_withdrawal_fee_reserve() -> Weight {
+        (3_104_000 as Weight)
+            .saturating_add(T::DbWeight::get().reads(1 as Weight))
+            .saturating_add(T::DbWeight::get().writes(1 as Weight))
+    }
+    fn sign_withdrawal_proposal() -> Weight {
+        (24_871_000 as Weight)
+            .saturating_add(T::DbWeight::get().reads(4 as Weight))
+            .saturating_add(T::DbWeight::get().writes(1 as Weight))
+    }
+    fn set_withdrawal_proposal_timeout() -> Weight {
+        (2_498_000 as Weight).saturating_add(T::DbWeight::get().writes(1 as Weight))
+    }
+    fn expire_withdrawal_proposal() -> Weight {
+        (74_116_000 as Weight)
+            .saturating_add(T::DbWeight::get().reads(9 as Weight))
+            .saturating_add(T::DbWeight::get().writes(7 as Weight))
+    }
+    fn set_reserve_check() -> Weight {
+        (3_012_000 as Weight).saturating_add(T::DbWeight::get().writes(2 as Weight))
+    }
+    fn set_trustee_utxos(n: u32) -> Weight {
+        (4_870_000 as Weight)
+            .saturating_add((1_742_000 as Weight).saturating_mul(n as Weight))
+            .saturating_add(T::DbWeight::get().reads((1 as Weight).saturating_mul(n as Weight)))
+            .saturating_add(T::DbWeight::get().writes(1 as Weight))
+            .saturating_add(T::DbWeight::get().writes((1 as Weight).saturating_mul(n as Weight)))
+    }
+    fn check_reserves() -> Weight {
+        (58_437_000 as Weight)
+            .saturating_add(T::DbWeight::get().reads(9 as Weight))
+            .saturating_add(T::DbWeight::get().writes(1 as Weight))
+    }
+    fn set_checkpoint(n: u32) -> Weight {
+        (21_560_000 as Weight)
+            .saturating_add((2_904_000 as Weight).saturating_mul(n as Weight))
+            .saturating_add(T::DbWeight::get().reads(5 as Weight))
+            .saturating_add(T::DbWeight::get().reads((1 as Weight).saturating_mul(n as Weight)))
+            .saturating_add(T::DbWeight::get().writes(7 as Weight))
+            .saturating_add(T::DbWeight::get().writes((1 as Weight).saturating_mul(n as Weight)))
+    }
+    fn set_rebalance_config() -> Weight {
+        (3_245_000 as Weight).saturating_add(T::DbWeight::get().writes(2 as Weight))
+    }
+    fn check_rebalance() -> Weight {
+        (12_806_000 as Weight)
+            .saturating_add(T::DbWeight::get().reads(5 as Weight))
+            .saturating_add(T::DbWeight::get().writes(1 as Weight))
+    }
 }
 
 // For backwards compatibility and tests
@@ -105,8 +175,13 @@ impl WeightInfo for () {
             .saturating_add(RocksDbWeight::get().reads(14 as Weight))
             .saturating_add(RocksDbWeight::get().writes(2 as Weight))
     }
-    fn set_best_index() -> Weight {
-        (3_180_000 as Weight).saturating_add(RocksDbWeight::get().writes(1 as Weight))
+    fn rollback_headers(n: u32) -> Weight {
+        (18_420_000 as Weight)
+            .saturating_add((6_315_000 as Weight).saturating_mul(n as Weight))
+            .saturating_add(RocksDbWeight::get().reads(4 as Weight))
+            .saturating_add(RocksDbWeight::get().reads((4 as Weight).saturating_mul(n as Weight)))
+            .saturating_add(RocksDbWeight::get().writes(2 as Weight))
+            .saturating_add(RocksDbWeight::get().writes((4 as Weight).saturating_mul(n as Weight)))
     }
     fn set_confirmed_index() -> Weight {
         (3_334_000 as Weight).saturating_add(RocksDbWeight::get().writes(1 as Weight))
@@ -130,4 +205,58 @@ impl WeightInfo for () {
     fn set_coming_bot() -> Weight {
         (2_887_000 as Weight).saturating_add(RocksDbWeight::get().writes(1 as Weight))
     }
+    fn bump_withdrawal_fee() -> Weight {
+        (118_420_000 as Weight)
+            .saturating_add(RocksDbWeight::get().reads(16 as Weight))
+            .saturating_add(RocksDbWeight::get().writes(5 as Weight))
+    }
+    fn set_withdrawal_fee_reserve() -> Weight {
+        (3_104_000 as Weight)
+            .saturating_add(RocksDbWeight::get().reads(1 as Weight))
+            .saturating_add(RocksDbWeight::get().writes(1 as Weight))
+    }
+    fn sign_withdrawal_proposal() -> Weight {
+        (24_871_000 as Weight)
+            .saturating_add(RocksDbWeight::get().reads(4 as Weight))
+            .saturating_add(RocksDbWeight::get().writes(1 as Weight))
+    }
+    fn set_withdrawal_proposal_timeout() -> Weight {
+        (2_498_000 as Weight).saturating_add(RocksDbWeight::get().writes(1 as Weight))
+    }
+    fn expire_withdrawal_proposal() -> Weight {
+        (74_116_000 as Weight)
+            .saturating_add(RocksDbWeight::get().reads(9 as Weight))
+            .saturating_add(RocksDbWeight::get().writes(7 as Weight))
+    }
+    fn set_reserve_check() -> Weight {
+        (3_012_000 as Weight).saturating_add(RocksDbWeight::get().writes(2 as Weight))
+    }
+    fn set_trustee_utxos(n: u32) -> Weight {
+        (4_870_000 as Weight)
+            .saturating_add((1_742_000 as Weight).saturating_mul(n as Weight))
+            .saturating_add(RocksDbWeight::get().reads((1 as Weight).saturating_mul(n as Weight)))
+            .saturating_add(RocksDbWeight::get().writes(1 as Weight))
+            .saturating_add(RocksDbWeight::get().writes((1 as Weight).saturating_mul(n as Weight)))
+    }
+    fn check_reserves() -> Weight {
+        (58_437_000 as Weight)
+            .saturating_add(RocksDbWeight::get().reads(9 as Weight))
+            .saturating_add(RocksDbWeight::get().writes(1 as Weight))
+    }
+    fn set_checkpoint(n: u32) -> Weight {
+        (21_560_000 as Weight)
+            .saturating_add((2_904_000 as Weight).saturating_mul(n as Weight))
+            .saturating_add(RocksDbWeight::get().reads(5 as Weight))
+            .saturating_add(RocksDbWeight::get().reads((1 as Weight).saturating_mul(n as Weight)))
+            .saturating_add(RocksDbWeight::get().writes(7 as Weight))
+            .saturating_add(RocksDbWeight::get().writes((1 as Weight).saturating_mul(n as Weight)))
+    }
+    fn set_rebalance_config() -> Weight {
+        (3_245_000 as Weight).saturating_add(RocksDbWeight::get().writes(2 as Weight))
+    }
+    fn check_rebalance() -> Weight {
+        (12_806_000 as Weight)
+            .saturating_add(RocksDbWeight::get().reads(5 as Weight))
+            .saturating_add(RocksDbWeight::get().writes(1 as Weight))
+    }
 }
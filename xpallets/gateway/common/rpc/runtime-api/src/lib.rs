@@ -16,11 +16,15 @@ pub use xp_runtime::Memo;
 pub use xpallet_assets::WithdrawalLimit;
 pub use xpallet_gateway_common::{
     trustees,
-    types::{GenericTrusteeIntentionProps, GenericTrusteeSessionInfo, ScriptInfo},
+    types::{
+        GenericTrusteeIntentionProps, GenericTrusteeSessionInfo, ScriptInfo, TrusteeLiveness,
+        WithdrawalAllowlist,
+    },
 };
 pub use xpallet_gateway_records::{Withdrawal, WithdrawalRecordId, WithdrawalState};
 sp_api::decl_runtime_apis! {
     /// The API to query account nonce (aka transaction index).
+    #[api_version(2)]
     pub trait XGatewayCommonApi<AccountId, Balance, BlockNumber>
     where
         AccountId: codec::Codec,
@@ -45,6 +49,12 @@ sp_api::decl_runtime_apis! {
 
         fn verify_withdrawal(asset_id: AssetId, value: Balance, addr: AddrStr, memo: Memo) -> Result<(), DispatchError>;
 
+        /// Verify the withdrawal of an account, including its withdrawal allowlist.
+        fn verify_account_withdrawal(who: AccountId, asset_id: AssetId, value: Balance, addr: AddrStr, memo: Memo) -> Result<(), DispatchError>;
+
+        /// Get the withdrawal allowlist of an account, including the pending changes.
+        fn withdrawal_allowlist(who: AccountId) -> WithdrawalAllowlist<BlockNumber>;
+
         /// Get all trustee multisig.
         fn trustee_multisigs() -> BTreeMap<Chain, AccountId>;
 
@@ -53,5 +63,8 @@ sp_api::decl_runtime_apis! {
         fn trustee_session_info(chain: Chain, session_number: i32) -> Option<GenericTrusteeSessionInfo<AccountId, BlockNumber>>;
 
         fn generate_trustee_session_info(chain: Chain, Vec<AccountId>) -> Result<(GenericTrusteeSessionInfo<AccountId, BlockNumber>, ScriptInfo<AccountId>), DispatchError>;
+
+        /// Get the liveness of the trustees of a session, `None` means the session in progress.
+        fn trustee_liveness(chain: Chain, session_number: Option<u32>) -> Vec<(AccountId, TrusteeLiveness)>;
     }
 }
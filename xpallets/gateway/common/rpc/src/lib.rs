@@ -12,7 +12,7 @@ use codec::Codec;
 use jsonrpc_derive::rpc;
 use serde::{Deserialize, Serialize};
 
-use sp_api::ProvideRuntimeApi;
+use sp_api::{ApiExt, ProvideRuntimeApi};
 use sp_blockchain::HeaderBackend;
 use sp_runtime::{generic::BlockId, traits::Block as BlockT};
 
@@ -26,8 +26,8 @@ use xpallet_gateway_common_rpc_runtime_api::trustees::bitcoin::{
 };
 use xpallet_gateway_common_rpc_runtime_api::{
     AssetId, Chain, GenericTrusteeIntentionProps, GenericTrusteeSessionInfo, ScriptInfo,
-    Withdrawal, WithdrawalLimit, WithdrawalRecordId, WithdrawalState,
-    XGatewayCommonApi as XGatewayCommonRuntimeApi,
+    TrusteeLiveness, Withdrawal, WithdrawalAllowlist, WithdrawalLimit, WithdrawalRecordId,
+    WithdrawalState, XGatewayCommonApi as XGatewayCommonRuntimeApi,
 };
 
 /// XGatewayCommon RPC methods.
@@ -79,6 +79,26 @@ where
         at: Option<BlockHash>,
     ) -> Result<bool>;
 
+    /// Same as `xgatewaycommon_verifyWithdrawal`, but also checks the withdrawal allowlist of the account.
+    #[rpc(name = "xgatewaycommon_verifyAccountWithdrawal")]
+    fn verify_account_withdrawal(
+        &self,
+        who: AccountId,
+        asset_id: AssetId,
+        value: u64,
+        addr: String,
+        memo: String,
+        at: Option<BlockHash>,
+    ) -> Result<bool>;
+
+    /// Return the withdrawal allowlist of an account, the addresses not active yet and the pending disabling are included.
+    #[rpc(name = "xgatewaycommon_withdrawalAllowlist")]
+    fn withdrawal_allowlist(
+        &self,
+        who: AccountId,
+        at: Option<BlockHash>,
+    ) -> Result<RpcWithdrawalAllowlist<BlockNumber>>;
+
     /// Return the trustee multisig address for all chain.
     #[rpc(name = "xgatewaycommon_trusteeMultisigs")]
     fn multisigs(&self, at: Option<BlockHash>) -> Result<BTreeMap<Chain, AccountId>>;
@@ -106,6 +126,14 @@ where
         candidates: Vec<AccountId>,
         at: Option<BlockHash>,
     ) -> Result<BtcTrusteeSessionInfo<AccountId, BlockNumber>>;
+
+    /// Return the liveness of the bitcoin trustees for a session, the session in progress if `session_number` is not given.
+    #[rpc(name = "xgatewaycommon_bitcoinTrusteeLiveness")]
+    fn btc_trustee_liveness(
+        &self,
+        session_number: Option<u32>,
+        at: Option<BlockHash>,
+    ) -> Result<Vec<(AccountId, TrusteeLiveness)>>;
 }
 
 /// A struct that implements the [`XStakingApi`].
@@ -303,11 +331,7 @@ where
         at: Option<<Block as BlockT>::Hash>,
     ) -> Result<bool> {
         let value: Balance = Balance::from(value);
-        let addr = if let Some(stirp_addr) = addr.strip_prefix("0x") {
-            hex::decode(&stirp_addr).map_err(hex_decode_error_into_rpc_err)?
-        } else {
-            hex::decode(&addr).unwrap_or_else(|_| addr.into_bytes())
-        };
+        let addr = decode_withdrawal_addr(addr)?;
         let memo = memo.into_bytes();
 
         let api = self.client.runtime_api();
@@ -320,6 +344,53 @@ where
             .is_ok())
     }
 
+    fn verify_account_withdrawal(
+        &self,
+        who: AccountId,
+        asset_id: AssetId,
+        value: u64,
+        addr: String,
+        memo: String,
+        at: Option<<Block as BlockT>::Hash>,
+    ) -> Result<bool> {
+        let value: Balance = Balance::from(value);
+        let addr = decode_withdrawal_addr(addr)?;
+        let memo = memo.into_bytes();
+
+        let api = self.client.runtime_api();
+        let at = BlockId::hash(at.unwrap_or_else(||
+            // If the block hash is not supplied assume the best block.
+            self.client.info().best_hash));
+        let version = api
+            .api_version::<dyn XGatewayCommonRuntimeApi<Block, AccountId, Balance, BlockNumber>>(
+                &at,
+            )
+            .map_err(runtime_error_into_rpc_err)?;
+        let result = match version {
+            Some(version) if version >= 2 => {
+                api.verify_account_withdrawal(&at, who, asset_id, value, addr, memo.into())
+            }
+            // The runtime of the block has no withdrawal allowlist.
+            _ => api.verify_withdrawal(&at, asset_id, value, addr, memo.into()),
+        };
+        Ok(result.map_err(runtime_error_into_rpc_err)?.is_ok())
+    }
+
+    fn withdrawal_allowlist(
+        &self,
+        who: AccountId,
+        at: Option<<Block as BlockT>::Hash>,
+    ) -> Result<RpcWithdrawalAllowlist<BlockNumber>> {
+        let api = self.client.runtime_api();
+        let at = BlockId::hash(at.unwrap_or_else(|| self.client.info().best_hash));
+
+        let result = api
+            .withdrawal_allowlist(&at, who)
+            .map_err(runtime_error_into_rpc_err)?;
+
+        Ok(result.into())
+    }
+
     fn multisigs(&self, at: Option<<Block as BlockT>::Hash>) -> Result<BTreeMap<Chain, AccountId>> {
         let api = self.client.runtime_api();
         let at = BlockId::hash(at.unwrap_or_else(||
@@ -359,6 +430,30 @@ where
         let info = self.generate_generic_trustee_session_info(Chain::Bitcoin, candidates, at)?;
         BtcTrusteeSessionInfo::<_, _>::try_from(info.0).map_err(trustee_decode_error_into_rpc_err)
     }
+
+    fn btc_trustee_liveness(
+        &self,
+        session_number: Option<u32>,
+        at: Option<<Block as BlockT>::Hash>,
+    ) -> Result<Vec<(AccountId, TrusteeLiveness)>> {
+        let api = self.client.runtime_api();
+        let at = BlockId::hash(at.unwrap_or_else(|| self.client.info().best_hash));
+
+        let result = api
+            .trustee_liveness(&at, Chain::Bitcoin, session_number)
+            .map_err(runtime_error_into_rpc_err)?;
+
+        Ok(result)
+    }
+}
+
+/// Decode the withdrawal address, which is either in hex or the plain string.
+fn decode_withdrawal_addr(addr: String) -> Result<Vec<u8>> {
+    if let Some(stirp_addr) = addr.strip_prefix("0x") {
+        hex::decode(&stirp_addr).map_err(hex_decode_error_into_rpc_err)
+    } else {
+        Ok(hex::decode(&addr).unwrap_or_else(|_| addr.into_bytes()))
+    }
 }
 
 #[derive(PartialEq, Eq, Clone, Serialize, Deserialize)]
@@ -390,3 +485,32 @@ impl<AccountId, Balance: Display + FromStr, BlockNumber>
         }
     }
 }
+
+#[derive(PartialEq, Eq, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RpcWithdrawalAllowlist<BlockNumber> {
+    pub enabled: bool,
+    pub disable_at: Option<BlockNumber>,
+    pub active: Vec<(Chain, String)>,
+    pub pending: Vec<(Chain, String, BlockNumber)>,
+}
+
+impl<BlockNumber> From<WithdrawalAllowlist<BlockNumber>> for RpcWithdrawalAllowlist<BlockNumber> {
+    fn from(allowlist: WithdrawalAllowlist<BlockNumber>) -> Self {
+        let to_string = |addr: Vec<u8>| String::from_utf8_lossy(&addr).into_owned();
+        Self {
+            enabled: allowlist.enabled,
+            disable_at: allowlist.disable_at,
+            active: allowlist
+                .active
+                .into_iter()
+                .map(|(chain, addr)| (chain, to_string(addr)))
+                .collect(),
+            pending: allowlist
+                .pending
+                .into_iter()
+                .map(|(chain, addr, active_at)| (chain, to_string(addr), active_at))
+                .collect(),
+        }
+    }
+}
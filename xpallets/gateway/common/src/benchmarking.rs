@@ -7,7 +7,7 @@ use frame_system::RawOrigin;
 use sp_core::crypto::AccountId32;
 #[cfg(feature = "runtime-benchmarks")]
 use sp_runtime::traits::CheckedDiv;
-use sp_runtime::traits::StaticLookup;
+use sp_runtime::{traits::StaticLookup, Percent};
 use sp_std::prelude::*;
 
 use xp_assets_registrar::Chain;
@@ -177,6 +177,7 @@ benchmarks! {
         let config = TrusteeInfoConfig {
             min_trustee_count: 5,
             max_trustee_count: 15,
+            recovery: None,
         };
     }: _(RawOrigin::Root, Chain::Bitcoin, config.clone())
     verify {
@@ -263,6 +264,23 @@ benchmarks! {
         assert!(Pallet::<T>::trustee_intention_props_of(caller, Chain::Bitcoin).is_some());
     }
 
+    set_trustee_bond_config {
+        let config = TrusteeBondConfig {
+            bond: 100u32.into(),
+            unbonding_period: 10u32.into(),
+        };
+    }: _(RawOrigin::Root, Chain::Bitcoin, config.clone())
+    verify {
+        assert_eq!(Pallet::<T>::trustee_bond_config_of(Chain::Bitcoin), config);
+    }
+
+    set_trustee_liveness_threshold {
+        let threshold = Percent::from_percent(50);
+    }: _(RawOrigin::Root, Chain::Bitcoin, threshold)
+    verify {
+        assert_eq!(Pallet::<T>::trustee_liveness_threshold(Chain::Bitcoin), threshold);
+    }
+
     force_set_referral_binding {
         let who: T::AccountId = alice::<T>();
         let who_lookup: <T::Lookup as StaticLookup>::Source = T::Lookup::unlookup(who.clone());
@@ -270,6 +288,48 @@ benchmarks! {
     verify {
         assert_eq!(Pallet::<T>::referral_binding_of(&who, Chain::Bitcoin), Some(who));
     }
+    enable_withdrawal_allowlist {
+        let caller: T::AccountId = alice::<T>();
+    }: _(RawOrigin::Signed(caller.clone()))
+    verify {
+        assert!(Pallet::<T>::withdrawal_allowlist_enabled(&caller));
+    }
+
+    disable_withdrawal_allowlist {
+        let caller: T::AccountId = alice::<T>();
+        Pallet::<T>::enable_withdrawal_allowlist(RawOrigin::Signed(caller.clone()).into())?;
+    }: _(RawOrigin::Signed(caller.clone()))
+    verify {
+        assert!(Pallet::<T>::withdrawal_allowlist_disable_at(&caller).is_some());
+    }
+
+    add_withdrawal_allowlist_addr {
+        let caller: T::AccountId = alice::<T>();
+        let addr = b"3PgYgJA6h5xPEc3HbnZrUZWkpRxuCZVyEP".to_vec();
+    }: _(RawOrigin::Signed(caller.clone()), Chain::Bitcoin, addr.clone())
+    verify {
+        assert!(Pallet::<T>::withdrawal_allowlist_of(&caller, (Chain::Bitcoin, addr)).is_some());
+    }
+
+    remove_withdrawal_allowlist_addr {
+        let caller: T::AccountId = alice::<T>();
+        let addr = b"3PgYgJA6h5xPEc3HbnZrUZWkpRxuCZVyEP".to_vec();
+        Pallet::<T>::add_withdrawal_allowlist_addr(
+            RawOrigin::Signed(caller.clone()).into(),
+            Chain::Bitcoin,
+            addr.clone(),
+        )?;
+    }: _(RawOrigin::Signed(caller.clone()), Chain::Bitcoin, addr.clone())
+    verify {
+        assert!(Pallet::<T>::withdrawal_allowlist_of(&caller, (Chain::Bitcoin, addr)).is_none());
+    }
+
+    set_withdrawal_allowlist_delay {
+        let delay: T::BlockNumber = 100u32.into();
+    }: _(RawOrigin::Root, delay)
+    verify {
+        assert_eq!(Pallet::<T>::withdrawal_allowlist_delay(), delay);
+    }
 }
 
 #[cfg(test)]
@@ -292,6 +352,13 @@ mod tests {
             assert_ok!(Pallet::<Test>::test_benchmark_force_trustee_election());
             assert_ok!(Pallet::<Test>::test_benchmark_force_update_trustee());
             assert_ok!(Pallet::<Test>::test_benchmark_force_set_referral_binding());
+            assert_ok!(Pallet::<Test>::test_benchmark_set_trustee_bond_config());
+            assert_ok!(Pallet::<Test>::test_benchmark_set_trustee_liveness_threshold());
+            assert_ok!(Pallet::<Test>::test_benchmark_enable_withdrawal_allowlist());
+            assert_ok!(Pallet::<Test>::test_benchmark_disable_withdrawal_allowlist());
+            assert_ok!(Pallet::<Test>::test_benchmark_add_withdrawal_allowlist_addr());
+            assert_ok!(Pallet::<Test>::test_benchmark_remove_withdrawal_allowlist_addr());
+            assert_ok!(Pallet::<Test>::test_benchmark_set_withdrawal_allowlist_delay());
         });
     }
 }
@@ -117,6 +117,10 @@ impl<T: Config, Address: Into<Vec<u8>>> AddressBinding<T::AccountId, Address> fo
         DstChainProxyAddress::<T>::get(&dst_chain)
     }
 
+    fn dst_chain_proxy_addresses() -> Vec<(DstChain, T::AccountId)> {
+        DstChainProxyAddress::<T>::iter().collect()
+    }
+
     fn address(chain: Chain, address: Address) -> Option<OpReturnAccount<T::AccountId>> {
         let addr_bytes: ChainAddress = address.into();
         let default_dst_chain = DefaultDstChain::<T>::get(&addr_bytes)?;
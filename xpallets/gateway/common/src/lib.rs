@@ -29,13 +29,16 @@ use frame_support::{
     dispatch::{DispatchError, DispatchResult},
     ensure,
     log::{error, info},
-    traits::{ChangeMembers, Currency, ExistenceRequirement, Get},
+    traits::{ChangeMembers, Currency, ExistenceRequirement, Get, Imbalance, ReservableCurrency},
+    PalletId,
 };
 use frame_system::{ensure_root, ensure_signed, pallet_prelude::OriginFor};
 
 use sp_runtime::{
-    traits::{CheckedDiv, Saturating, StaticLookup, UniqueSaturatedInto, Zero},
-    SaturatedConversion,
+    traits::{
+        AccountIdConversion, CheckedDiv, Saturating, StaticLookup, UniqueSaturatedInto, Zero,
+    },
+    Percent, SaturatedConversion,
 };
 use sp_std::{collections::btree_map::BTreeMap, convert::TryFrom, prelude::*};
 
@@ -55,7 +58,8 @@ use self::{
     trustees::bitcoin::BtcTrusteeAddrInfo,
     types::{
         GenericTrusteeIntentionProps, GenericTrusteeSessionInfo, RewardInfo, ScriptInfo,
-        TrusteeInfoConfig, TrusteeIntentionProps, TrusteeSessionInfo,
+        TrusteeBond, TrusteeBondConfig, TrusteeFault, TrusteeInfoConfig, TrusteeIntentionProps,
+        TrusteeLiveness, TrusteeSessionInfo, WithdrawalAllowlist,
     },
 };
 
@@ -66,8 +70,12 @@ pub use weights::WeightInfo;
 pub mod pallet {
     use super::*;
     use frame_support::{pallet_prelude::*, transactional};
+    use frame_system::pallet_prelude::BlockNumberFor;
     use xp_gateway_common::DstChainConfig;
 
+    /// The current storage version.
+    const STORAGE_VERSION: StorageVersion = StorageVersion::new(3);
+
     #[pallet::config]
     pub trait Config:
         frame_system::Config + pallet_elections_phragmen::Config + xpallet_gateway_records::Config
@@ -109,15 +117,43 @@ pub mod pallet {
         /// Get btc withdrawal proposal.
         type BitcoinWithdrawalProposal: ProposalProvider;
 
+        /// The insurance pot receiving the slashed trustee bonds.
+        #[pallet::constant]
+        type InsurancePalletId: Get<PalletId>;
+
         /// Weight information for extrinsics in this pallet.
         type WeightInfo: WeightInfo;
     }
 
     #[pallet::pallet]
     #[pallet::generate_store(pub(super) trait Store)]
+    #[pallet::storage_version(STORAGE_VERSION)]
     #[pallet::without_storage_info]
     pub struct Pallet<T>(PhantomData<T>);
 
+    #[pallet::hooks]
+    impl<T: Config> Hooks<BlockNumberFor<T>> for Pallet<T> {
+        fn on_runtime_upgrade() -> Weight {
+            let version = StorageVersion::get::<Pallet<T>>();
+            if version >= STORAGE_VERSION {
+                return T::DbWeight::get().reads(1);
+            }
+
+            let mut weight = T::DbWeight::get().reads_writes(1, 1);
+            if version < 1 {
+                weight = weight.saturating_add(migrations::recovery::apply::<T>());
+            }
+            if version < 2 {
+                weight = weight.saturating_add(migrations::bond::apply::<T>());
+            }
+            if version < 3 {
+                weight = weight.saturating_add(migrations::script_info::apply::<T>());
+            }
+            STORAGE_VERSION.put::<Pallet<T>>();
+            weight
+        }
+    }
+
     #[pallet::call]
     impl<T: Config> Pallet<T> {
         /// Create a withdrawal.
@@ -141,15 +177,16 @@ pub mod pallet {
                 xpallet_assets::Pallet::<T>::can_do(&asset_id, AssetRestrictions::WITHDRAW),
                 xpallet_assets::Error::<T>::ActionNotAllowed,
             );
-            Self::verify_withdrawal(asset_id, value, &addr, &ext)?;
+            Self::verify_account_withdrawal(&who, asset_id, value, &addr, &ext)?;
 
             xpallet_gateway_records::Pallet::<T>::withdraw(&who, asset_id, value, addr, ext)?;
             Ok(())
         }
 
-        /// Cancel the withdrawal by the applicant.
+        /// Cancel the withdrawal by the applicant, or by the guardian of the applicant if the
+        /// withdrawal is delayed.
         ///
-        /// WithdrawalRecord State: `Applying` ==> `NormalCancel`
+        /// WithdrawalRecord State: `Applying`|`Delayed` ==> `NormalCancel`
         #[pallet::weight(<T as Config>::WeightInfo::cancel_withdrawal())]
         #[transactional]
         pub fn cancel_withdrawal(origin: OriginFor<T>, id: WithdrawalRecordId) -> DispatchResult {
@@ -180,7 +217,11 @@ pub mod pallet {
         /// The disadvantage is that the update of the hot and cold public keys requires the
         /// participation of the admin account and the user cannot update the hot and cold public
         /// keys at will.
+        ///
+        /// The bond configured in `TrusteeBondConfigOf` is reserved from the caller, and it stays
+        /// reserved until the caller unbonds it after leaving the trustee.
         #[pallet::weight(< T as Config >::WeightInfo::setup_trustee())]
+        #[transactional]
         pub fn setup_trustee(
             origin: OriginFor<T>,
             proxy_account: Option<T::AccountId>,
@@ -203,6 +244,7 @@ pub mod pallet {
                 Error::<T>::ExistCurrentTrustee
             );
 
+            Self::bond_trustee_impl(&who, chain)?;
             Self::setup_trustee_impl(who, proxy_account, chain, about, hot_entity, cold_entity)
         }
 
@@ -537,6 +579,255 @@ pub mod pallet {
             });
             Ok(())
         }
+
+        /// Set the bond required from the trustee candidates.
+        ///
+        /// This is called by the council or root.
+        #[pallet::weight(< T as Config >::WeightInfo::set_trustee_bond_config())]
+        pub fn set_trustee_bond_config(
+            origin: OriginFor<T>,
+            chain: Chain,
+            config: TrusteeBondConfig<BalanceOf<T>, T::BlockNumber>,
+        ) -> DispatchResult {
+            T::CouncilOrigin::try_origin(origin)
+                .map(|_| ())
+                .or_else(ensure_root)?;
+
+            TrusteeBondConfigOf::<T>::insert(chain, config);
+            Ok(())
+        }
+
+        /// Top up the trustee bond to the required amount.
+        ///
+        /// It also cancels the unbonding of the bond, so that the trustee could be elected again.
+        #[pallet::weight(< T as Config >::WeightInfo::bond_trustee())]
+        #[transactional]
+        pub fn bond_trustee(origin: OriginFor<T>, chain: Chain) -> DispatchResult {
+            let who = ensure_signed(origin)?;
+
+            ensure!(
+                TrusteeIntentionPropertiesOf::<T>::contains_key(&who, chain),
+                Error::<T>::NotRegistered
+            );
+
+            Self::bond_trustee_impl(&who, chain)
+        }
+
+        /// Start unbonding the trustee bond.
+        ///
+        /// The bond could be withdrawn after the unbonding period, and the trustee in unbonding
+        /// will not be elected.
+        #[pallet::weight(< T as Config >::WeightInfo::unbond_trustee())]
+        pub fn unbond_trustee(origin: OriginFor<T>, chain: Chain) -> DispatchResult {
+            let who = ensure_signed(origin)?;
+
+            ensure!(
+                Self::ensure_not_current_trustee(&who) && !Self::trustee_transition_status(chain),
+                Error::<T>::ExistCurrentTrustee
+            );
+
+            let mut bond = Self::trustee_bond_of(&who, chain).ok_or(Error::<T>::NoTrusteeBond)?;
+            ensure!(
+                bond.unlocking_at.is_none(),
+                Error::<T>::TrusteeBondUnbonding
+            );
+
+            let unlocking_at = frame_system::Pallet::<T>::block_number()
+                .saturating_add(Self::trustee_bond_config_of(chain).unbonding_period);
+            bond.unlocking_at = Some(unlocking_at);
+            TrusteeBondOf::<T>::insert(&who, chain, bond.clone());
+
+            Self::deposit_event(Event::<T>::TrusteeUnbonded(
+                who,
+                chain,
+                bond.amount,
+                unlocking_at,
+            ));
+            Ok(())
+        }
+
+        /// Withdraw the trustee bond after the unbonding period.
+        #[pallet::weight(< T as Config >::WeightInfo::withdraw_trustee_bond())]
+        pub fn withdraw_trustee_bond(origin: OriginFor<T>, chain: Chain) -> DispatchResult {
+            let who = ensure_signed(origin)?;
+
+            let bond = Self::trustee_bond_of(&who, chain).ok_or(Error::<T>::NoTrusteeBond)?;
+            let now = frame_system::Pallet::<T>::block_number();
+            ensure!(
+                matches!(bond.unlocking_at, Some(unlocking_at) if unlocking_at <= now),
+                Error::<T>::TrusteeBondLocked
+            );
+
+            <T as xpallet_assets::Config>::Currency::unreserve(&who, bond.amount);
+            TrusteeBondOf::<T>::remove(&who, chain);
+
+            Self::deposit_event(Event::<T>::TrusteeBondWithdrawn(who, chain, bond.amount));
+            Ok(())
+        }
+
+        /// Slash the bond of a trustee for a provable fault.
+        ///
+        /// The slashed amount is moved into the insurance pot. The bond in unbonding could still
+        /// be slashed.
+        ///
+        /// This is called by the council or root.
+        #[pallet::weight(< T as Config >::WeightInfo::slash_trustee())]
+        #[transactional]
+        pub fn slash_trustee(
+            origin: OriginFor<T>,
+            chain: Chain,
+            who: <T::Lookup as StaticLookup>::Source,
+            #[pallet::compact] value: BalanceOf<T>,
+            fault: TrusteeFault,
+        ) -> DispatchResult {
+            T::CouncilOrigin::try_origin(origin)
+                .map(|_| ())
+                .or_else(ensure_root)?;
+            let who = T::Lookup::lookup(who)?;
+
+            Self::slash_trustee_impl(&who, chain, value, fault)
+        }
+
+        /// Set the liveness threshold of the trustees.
+        ///
+        /// The current trustees whose liveness score is below the threshold are excluded from
+        /// the next trustee election. Zero means no trustee is excluded.
+        ///
+        /// This is called by the council or root.
+        #[pallet::weight(< T as Config >::WeightInfo::set_trustee_liveness_threshold())]
+        pub fn set_trustee_liveness_threshold(
+            origin: OriginFor<T>,
+            chain: Chain,
+            threshold: Percent,
+        ) -> DispatchResult {
+            T::CouncilOrigin::try_origin(origin)
+                .map(|_| ())
+                .or_else(ensure_root)?;
+
+            TrusteeLivenessThreshold::<T>::insert(chain, threshold);
+            Ok(())
+        }
+
+        /// Approve to replace the current trustee `old` by `new` without a full election.
+        ///
+        /// Once a supermajority (two thirds) of the current trustees approved it, the trustee
+        /// session is regenerated with the keys of `old` swapped for the ones of `new`, and the
+        /// trustee transition starts as for an election.
+        ///
+        /// This is called by the current trustees.
+        #[pallet::weight(< T as Config >::WeightInfo::replace_trustee())]
+        #[transactional]
+        pub fn replace_trustee(
+            origin: OriginFor<T>,
+            chain: Chain,
+            old: T::AccountId,
+            new: T::AccountId,
+        ) -> DispatchResult {
+            let who = ensure_signed(origin)?;
+            Self::approve_trustee_replacement(who, chain, old, new)
+        }
+
+        /// Restrict the withdrawals of the caller to the active addresses of its allowlist.
+        ///
+        /// It also cancels the pending disabling of the allowlist.
+        #[pallet::weight(< T as Config >::WeightInfo::enable_withdrawal_allowlist())]
+        pub fn enable_withdrawal_allowlist(origin: OriginFor<T>) -> DispatchResult {
+            let who = ensure_signed(origin)?;
+
+            WithdrawalAllowlistEnabled::<T>::insert(&who, true);
+            WithdrawalAllowlistDisableAt::<T>::remove(&who);
+
+            Self::deposit_event(Event::<T>::WithdrawalAllowlistEnabled(who));
+            Ok(())
+        }
+
+        /// Disable the withdrawal allowlist of the caller after the allowlist delay.
+        #[pallet::weight(< T as Config >::WeightInfo::disable_withdrawal_allowlist())]
+        pub fn disable_withdrawal_allowlist(origin: OriginFor<T>) -> DispatchResult {
+            let who = ensure_signed(origin)?;
+
+            ensure!(
+                Self::withdrawal_allowlist_enabled(&who),
+                Error::<T>::WithdrawalAllowlistNotEnabled
+            );
+            ensure!(
+                !WithdrawalAllowlistDisableAt::<T>::contains_key(&who),
+                Error::<T>::WithdrawalAllowlistDisabling
+            );
+
+            let disable_at = frame_system::Pallet::<T>::block_number()
+                .saturating_add(Self::withdrawal_allowlist_delay());
+            WithdrawalAllowlistDisableAt::<T>::insert(&who, disable_at);
+
+            Self::deposit_event(Event::<T>::WithdrawalAllowlistDisabling(who, disable_at));
+            Ok(())
+        }
+
+        /// Add an address into the withdrawal allowlist of the caller, the address becomes active
+        /// after the allowlist delay.
+        #[pallet::weight(< T as Config >::WeightInfo::add_withdrawal_allowlist_addr())]
+        pub fn add_withdrawal_allowlist_addr(
+            origin: OriginFor<T>,
+            chain: Chain,
+            addr: AddrStr,
+        ) -> DispatchResult {
+            let who = ensure_signed(origin)?;
+
+            Self::check_withdrawal_addr(chain, &addr)?;
+            let key = (chain, addr);
+            ensure!(
+                !WithdrawalAllowlistOf::<T>::contains_key(&who, &key),
+                Error::<T>::DuplicatedWithdrawalAddr
+            );
+
+            let active_at = frame_system::Pallet::<T>::block_number()
+                .saturating_add(Self::withdrawal_allowlist_delay());
+            WithdrawalAllowlistOf::<T>::insert(&who, &key, active_at);
+
+            Self::deposit_event(Event::<T>::WithdrawalAllowlistAddrAdded(
+                who, key.0, key.1, active_at,
+            ));
+            Ok(())
+        }
+
+        /// Remove an address from the withdrawal allowlist of the caller immediately.
+        #[pallet::weight(< T as Config >::WeightInfo::remove_withdrawal_allowlist_addr())]
+        pub fn remove_withdrawal_allowlist_addr(
+            origin: OriginFor<T>,
+            chain: Chain,
+            addr: AddrStr,
+        ) -> DispatchResult {
+            let who = ensure_signed(origin)?;
+
+            let key = (chain, addr);
+            ensure!(
+                WithdrawalAllowlistOf::<T>::contains_key(&who, &key),
+                Error::<T>::WithdrawalAddrNotFound
+            );
+            WithdrawalAllowlistOf::<T>::remove(&who, &key);
+
+            Self::deposit_event(Event::<T>::WithdrawalAllowlistAddrRemoved(
+                who, key.0, key.1,
+            ));
+            Ok(())
+        }
+
+        /// Set the blocks before an added address becomes active and a disabled allowlist
+        /// stops being enforced.
+        ///
+        /// This is called by the council or root.
+        #[pallet::weight(< T as Config >::WeightInfo::set_withdrawal_allowlist_delay())]
+        pub fn set_withdrawal_allowlist_delay(
+            origin: OriginFor<T>,
+            delay: T::BlockNumber,
+        ) -> DispatchResult {
+            T::CouncilOrigin::try_origin(origin)
+                .map(|_| ())
+                .or_else(ensure_root)?;
+
+            WithdrawalAllowlistDelay::<T>::put(delay);
+            Ok(())
+        }
     }
 
     #[pallet::event]
@@ -567,6 +858,28 @@ pub mod pallet {
         AllocNativeReward(T::AccountId, u32, BalanceOf<T>),
         /// The not native asset of trustee multi_account is assigned. [multi_account, session_number, asset_id, total_reward]
         AllocNotNativeReward(T::AccountId, u32, AssetId, BalanceOf<T>),
+        /// A trustee reserved the bond. [who, chain, total_bond]
+        TrusteeBonded(T::AccountId, Chain, BalanceOf<T>),
+        /// A trustee started unbonding the bond. [who, chain, bond, unlocking_at]
+        TrusteeUnbonded(T::AccountId, Chain, BalanceOf<T>, T::BlockNumber),
+        /// A trustee withdrew the bond. [who, chain, bond]
+        TrusteeBondWithdrawn(T::AccountId, Chain, BalanceOf<T>),
+        /// The bond of a trustee was slashed into the insurance pot. [who, chain, fault, slashed]
+        TrusteeSlashed(T::AccountId, Chain, TrusteeFault, BalanceOf<T>),
+        /// The trustees with low liveness were excluded from the trustee election. [chain, trustees]
+        TrusteeLivenessExcluded(Chain, Vec<T::AccountId>),
+        /// A trustee approved the replacement of a trustee. [chain, approver, old, new, approvals]
+        TrusteeReplacementApproved(Chain, T::AccountId, T::AccountId, T::AccountId, u32),
+        /// A trustee was replaced without a full election. [chain, old, new]
+        TrusteeReplaced(Chain, T::AccountId, T::AccountId),
+        /// An account enabled its withdrawal allowlist. [who]
+        WithdrawalAllowlistEnabled(T::AccountId),
+        /// An account started disabling its withdrawal allowlist. [who, disable_at]
+        WithdrawalAllowlistDisabling(T::AccountId, T::BlockNumber),
+        /// An address was added into the withdrawal allowlist of an account. [who, chain, addr, active_at]
+        WithdrawalAllowlistAddrAdded(T::AccountId, Chain, AddrStr, T::BlockNumber),
+        /// An address was removed from the withdrawal allowlist of an account. [who, chain, addr]
+        WithdrawalAllowlistAddrRemoved(T::AccountId, Chain, AddrStr),
     }
 
     #[pallet::error]
@@ -605,6 +918,28 @@ pub mod pallet {
         TrusteeMembersNotEnough,
         /// exist in current trustee
         ExistCurrentTrustee,
+        /// the trustee has not reserved the bond
+        NoTrusteeBond,
+        /// the trustee bond is already in unbonding
+        TrusteeBondUnbonding,
+        /// the trustee bond is still locked
+        TrusteeBondLocked,
+        /// not a trustee of the current trustee session
+        NotCurrentTrustee,
+        /// the new trustee can't replace the old one
+        InvalidTrusteeReplacement,
+        /// already approved the trustee replacement
+        DuplicatedApproval,
+        /// the withdrawal address is not active in the withdrawal allowlist
+        WithdrawalAddrNotAllowed,
+        /// the withdrawal allowlist is not enabled
+        WithdrawalAllowlistNotEnabled,
+        /// the withdrawal allowlist is already being disabled
+        WithdrawalAllowlistDisabling,
+        /// the address is already in the withdrawal allowlist
+        DuplicatedWithdrawalAddr,
+        /// the address is not in the withdrawal allowlist
+        WithdrawalAddrNotFound,
     }
 
     #[pallet::storage]
@@ -646,6 +981,13 @@ pub mod pallet {
         GenericTrusteeSessionInfo<T::AccountId, T::BlockNumber>,
     >;
 
+    /// The aggregated public keys of the trustee session, which identify the signers of the
+    /// transactions spending the trustee addresses of that session.
+    #[pallet::storage]
+    #[pallet::getter(fn trustee_script_info_of)]
+    pub(crate) type TrusteeScriptInfoOf<T: Config> =
+        StorageDoubleMap<_, Twox64Concat, Chain, Twox64Concat, u32, ScriptInfo<T::AccountId>>;
+
     /// Trustee intention properties of the corresponding account and chain.
     #[pallet::storage]
     #[pallet::getter(fn trustee_intention_props_of)]
@@ -788,6 +1130,118 @@ pub mod pallet {
     pub(crate) type PreTotalSupply<T: Config> =
         StorageDoubleMap<_, Twox64Concat, Chain, Twox64Concat, u32, BalanceOf<T>, ValueQuery>;
 
+    /// The bond required from the trustee candidates of the corresponding chain.
+    #[pallet::storage]
+    #[pallet::getter(fn trustee_bond_config_of)]
+    pub(crate) type TrusteeBondConfigOf<T: Config> = StorageMap<
+        _,
+        Twox64Concat,
+        Chain,
+        TrusteeBondConfig<BalanceOf<T>, T::BlockNumber>,
+        ValueQuery,
+    >;
+
+    /// The bond reserved by the corresponding account and chain.
+    #[pallet::storage]
+    #[pallet::getter(fn trustee_bond_of)]
+    pub(crate) type TrusteeBondOf<T: Config> = StorageDoubleMap<
+        _,
+        Blake2_128Concat,
+        T::AccountId,
+        Twox64Concat,
+        Chain,
+        TrusteeBond<BalanceOf<T>, T::BlockNumber>,
+    >;
+
+    /// The trustee candidates registered before the bond was introduced, which are regarded as
+    /// bonded until the corresponding height.
+    #[pallet::storage]
+    #[pallet::getter(fn trustee_bond_exempt_until)]
+    pub(crate) type TrusteeBondExemptUntil<T: Config> =
+        StorageDoubleMap<_, Blake2_128Concat, T::AccountId, Twox64Concat, Chain, T::BlockNumber>;
+
+    /// The signing rounds of the trustee session in progress.
+    #[pallet::storage]
+    #[pallet::getter(fn trustee_signing_rounds)]
+    pub(crate) type TrusteeSigningRounds<T: Config> =
+        StorageMap<_, Twox64Concat, Chain, u32, ValueQuery>;
+
+    /// The signing rounds each trustee participated in during the trustee session in progress.
+    #[pallet::storage]
+    #[pallet::getter(fn trustee_signed_rounds)]
+    pub(crate) type TrusteeSignedRounds<T: Config> =
+        StorageDoubleMap<_, Twox64Concat, Chain, Twox64Concat, T::AccountId, u32, ValueQuery>;
+
+    /// The archived liveness of the trustees of the corresponding chain and session number.
+    #[pallet::storage]
+    #[pallet::getter(fn trustee_liveness_of)]
+    pub(crate) type TrusteeLivenessOf<T: Config> = StorageDoubleMap<
+        _,
+        Twox64Concat,
+        Chain,
+        Twox64Concat,
+        u32,
+        Vec<(T::AccountId, TrusteeLiveness)>,
+        ValueQuery,
+    >;
+
+    /// The liveness score below which the trustees are excluded from the trustee election.
+    #[pallet::storage]
+    #[pallet::getter(fn trustee_liveness_threshold)]
+    pub(crate) type TrusteeLivenessThreshold<T: Config> =
+        StorageMap<_, Twox64Concat, Chain, Percent, ValueQuery>;
+
+    /// The current trustees who approved to replace a trustee, [chain, (old, new)] => approvers.
+    ///
+    /// All the approvals of a chain are dropped once its trustee session changes.
+    #[pallet::storage]
+    #[pallet::getter(fn trustee_replacement_approvals)]
+    pub(crate) type TrusteeReplacementApprovals<T: Config> = StorageDoubleMap<
+        _,
+        Twox64Concat,
+        Chain,
+        Blake2_128Concat,
+        (T::AccountId, T::AccountId),
+        Vec<T::AccountId>,
+        ValueQuery,
+    >;
+
+    /// Whether the withdrawals of the account are restricted to its withdrawal allowlist.
+    #[pallet::storage]
+    pub(crate) type WithdrawalAllowlistEnabled<T: Config> =
+        StorageMap<_, Blake2_128Concat, T::AccountId, bool, ValueQuery>;
+
+    /// The height from which the withdrawal allowlist of the account is disabled.
+    #[pallet::storage]
+    #[pallet::getter(fn withdrawal_allowlist_disable_at)]
+    pub(crate) type WithdrawalAllowlistDisableAt<T: Config> =
+        StorageMap<_, Blake2_128Concat, T::AccountId, T::BlockNumber>;
+
+    /// The withdrawal allowlist of the account, [who, (chain, addr)] => active_at.
+    #[pallet::storage]
+    #[pallet::getter(fn withdrawal_allowlist_of)]
+    pub(crate) type WithdrawalAllowlistOf<T: Config> = StorageDoubleMap<
+        _,
+        Blake2_128Concat,
+        T::AccountId,
+        Blake2_128Concat,
+        (Chain, AddrStr),
+        T::BlockNumber,
+    >;
+
+    #[pallet::type_value]
+    pub fn DefaultForWithdrawalAllowlistDelay<T: Config>() -> T::BlockNumber {
+        // 1 day
+        14_400u32.into()
+    }
+
+    /// The blocks before a change of the withdrawal allowlist loosening the restriction takes
+    /// effect.
+    #[pallet::storage]
+    #[pallet::getter(fn withdrawal_allowlist_delay)]
+    pub(crate) type WithdrawalAllowlistDelay<T: Config> =
+        StorageValue<_, T::BlockNumber, ValueQuery, DefaultForWithdrawalAllowlistDelay<T>>;
+
     #[pallet::genesis_config]
     pub struct GenesisConfig<T: Config> {
         pub trustees: Vec<(
@@ -843,13 +1297,7 @@ impl<T: Config> Pallet<T> {
         ext.check_validity()?;
 
         let chain = xpallet_assets_registrar::Pallet::<T>::chain_of(&asset_id)?;
-        match chain {
-            Chain::Bitcoin => {
-                // bitcoin do not need memo
-                T::Bitcoin::check_addr(addr, b"")?;
-            }
-            _ => return Err(Error::<T>::NotSupportedChain.into()),
-        };
+        Self::check_withdrawal_addr(chain, addr)?;
         // we could only split withdrawal limit due to a runtime-api would call `withdrawal_limit`
         // to export `WithdrawalLimit` for an asset.
         let limit = Self::withdrawal_limit(&asset_id)?;
@@ -860,6 +1308,71 @@ impl<T: Config> Pallet<T> {
         );
         Ok(())
     }
+
+    /// Verify the withdrawal of the account, including its withdrawal allowlist.
+    pub fn verify_account_withdrawal(
+        who: &T::AccountId,
+        asset_id: AssetId,
+        value: BalanceOf<T>,
+        addr: &[u8],
+        ext: &Memo,
+    ) -> DispatchResult {
+        Self::verify_withdrawal(asset_id, value, addr, ext)?;
+        let chain = xpallet_assets_registrar::Pallet::<T>::chain_of(&asset_id)?;
+        Self::ensure_allowed_withdrawal_addr(who, chain, addr)
+    }
+
+    fn check_withdrawal_addr(chain: Chain, addr: &[u8]) -> DispatchResult {
+        match chain {
+            Chain::Bitcoin => {
+                // bitcoin do not need memo
+                T::Bitcoin::check_addr(addr, b"")
+            }
+            _ => Err(Error::<T>::NotSupportedChain.into()),
+        }
+    }
+
+    /// Whether the withdrawal allowlist of `who` is enforced now.
+    pub fn withdrawal_allowlist_enabled(who: &T::AccountId) -> bool {
+        WithdrawalAllowlistEnabled::<T>::get(who)
+            && Self::withdrawal_allowlist_disable_at(who).map_or(true, |disable_at| {
+                frame_system::Pallet::<T>::block_number() < disable_at
+            })
+    }
+
+    fn ensure_allowed_withdrawal_addr(
+        who: &T::AccountId,
+        chain: Chain,
+        addr: &[u8],
+    ) -> DispatchResult {
+        if !Self::withdrawal_allowlist_enabled(who) {
+            return Ok(());
+        }
+        let now = frame_system::Pallet::<T>::block_number();
+        match Self::withdrawal_allowlist_of(who, (chain, addr.to_vec())) {
+            Some(active_at) if active_at <= now => Ok(()),
+            _ => Err(Error::<T>::WithdrawalAddrNotAllowed.into()),
+        }
+    }
+
+    /// Get the withdrawal allowlist of `who`, including the pending changes.
+    pub fn withdrawal_allowlist(who: &T::AccountId) -> WithdrawalAllowlist<T::BlockNumber> {
+        let now = frame_system::Pallet::<T>::block_number();
+        let mut allowlist = WithdrawalAllowlist {
+            enabled: Self::withdrawal_allowlist_enabled(who),
+            disable_at: Self::withdrawal_allowlist_disable_at(who)
+                .filter(|disable_at| *disable_at > now),
+            ..Default::default()
+        };
+        for ((chain, addr), active_at) in WithdrawalAllowlistOf::<T>::iter_prefix(who) {
+            if active_at <= now {
+                allowlist.active.push((chain, addr));
+            } else {
+                allowlist.pending.push((chain, addr, active_at));
+            }
+        }
+        allowlist
+    }
 }
 
 /// Trustee setup
@@ -935,6 +1448,144 @@ impl<T: Config> Pallet<T> {
     }
 }
 
+/// Trustee bond
+impl<T: Config> Pallet<T> {
+    /// The account of the insurance pot.
+    pub fn insurance_account() -> T::AccountId {
+        T::InsurancePalletId::get().into_account()
+    }
+
+    /// Whether the bond of the trustee meets the requirement and is not in unbonding.
+    ///
+    /// The trustee candidates registered before the bond was introduced are exempted until the
+    /// end of the grace period, see `migrations::bond`.
+    pub fn has_active_bond(who: &T::AccountId, chain: Chain) -> bool {
+        let required = Self::trustee_bond_config_of(chain).bond;
+        let bonded = match Self::trustee_bond_of(who, chain) {
+            Some(bond) => bond.unlocking_at.is_none() && bond.amount >= required,
+            None => required.is_zero(),
+        };
+        bonded
+            || Self::trustee_bond_exempt_until(who, chain).map_or(false, |until| {
+                frame_system::Pallet::<T>::block_number() < until
+            })
+    }
+
+    fn bond_trustee_impl(who: &T::AccountId, chain: Chain) -> DispatchResult {
+        let required = Self::trustee_bond_config_of(chain).bond;
+        let mut bond = Self::trustee_bond_of(who, chain).unwrap_or_default();
+
+        let extra = required.saturating_sub(bond.amount);
+        if extra.is_zero() && bond.unlocking_at.is_none() {
+            return Ok(());
+        }
+        <T as xpallet_assets::Config>::Currency::reserve(who, extra)?;
+
+        bond.amount = bond.amount.saturating_add(extra);
+        bond.unlocking_at = None;
+        TrusteeBondOf::<T>::insert(who, chain, bond.clone());
+        TrusteeBondExemptUntil::<T>::remove(who, chain);
+
+        Self::deposit_event(Event::<T>::TrusteeBonded(who.clone(), chain, bond.amount));
+        Ok(())
+    }
+
+    fn slash_trustee_impl(
+        who: &T::AccountId,
+        chain: Chain,
+        value: BalanceOf<T>,
+        fault: TrusteeFault,
+    ) -> DispatchResult {
+        let mut bond = Self::trustee_bond_of(who, chain).ok_or(Error::<T>::NoTrusteeBond)?;
+
+        let (imbalance, _) =
+            <T as xpallet_assets::Config>::Currency::slash_reserved(who, value.min(bond.amount));
+        let slashed = imbalance.peek();
+        <T as xpallet_assets::Config>::Currency::resolve_creating(
+            &Self::insurance_account(),
+            imbalance,
+        );
+
+        bond.amount = bond.amount.saturating_sub(slashed);
+        if bond.amount.is_zero() {
+            TrusteeBondOf::<T>::remove(who, chain);
+        } else {
+            TrusteeBondOf::<T>::insert(who, chain, bond);
+        }
+
+        info!(
+            target: "runtime::gateway::common",
+            "[slash_trustee] Slash the trustee bond, trustee:{:?}, fault:{:?}, slashed:{:?}",
+            who, fault, slashed
+        );
+        Self::deposit_event(Event::<T>::TrusteeSlashed(
+            who.clone(),
+            chain,
+            fault,
+            slashed,
+        ));
+        Ok(())
+    }
+}
+
+/// Trustee liveness
+impl<T: Config> Pallet<T> {
+    /// Record a signing round of the trustee session in progress.
+    pub(crate) fn record_signing_round(chain: Chain, signers: &[T::AccountId]) {
+        TrusteeSigningRounds::<T>::mutate(chain, |rounds| *rounds = rounds.saturating_add(1));
+        for signer in signers.iter() {
+            TrusteeSignedRounds::<T>::mutate(chain, signer, |rounds| {
+                *rounds = rounds.saturating_add(1)
+            });
+        }
+    }
+
+    /// Get the liveness of the trustees of a session, `None` means the session in progress.
+    ///
+    /// The session in progress is the last session during the trustee transition.
+    pub fn trustee_liveness(
+        chain: Chain,
+        session_number: Option<u32>,
+    ) -> Vec<(T::AccountId, TrusteeLiveness)> {
+        let session_len = Self::trustee_session_info_len(chain);
+        let in_progress = if Self::trustee_transition_status(chain) {
+            session_len.saturating_sub(1)
+        } else {
+            session_len
+        };
+
+        match session_number {
+            Some(number) if number != in_progress => Self::trustee_liveness_of(chain, number),
+            _ => {
+                let total_rounds = Self::trustee_signing_rounds(chain);
+                Self::trustee_session_info_of(chain, in_progress)
+                    .map(|info| {
+                        info.0
+                            .trustee_list
+                            .into_iter()
+                            .map(|(who, _)| {
+                                let liveness = TrusteeLiveness {
+                                    signed_rounds: Self::trustee_signed_rounds(chain, &who),
+                                    total_rounds,
+                                };
+                                (who, liveness)
+                            })
+                            .collect()
+                    })
+                    .unwrap_or_default()
+            }
+        }
+    }
+
+    /// Archive the liveness of the finished session and start over.
+    pub(crate) fn archive_trustee_liveness(chain: Chain, session_number: u32) {
+        let liveness = Self::trustee_liveness(chain, None);
+        TrusteeLivenessOf::<T>::insert(chain, session_number, liveness);
+        TrusteeSigningRounds::<T>::remove(chain);
+        TrusteeSignedRounds::<T>::remove_prefix(chain, None);
+    }
+}
+
 /// Trustee common
 impl<T: Config> Pallet<T> {
     pub fn generate_trustee_pool() -> Vec<T::AccountId> {
@@ -977,27 +1628,51 @@ impl<T: Config> Pallet<T> {
             Error::<T>::LastTransitionNotCompleted
         );
 
-        ensure!(
-            T::BitcoinWithdrawalProposal::get_withdrawal_proposal().is_none(),
-            Error::<T>::WithdrawalProposalExist,
-        );
+        if chain == Chain::Bitcoin {
+            ensure!(
+                T::BitcoinWithdrawalProposal::get_withdrawal_proposal().is_none(),
+                Error::<T>::WithdrawalProposalExist,
+            );
+        }
 
         // Current trustee list
-        let old_trustee_candidate: Vec<T::AccountId> =
-            if let Ok(info) = T::BitcoinTrusteeSessionProvider::current_trustee_session() {
-                info.trustee_list.into_iter().unzip::<_, _, _, Vec<u64>>().0
-            } else {
-                vec![]
-            };
+        let old_trustee_candidate: Vec<T::AccountId> = if let Some(info) =
+            Self::trustee_session_info_of(chain, Self::trustee_session_info_len(chain))
+        {
+            info.0
+                .trustee_list
+                .into_iter()
+                .unzip::<_, _, _, Vec<u64>>()
+                .0
+        } else {
+            vec![]
+        };
 
         let filter_members: Vec<T::AccountId> = Self::little_black_house(chain);
 
+        let threshold = Self::trustee_liveness_threshold(chain);
+        let inactive_members: Vec<T::AccountId> = if threshold.is_zero() {
+            vec![]
+        } else {
+            Self::trustee_liveness(chain, None)
+                .into_iter()
+                .filter_map(|(who, liveness)| match liveness.score() < threshold {
+                    true => Some(who),
+                    false => None,
+                })
+                .collect()
+        };
+
         let all_trustee_pool = Self::generate_trustee_pool();
 
         let new_trustee_pool: Vec<T::AccountId> = all_trustee_pool
             .iter()
             .filter_map(|who| {
-                match filter_members.contains(who) || !Self::ensure_set_address(who, chain) {
+                match filter_members.contains(who)
+                    || inactive_members.contains(who)
+                    || !Self::ensure_set_address(who, chain)
+                    || !Self::has_active_bond(who, chain)
+                {
                     true => None,
                     false => Some(who.clone()),
                 }
@@ -1039,15 +1714,91 @@ impl<T: Config> Pallet<T> {
 
         Self::transition_trustee_session_impl(chain, new_trustee_candidate)?;
         LittleBlackHouse::<T>::insert(chain, remain_filter_members);
+        if !inactive_members.is_empty() {
+            Self::deposit_event(Event::<T>::TrusteeLivenessExcluded(chain, inactive_members));
+        }
+        Self::start_trustee_transition(chain);
+        Ok(())
+    }
+
+    /// Start moving the assets to the new trustees, except for the first trustee session.
+    fn start_trustee_transition(chain: Chain) {
         if Self::trustee_session_info_len(chain) != 1 {
             TrusteeTransitionStatus::<T>::insert(chain, true);
-            let total_supply = T::BitcoinTotalSupply::total_supply();
-            PreTotalSupply::<T>::insert(
-                chain,
-                Self::trustee_session_info_len(chain) - 1,
-                total_supply,
+            if chain == Chain::Bitcoin {
+                let total_supply = T::BitcoinTotalSupply::total_supply();
+                PreTotalSupply::<T>::insert(
+                    chain,
+                    Self::trustee_session_info_len(chain) - 1,
+                    total_supply,
+                );
+            }
+        }
+    }
+
+    pub fn approve_trustee_replacement(
+        who: T::AccountId,
+        chain: Chain,
+        old: T::AccountId,
+        new: T::AccountId,
+    ) -> DispatchResult {
+        ensure!(
+            !Self::trustee_transition_status(chain),
+            Error::<T>::LastTransitionNotCompleted
+        );
+        if chain == Chain::Bitcoin {
+            ensure!(
+                T::BitcoinWithdrawalProposal::get_withdrawal_proposal().is_none(),
+                Error::<T>::WithdrawalProposalExist,
             );
         }
+
+        let trustees: Vec<T::AccountId> =
+            Self::trustee_session_info_of(chain, Self::trustee_session_info_len(chain))
+                .ok_or(Error::<T>::InvalidTrusteeSession)?
+                .0
+                .trustee_list
+                .into_iter()
+                .map(|(trustee, _)| trustee)
+                .collect();
+        ensure!(trustees.contains(&who), Error::<T>::NotCurrentTrustee);
+        ensure!(
+            trustees.contains(&old)
+                && !trustees.contains(&new)
+                && !Self::little_black_house(chain).contains(&new)
+                && Self::ensure_set_address(&new, chain)
+                && Self::has_active_bond(&new, chain),
+            Error::<T>::InvalidTrusteeReplacement
+        );
+
+        let approvals = TrusteeReplacementApprovals::<T>::try_mutate(
+            chain,
+            (&old, &new),
+            |approvers| -> Result<u32, DispatchError> {
+                ensure!(!approvers.contains(&who), Error::<T>::DuplicatedApproval);
+                approvers.push(who.clone());
+                Ok(approvers.len() as u32)
+            },
+        )?;
+        Self::deposit_event(Event::<T>::TrusteeReplacementApproved(
+            chain,
+            who,
+            old.clone(),
+            new.clone(),
+            approvals,
+        ));
+
+        // two thirds of the current trustees, rounded up
+        let supermajority = (trustees.len() as u32 * 2 + 2) / 3;
+        if approvals >= supermajority {
+            let new_trustees = trustees
+                .into_iter()
+                .map(|trustee| if trustee == old { new.clone() } else { trustee })
+                .collect();
+            Self::transition_trustee_session_impl(chain, new_trustees)?;
+            Self::start_trustee_transition(chain);
+            Self::deposit_event(Event::<T>::TrusteeReplaced(chain, old, new));
+        }
         Ok(())
     }
 
@@ -1128,6 +1879,7 @@ impl<T: Config> Pallet<T> {
                 session_info.1.personal_accounts[index].clone(),
             );
         }
+        TrusteeScriptInfoOf::<T>::insert(chain, session_number, session_info.1.clone());
         TrusteeAdmin::<T>::remove(chain);
 
         Self::deposit_event(Event::<T>::TrusteeSetChanged(
@@ -1147,7 +1899,9 @@ impl<T: Config> Pallet<T> {
             .checked_add(1)
             .unwrap_or(0u32);
         let mut session_info = Self::try_generate_session_info(chain, new_trustees)?;
-        Self::alter_trustee_session(chain, session_number, &mut session_info)
+        Self::alter_trustee_session(chain, session_number, &mut session_info)?;
+        TrusteeReplacementApprovals::<T>::remove_prefix(chain, None);
+        Ok(())
     }
 
     fn cancel_trustee_transition_impl(chain: Chain) -> DispatchResult {
@@ -0,0 +1,47 @@
+// Copyright 2019-2023 ChainX Project Authors. Licensed under GPL-3.0.
+
+use crate::{Config, TrusteeBondExemptUntil, TrusteeBondOf, TrusteeIntentionPropertiesOf};
+use frame_support::{log::info, traits::Get, weights::Weight};
+use sp_runtime::traits::Saturating;
+
+/// The blocks the trustee candidates registered before the bond was introduced could still be
+/// elected without the bond, about 14 days with 6s block time.
+pub const TRUSTEE_BOND_GRACE_PERIOD: u32 = 14 * 14_400;
+
+/// Apply all of the migrations due to the trustee bond.
+///
+/// ### Warning
+///
+/// Use with care and run at your own risk.
+pub fn apply<T: Config>() -> Weight {
+    info!(
+        target: "runtime::gateway::common",
+        "Running migration for the trustee bond"
+    );
+
+    exempt_registered_trustees::<T>()
+}
+
+/// Exempt the registered trustee candidates from the bond for `TRUSTEE_BOND_GRACE_PERIOD`, so
+/// that they have time to call `bond_trustee` before being left out of the trustee election.
+pub fn exempt_registered_trustees<T: Config>() -> Weight {
+    let until =
+        frame_system::Pallet::<T>::block_number().saturating_add(TRUSTEE_BOND_GRACE_PERIOD.into());
+    let mut reads = 0;
+    let mut count = 0;
+    for (who, chain, _) in TrusteeIntentionPropertiesOf::<T>::iter() {
+        reads += 2;
+        if TrusteeBondOf::<T>::contains_key(&who, chain) {
+            continue;
+        }
+        TrusteeBondExemptUntil::<T>::insert(&who, chain, until);
+        count += 1;
+    }
+    info!(
+        target: "runtime::gateway::common",
+        "exempted {} trustee candidates from the bond until {:?}.",
+        count,
+        until,
+    );
+    <T as frame_system::Config>::DbWeight::get().reads_writes(reads + 1, count)
+}
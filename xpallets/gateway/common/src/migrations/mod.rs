@@ -2,4 +2,7 @@
 
 //! All migrations of this pallet.
 
+pub mod bond;
+pub mod recovery;
+pub mod script_info;
 pub mod taproot;
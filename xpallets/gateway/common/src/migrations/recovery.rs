@@ -0,0 +1,46 @@
+// Copyright 2019-2023 ChainX Project Authors. Licensed under GPL-3.0.
+
+use crate::{Config, TrusteeInfoConfig, TrusteeInfoConfigOf};
+use codec::{Decode, Encode};
+use frame_support::{log::info, traits::Get, weights::Weight, RuntimeDebug};
+use scale_info::TypeInfo;
+
+/// The config of trustee info.
+#[derive(PartialEq, Clone, Encode, Decode, Default, RuntimeDebug, TypeInfo)]
+struct OldTrusteeInfoConfig {
+    pub min_trustee_count: u32,
+    pub max_trustee_count: u32,
+}
+
+/// Apply all of the migrations due to the trustee recovery path.
+///
+/// ### Warning
+///
+/// Use with care and run at your own risk.
+pub fn apply<T: Config>() -> Weight {
+    info!(
+        target: "runtime::gateway::common",
+        "Running migration for the trustee recovery path"
+    );
+
+    migrate_trustee_info_config::<T>()
+}
+
+/// Migrate from the old trustee info config, no recovery path by default.
+pub fn migrate_trustee_info_config<T: Config>() -> Weight {
+    TrusteeInfoConfigOf::<T>::translate_values::<OldTrusteeInfoConfig, _>(|config| {
+        Some(TrusteeInfoConfig {
+            min_trustee_count: config.min_trustee_count,
+            max_trustee_count: config.max_trustee_count,
+            recovery: None,
+        })
+    });
+    let count = TrusteeInfoConfigOf::<T>::iter_values().count();
+    info!(
+        target: "runtime::gateway::common",
+        "migrated {} trustee info configs.",
+        count,
+    );
+    <T as frame_system::Config>::DbWeight::get()
+        .reads_writes(count as Weight + 1, count as Weight + 1)
+}
@@ -0,0 +1,64 @@
+// Copyright 2019-2023 ChainX Project Authors. Licensed under GPL-3.0.
+
+use crate::{
+    AggPubkeyInfo, Config, ScriptInfo, TrusteeScriptInfoOf, TrusteeSessionInfoLen,
+    TrusteeTransitionStatus,
+};
+use frame_support::{
+    log::{info, warn},
+    traits::Get,
+    weights::Weight,
+};
+use sp_std::{collections::btree_map::BTreeMap, prelude::*};
+use xp_assets_registrar::Chain;
+
+/// Apply all of the migrations due to the script info of the trustee sessions.
+///
+/// ### Warning
+///
+/// Use with care and run at your own risk.
+pub fn apply<T: Config>() -> Weight {
+    info!(
+        target: "runtime::gateway::common",
+        "Running migration for the script info of the trustee sessions"
+    );
+
+    migrate_current_script_info::<T>()
+}
+
+/// Store the aggregated public keys of the current trustee session as its script info.
+///
+/// The script info of the previous sessions has been dropped, so the signers of a trustee
+/// transition in progress could not be recovered.
+pub fn migrate_current_script_info<T: Config>() -> Weight {
+    let mut reads = 0;
+    let mut script_infos = BTreeMap::<Chain, ScriptInfo<T::AccountId>>::new();
+    for (chain, agg_pubkey, accounts) in AggPubkeyInfo::<T>::iter() {
+        reads += 1;
+        let script_info = script_infos.entry(chain).or_insert_with(|| ScriptInfo {
+            agg_pubkeys: vec![],
+            personal_accounts: vec![],
+        });
+        script_info.agg_pubkeys.push(agg_pubkey);
+        script_info.personal_accounts.push(accounts);
+    }
+
+    let count = script_infos.len() as Weight;
+    for (chain, script_info) in script_infos {
+        if TrusteeTransitionStatus::<T>::get(chain) {
+            warn!(
+                target: "runtime::gateway::common",
+                "[migrate_current_script_info] The script info of the last trustee session is missing, chain:{:?}",
+                chain
+            );
+        }
+        let session_num = TrusteeSessionInfoLen::<T>::get(chain);
+        TrusteeScriptInfoOf::<T>::insert(chain, session_num, script_info);
+    }
+    info!(
+        target: "runtime::gateway::common",
+        "migrated {} trustee script infos.",
+        count,
+    );
+    <T as frame_system::Config>::DbWeight::get().reads_writes(reads + 2 * count, count)
+}
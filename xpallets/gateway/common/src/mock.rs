@@ -267,6 +267,7 @@ impl xpallet_assets::Config for Test {
     type TreasuryAccount = SimpleTreasuryAccount;
     type OnCreatedAccount = frame_system::Provider<Test>;
     type OnAssetChanged = ();
+    type AssetLedger = ();
     type WeightInfo = ();
 }
 
@@ -283,6 +284,7 @@ parameter_types! {
 
 impl xpallet_gateway_records::Config for Test {
     type Event = ();
+    type CouncilOrigin = EnsureSigned<AccountId>;
     type WeightInfo = ();
 }
 
@@ -307,6 +309,7 @@ impl UnixTime for CustomTimestamp {
 impl xpallet_gateway_bitcoin::Config for Test {
     type Event = ();
     type UnixTime = CustomTimestamp;
+    type Family = xpallet_gateway_bitcoin::Bitcoin;
     type AccountExtractor = xp_gateway_bitcoin::OpReturnExtractor;
     type CouncilOrigin = EnsureSigned<AccountId>;
     type TrusteeSessionProvider = ();
@@ -416,8 +419,8 @@ impl<T: xpallet_gateway_bitcoin::Config>
             .unzip();
 
         // judge all props has different pubkey
-        check_keys::<T>(&hot_keys)?;
-        check_keys::<T>(&cold_keys)?;
+        check_keys::<T, ()>(&hot_keys)?;
+        check_keys::<T, ()>(&cold_keys)?;
 
         // [min, max] e.g. bitcoin min is 4, max is 15
         if (trustees.len() as u32) < config.min_trustee_count
@@ -517,6 +520,10 @@ impl<T: xpallet_gateway_bitcoin::Config>
     }
 }
 
+parameter_types! {
+    pub const TrusteeInsurancePalletId: PalletId = PalletId(*b"pcx/trin");
+}
+
 impl crate::Config for Test {
     type Event = ();
     type Validator = AlwaysValidator;
@@ -527,6 +534,7 @@ impl crate::Config for Test {
     type BitcoinTrusteeSessionProvider = trustees::bitcoin::BtcTrusteeSessionManager<Test>;
     type BitcoinTotalSupply = MockBitcoin<Test>;
     type BitcoinWithdrawalProposal = ();
+    type InsurancePalletId = TrusteeInsurancePalletId;
     type WeightInfo = ();
 }
 
@@ -649,6 +657,7 @@ fn trustees() -> Vec<(
     let btc_config = TrusteeInfoConfig {
         min_trustee_count: 3,
         max_trustee_count: 15,
+        recovery: None,
     };
     vec![(Chain::Bitcoin, btc_config, btc_trustees)]
 }
@@ -1,10 +1,21 @@
 use frame_system::RawOrigin;
 
 use crate::{
-    mock::{bob, charlie, dave, ExtBuilder, Test, XAssets, XGatewayCommon, XGatewayRecords},
-    Pallet, TrusteeSessionInfoLen, TrusteeSessionInfoOf, TrusteeSigRecord,
+    migrations::bond::TRUSTEE_BOND_GRACE_PERIOD,
+    mock::{
+        alice, bob, charlie, dave, Balances, ExtBuilder, Origin, System, Test, XAssets,
+        XGatewayCommon, XGatewayRecords,
+    },
+    traits::TrusteeInfoUpdate,
+    types::{TrusteeBondConfig, TrusteeFault, TrusteeLiveness},
+    Error, Pallet, TrusteeScriptInfoOf, TrusteeSessionInfoLen, TrusteeSessionInfoOf,
+    TrusteeSigRecord,
 };
-use frame_support::assert_ok;
+use frame_support::{
+    assert_noop, assert_ok,
+    traits::{Currency, Hooks, ReservableCurrency, StorageVersion},
+};
+use sp_runtime::Percent;
 use xp_assets_registrar::Chain;
 use xp_protocol::X_BTC;
 
@@ -83,3 +94,449 @@ fn test_claim_not_native_asset_reward() {
         assert_eq!(XAssets::usable_balance(&charlie(), &X_BTC), 1);
     });
 }
+
+fn setup_bonded_trustee(who: crate::mock::AccountId, hot: &str, cold: &str) {
+    let _ = Balances::deposit_creating(&who, 1000);
+    assert_ok!(XGatewayCommon::set_trustee_bond_config(
+        Origin::signed(who.clone()),
+        Chain::Bitcoin,
+        TrusteeBondConfig {
+            bond: 100,
+            unbonding_period: 10,
+        },
+    ));
+    assert!(!XGatewayCommon::has_active_bond(&who, Chain::Bitcoin));
+
+    assert_ok!(XGatewayCommon::setup_trustee(
+        Origin::signed(who.clone()),
+        None,
+        Chain::Bitcoin,
+        b"".to_vec(),
+        hex::decode(hot).unwrap(),
+        hex::decode(cold).unwrap(),
+    ));
+    assert_eq!(Balances::reserved_balance(&who), 100);
+    assert!(XGatewayCommon::has_active_bond(&who, Chain::Bitcoin));
+}
+
+#[test]
+fn test_trustee_bond() {
+    ExtBuilder::default().build().execute_with(|| {
+        System::set_block_number(1);
+        setup_bonded_trustee(
+            alice(),
+            "02df92e88c4380778c9c48268460a124a8f4e7da883f80477deaa644ced486efc6",
+            "0386b58f51da9b37e59c40262153173bdb59d7e4e45b73994b99eec4d964ee7e88",
+        );
+
+        assert_ok!(XGatewayCommon::unbond_trustee(
+            Origin::signed(alice()),
+            Chain::Bitcoin
+        ));
+        assert_eq!(
+            XGatewayCommon::trustee_bond_of(alice(), Chain::Bitcoin)
+                .unwrap()
+                .unlocking_at,
+            Some(11)
+        );
+        assert!(!XGatewayCommon::has_active_bond(&alice(), Chain::Bitcoin));
+        assert_noop!(
+            XGatewayCommon::withdraw_trustee_bond(Origin::signed(alice()), Chain::Bitcoin),
+            Error::<Test>::TrusteeBondLocked
+        );
+
+        System::set_block_number(11);
+        assert_ok!(XGatewayCommon::withdraw_trustee_bond(
+            Origin::signed(alice()),
+            Chain::Bitcoin
+        ));
+        assert_eq!(Balances::reserved_balance(&alice()), 0);
+        assert!(XGatewayCommon::trustee_bond_of(alice(), Chain::Bitcoin).is_none());
+    });
+}
+
+#[test]
+fn test_slash_trustee() {
+    ExtBuilder::default().build().execute_with(|| {
+        setup_bonded_trustee(
+            bob(),
+            "0244d81efeb4171b1a8a433b87dd202117f94e44c909c49e42e77b69b5a6ce7d0d",
+            "02e4631e46255571122d6e11cda75d5d601d5eb2585e65e4e87fe9f68c7838a278",
+        );
+
+        assert_ok!(XGatewayCommon::slash_trustee(
+            Origin::signed(alice()),
+            Chain::Bitcoin,
+            bob(),
+            30,
+            TrusteeFault::MismatchedSignature,
+        ));
+        assert_eq!(Balances::reserved_balance(&bob()), 70);
+        assert_eq!(
+            Balances::free_balance(&XGatewayCommon::insurance_account()),
+            30
+        );
+        assert_eq!(
+            XGatewayCommon::trustee_bond_of(bob(), Chain::Bitcoin)
+                .unwrap()
+                .amount,
+            70
+        );
+
+        // The slashed amount is capped by the bond.
+        assert_ok!(XGatewayCommon::slash_trustee(
+            Origin::signed(alice()),
+            Chain::Bitcoin,
+            bob(),
+            1000,
+            TrusteeFault::MissedSigning,
+        ));
+        assert_eq!(Balances::reserved_balance(&bob()), 0);
+        assert_eq!(Balances::free_balance(&bob()), 900);
+        assert!(XGatewayCommon::trustee_bond_of(bob(), Chain::Bitcoin).is_none());
+    });
+}
+
+#[test]
+fn test_trustee_bond_migration() {
+    ExtBuilder::default().build().execute_with(|| {
+        System::set_block_number(10);
+        StorageVersion::new(1).put::<XGatewayCommon>();
+        XGatewayCommon::on_runtime_upgrade();
+        assert_eq!(StorageVersion::get::<XGatewayCommon>(), 3);
+
+        let until = 10 + TRUSTEE_BOND_GRACE_PERIOD as u64;
+        for who in [alice(), bob(), charlie(), dave()] {
+            assert_eq!(
+                XGatewayCommon::trustee_bond_exempt_until(&who, Chain::Bitcoin),
+                Some(until)
+            );
+        }
+
+        // The registered trustees stay electable in the grace period.
+        assert_ok!(XGatewayCommon::set_trustee_bond_config(
+            Origin::signed(alice()),
+            Chain::Bitcoin,
+            TrusteeBondConfig {
+                bond: 100,
+                unbonding_period: 10,
+            },
+        ));
+        assert!(XGatewayCommon::has_active_bond(&alice(), Chain::Bitcoin));
+        assert!(XGatewayCommon::has_active_bond(&bob(), Chain::Bitcoin));
+
+        let _ = Balances::deposit_creating(&alice(), 1000);
+        assert_ok!(XGatewayCommon::bond_trustee(
+            Origin::signed(alice()),
+            Chain::Bitcoin
+        ));
+        assert_eq!(Balances::reserved_balance(&alice()), 100);
+        assert!(XGatewayCommon::trustee_bond_exempt_until(&alice(), Chain::Bitcoin).is_none());
+
+        System::set_block_number(until);
+        assert!(XGatewayCommon::has_active_bond(&alice(), Chain::Bitcoin));
+        assert!(!XGatewayCommon::has_active_bond(&bob(), Chain::Bitcoin));
+    });
+}
+
+#[test]
+fn test_trustee_liveness_exclusion() {
+    ExtBuilder::default().build().execute_with(|| {
+        assert_ok!(XGatewayCommon::do_trustee_election(Chain::Bitcoin));
+        let trustees = XGatewayCommon::trustee_liveness(Chain::Bitcoin, None)
+            .into_iter()
+            .map(|(who, _)| who)
+            .collect::<Vec<_>>();
+        assert_eq!(trustees.len(), 3);
+        let inactive = trustees[0].clone();
+
+        XGatewayCommon::record_signing_round(Chain::Bitcoin, &trustees[1..]);
+        let liveness = XGatewayCommon::trustee_liveness(Chain::Bitcoin, None);
+        assert_eq!(liveness[0].1.score(), Percent::from_percent(0));
+        assert_eq!(
+            liveness[1].1,
+            TrusteeLiveness {
+                signed_rounds: 1,
+                total_rounds: 1
+            }
+        );
+
+        assert_ok!(XGatewayCommon::set_trustee_liveness_threshold(
+            Origin::signed(alice()),
+            Chain::Bitcoin,
+            Percent::from_percent(50),
+        ));
+        assert_ok!(XGatewayCommon::do_trustee_election(Chain::Bitcoin));
+        assert!(XGatewayCommon::trustee_transition_status(Chain::Bitcoin));
+        let new_trustees = XGatewayCommon::trustee_session_info_of(Chain::Bitcoin, 2)
+            .unwrap()
+            .0
+            .trustee_list;
+        assert!(!new_trustees.iter().any(|(who, _)| *who == inactive));
+
+        // The liveness is archived once the transition is completed.
+        XGatewayCommon::update_transition_status(Chain::Bitcoin, false, None);
+        assert_eq!(
+            XGatewayCommon::trustee_liveness(Chain::Bitcoin, Some(1)),
+            liveness
+        );
+        assert_eq!(XGatewayCommon::trustee_signing_rounds(Chain::Bitcoin), 0);
+    });
+}
+
+#[test]
+fn test_transition_sig_record() {
+    ExtBuilder::default().build().execute_with(|| {
+        assert_ok!(XGatewayCommon::do_trustee_election(Chain::Bitcoin));
+        let script_info = XGatewayCommon::trustee_script_info_of(Chain::Bitcoin, 1).unwrap();
+        for (agg_pubkey, accounts) in script_info
+            .agg_pubkeys
+            .iter()
+            .zip(script_info.personal_accounts.iter())
+        {
+            assert_eq!(
+                &XGatewayCommon::agg_pubkey_info(Chain::Bitcoin, agg_pubkey),
+                accounts
+            );
+        }
+        XGatewayCommon::update_transition_status(Chain::Bitcoin, false, None);
+
+        // The transition is signed by the trustees of the last session.
+        assert_ok!(XGatewayCommon::do_trustee_election(Chain::Bitcoin));
+        assert!(XGatewayCommon::trustee_script_info_of(Chain::Bitcoin, 2).is_some());
+        XGatewayCommon::update_transition_sig_record(Chain::Bitcoin, &script_info.agg_pubkeys[0]);
+        assert_eq!(XGatewayCommon::trustee_signing_rounds(Chain::Bitcoin), 1);
+        for signer in script_info.personal_accounts[0].iter() {
+            assert_eq!(
+                XGatewayCommon::trustee_signed_rounds(Chain::Bitcoin, signer),
+                1
+            );
+        }
+    });
+}
+
+#[test]
+fn test_script_info_migration() {
+    ExtBuilder::default().build().execute_with(|| {
+        assert_ok!(XGatewayCommon::do_trustee_election(Chain::Bitcoin));
+        let script_info = TrusteeScriptInfoOf::<Test>::take(Chain::Bitcoin, 1).unwrap();
+
+        StorageVersion::new(2).put::<XGatewayCommon>();
+        XGatewayCommon::on_runtime_upgrade();
+        let migrated = XGatewayCommon::trustee_script_info_of(Chain::Bitcoin, 1).unwrap();
+        for (agg_pubkey, accounts) in migrated
+            .agg_pubkeys
+            .iter()
+            .zip(migrated.personal_accounts.iter())
+        {
+            let index = script_info
+                .agg_pubkeys
+                .iter()
+                .position(|p| p == agg_pubkey)
+                .unwrap();
+            assert_eq!(&script_info.personal_accounts[index], accounts);
+        }
+        assert_eq!(migrated.agg_pubkeys.len(), script_info.agg_pubkeys.len());
+    });
+}
+
+#[test]
+fn test_replace_trustee() {
+    ExtBuilder::default().build().execute_with(|| {
+        assert_ok!(XGatewayCommon::do_trustee_election(Chain::Bitcoin));
+        let trustees = XGatewayCommon::trustee_session_info_of(Chain::Bitcoin, 1)
+            .unwrap()
+            .0
+            .trustee_list
+            .into_iter()
+            .map(|(who, _)| who)
+            .collect::<Vec<_>>();
+        assert_eq!(trustees.len(), 3);
+        let outsider = [alice(), bob(), charlie(), dave()]
+            .into_iter()
+            .find(|who| !trustees.contains(who))
+            .unwrap();
+        let old = trustees[0].clone();
+
+        assert_noop!(
+            XGatewayCommon::replace_trustee(
+                Origin::signed(outsider.clone()),
+                Chain::Bitcoin,
+                old.clone(),
+                outsider.clone(),
+            ),
+            Error::<Test>::NotCurrentTrustee
+        );
+        assert_noop!(
+            XGatewayCommon::replace_trustee(
+                Origin::signed(trustees[0].clone()),
+                Chain::Bitcoin,
+                old.clone(),
+                trustees[1].clone(),
+            ),
+            Error::<Test>::InvalidTrusteeReplacement
+        );
+
+        assert_ok!(XGatewayCommon::replace_trustee(
+            Origin::signed(trustees[0].clone()),
+            Chain::Bitcoin,
+            old.clone(),
+            outsider.clone(),
+        ));
+        assert_noop!(
+            XGatewayCommon::replace_trustee(
+                Origin::signed(trustees[0].clone()),
+                Chain::Bitcoin,
+                old.clone(),
+                outsider.clone(),
+            ),
+            Error::<Test>::DuplicatedApproval
+        );
+        assert_eq!(TrusteeSessionInfoLen::<Test>::get(Chain::Bitcoin), 1);
+
+        // two of the three trustees approved
+        assert_ok!(XGatewayCommon::replace_trustee(
+            Origin::signed(trustees[1].clone()),
+            Chain::Bitcoin,
+            old.clone(),
+            outsider.clone(),
+        ));
+        assert_eq!(TrusteeSessionInfoLen::<Test>::get(Chain::Bitcoin), 2);
+        let new_trustees = XGatewayCommon::trustee_session_info_of(Chain::Bitcoin, 2)
+            .unwrap()
+            .0
+            .trustee_list
+            .into_iter()
+            .map(|(who, _)| who)
+            .collect::<Vec<_>>();
+        assert_eq!(
+            new_trustees,
+            vec![outsider.clone(), trustees[1].clone(), trustees[2].clone()]
+        );
+        assert!(XGatewayCommon::trustee_transition_status(Chain::Bitcoin));
+        assert!(
+            XGatewayCommon::trustee_replacement_approvals(Chain::Bitcoin, (old, outsider))
+                .is_empty()
+        );
+    });
+}
+
+#[test]
+fn test_withdrawal_allowlist() {
+    ExtBuilder::default().build().execute_with(|| {
+        System::set_block_number(1);
+        let addr = b"3PgYgJA6h5xPEc3HbnZrUZWkpRxuCZVyEP".to_vec();
+        let other = b"1FM5p9iaWLjDDu9m7ZLQ1PJkAgz7HhjTcx".to_vec();
+        let value = 100_000_000;
+        let memo = b"".to_vec().into();
+
+        // not restricted until the allowlist is enabled
+        assert_ok!(XGatewayCommon::verify_account_withdrawal(
+            &alice(),
+            X_BTC,
+            value,
+            &other,
+            &memo
+        ));
+
+        assert_ok!(XGatewayCommon::set_withdrawal_allowlist_delay(
+            Origin::root(),
+            10
+        ));
+        assert_ok!(XGatewayCommon::add_withdrawal_allowlist_addr(
+            Origin::signed(alice()),
+            Chain::Bitcoin,
+            addr.clone()
+        ));
+        assert_noop!(
+            XGatewayCommon::add_withdrawal_allowlist_addr(
+                Origin::signed(alice()),
+                Chain::Bitcoin,
+                addr.clone()
+            ),
+            Error::<Test>::DuplicatedWithdrawalAddr
+        );
+        assert_noop!(
+            XGatewayCommon::disable_withdrawal_allowlist(Origin::signed(alice())),
+            Error::<Test>::WithdrawalAllowlistNotEnabled
+        );
+        assert_ok!(XGatewayCommon::enable_withdrawal_allowlist(Origin::signed(
+            alice()
+        )));
+
+        // the added address is pending
+        let allowlist = XGatewayCommon::withdrawal_allowlist(&alice());
+        assert!(allowlist.enabled);
+        assert!(allowlist.active.is_empty());
+        assert_eq!(allowlist.pending, vec![(Chain::Bitcoin, addr.clone(), 11)]);
+        assert_noop!(
+            XGatewayCommon::verify_account_withdrawal(&alice(), X_BTC, value, &addr, &memo),
+            Error::<Test>::WithdrawalAddrNotAllowed
+        );
+        // other accounts are not restricted
+        assert_ok!(XGatewayCommon::verify_account_withdrawal(
+            &bob(),
+            X_BTC,
+            value,
+            &other,
+            &memo
+        ));
+
+        System::set_block_number(11);
+        assert_ok!(XGatewayCommon::verify_account_withdrawal(
+            &alice(),
+            X_BTC,
+            value,
+            &addr,
+            &memo
+        ));
+        assert_noop!(
+            XGatewayCommon::verify_account_withdrawal(&alice(), X_BTC, value, &other, &memo),
+            Error::<Test>::WithdrawalAddrNotAllowed
+        );
+        // the allowlist is not checked without the account
+        assert_ok!(XGatewayCommon::verify_withdrawal(
+            X_BTC, value, &other, &memo
+        ));
+
+        // disabling is delayed
+        assert_ok!(XGatewayCommon::disable_withdrawal_allowlist(
+            Origin::signed(alice())
+        ));
+        assert_noop!(
+            XGatewayCommon::disable_withdrawal_allowlist(Origin::signed(alice())),
+            Error::<Test>::WithdrawalAllowlistDisabling
+        );
+        assert_eq!(
+            XGatewayCommon::withdrawal_allowlist(&alice()).disable_at,
+            Some(21)
+        );
+        assert_noop!(
+            XGatewayCommon::verify_account_withdrawal(&alice(), X_BTC, value, &other, &memo),
+            Error::<Test>::WithdrawalAddrNotAllowed
+        );
+        System::set_block_number(21);
+        assert!(!XGatewayCommon::withdrawal_allowlist_enabled(&alice()));
+        assert_ok!(XGatewayCommon::verify_account_withdrawal(
+            &alice(),
+            X_BTC,
+            value,
+            &other,
+            &memo
+        ));
+
+        // removing is immediate
+        assert_ok!(XGatewayCommon::enable_withdrawal_allowlist(Origin::signed(
+            alice()
+        )));
+        assert_ok!(XGatewayCommon::remove_withdrawal_allowlist_addr(
+            Origin::signed(alice()),
+            Chain::Bitcoin,
+            addr.clone()
+        ));
+        assert_noop!(
+            XGatewayCommon::verify_account_withdrawal(&alice(), X_BTC, value, &addr, &memo),
+            Error::<Test>::WithdrawalAddrNotAllowed
+        );
+    })
+}
@@ -106,17 +106,25 @@ impl<AccountId, BlockNumber, TrusteeAddress: BytesLike>
     fn genesis_trustee(_: Chain, _: &[AccountId]) {}
 }
 
-pub trait TrusteeInfoUpdate {
+pub trait TrusteeInfoUpdate<AccountId> {
     /// Update the trustee trasition status when the renewal of the trustee is completed
     fn update_transition_status(chain: Chain, status: bool, trans_amount: Option<u64>);
     /// Each withdrawal is completed to record the weight of the signer
     fn update_trustee_sig_record(chain: Chain, script: &[u8], withdraw_amout: u64);
+    /// The trustee transition tx is relayed to record the liveness of the last trustees
+    fn update_transition_sig_record(chain: Chain, script: &[u8]);
+    /// A signing round is expired to record the liveness of the trustees who signed it
+    fn update_trustee_liveness(chain: Chain, signers: &[AccountId]);
 }
 
-impl TrusteeInfoUpdate for () {
+impl<AccountId> TrusteeInfoUpdate<AccountId> for () {
     fn update_transition_status(_: Chain, _: bool, _: Option<u64>) {}
 
     fn update_trustee_sig_record(_: Chain, _: &[u8], _: u64) {}
+
+    fn update_transition_sig_record(_: Chain, _: &[u8]) {}
+
+    fn update_trustee_liveness(_: Chain, _: &[AccountId]) {}
 }
 
 pub trait ReferralBinding<AccountId> {
@@ -135,6 +143,7 @@ pub trait AddressBinding<AccountId, Address: Into<Vec<u8>>> {
     fn update_binding(chain: Chain, address: Address, who: OpReturnAccount<AccountId>);
     fn check_allowed_binding(info: BtcDepositInfo<AccountId>) -> BtcDepositInfo<AccountId>;
     fn dst_chain_proxy_address(dst_chain: DstChain) -> Option<AccountId>;
+    fn dst_chain_proxy_addresses() -> Vec<(DstChain, AccountId)>;
     fn address(chain: Chain, address: Address) -> Option<OpReturnAccount<AccountId>>;
 }
 
@@ -146,6 +155,9 @@ impl<AccountId, Address: Into<Vec<u8>>> AddressBinding<AccountId, Address> for (
     fn dst_chain_proxy_address(_: DstChain) -> Option<AccountId> {
         None
     }
+    fn dst_chain_proxy_addresses() -> Vec<(DstChain, AccountId)> {
+        Vec::new()
+    }
     fn address(_: Chain, _: Address) -> Option<OpReturnAccount<AccountId>> {
         None
     }
@@ -16,20 +16,23 @@ use crate::{
     Config, Error, Pallet, TrusteeSessionInfoOf, TrusteeSigRecord, TrusteeTransitionStatus,
 };
 
-pub struct TrusteeSessionManager<T: Config, TrusteeAddress>(
+/// The trustee sessions of the chain provided by `C`, which is the chain of the trustee address
+/// by default. The bitcoin-family chains share the same trustee address type, so `C` is the
+/// gateway instance of the chain for them.
+pub struct TrusteeSessionManager<T: Config, TrusteeAddress, C = TrusteeAddress>(
     PhantomData<T>,
-    PhantomData<TrusteeAddress>,
+    PhantomData<(TrusteeAddress, C)>,
 );
 
-impl<T: Config, TrusteeAddress: BytesLike + ChainProvider>
+impl<T: Config, TrusteeAddress: BytesLike, C: ChainProvider>
     TrusteeSession<T::AccountId, T::BlockNumber, TrusteeAddress>
-    for TrusteeSessionManager<T, TrusteeAddress>
+    for TrusteeSessionManager<T, TrusteeAddress, C>
 {
     fn trustee_session(
         number: u32,
     ) -> Result<TrusteeSessionInfo<T::AccountId, T::BlockNumber, TrusteeAddress>, DispatchError>
     {
-        let chain = TrusteeAddress::chain();
+        let chain = C::chain();
         let generic_info =
             Pallet::<T>::trustee_session_info_of(chain, number).ok_or_else(|| {
                 error!(
@@ -50,7 +53,7 @@ impl<T: Config, TrusteeAddress: BytesLike + ChainProvider>
     fn current_trustee_session(
     ) -> Result<TrusteeSessionInfo<T::AccountId, T::BlockNumber, TrusteeAddress>, DispatchError>
     {
-        let chain = TrusteeAddress::chain();
+        let chain = C::chain();
         let number = Pallet::<T>::trustee_session_info_len(chain);
         Self::trustee_session(number)
     }
@@ -59,19 +62,19 @@ impl<T: Config, TrusteeAddress: BytesLike + ChainProvider>
         Ok(Self::current_trustee_session()?
             .trustee_list
             .iter()
-            .filter_map(|info| {
-                match Pallet::<T>::trustee_intention_props_of(&info.0, TrusteeAddress::chain()) {
+            .filter_map(
+                |info| match Pallet::<T>::trustee_intention_props_of(&info.0, C::chain()) {
                     None => None,
                     Some(n) => n.0.proxy_account,
-                }
-            })
+                },
+            )
             .collect::<Vec<T::AccountId>>())
     }
 
     fn last_trustee_session(
     ) -> Result<TrusteeSessionInfo<T::AccountId, T::BlockNumber, TrusteeAddress>, DispatchError>
     {
-        let chain = TrusteeAddress::chain();
+        let chain = C::chain();
         let number = match Pallet::<T>::trustee_session_info_len(chain).checked_sub(1) {
             Some(r) => r,
             None => u32::MAX,
@@ -87,7 +90,7 @@ impl<T: Config, TrusteeAddress: BytesLike + ChainProvider>
     }
 
     fn trustee_transition_state() -> bool {
-        Pallet::<T>::trustee_transition_status(TrusteeAddress::chain())
+        Pallet::<T>::trustee_transition_status(C::chain())
     }
 
     #[cfg(feature = "std")]
@@ -97,7 +100,7 @@ impl<T: Config, TrusteeAddress: BytesLike + ChainProvider>
     }
 }
 
-impl<T: Config> TrusteeInfoUpdate for Pallet<T> {
+impl<T: Config> TrusteeInfoUpdate<T::AccountId> for Pallet<T> {
     fn update_transition_status(chain: Chain, status: bool, _: Option<u64>) {
         // The renewal of the trustee is completed, the current trustee information is replaced
         // and the number of multiple signings is archived. Currently only supports bitcoin
@@ -122,6 +125,7 @@ impl<T: Config> TrusteeInfoUpdate for Pallet<T> {
                 }
             });
             TrusteeSigRecord::<T>::remove_prefix(chain, None);
+            Self::archive_trustee_liveness(chain, last_session_num);
         }
 
         TrusteeTransitionStatus::<T>::insert(chain, status);
@@ -129,7 +133,7 @@ impl<T: Config> TrusteeInfoUpdate for Pallet<T> {
 
     fn update_trustee_sig_record(chain: Chain, script: &[u8], withdraw_amount: u64) {
         let signed_trustees = Self::agg_pubkey_info(chain, script);
-        signed_trustees.into_iter().for_each(|trustee| {
+        signed_trustees.iter().for_each(|trustee| {
             let amount = if Some(trustee.clone()) == Self::trustee_admin(chain) {
                 withdraw_amount
                     .saturating_mul(Self::trustee_admin_multiply(chain))
@@ -138,11 +142,39 @@ impl<T: Config> TrusteeInfoUpdate for Pallet<T> {
             } else {
                 withdraw_amount
             };
-            if TrusteeSigRecord::<T>::contains_key(chain, &trustee) {
-                TrusteeSigRecord::<T>::mutate(chain, &trustee, |record| *record += amount);
+            if TrusteeSigRecord::<T>::contains_key(chain, trustee) {
+                TrusteeSigRecord::<T>::mutate(chain, trustee, |record| *record += amount);
             } else {
                 TrusteeSigRecord::<T>::insert(chain, trustee, amount);
             }
         });
+        Self::record_signing_round(chain, &signed_trustees);
+    }
+
+    fn update_transition_sig_record(chain: Chain, script: &[u8]) {
+        // The aggregated pubkeys have been replaced by the new trustees, so the signers are
+        // recovered from the script info of the last trustee session.
+        let last_session_num = Self::trustee_session_info_len(chain).saturating_sub(1);
+        let signers =
+            Self::trustee_script_info_of(chain, last_session_num).and_then(|script_info| {
+                script_info
+                    .agg_pubkeys
+                    .iter()
+                    .position(|agg_pubkey| agg_pubkey.as_slice() == script)
+                    .and_then(|index| script_info.personal_accounts.get(index).cloned())
+            });
+
+        match signers {
+            Some(signers) => Self::record_signing_round(chain, &signers),
+            None => warn!(
+                target: "runtime::gateway::common",
+                "[update_transition_sig_record] Can not find the signers of the transition for chain:{:?}",
+                chain
+            ),
+        }
+    }
+
+    fn update_trustee_liveness(chain: Chain, signers: &[T::AccountId]) {
+        Self::record_signing_round(chain, signers);
     }
 }
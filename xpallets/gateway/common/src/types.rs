@@ -5,10 +5,11 @@ use scale_info::TypeInfo;
 #[cfg(feature = "std")]
 use serde::{Deserialize, Serialize};
 
-use sp_runtime::RuntimeDebug;
+use sp_runtime::{Percent, RuntimeDebug};
 use sp_std::{convert::TryFrom, prelude::Vec};
 
-use chainx_primitives::Text;
+use chainx_primitives::{AddrStr, Text};
+use xp_assets_registrar::Chain;
 
 use crate::traits::BytesLike;
 
@@ -19,6 +20,25 @@ use crate::traits::BytesLike;
 pub struct TrusteeInfoConfig {
     pub min_trustee_count: u32,
     pub max_trustee_count: u32,
+    /// The timelocked recovery path added into the trustee addresses.
+    #[cfg_attr(feature = "std", serde(default))]
+    pub recovery: Option<TrusteeRecoveryConfig>,
+}
+
+/// The timelocked recovery path of the trustee addresses.
+///
+/// The recovery keys held by the council could spend the trustee utxos once `csv_delay` blocks
+/// have passed since the utxo was confirmed, in case the trustees are gone.
+#[derive(PartialEq, Eq, Clone, Encode, Decode, Default, RuntimeDebug, TypeInfo)]
+#[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "std", serde(rename_all = "camelCase"))]
+pub struct TrusteeRecoveryConfig {
+    /// The compressed public keys of the recovery key set
+    pub keys: Vec<Vec<u8>>,
+    /// The number of recovery keys required
+    pub threshold: u32,
+    /// The relative timelock (BIP68, in blocks) of the recovery path
+    pub csv_delay: u32,
 }
 
 /// The trustee session info.
@@ -61,6 +81,78 @@ pub struct RewardInfo<AccountId, Balance> {
     pub rewards: Vec<(AccountId, Balance)>,
 }
 
+/// The bond required from the trustee candidates of a chain.
+#[derive(PartialEq, Eq, Clone, Encode, Decode, Default, RuntimeDebug, TypeInfo)]
+#[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "std", serde(rename_all = "camelCase"))]
+pub struct TrusteeBondConfig<Balance, BlockNumber> {
+    /// The amount of native asset reserved when setting up the trustee
+    pub bond: Balance,
+    /// The blocks the bond stays reserved after the trustee starts unbonding
+    pub unbonding_period: BlockNumber,
+}
+
+/// The bond of a trustee.
+#[derive(PartialEq, Eq, Clone, Encode, Decode, Default, RuntimeDebug, TypeInfo)]
+#[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "std", serde(rename_all = "camelCase"))]
+pub struct TrusteeBond<Balance, BlockNumber> {
+    /// The reserved amount
+    pub amount: Balance,
+    /// The height after which the bond could be withdrawn, `None` if it is not unbonding
+    pub unlocking_at: Option<BlockNumber>,
+}
+
+/// The withdrawal allowlist of an account.
+#[derive(PartialEq, Eq, Clone, Encode, Decode, Default, RuntimeDebug, TypeInfo)]
+#[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "std", serde(rename_all = "camelCase"))]
+pub struct WithdrawalAllowlist<BlockNumber> {
+    /// Whether the withdrawals are restricted to the active addresses
+    pub enabled: bool,
+    /// The height from which the allowlist is disabled, if the disabling is pending
+    pub disable_at: Option<BlockNumber>,
+    /// The addresses which could be withdrawn to, [chain, addr]
+    pub active: Vec<(Chain, AddrStr)>,
+    /// The addresses which are not active yet, [chain, addr, active_at]
+    pub pending: Vec<(Chain, AddrStr, BlockNumber)>,
+}
+
+/// The participation of a trustee in the signing rounds of a trustee session.
+///
+/// A signing round is a withdrawal proposal which is finished or expired, or the trustee
+/// transition of the session.
+#[derive(PartialEq, Eq, Clone, Copy, Encode, Decode, Default, RuntimeDebug, TypeInfo)]
+#[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "std", serde(rename_all = "camelCase"))]
+pub struct TrusteeLiveness {
+    /// The signing rounds the trustee participated in
+    pub signed_rounds: u32,
+    /// All the signing rounds of the session
+    pub total_rounds: u32,
+}
+
+impl TrusteeLiveness {
+    /// The liveness score, which is full when there is no signing round yet.
+    pub fn score(&self) -> Percent {
+        if self.total_rounds == 0 {
+            Percent::from_percent(100)
+        } else {
+            Percent::from_rational(self.signed_rounds, self.total_rounds)
+        }
+    }
+}
+
+/// The provable faults of a trustee.
+#[derive(PartialEq, Eq, Clone, Copy, Encode, Decode, RuntimeDebug, TypeInfo)]
+#[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
+pub enum TrusteeFault {
+    /// Signed a transaction that does not match the withdrawal proposal
+    MismatchedSignature,
+    /// Did not sign the withdrawal proposals or the trustee transition
+    MissedSigning,
+}
+
 /// The generic trustee session info.
 #[derive(PartialEq, Eq, Clone, Encode, Decode, RuntimeDebug, TypeInfo)]
 #[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
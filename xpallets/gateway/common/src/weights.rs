@@ -42,6 +42,18 @@ pub trait WeightInfo {
     fn force_trustee_election() -> Weight;
     fn force_update_trustee() -> Weight;
     fn force_set_referral_binding() -> Weight;
+    fn set_trustee_bond_config() -> Weight;
+    fn bond_trustee() -> Weight;
+    fn unbond_trustee() -> Weight;
+    fn withdraw_trustee_bond() -> Weight;
+    fn slash_trustee() -> Weight;
+    fn set_trustee_liveness_threshold() -> Weight;
+    fn replace_trustee() -> Weight;
+    fn enable_withdrawal_allowlist() -> Weight;
+    fn disable_withdrawal_allowlist() -> Weight;
+    fn add_withdrawal_allowlist_addr() -> Weight;
+    fn remove_withdrawal_allowlist_addr() -> Weight;
+    fn set_withdrawal_allowlist_delay() -> Weight;
 }
 
 /// Weights for xpallet_gateway_common using the Substrate node and recommended hardware.
@@ -49,7 +61,7 @@ pub struct SubstrateWeight<T>(PhantomData<T>);
 impl<T: frame_system::Config> WeightInfo for SubstrateWeight<T> {
     fn withdraw() -> Weight {
         (148_184_000 as Weight)
-            .saturating_add(T::DbWeight::get().reads(9 as Weight))
+            .saturating_add(T::DbWeight::get().reads(11 as Weight))
             .saturating_add(T::DbWeight::get().writes(5 as Weight))
     }
     fn cancel_withdrawal() -> Weight {
@@ -94,13 +106,65 @@ impl<T: frame_system::Config> WeightInfo for SubstrateWeight<T> {
     fn force_set_referral_binding() -> Weight {
         (19_517_000 as Weight).saturating_add(T::DbWeight::get().writes(1 as Weight))
     }
+    fn set_trustee_bond_config() -> Weight {
+        (3_214_000 as Weight).saturating_add(T::DbWeight::get().writes(1 as Weight))
+    }
+    fn bond_trustee() -> Weight {
+        (45_672_000 as Weight)
+            .saturating_add(T::DbWeight::get().reads(4 as Weight))
+            .saturating_add(T::DbWeight::get().writes(2 as Weight))
+    }
+    fn unbond_trustee() -> Weight {
+        (28_941_000 as Weight)
+            .saturating_add(T::DbWeight::get().reads(5 as Weight))
+            .saturating_add(T::DbWeight::get().writes(1 as Weight))
+    }
+    fn withdraw_trustee_bond() -> Weight {
+        (41_387_000 as Weight)
+            .saturating_add(T::DbWeight::get().reads(2 as Weight))
+            .saturating_add(T::DbWeight::get().writes(2 as Weight))
+    }
+    fn slash_trustee() -> Weight {
+        (62_805_000 as Weight)
+            .saturating_add(T::DbWeight::get().reads(3 as Weight))
+            .saturating_add(T::DbWeight::get().writes(3 as Weight))
+    }
+    fn set_trustee_liveness_threshold() -> Weight {
+        (3_158_000 as Weight).saturating_add(T::DbWeight::get().writes(1 as Weight))
+    }
+    fn replace_trustee() -> Weight {
+        (612_340_000 as Weight)
+            .saturating_add(T::DbWeight::get().reads(36 as Weight))
+            .saturating_add(T::DbWeight::get().writes(12 as Weight))
+    }
+    fn enable_withdrawal_allowlist() -> Weight {
+        (14_212_000 as Weight).saturating_add(T::DbWeight::get().writes(2 as Weight))
+    }
+    fn disable_withdrawal_allowlist() -> Weight {
+        (19_874_000 as Weight)
+            .saturating_add(T::DbWeight::get().reads(3 as Weight))
+            .saturating_add(T::DbWeight::get().writes(1 as Weight))
+    }
+    fn add_withdrawal_allowlist_addr() -> Weight {
+        (31_560_000 as Weight)
+            .saturating_add(T::DbWeight::get().reads(3 as Weight))
+            .saturating_add(T::DbWeight::get().writes(1 as Weight))
+    }
+    fn remove_withdrawal_allowlist_addr() -> Weight {
+        (17_936_000 as Weight)
+            .saturating_add(T::DbWeight::get().reads(1 as Weight))
+            .saturating_add(T::DbWeight::get().writes(1 as Weight))
+    }
+    fn set_withdrawal_allowlist_delay() -> Weight {
+        (3_021_000 as Weight).saturating_add(T::DbWeight::get().writes(1 as Weight))
+    }
 }
 
 // For backwards compatibility and tests
 impl WeightInfo for () {
     fn withdraw() -> Weight {
         (148_184_000 as Weight)
-            .saturating_add(RocksDbWeight::get().reads(9 as Weight))
+            .saturating_add(RocksDbWeight::get().reads(11 as Weight))
             .saturating_add(RocksDbWeight::get().writes(5 as Weight))
     }
     fn cancel_withdrawal() -> Weight {
@@ -145,4 +209,56 @@ impl WeightInfo for () {
     fn force_set_referral_binding() -> Weight {
         (19_517_000 as Weight).saturating_add(RocksDbWeight::get().writes(1 as Weight))
     }
+    fn set_trustee_bond_config() -> Weight {
+        (3_214_000 as Weight).saturating_add(RocksDbWeight::get().writes(1 as Weight))
+    }
+    fn bond_trustee() -> Weight {
+        (45_672_000 as Weight)
+            .saturating_add(RocksDbWeight::get().reads(4 as Weight))
+            .saturating_add(RocksDbWeight::get().writes(2 as Weight))
+    }
+    fn unbond_trustee() -> Weight {
+        (28_941_000 as Weight)
+            .saturating_add(RocksDbWeight::get().reads(5 as Weight))
+            .saturating_add(RocksDbWeight::get().writes(1 as Weight))
+    }
+    fn withdraw_trustee_bond() -> Weight {
+        (41_387_000 as Weight)
+            .saturating_add(RocksDbWeight::get().reads(2 as Weight))
+            .saturating_add(RocksDbWeight::get().writes(2 as Weight))
+    }
+    fn slash_trustee() -> Weight {
+        (62_805_000 as Weight)
+            .saturating_add(RocksDbWeight::get().reads(3 as Weight))
+            .saturating_add(RocksDbWeight::get().writes(3 as Weight))
+    }
+    fn set_trustee_liveness_threshold() -> Weight {
+        (3_158_000 as Weight).saturating_add(RocksDbWeight::get().writes(1 as Weight))
+    }
+    fn replace_trustee() -> Weight {
+        (612_340_000 as Weight)
+            .saturating_add(RocksDbWeight::get().reads(36 as Weight))
+            .saturating_add(RocksDbWeight::get().writes(12 as Weight))
+    }
+    fn enable_withdrawal_allowlist() -> Weight {
+        (14_212_000 as Weight).saturating_add(RocksDbWeight::get().writes(2 as Weight))
+    }
+    fn disable_withdrawal_allowlist() -> Weight {
+        (19_874_000 as Weight)
+            .saturating_add(RocksDbWeight::get().reads(3 as Weight))
+            .saturating_add(RocksDbWeight::get().writes(1 as Weight))
+    }
+    fn add_withdrawal_allowlist_addr() -> Weight {
+        (31_560_000 as Weight)
+            .saturating_add(RocksDbWeight::get().reads(3 as Weight))
+            .saturating_add(RocksDbWeight::get().writes(1 as Weight))
+    }
+    fn remove_withdrawal_allowlist_addr() -> Weight {
+        (17_936_000 as Weight)
+            .saturating_add(RocksDbWeight::get().reads(1 as Weight))
+            .saturating_add(RocksDbWeight::get().writes(1 as Weight))
+    }
+    fn set_withdrawal_allowlist_delay() -> Weight {
+        (3_021_000 as Weight).saturating_add(RocksDbWeight::get().writes(1 as Weight))
+    }
 }
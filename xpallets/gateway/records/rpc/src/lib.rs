@@ -16,7 +16,8 @@ use sp_runtime::{generic::BlockId, traits::Block as BlockT};
 use xp_rpc::{runtime_error_into_rpc_err, Result};
 
 use xpallet_gateway_records_rpc_runtime_api::{
-    AssetId, Chain, Withdrawal, WithdrawalRecordId, WithdrawalState,
+    ArchivedWithdrawal, AssetId, Chain, Hash, Withdrawal, WithdrawalFilter, WithdrawalPage,
+    WithdrawalRecordId, WithdrawalState, WithdrawalStateTransition,
     XGatewayRecordsApi as GatewayRecordsRuntimeApi,
 };
 
@@ -62,6 +63,48 @@ where
         chain: Chain,
         at: Option<BlockHash>,
     ) -> Result<BTreeMap<WithdrawalRecordId, RpcWithdrawalRecord<AccountId, Balance, BlockNumber>>>;
+
+    /// Return a page of at most `limit` withdraw records matching the filter, starting after
+    /// the cursor `start_after` (the `next` of the previous page)
+    #[rpc(name = "xgatewayrecords_withdrawalPage")]
+    fn withdrawal_page(
+        &self,
+        filter: Option<WithdrawalFilter<AccountId, BlockNumber>>,
+        start_after: Option<WithdrawalRecordId>,
+        limit: u32,
+        at: Option<BlockHash>,
+    ) -> Result<RpcWithdrawalPage<AccountId, Balance, BlockNumber>>;
+
+    /// Return the withdraw record of the id with its state transitions, including the archived ones
+    #[rpc(name = "xgatewayrecords_withdrawalRecord")]
+    fn withdrawal_record(
+        &self,
+        id: WithdrawalRecordId,
+        at: Option<BlockHash>,
+    ) -> Result<Option<RpcWithdrawalRecordWithHistory<AccountId, Balance, BlockNumber>>>;
+
+    /// Return the finished or canceled withdraw record of the id in the archive
+    #[rpc(name = "xgatewayrecords_archivedWithdrawal")]
+    fn archived_withdrawal(
+        &self,
+        id: WithdrawalRecordId,
+        at: Option<BlockHash>,
+    ) -> Result<Option<RpcArchivedWithdrawal<AccountId, Balance, BlockNumber>>>;
+
+    /// Return at most `limit` archived withdraw records, starting after the id `start_after`
+    /// (the last id of the previous call)
+    #[rpc(name = "xgatewayrecords_archivedWithdrawals")]
+    fn archived_withdrawals(
+        &self,
+        start_after: Option<WithdrawalRecordId>,
+        limit: u32,
+        at: Option<BlockHash>,
+    ) -> Result<
+        Vec<(
+            WithdrawalRecordId,
+            RpcArchivedWithdrawal<AccountId, Balance, BlockNumber>,
+        )>,
+    >;
 }
 
 impl<C, Block, AccountId, Balance, BlockNumber>
@@ -127,6 +170,71 @@ where
             })
             .map_err(runtime_error_into_rpc_err)
     }
+
+    fn withdrawal_page(
+        &self,
+        filter: Option<WithdrawalFilter<AccountId, BlockNumber>>,
+        start_after: Option<WithdrawalRecordId>,
+        limit: u32,
+        at: Option<<Block as BlockT>::Hash>,
+    ) -> Result<RpcWithdrawalPage<AccountId, Balance, BlockNumber>> {
+        let api = self.client.runtime_api();
+        let at = BlockId::hash(at.unwrap_or_else(|| self.client.info().best_hash));
+        api.withdrawal_page(&at, filter.unwrap_or_default(), start_after, limit)
+            .map(Into::into)
+            .map_err(runtime_error_into_rpc_err)
+    }
+
+    fn withdrawal_record(
+        &self,
+        id: WithdrawalRecordId,
+        at: Option<<Block as BlockT>::Hash>,
+    ) -> Result<Option<RpcWithdrawalRecordWithHistory<AccountId, Balance, BlockNumber>>> {
+        let api = self.client.runtime_api();
+        let at = BlockId::hash(at.unwrap_or_else(|| self.client.info().best_hash));
+        api.withdrawal_with_history(&at, id)
+            .map(|detail| {
+                detail.map(|(withdrawal, history)| RpcWithdrawalRecordWithHistory {
+                    record: withdrawal.into(),
+                    history,
+                })
+            })
+            .map_err(runtime_error_into_rpc_err)
+    }
+
+    fn archived_withdrawal(
+        &self,
+        id: WithdrawalRecordId,
+        at: Option<<Block as BlockT>::Hash>,
+    ) -> Result<Option<RpcArchivedWithdrawal<AccountId, Balance, BlockNumber>>> {
+        let api = self.client.runtime_api();
+        let at = BlockId::hash(at.unwrap_or_else(|| self.client.info().best_hash));
+        api.archived_withdrawal(&at, id)
+            .map(|archived| archived.map(Into::into))
+            .map_err(runtime_error_into_rpc_err)
+    }
+
+    fn archived_withdrawals(
+        &self,
+        start_after: Option<WithdrawalRecordId>,
+        limit: u32,
+        at: Option<<Block as BlockT>::Hash>,
+    ) -> Result<
+        Vec<(
+            WithdrawalRecordId,
+            RpcArchivedWithdrawal<AccountId, Balance, BlockNumber>,
+        )>,
+    > {
+        let api = self.client.runtime_api();
+        let at = BlockId::hash(at.unwrap_or_else(|| self.client.info().best_hash));
+        api.archived_withdrawals(&at, start_after, limit)
+            .map(|list| {
+                list.into_iter()
+                    .map(|(id, archived)| (id, archived.into()))
+                    .collect()
+            })
+            .map_err(runtime_error_into_rpc_err)
+    }
 }
 
 #[derive(PartialEq, Eq, Clone, Serialize, Deserialize)]
@@ -158,3 +266,59 @@ impl<AccountId, Balance: Display + FromStr, BlockNumber>
         }
     }
 }
+
+#[derive(PartialEq, Eq, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RpcWithdrawalPage<AccountId, Balance: Display + FromStr, BlockNumber> {
+    pub withdrawals: Vec<(
+        WithdrawalRecordId,
+        RpcWithdrawalRecord<AccountId, Balance, BlockNumber>,
+    )>,
+    pub next: Option<WithdrawalRecordId>,
+}
+
+impl<AccountId, Balance: Display + FromStr, BlockNumber>
+    From<WithdrawalPage<AccountId, Balance, BlockNumber>>
+    for RpcWithdrawalPage<AccountId, Balance, BlockNumber>
+{
+    fn from(page: WithdrawalPage<AccountId, Balance, BlockNumber>) -> Self {
+        Self {
+            withdrawals: page
+                .withdrawals
+                .into_iter()
+                .map(|(id, withdrawal)| (id, withdrawal.into()))
+                .collect(),
+            next: page.next,
+        }
+    }
+}
+
+#[derive(PartialEq, Eq, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RpcWithdrawalRecordWithHistory<AccountId, Balance: Display + FromStr, BlockNumber> {
+    #[serde(flatten)]
+    pub record: RpcWithdrawalRecord<AccountId, Balance, BlockNumber>,
+    pub history: Vec<WithdrawalStateTransition<BlockNumber>>,
+}
+
+#[derive(PartialEq, Eq, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RpcArchivedWithdrawal<AccountId, Balance: Display + FromStr, BlockNumber> {
+    #[serde(flatten)]
+    pub record: RpcWithdrawalRecord<AccountId, Balance, BlockNumber>,
+    pub history: Vec<WithdrawalStateTransition<BlockNumber>>,
+    pub txid: Option<Hash>,
+}
+
+impl<AccountId, Balance: Display + FromStr, BlockNumber>
+    From<ArchivedWithdrawal<AccountId, Balance, BlockNumber>>
+    for RpcArchivedWithdrawal<AccountId, Balance, BlockNumber>
+{
+    fn from(archived: ArchivedWithdrawal<AccountId, Balance, BlockNumber>) -> Self {
+        Self {
+            record: archived.withdrawal.into(),
+            history: archived.history,
+            txid: archived.txid,
+        }
+    }
+}
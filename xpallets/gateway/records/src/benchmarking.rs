@@ -87,6 +87,51 @@ benchmarks! {
     verify {
         assert_eq!(XGatewayRecords::<T>::state_of(0), None);
     }
+    set_delayed_withdrawal_config {
+        let config = DelayedWithdrawalConfig {
+            threshold: 1000u32.into(),
+            delay: 100u32.into(),
+        };
+    }: _(RawOrigin::Root, ASSET_ID, Some(config))
+    verify {
+        assert_eq!(XGatewayRecords::<T>::delayed_withdrawal_config_of(ASSET_ID), Some(config));
+    }
+
+    set_withdrawal_guardian {
+        let caller: T::AccountId = whitelisted_caller();
+        let guardian: T::AccountId = frame_benchmarking::account("guardian", 0, 0);
+    }: _(RawOrigin::Signed(caller.clone()), Some(guardian.clone()))
+    verify {
+        assert_eq!(XGatewayRecords::<T>::withdrawal_guardian(caller), Some(guardian));
+    }
+
+    set_withdrawal_archive_capacity {
+    }: _(RawOrigin::Root, 100)
+    verify {
+        assert_eq!(XGatewayRecords::<T>::withdrawal_archive_capacity(), 100);
+    }
+
+    prune_withdrawal_archive {
+        let n in 1 .. 1000;
+
+        let receiver: T::AccountId = whitelisted_caller();
+        let amount: BalanceOf<T> = 1000u32.into();
+        deposit_and_withdraw::<T>(receiver, amount);
+        let record = XGatewayRecords::<T>::pending_withdrawals(0).unwrap();
+        for i in 0..n {
+            ArchivedWithdrawals::<T>::insert(i, ArchivedWithdrawal {
+                withdrawal: Withdrawal::new(record.clone(), WithdrawalState::RootFinish),
+                history: vec![],
+                txid: None,
+            });
+            WithdrawalArchiveOrder::<T>::insert(i, i);
+        }
+        WithdrawalArchiveRange::<T>::put((0, n));
+    }: _(RawOrigin::Root, n)
+    verify {
+        assert_eq!(WithdrawalArchiveRange::<T>::get(), (n, n));
+        assert!(XGatewayRecords::<T>::archived_withdrawal(0).is_none());
+    }
 }
 
 #[cfg(test)]
@@ -101,6 +146,10 @@ mod tests {
             assert_ok!(Pallet::<Test>::test_benchmark_root_deposit());
             assert_ok!(Pallet::<Test>::test_benchmark_root_withdraw());
             assert_ok!(Pallet::<Test>::test_benchmark_set_withdrawal_state());
+            assert_ok!(Pallet::<Test>::test_benchmark_set_delayed_withdrawal_config());
+            assert_ok!(Pallet::<Test>::test_benchmark_set_withdrawal_guardian());
+            assert_ok!(Pallet::<Test>::test_benchmark_set_withdrawal_archive_capacity());
+            assert_ok!(Pallet::<Test>::test_benchmark_prune_withdrawal_archive());
         });
     }
 }
@@ -21,14 +21,17 @@ use frame_support::{
     transactional,
 };
 use frame_system::ensure_root;
-use sp_runtime::traits::StaticLookup;
+use sp_runtime::traits::{Saturating, StaticLookup, Zero};
 
-use chainx_primitives::{AddrStr, AssetId};
+use chainx_primitives::{AddrStr, AssetId, Hash};
 use xp_runtime::Memo;
 use xpallet_assets::{AssetType, BalanceOf, Chain};
 use xpallet_support::try_addr;
 
-pub use self::types::{Withdrawal, WithdrawalRecord, WithdrawalRecordId, WithdrawalState};
+pub use self::types::{
+    ArchivedWithdrawal, DelayedWithdrawalConfig, Withdrawal, WithdrawalFilter, WithdrawalPage,
+    WithdrawalRecord, WithdrawalRecordId, WithdrawalState, WithdrawalStateTransition,
+};
 pub use self::weights::WeightInfo;
 
 pub type WithdrawalRecordOf<T> = WithdrawalRecord<
@@ -37,6 +40,15 @@ pub type WithdrawalRecordOf<T> = WithdrawalRecord<
     <T as frame_system::Config>::BlockNumber,
 >;
 
+pub type ArchivedWithdrawalOf<T> = ArchivedWithdrawal<
+    <T as frame_system::Config>::AccountId,
+    BalanceOf<T>,
+    <T as frame_system::Config>::BlockNumber,
+>;
+
+/// The maximum number of withdrawal records returned in a page.
+pub const MAX_WITHDRAWAL_PAGE_SIZE: u32 = 1000;
+
 pub use pallet::*;
 
 #[frame_support::pallet]
@@ -50,6 +62,9 @@ pub mod pallet {
         /// The overarching event type.
         type Event: From<Event<Self>> + IsType<<Self as frame_system::Config>::Event>;
 
+        /// A majority of the council can excute some transactions.
+        type CouncilOrigin: EnsureOrigin<Self::Origin>;
+
         /// Weight information for extrinsics in this pallet.
         type WeightInfo: WeightInfo;
     }
@@ -59,6 +74,22 @@ pub mod pallet {
     #[pallet::without_storage_info]
     pub struct Pallet<T>(PhantomData<T>);
 
+    #[pallet::hooks]
+    impl<T: Config> Hooks<BlockNumberFor<T>> for Pallet<T> {
+        fn on_initialize(now: T::BlockNumber) -> Weight {
+            let ids = DelayedWithdrawals::<T>::take(now);
+            let count = ids.len() as u32;
+            for id in ids {
+                // the withdrawal may be canceled during the delay
+                if Self::state_of(id) == Some(WithdrawalState::Delayed) {
+                    Self::set_state(id, WithdrawalState::Applying);
+                    Self::deposit_event(Event::<T>::WithdrawalDelayEnded(id));
+                }
+            }
+            <T as Config>::WeightInfo::release_delayed_withdrawals(count)
+        }
+    }
+
     #[pallet::call]
     impl<T: Config> Pallet<T> {
         /// Deposit asset token.
@@ -122,6 +153,76 @@ pub mod pallet {
             }
             Ok(())
         }
+
+        /// Set the threshold from which the withdrawals of the asset are delayed, `None` means
+        /// no withdrawal is delayed.
+        ///
+        /// This is called by the council or root.
+        #[pallet::weight(<T as Config>::WeightInfo::set_delayed_withdrawal_config())]
+        pub fn set_delayed_withdrawal_config(
+            origin: OriginFor<T>,
+            #[pallet::compact] asset_id: AssetId,
+            config: Option<DelayedWithdrawalConfig<BalanceOf<T>, T::BlockNumber>>,
+        ) -> DispatchResult {
+            T::CouncilOrigin::try_origin(origin)
+                .map(|_| ())
+                .or_else(ensure_root)?;
+            match config {
+                Some(config) => DelayedWithdrawalConfigOf::<T>::insert(asset_id, config),
+                None => DelayedWithdrawalConfigOf::<T>::remove(asset_id),
+            }
+            Ok(())
+        }
+
+        /// Set the guardian which could cancel the delayed withdrawals of the caller, `None`
+        /// removes the guardian.
+        #[pallet::weight(<T as Config>::WeightInfo::set_withdrawal_guardian())]
+        pub fn set_withdrawal_guardian(
+            origin: OriginFor<T>,
+            guardian: Option<T::AccountId>,
+        ) -> DispatchResult {
+            let who = ensure_signed(origin)?;
+            match guardian {
+                Some(ref guardian) => WithdrawalGuardian::<T>::insert(&who, guardian),
+                None => WithdrawalGuardian::<T>::remove(&who),
+            }
+            Self::deposit_event(Event::<T>::WithdrawalGuardianSet(who, guardian));
+            Ok(())
+        }
+
+        /// Set the maximum number of the archived withdrawals, `0` stops archiving.
+        ///
+        /// A full archive replaces its oldest entry with the newly archived withdrawal, the
+        /// entries beyond a reduced capacity are removed with `prune_withdrawal_archive`.
+        ///
+        /// This is called by the council or root.
+        #[pallet::weight(<T as Config>::WeightInfo::set_withdrawal_archive_capacity())]
+        pub fn set_withdrawal_archive_capacity(
+            origin: OriginFor<T>,
+            #[pallet::compact] capacity: u32,
+        ) -> DispatchResult {
+            T::CouncilOrigin::try_origin(origin)
+                .map(|_| ())
+                .or_else(ensure_root)?;
+            WithdrawalArchiveCapacity::<T>::put(capacity);
+            Ok(())
+        }
+
+        /// Remove at most `count` oldest withdrawals from the archive.
+        ///
+        /// This is called by the council or root.
+        #[pallet::weight(<T as Config>::WeightInfo::prune_withdrawal_archive(*count))]
+        pub fn prune_withdrawal_archive(
+            origin: OriginFor<T>,
+            #[pallet::compact] count: u32,
+        ) -> DispatchResult {
+            T::CouncilOrigin::try_origin(origin)
+                .map(|_| ())
+                .or_else(ensure_root)?;
+            let pruned = Self::prune_archive(count);
+            Self::deposit_event(Event::<T>::WithdrawalArchivePruned(pruned));
+            Ok(())
+        }
     }
 
     #[pallet::event]
@@ -139,6 +240,14 @@ pub mod pallet {
         WithdrawalCanceled(WithdrawalRecordId, WithdrawalState),
         /// A withdrawal proposal was finished successfully. [withdrawal_id, withdrawal_state]
         WithdrawalFinished(WithdrawalRecordId, WithdrawalState),
+        /// A large withdrawal was delayed. [withdrawal_id, delayed_until]
+        WithdrawalDelayed(WithdrawalRecordId, T::BlockNumber),
+        /// The delay of a withdrawal ended, it could be processed. [withdrawal_id]
+        WithdrawalDelayEnded(WithdrawalRecordId),
+        /// An account set the guardian of its delayed withdrawals. [who, guardian]
+        WithdrawalGuardianSet(T::AccountId, Option<T::AccountId>),
+        /// Some oldest withdrawals were removed from the archive. [count]
+        WithdrawalArchivePruned(u32),
     }
 
     #[pallet::error]
@@ -179,6 +288,61 @@ pub mod pallet {
     #[pallet::getter(fn state_of)]
     pub(crate) type WithdrawalStateOf<T: Config> =
         StorageMap<_, Twox64Concat, WithdrawalRecordId, WithdrawalState>;
+
+    /// The state transitions of a pending withdrawal record.
+    #[pallet::storage]
+    #[pallet::getter(fn state_history_of)]
+    pub(crate) type WithdrawalStateHistory<T: Config> = StorageMap<
+        _,
+        Twox64Concat,
+        WithdrawalRecordId,
+        Vec<WithdrawalStateTransition<T::BlockNumber>>,
+        ValueQuery,
+    >;
+
+    #[pallet::type_value]
+    pub fn DefaultForWithdrawalArchiveCapacity<T: Config>() -> u32 {
+        10_000
+    }
+
+    /// The maximum number of the archived withdrawals.
+    #[pallet::storage]
+    #[pallet::getter(fn withdrawal_archive_capacity)]
+    pub(crate) type WithdrawalArchiveCapacity<T: Config> =
+        StorageValue<_, u32, ValueQuery, DefaultForWithdrawalArchiveCapacity<T>>;
+
+    /// The finished or canceled withdrawals.
+    #[pallet::storage]
+    #[pallet::getter(fn archived_withdrawal)]
+    pub(crate) type ArchivedWithdrawals<T: Config> =
+        StorageMap<_, Twox64Concat, WithdrawalRecordId, ArchivedWithdrawalOf<T>>;
+
+    /// The withdrawal ids in the archive by the order of archiving.
+    #[pallet::storage]
+    pub(crate) type WithdrawalArchiveOrder<T: Config> =
+        StorageMap<_, Twox64Concat, u32, WithdrawalRecordId>;
+
+    /// The range `[first, next)` of the archiving order in `WithdrawalArchiveOrder`.
+    #[pallet::storage]
+    pub(crate) type WithdrawalArchiveRange<T: Config> = StorageValue<_, (u32, u32), ValueQuery>;
+
+    /// The delayed withdrawal config of an asset.
+    #[pallet::storage]
+    #[pallet::getter(fn delayed_withdrawal_config_of)]
+    pub(crate) type DelayedWithdrawalConfigOf<T: Config> =
+        StorageMap<_, Twox64Concat, AssetId, DelayedWithdrawalConfig<BalanceOf<T>, T::BlockNumber>>;
+
+    /// The delayed withdrawals which could be processed from the block.
+    #[pallet::storage]
+    #[pallet::getter(fn delayed_withdrawals)]
+    pub(crate) type DelayedWithdrawals<T: Config> =
+        StorageMap<_, Twox64Concat, T::BlockNumber, Vec<WithdrawalRecordId>, ValueQuery>;
+
+    /// The account which could cancel the delayed withdrawals of the applicant.
+    #[pallet::storage]
+    #[pallet::getter(fn withdrawal_guardian)]
+    pub(crate) type WithdrawalGuardian<T: Config> =
+        StorageMap<_, Blake2_128Concat, T::AccountId, T::AccountId>;
 }
 
 impl<T: Config> Pallet<T> {
@@ -208,6 +372,63 @@ impl<T: Config> Pallet<T> {
         let state = Self::state_of(id).ok_or(Error::<T>::NotExisted)?;
         Ok((record, state))
     }
+
+    fn set_state(id: WithdrawalRecordId, state: WithdrawalState) {
+        WithdrawalStateOf::<T>::insert(id, state);
+        let height = frame_system::Pallet::<T>::block_number();
+        WithdrawalStateHistory::<T>::append(id, WithdrawalStateTransition { state, height });
+    }
+
+    /// Remove the withdrawal record and move it into the archive with the final state.
+    fn archive_record(
+        id: WithdrawalRecordId,
+        record: WithdrawalRecordOf<T>,
+        final_state: WithdrawalState,
+    ) {
+        PendingWithdrawals::<T>::remove(id);
+        WithdrawalStateOf::<T>::remove(id);
+        let mut history = WithdrawalStateHistory::<T>::take(id);
+
+        let capacity = Self::withdrawal_archive_capacity();
+        if capacity == 0 {
+            return;
+        }
+        let (first, next) = WithdrawalArchiveRange::<T>::get();
+        if next.wrapping_sub(first) >= capacity {
+            // replace the oldest one, the archive beyond a reduced capacity is pruned by
+            // `prune_withdrawal_archive`
+            Self::prune_archive(1);
+        }
+
+        history.push(WithdrawalStateTransition {
+            state: final_state,
+            height: frame_system::Pallet::<T>::block_number(),
+        });
+        let archived = ArchivedWithdrawal {
+            withdrawal: Withdrawal::new(record, final_state),
+            history,
+            txid: None,
+        };
+        ArchivedWithdrawals::<T>::insert(id, archived);
+        let (first, next) = WithdrawalArchiveRange::<T>::get();
+        WithdrawalArchiveOrder::<T>::insert(next, id);
+        WithdrawalArchiveRange::<T>::put((first, next.wrapping_add(1)));
+    }
+
+    /// Remove at most `count` oldest withdrawals from the archive, return the number of the
+    /// removed ones.
+    fn prune_archive(count: u32) -> u32 {
+        let (mut first, next) = WithdrawalArchiveRange::<T>::get();
+        let count = count.min(next.wrapping_sub(first));
+        for _ in 0..count {
+            if let Some(id) = WithdrawalArchiveOrder::<T>::take(first) {
+                ArchivedWithdrawals::<T>::remove(id);
+            }
+            first = first.wrapping_add(1);
+        }
+        WithdrawalArchiveRange::<T>::put((first, next));
+        count
+    }
 }
 
 impl<T: Config> Pallet<T> {
@@ -230,7 +451,8 @@ impl<T: Config> Pallet<T> {
 
     /// Withdrawal asset (lock asset token firstly, follow-up operations are required).
     ///
-    /// WithdrawalRecord State: `Applying`
+    /// WithdrawalRecord State: `Applying`, or `Delayed` if the balance reaches the delayed
+    /// withdrawal threshold of the asset.
     ///
     /// NOTE: this function has included withdrawal_init and withdrawal_locking.
     pub fn withdraw(
@@ -258,16 +480,32 @@ impl<T: Config> Pallet<T> {
         let record =
             WithdrawalRecordOf::<T>::new(who.clone(), asset_id, balance, addr, ext, height);
 
+        let delayed_until = match Self::delayed_withdrawal_config_of(asset_id) {
+            Some(config) if balance >= config.threshold && !config.delay.is_zero() => {
+                Some(height.saturating_add(config.delay))
+            }
+            _ => None,
+        };
+
         // Lock usable asset token
         Self::lock(record.applicant(), record.asset_id(), record.balance())?;
 
         // Set storages
         PendingWithdrawals::<T>::insert(id, record.clone());
-        WithdrawalStateOf::<T>::insert(id, WithdrawalState::Applying);
+        let state = match delayed_until {
+            Some(_) => WithdrawalState::Delayed,
+            None => WithdrawalState::Applying,
+        };
+        Self::set_state(id, state);
         let next_id = id.checked_add(1_u32).unwrap_or(0);
         NextWithdrawalRecordId::<T>::put(next_id);
 
         Self::deposit_event(Event::<T>::WithdrawalCreated(id, record));
+
+        if let Some(delayed_until) = delayed_until {
+            DelayedWithdrawals::<T>::append(delayed_until, id);
+            Self::deposit_event(Event::<T>::WithdrawalDelayed(id, delayed_until));
+        }
         Ok(())
     }
 
@@ -292,7 +530,7 @@ impl<T: Config> Pallet<T> {
             );
             return Err(Error::<T>::NotApplyingState.into());
         }
-        WithdrawalStateOf::<T>::insert(id, WithdrawalState::Processing);
+        Self::set_state(id, WithdrawalState::Processing);
         Self::deposit_event(Event::<T>::WithdrawalProcessed(id));
         Ok(())
     }
@@ -327,17 +565,20 @@ impl<T: Config> Pallet<T> {
             );
             return Err(Error::<T>::NotProcessingState.into());
         }
-        WithdrawalStateOf::<T>::insert(id, WithdrawalState::Applying);
+        Self::set_state(id, WithdrawalState::Applying);
         Self::deposit_event(Event::<T>::WithdrawalRecovered(id));
         Ok(())
     }
 
-    /// Cancel withdrawal
+    /// Cancel withdrawal, the delayed withdrawal could also be canceled by the guardian of the
+    /// applicant.
     ///
-    /// WithdrawalRecord State: `Applying` ==> `NormalCancel`
+    /// WithdrawalRecord State: `Applying`|`Delayed` ==> `NormalCancel`
     pub fn cancel_withdrawal(id: WithdrawalRecordId, who: &T::AccountId) -> DispatchResult {
         let (record, curr_state) = Self::ensure_withdrawal_records_exists(id)?;
-        if record.applicant() != who {
+        let is_guardian = curr_state == WithdrawalState::Delayed
+            && Self::withdrawal_guardian(record.applicant()).as_ref() == Some(who);
+        if record.applicant() != who && !is_guardian {
             error!(
                 target: "runtime::gateway::records",
                 "[cancel_withdrawal] id:{}, account {:?} is not the applicant {:?}",
@@ -357,10 +598,10 @@ impl<T: Config> Pallet<T> {
         curr_state: WithdrawalState,
         new_state: WithdrawalState,
     ) -> DispatchResult {
-        if curr_state != WithdrawalState::Applying {
+        if curr_state != WithdrawalState::Applying && curr_state != WithdrawalState::Delayed {
             error!(
                 target: "runtime::gateway::records",
-                "[cancel_withdrawal] id:{}, current withdrawal state ({:?}) must be `Applying`",
+                "[cancel_withdrawal] id:{}, current withdrawal state ({:?}) must be `Applying` or `Delayed`",
                 id, curr_state
             );
             return Err(Error::<T>::NotApplyingState.into());
@@ -370,8 +611,7 @@ impl<T: Config> Pallet<T> {
         Self::unlock(record.applicant(), record.asset_id(), record.balance())?;
 
         // Remove storage
-        PendingWithdrawals::<T>::remove(id);
-        WithdrawalStateOf::<T>::remove(id);
+        Self::archive_record(id, record, new_state);
 
         Self::deposit_event(Event::<T>::WithdrawalCanceled(id, new_state));
         Ok(())
@@ -420,8 +660,7 @@ impl<T: Config> Pallet<T> {
         Self::destroy(record.applicant(), record.asset_id(), record.balance())?;
 
         // Remove storage
-        PendingWithdrawals::<T>::remove(id);
-        WithdrawalStateOf::<T>::remove(id);
+        Self::archive_record(id, record, new_state);
 
         Self::deposit_event(Event::<T>::WithdrawalFinished(id, new_state));
         Ok(())
@@ -454,9 +693,17 @@ impl<T: Config> Pallet<T> {
                 // State: `Processing` ==> `Applying`
                 Self::recover_withdrawal_impl(id, curr_state)
             }
+            (WithdrawalState::Delayed, WithdrawalState::Applying) => {
+                // State: `Delayed` ==> `Applying`
+                Self::set_state(id, WithdrawalState::Applying);
+                Self::deposit_event(Event::<T>::WithdrawalDelayEnded(id));
+                Ok(())
+            }
             (WithdrawalState::Applying, WithdrawalState::NormalCancel)
-            | (WithdrawalState::Applying, WithdrawalState::RootCancel) => {
-                // State: `Applying` ==> `NormalCancel`|`RootCancel`
+            | (WithdrawalState::Applying, WithdrawalState::RootCancel)
+            | (WithdrawalState::Delayed, WithdrawalState::NormalCancel)
+            | (WithdrawalState::Delayed, WithdrawalState::RootCancel) => {
+                // State: `Applying`|`Delayed` ==> `NormalCancel`|`RootCancel`
                 Self::cancel_withdrawal_impl(id, record, curr_state, new_state)
             }
             (WithdrawalState::Applying, WithdrawalState::NormalFinish)
@@ -575,7 +822,114 @@ impl<T: Config> Pallet<T> {
             .collect()
     }
 
+    /// Return a page of the withdrawal records which match the `filter`, starting after the
+    /// record `start_after`.
+    ///
+    /// The records are in the order of the storage keys rather than the ids, so the cursor
+    /// `next` of the page should be passed as `start_after` to get the following page.
+    pub fn withdrawal_page(
+        filter: WithdrawalFilter<T::AccountId, T::BlockNumber>,
+        start_after: Option<WithdrawalRecordId>,
+        limit: u32,
+    ) -> WithdrawalPage<T::AccountId, BalanceOf<T>, T::BlockNumber> {
+        let limit = limit.clamp(1, MAX_WITHDRAWAL_PAGE_SIZE) as usize;
+        let iter = match start_after {
+            Some(id) => {
+                PendingWithdrawals::<T>::iter_from(PendingWithdrawals::<T>::hashed_key_for(id))
+            }
+            None => PendingWithdrawals::<T>::iter(),
+        };
+
+        let mut withdrawals = Vec::new();
+        let mut next = None;
+        for (id, record) in iter {
+            let state = Self::state_of(id).unwrap_or_default();
+            if !Self::match_filter(&filter, &record, state) {
+                continue;
+            }
+            if withdrawals.len() == limit {
+                next = withdrawals.last().map(|(id, _)| *id);
+                break;
+            }
+            withdrawals.push((id, Withdrawal::new(record, state)));
+        }
+        WithdrawalPage { withdrawals, next }
+    }
+
+    fn match_filter(
+        filter: &WithdrawalFilter<T::AccountId, T::BlockNumber>,
+        record: &WithdrawalRecordOf<T>,
+        state: WithdrawalState,
+    ) -> bool {
+        filter
+            .applicant
+            .as_ref()
+            .map_or(true, |who| who == record.applicant())
+            && filter
+                .asset_id
+                .map_or(true, |asset_id| asset_id == record.asset_id())
+            && filter.state.map_or(true, |s| s == state)
+            && filter
+                .from_height
+                .map_or(true, |height| record.height() >= height)
+            && filter
+                .to_height
+                .map_or(true, |height| record.height() <= height)
+            && filter.chain.map_or(true, |chain| {
+                Self::ensure_asset_belongs_to_chain(record.asset_id(), chain).is_ok()
+            })
+    }
+
+    /// Return the withdrawal record and its state transitions, the finished or canceled one is
+    /// looked up in the archive.
+    pub fn withdrawal_with_history(
+        id: WithdrawalRecordId,
+    ) -> Option<(
+        Withdrawal<T::AccountId, BalanceOf<T>, T::BlockNumber>,
+        Vec<WithdrawalStateTransition<T::BlockNumber>>,
+    )> {
+        match Self::pending_withdrawals(id) {
+            Some(record) => {
+                let state = Self::state_of(id).unwrap_or_default();
+                Some((Withdrawal::new(record, state), Self::state_history_of(id)))
+            }
+            None => Self::archived_withdrawal(id)
+                .map(|archived| (archived.withdrawal, archived.history)),
+        }
+    }
+
+    /// Record the txid of the transaction which settled the archived withdrawals.
+    pub fn settle_archived_withdrawals(ids: &[WithdrawalRecordId], txid: Hash) {
+        for id in ids {
+            ArchivedWithdrawals::<T>::mutate(id, |archived| {
+                if let Some(archived) = archived {
+                    archived.txid = Some(txid);
+                }
+            });
+        }
+    }
+
+    /// Return at most `limit` archived withdrawals, starting after the withdrawal
+    /// `start_after`.
+    ///
+    /// The withdrawals are in the order of the storage keys, the id of the last one should be
+    /// passed as `start_after` to get the following ones.
+    pub fn archived_withdrawals(
+        start_after: Option<WithdrawalRecordId>,
+        limit: u32,
+    ) -> Vec<(WithdrawalRecordId, ArchivedWithdrawalOf<T>)> {
+        let limit = limit.min(MAX_WITHDRAWAL_PAGE_SIZE) as usize;
+        match start_after {
+            Some(id) => {
+                ArchivedWithdrawals::<T>::iter_from(ArchivedWithdrawals::<T>::hashed_key_for(id))
+                    .take(limit)
+                    .collect()
+            }
+            None => ArchivedWithdrawals::<T>::iter().take(limit).collect(),
+        }
+    }
+
     pub fn withdrawal_state_insert(id: WithdrawalRecordId, state: WithdrawalState) {
-        WithdrawalStateOf::<T>::insert(id, state)
+        Self::set_state(id, state)
     }
 }
@@ -103,11 +103,13 @@ impl xpallet_assets::Config for Test {
     type TreasuryAccount = ();
     type OnCreatedAccount = frame_system::Provider<Test>;
     type OnAssetChanged = ();
+    type AssetLedger = ();
     type WeightInfo = ();
 }
 
 impl Config for Test {
     type Event = ();
+    type CouncilOrigin = frame_system::EnsureSigned<AccountId>;
     type WeightInfo = ();
 }
 
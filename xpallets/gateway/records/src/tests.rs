@@ -3,7 +3,7 @@
 pub use super::mock::*;
 use super::*;
 
-use frame_support::{assert_noop, assert_ok};
+use frame_support::{assert_noop, assert_ok, traits::Hooks};
 use frame_system::RawOrigin;
 
 #[test]
@@ -206,3 +206,304 @@ fn test_withdrawal_chainx() {
         );
     })
 }
+
+#[test]
+fn test_delayed_withdrawal() {
+    ExtBuilder::default().build_and_execute(|| {
+        assert_ok!(XGatewayRecords::deposit(&ALICE, X_BTC, 100));
+        assert_ok!(XGatewayRecords::set_delayed_withdrawal_config(
+            RawOrigin::Signed(ALICE).into(),
+            X_BTC,
+            Some(DelayedWithdrawalConfig {
+                threshold: 50,
+                delay: 10,
+            })
+        ));
+
+        // below the threshold
+        assert_ok!(XGatewayRecords::withdraw(
+            &ALICE,
+            X_BTC,
+            30,
+            b"addr".to_vec(),
+            b"ext".to_vec().into()
+        ));
+        assert_eq!(
+            XGatewayRecords::state_of(0),
+            Some(WithdrawalState::Applying)
+        );
+
+        // reach the threshold
+        assert_ok!(XGatewayRecords::withdraw(
+            &ALICE,
+            X_BTC,
+            60,
+            b"addr".to_vec(),
+            b"ext".to_vec().into()
+        ));
+        assert_ok!(XGatewayRecords::withdraw(
+            &ALICE,
+            X_BTC,
+            60,
+            b"addr".to_vec(),
+            b"ext".to_vec().into()
+        ));
+        assert_eq!(XGatewayRecords::state_of(1), Some(WithdrawalState::Delayed));
+        assert_eq!(XGatewayRecords::state_of(2), Some(WithdrawalState::Delayed));
+        assert_eq!(XGatewayRecords::delayed_withdrawals(11), vec![1, 2]);
+        // the delayed withdrawal never enters `Applying` before the delay ends
+        assert_eq!(
+            XGatewayRecords::state_history_of(2),
+            vec![WithdrawalStateTransition {
+                state: WithdrawalState::Delayed,
+                height: 1,
+            }]
+        );
+        assert_noop!(
+            XGatewayRecords::process_withdrawals(&[1], Chain::Bitcoin),
+            XRecordsErr::NotApplyingState
+        );
+
+        // the guardian could only cancel the delayed withdrawals
+        assert_ok!(XGatewayRecords::set_withdrawal_guardian(
+            RawOrigin::Signed(ALICE).into(),
+            Some(BOB)
+        ));
+        assert_noop!(
+            XGatewayRecords::cancel_withdrawal(0, &BOB),
+            XRecordsErr::InvalidAccount
+        );
+        assert_noop!(
+            XGatewayRecords::cancel_withdrawal(1, &CHARLIE),
+            XRecordsErr::InvalidAccount
+        );
+        assert_ok!(XGatewayRecords::cancel_withdrawal(1, &BOB));
+        assert_eq!(XGatewayRecords::state_of(1), None);
+        assert_eq!(XAssets::usable_balance(&ALICE, &X_BTC), 200 - 30 - 60);
+
+        XGatewayRecords::on_initialize(10);
+        assert_eq!(XGatewayRecords::state_of(2), Some(WithdrawalState::Delayed));
+        XGatewayRecords::on_initialize(11);
+        assert_eq!(
+            XGatewayRecords::state_of(2),
+            Some(WithdrawalState::Applying)
+        );
+        assert!(XGatewayRecords::delayed_withdrawals(11).is_empty());
+        assert_eq!(
+            XGatewayRecords::state_history_of(2),
+            vec![
+                WithdrawalStateTransition {
+                    state: WithdrawalState::Delayed,
+                    height: 1,
+                },
+                WithdrawalStateTransition {
+                    state: WithdrawalState::Applying,
+                    height: 1,
+                },
+            ]
+        );
+        assert_ok!(XGatewayRecords::process_withdrawals(&[2], Chain::Bitcoin));
+        assert_noop!(
+            XGatewayRecords::cancel_withdrawal(2, &BOB),
+            XRecordsErr::InvalidAccount
+        );
+    })
+}
+
+#[test]
+fn test_withdrawal_page_and_history() {
+    ExtBuilder::default().build_and_execute(|| {
+        let withdrawals = [
+            (ALICE, X_BTC, 10),
+            (BOB, X_BTC, 20),
+            (ALICE, X_ETH, 30),
+            (CHARLIE, X_BTC, 40),
+        ];
+        for (i, (who, asset_id, balance)) in withdrawals.into_iter().enumerate() {
+            System::set_block_number(i as u64 + 1);
+            assert_ok!(XGatewayRecords::withdraw(
+                &who,
+                asset_id,
+                balance,
+                b"addr".to_vec(),
+                b"ext".to_vec().into()
+            ));
+        }
+        System::set_block_number(5);
+        assert_ok!(XGatewayRecords::process_withdrawal(1, Chain::Bitcoin));
+
+        let ids_of = |filter: WithdrawalFilter<AccountId, u64>| {
+            let mut ids = XGatewayRecords::withdrawal_page(filter, None, 10)
+                .withdrawals
+                .into_iter()
+                .map(|(id, _)| id)
+                .collect::<Vec<_>>();
+            ids.sort_unstable();
+            ids
+        };
+        assert_eq!(ids_of(Default::default()), vec![0, 1, 2, 3]);
+        assert_eq!(
+            ids_of(WithdrawalFilter {
+                applicant: Some(ALICE),
+                ..Default::default()
+            }),
+            vec![0, 2]
+        );
+        assert_eq!(
+            ids_of(WithdrawalFilter {
+                asset_id: Some(X_ETH),
+                ..Default::default()
+            }),
+            vec![2]
+        );
+        assert_eq!(
+            ids_of(WithdrawalFilter {
+                chain: Some(Chain::Bitcoin),
+                ..Default::default()
+            }),
+            vec![0, 1, 3]
+        );
+        assert_eq!(
+            ids_of(WithdrawalFilter {
+                state: Some(WithdrawalState::Processing),
+                ..Default::default()
+            }),
+            vec![1]
+        );
+        assert_eq!(
+            ids_of(WithdrawalFilter {
+                from_height: Some(2),
+                to_height: Some(3),
+                ..Default::default()
+            }),
+            vec![1, 2]
+        );
+
+        // walk through the pages with the cursor
+        let mut ids = vec![];
+        let mut start_after = None;
+        loop {
+            let page = XGatewayRecords::withdrawal_page(Default::default(), start_after, 3);
+            assert!(page.withdrawals.len() <= 3);
+            ids.extend(page.withdrawals.into_iter().map(|(id, _)| id));
+            match page.next {
+                Some(next) => start_after = Some(next),
+                None => break,
+            }
+        }
+        ids.sort_unstable();
+        assert_eq!(ids, vec![0, 1, 2, 3]);
+
+        // state history
+        let (withdrawal, history) = XGatewayRecords::withdrawal_with_history(1).unwrap();
+        assert_eq!(withdrawal.state, WithdrawalState::Processing);
+        assert_eq!(
+            history,
+            vec![
+                WithdrawalStateTransition {
+                    state: WithdrawalState::Applying,
+                    height: 2,
+                },
+                WithdrawalStateTransition {
+                    state: WithdrawalState::Processing,
+                    height: 5,
+                },
+            ]
+        );
+
+        // the canceled withdrawal is looked up in the archive
+        assert_ok!(XGatewayRecords::cancel_withdrawal(0, &ALICE));
+        assert!(XGatewayRecords::state_history_of(0).is_empty());
+        let (withdrawal, history) = XGatewayRecords::withdrawal_with_history(0).unwrap();
+        assert_eq!(withdrawal.state, WithdrawalState::NormalCancel);
+        assert_eq!(
+            history,
+            vec![
+                WithdrawalStateTransition {
+                    state: WithdrawalState::Applying,
+                    height: 1,
+                },
+                WithdrawalStateTransition {
+                    state: WithdrawalState::NormalCancel,
+                    height: 5,
+                },
+            ]
+        );
+        assert!(XGatewayRecords::withdrawal_with_history(4).is_none());
+    })
+}
+
+#[test]
+fn test_withdrawal_archive() {
+    ExtBuilder::default().build_and_execute(|| {
+        assert_ok!(XGatewayRecords::set_withdrawal_archive_capacity(
+            RawOrigin::Signed(ALICE).into(),
+            2
+        ));
+        for _ in 0..3 {
+            assert_ok!(XGatewayRecords::withdraw(
+                &ALICE,
+                X_BTC,
+                10,
+                b"addr".to_vec(),
+                b"ext".to_vec().into()
+            ));
+        }
+
+        System::set_block_number(3);
+        assert_ok!(XGatewayRecords::cancel_withdrawal(0, &ALICE));
+        let archived = XGatewayRecords::archived_withdrawal(0).unwrap();
+        assert_eq!(archived.withdrawal.state, WithdrawalState::NormalCancel);
+        assert_eq!(
+            archived.history,
+            vec![
+                WithdrawalStateTransition {
+                    state: WithdrawalState::Applying,
+                    height: 1,
+                },
+                WithdrawalStateTransition {
+                    state: WithdrawalState::NormalCancel,
+                    height: 3,
+                },
+            ]
+        );
+        assert_eq!(archived.txid, None);
+
+        assert_ok!(XGatewayRecords::process_withdrawal(1, Chain::Bitcoin));
+        System::set_block_number(4);
+        assert_ok!(XGatewayRecords::finish_withdrawal(1, None));
+        XGatewayRecords::settle_archived_withdrawals(&[1], Hash::repeat_byte(1));
+        let archived = XGatewayRecords::archived_withdrawal(1).unwrap();
+        assert_eq!(archived.withdrawal.state, WithdrawalState::NormalFinish);
+        assert_eq!(archived.history.len(), 3);
+        assert_eq!(archived.txid, Some(Hash::repeat_byte(1)));
+
+        // the oldest one is replaced when the archive is full
+        assert_ok!(XGatewayRecords::cancel_withdrawal(2, &ALICE));
+        assert!(XGatewayRecords::archived_withdrawal(0).is_none());
+        assert!(XGatewayRecords::archived_withdrawal(2).is_some());
+        assert_eq!(XGatewayRecords::archived_withdrawals(None, 10).len(), 2);
+
+        assert_ok!(XGatewayRecords::prune_withdrawal_archive(
+            RawOrigin::Signed(ALICE).into(),
+            5
+        ));
+        assert!(XGatewayRecords::archived_withdrawals(None, 10).is_empty());
+        assert_eq!(WithdrawalArchiveRange::<Test>::get(), (3, 3));
+
+        // stop archiving
+        assert_ok!(XGatewayRecords::set_withdrawal_archive_capacity(
+            RawOrigin::Signed(ALICE).into(),
+            0
+        ));
+        assert_ok!(XGatewayRecords::withdraw(
+            &ALICE,
+            X_BTC,
+            10,
+            b"addr".to_vec(),
+            b"ext".to_vec().into()
+        ));
+        assert_ok!(XGatewayRecords::cancel_withdrawal(3, &ALICE));
+        assert!(XGatewayRecords::archived_withdrawal(3).is_none());
+    })
+}
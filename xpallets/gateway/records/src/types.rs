@@ -6,15 +6,22 @@ use scale_info::TypeInfo;
 use serde::{Deserialize, Serialize};
 
 use sp_runtime::RuntimeDebug;
+use sp_std::prelude::*;
 
-use chainx_primitives::{AddrStr, AssetId};
+use chainx_primitives::{AddrStr, AssetId, Hash};
 use xp_runtime::Memo;
+use xpallet_assets::Chain;
 
 /// The id of withdrawal record (u32 is enough).
 pub type WithdrawalRecordId = u32;
 
 /// The state machine of WithdrawState:
 ///
+/// Delayed (lock token, the large withdrawal waits for the delay)
+///     |
+///     +----> NormalCancel|RootCancel (unlock token)
+///     |
+///     v
 /// Applying (lock token) <---> Processing (can't cancel, but can be recovered to `Applying`)
 ///     |                           |
 ///     |                           +----> NormalFinish|RootFinish (destroy token)
@@ -32,6 +39,7 @@ pub enum WithdrawalState {
     RootFinish,
     NormalCancel,
     RootCancel,
+    Delayed,
 }
 
 impl Default for WithdrawalState {
@@ -40,6 +48,53 @@ impl Default for WithdrawalState {
     }
 }
 
+/// The withdrawals of no less than `threshold` are delayed for `delay` blocks before the
+/// trustees could process them.
+#[derive(PartialEq, Eq, Clone, Copy, Encode, Decode, Default, RuntimeDebug, TypeInfo)]
+#[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "std", serde(rename_all = "camelCase"))]
+pub struct DelayedWithdrawalConfig<Balance, BlockNumber> {
+    pub threshold: Balance,
+    pub delay: BlockNumber,
+}
+
+/// A transition of the withdrawal state and the block where it happened.
+#[derive(PartialEq, Eq, Clone, Copy, Encode, Decode, RuntimeDebug, TypeInfo)]
+#[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "std", serde(rename_all = "camelCase"))]
+pub struct WithdrawalStateTransition<BlockNumber> {
+    pub state: WithdrawalState,
+    pub height: BlockNumber,
+}
+
+/// The filter of the withdrawal record queries, `None` matches any value.
+///
+/// The height range `[from_height, to_height]` is inclusive on both ends.
+#[derive(PartialEq, Eq, Clone, Encode, Decode, RuntimeDebug, TypeInfo)]
+#[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "std", serde(rename_all = "camelCase"))]
+pub struct WithdrawalFilter<AccountId, BlockNumber> {
+    pub applicant: Option<AccountId>,
+    pub asset_id: Option<AssetId>,
+    pub chain: Option<Chain>,
+    pub state: Option<WithdrawalState>,
+    pub from_height: Option<BlockNumber>,
+    pub to_height: Option<BlockNumber>,
+}
+
+impl<AccountId, BlockNumber> Default for WithdrawalFilter<AccountId, BlockNumber> {
+    fn default() -> Self {
+        Self {
+            applicant: None,
+            asset_id: None,
+            chain: None,
+            state: None,
+            from_height: None,
+            to_height: None,
+        }
+    }
+}
+
 /// WithdrawalRecord for withdrawal
 #[derive(PartialEq, Eq, Clone, Encode, Decode, RuntimeDebug, TypeInfo)]
 pub struct WithdrawalRecord<AccountId, Balance, BlockNumber> {
@@ -94,7 +149,7 @@ where
     }
 }
 
-#[derive(PartialEq, Eq, Clone, Encode, Decode, TypeInfo)]
+#[derive(PartialEq, Eq, Clone, Encode, Decode, RuntimeDebug, TypeInfo)]
 pub struct Withdrawal<AccountId, Balance, BlockNumber> {
     pub asset_id: AssetId,
     pub applicant: AccountId,
@@ -121,3 +176,26 @@ impl<AccountId, Balance, BlockNumber> Withdrawal<AccountId, Balance, BlockNumber
         }
     }
 }
+
+/// A finished or canceled withdrawal kept in the archive.
+#[derive(PartialEq, Eq, Clone, Encode, Decode, RuntimeDebug, TypeInfo)]
+pub struct ArchivedWithdrawal<AccountId, Balance, BlockNumber> {
+    /// The withdrawal with its final state.
+    pub withdrawal: Withdrawal<AccountId, Balance, BlockNumber>,
+    /// The state transitions, including the final one.
+    pub history: Vec<WithdrawalStateTransition<BlockNumber>>,
+    /// The txid of the transaction which settled the withdrawal, if any.
+    pub txid: Option<Hash>,
+}
+
+/// A page of the withdrawal records.
+///
+/// `next` is the cursor of the following page, `None` if there are no more records.
+#[derive(PartialEq, Eq, Clone, Encode, Decode, TypeInfo)]
+pub struct WithdrawalPage<AccountId, Balance, BlockNumber> {
+    pub withdrawals: Vec<(
+        WithdrawalRecordId,
+        Withdrawal<AccountId, Balance, BlockNumber>,
+    )>,
+    pub next: Option<WithdrawalRecordId>,
+}
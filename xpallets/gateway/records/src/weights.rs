@@ -35,6 +35,11 @@ pub trait WeightInfo {
     fn root_withdraw() -> Weight;
     fn set_withdrawal_state() -> Weight;
     fn set_withdrawal_state_list(u: u32) -> Weight;
+    fn set_delayed_withdrawal_config() -> Weight;
+    fn set_withdrawal_guardian() -> Weight;
+    fn release_delayed_withdrawals(n: u32) -> Weight;
+    fn set_withdrawal_archive_capacity() -> Weight;
+    fn prune_withdrawal_archive(n: u32) -> Weight;
 }
 
 /// Weights for xpallet_gateway_records using the Substrate node and recommended hardware.
@@ -47,18 +52,43 @@ impl<T: frame_system::Config> WeightInfo for SubstrateWeight<T> {
     }
     fn root_withdraw() -> Weight {
         (109_687_000 as Weight)
-            .saturating_add(T::DbWeight::get().reads(5 as Weight))
-            .saturating_add(T::DbWeight::get().writes(5 as Weight))
+            .saturating_add(T::DbWeight::get().reads(6 as Weight))
+            .saturating_add(T::DbWeight::get().writes(6 as Weight))
     }
     fn set_withdrawal_state() -> Weight {
         (121_624_000 as Weight)
-            .saturating_add(T::DbWeight::get().reads(8 as Weight))
-            .saturating_add(T::DbWeight::get().writes(6 as Weight))
+            .saturating_add(T::DbWeight::get().reads(11 as Weight))
+            .saturating_add(T::DbWeight::get().writes(11 as Weight))
     }
     fn set_withdrawal_state_list(_u: u32) -> Weight {
         (113_045_000 as Weight)
-            .saturating_add(T::DbWeight::get().reads(8 as Weight))
-            .saturating_add(T::DbWeight::get().writes(6 as Weight))
+            .saturating_add(T::DbWeight::get().reads(11 as Weight))
+            .saturating_add(T::DbWeight::get().writes(11 as Weight))
+    }
+    fn set_delayed_withdrawal_config() -> Weight {
+        (4_108_000 as Weight).saturating_add(T::DbWeight::get().writes(1 as Weight))
+    }
+    fn set_withdrawal_guardian() -> Weight {
+        (12_635_000 as Weight).saturating_add(T::DbWeight::get().writes(1 as Weight))
+    }
+    fn release_delayed_withdrawals(n: u32) -> Weight {
+        (3_472_000 as Weight)
+            .saturating_add((6_081_000 as Weight).saturating_mul(n as Weight))
+            .saturating_add(T::DbWeight::get().reads(1 as Weight))
+            .saturating_add(T::DbWeight::get().reads((1 as Weight).saturating_mul(n as Weight)))
+            .saturating_add(T::DbWeight::get().writes(1 as Weight))
+            .saturating_add(T::DbWeight::get().writes((1 as Weight).saturating_mul(n as Weight)))
+    }
+    fn set_withdrawal_archive_capacity() -> Weight {
+        (3_867_000 as Weight).saturating_add(T::DbWeight::get().writes(1 as Weight))
+    }
+    fn prune_withdrawal_archive(n: u32) -> Weight {
+        (5_214_000 as Weight)
+            .saturating_add((7_352_000 as Weight).saturating_mul(n as Weight))
+            .saturating_add(T::DbWeight::get().reads(1 as Weight))
+            .saturating_add(T::DbWeight::get().reads((1 as Weight).saturating_mul(n as Weight)))
+            .saturating_add(T::DbWeight::get().writes(1 as Weight))
+            .saturating_add(T::DbWeight::get().writes((2 as Weight).saturating_mul(n as Weight)))
     }
 }
 
@@ -71,17 +101,42 @@ impl WeightInfo for () {
     }
     fn root_withdraw() -> Weight {
         (109_687_000 as Weight)
-            .saturating_add(RocksDbWeight::get().reads(5 as Weight))
-            .saturating_add(RocksDbWeight::get().writes(5 as Weight))
+            .saturating_add(RocksDbWeight::get().reads(6 as Weight))
+            .saturating_add(RocksDbWeight::get().writes(6 as Weight))
     }
     fn set_withdrawal_state() -> Weight {
         (121_624_000 as Weight)
-            .saturating_add(RocksDbWeight::get().reads(8 as Weight))
-            .saturating_add(RocksDbWeight::get().writes(6 as Weight))
+            .saturating_add(RocksDbWeight::get().reads(11 as Weight))
+            .saturating_add(RocksDbWeight::get().writes(11 as Weight))
     }
     fn set_withdrawal_state_list(_u: u32) -> Weight {
         (113_045_000 as Weight)
-            .saturating_add(RocksDbWeight::get().reads(8 as Weight))
-            .saturating_add(RocksDbWeight::get().writes(6 as Weight))
+            .saturating_add(RocksDbWeight::get().reads(11 as Weight))
+            .saturating_add(RocksDbWeight::get().writes(11 as Weight))
+    }
+    fn set_delayed_withdrawal_config() -> Weight {
+        (4_108_000 as Weight).saturating_add(RocksDbWeight::get().writes(1 as Weight))
+    }
+    fn set_withdrawal_guardian() -> Weight {
+        (12_635_000 as Weight).saturating_add(RocksDbWeight::get().writes(1 as Weight))
+    }
+    fn release_delayed_withdrawals(n: u32) -> Weight {
+        (3_472_000 as Weight)
+            .saturating_add((6_081_000 as Weight).saturating_mul(n as Weight))
+            .saturating_add(RocksDbWeight::get().reads(1 as Weight))
+            .saturating_add(RocksDbWeight::get().reads((1 as Weight).saturating_mul(n as Weight)))
+            .saturating_add(RocksDbWeight::get().writes(1 as Weight))
+            .saturating_add(RocksDbWeight::get().writes((1 as Weight).saturating_mul(n as Weight)))
+    }
+    fn set_withdrawal_archive_capacity() -> Weight {
+        (3_867_000 as Weight).saturating_add(RocksDbWeight::get().writes(1 as Weight))
+    }
+    fn prune_withdrawal_archive(n: u32) -> Weight {
+        (5_214_000 as Weight)
+            .saturating_add((7_352_000 as Weight).saturating_mul(n as Weight))
+            .saturating_add(RocksDbWeight::get().reads(1 as Weight))
+            .saturating_add(RocksDbWeight::get().reads((1 as Weight).saturating_mul(n as Weight)))
+            .saturating_add(RocksDbWeight::get().writes(1 as Weight))
+            .saturating_add(RocksDbWeight::get().writes((2 as Weight).saturating_mul(n as Weight)))
     }
 }
@@ -132,6 +132,7 @@ impl xpallet_assets::Config for Test {
     type TreasuryAccount = ();
     type OnCreatedAccount = frame_system::Provider<Test>;
     type OnAssetChanged = XMiningAsset;
+    type AssetLedger = ();
     type WeightInfo = ();
 }
 